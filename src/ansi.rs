@@ -6,6 +6,7 @@
 //! `0`-based and silently add `1` to values.
 
 use crate::color::Color;
+use crate::config::CursorShape;
 use crate::size::Point;
 
 pub fn alt_screen(on: bool) -> &'static str {
@@ -24,6 +25,24 @@ pub fn track_mouse(on: bool) -> &'static str {
     }
 }
 
+pub fn save_title() -> &'static str {
+    "\x1b[22;0t"
+}
+
+pub fn restore_title() -> &'static str {
+    "\x1b[23;0t"
+}
+
+pub fn set_title(title: &str) -> String {
+    // Control characters, which could otherwise terminate the sequence early and
+    // inject others, are removed from the title.
+    let title = title
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect::<String>();
+    format!("\x1b]2;{title}\x07")
+}
+
 pub fn clear_screen() -> &'static str {
     "\x1b[2J\x1b[H"
 }
@@ -36,6 +55,19 @@ pub fn hide_cursor() -> &'static str {
     "\x1b[?25l"
 }
 
+pub fn set_cursor_shape(shape: CursorShape, blink: bool) -> String {
+    let n = match shape {
+        CursorShape::Block => 1,
+        CursorShape::Underline => 3,
+        CursorShape::Bar => 5,
+    } + if blink { 0 } else { 1 };
+    format!("\x1b[{n} q")
+}
+
+pub fn reset_cursor_shape() -> &'static str {
+    "\x1b[0 q"
+}
+
 pub fn set_cursor(p: Point) -> String {
     format!("\x1b[{};{}H", p.row + 1, p.col + 1)
 }
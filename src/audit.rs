@@ -0,0 +1,86 @@
+//! Records an append-only audit trail of editing operations.
+//!
+//! When enabled by the `audit-log` setting, each editing operation run by the
+//! controller is appended to a file in the state directory as a line containing the
+//! local time, the name of the operation, and the source of the editor in the active
+//! window, separated by tabs. The content of buffers is never recorded, so characters
+//! typed into an editor appear only as `insert-char`.
+//!
+//! The file is only ever opened for appending, and entries written in prior sessions
+//! are never altered, which makes the trail suitable for reconstructing the sequence
+//! of operations that led to a problem.
+
+use crate::error::{Error, Result};
+use crate::io;
+use crate::sys::{self, AsString};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// An audit trail, which records nothing unless opened.
+pub struct Audit {
+    file: Option<File>,
+}
+
+impl Audit {
+    /// Name of the file in the state directory to which the audit trail is appended.
+    const FILE_NAME: &str = "audit.log";
+
+    /// Returns the path of the audit file.
+    pub fn path() -> PathBuf {
+        sys::state_dir().join(Self::FILE_NAME)
+    }
+
+    /// Returns an audit trail that records nothing.
+    pub fn new() -> Audit {
+        Audit { file: None }
+    }
+
+    /// Returns `true` if entries are being recorded.
+    pub fn is_open(&self) -> bool {
+        self.file.is_some()
+    }
+
+    /// Opens the audit file for appending, creating the file if necessary.
+    pub fn open() -> Result<Audit> {
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            io::create_dir(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| Error::io(&path.as_string(), e))?;
+        Ok(Audit { file: Some(file) })
+    }
+
+    /// Appends an entry recording the operation `op` performed on the editor whose
+    /// source is `source`.
+    ///
+    /// If the entry cannot be written, recording stops so that subsequent
+    /// operations are not burdened with repeated failures, and the error is
+    /// returned.
+    pub fn record(&mut self, op: &str, source: &str) -> Result<()> {
+        if let Some(ref mut file) = self.file {
+            let entry = format!("{}\t{op}\t{source}\n", sys::local_time());
+            if let Err(e) = file.write_all(entry.as_bytes()) {
+                self.file = None;
+                return Err(Error::io(&Self::path().as_string(), e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the content of the audit file, or `None` if it does not exist.
+    pub fn load() -> Result<Option<String>> {
+        let path = Self::path();
+        if sys::is_file(&path) {
+            fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|e| Error::io(&path.as_string(), e))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -4,11 +4,18 @@
 //! defined authoritatively in the map produced by [`init_key_map`](key::init_key_map),
 //! and similarly, the recognized set of editing operations is defined in the map
 //! produced by [`init_op_map`](op::init_op_map).
+//!
+//! Key sequences may also be bound within a [`Scope`], such as a _readonly_ editor
+//! or an active selection, so that the same keys perform different operations in
+//! different contexts. When resolving keys, the scopes that apply to a [`Context`],
+//! which is a snapshot of the environment, are consulted in order of precedence
+//! before bindings that apply in all contexts.
 
 use crate::error::{Error, Result};
 use crate::key::{self, Key, KeyMap};
 use crate::op::{self, OpFn, OpMap};
 use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
 
 /// A mapping of [`Key`] sequences to editing functions.
 pub struct Bindings {
@@ -17,6 +24,78 @@ pub struct Bindings {
     bind_map: HashMap<Vec<Key>, String>,
     bind_prefixes: HashSet<Vec<Key>>,
     restricted_keys: HashSet<Vec<Key>>,
+
+    /// Key sequences bound via [`bind()`](Self::bind), as opposed to those bound
+    /// by default, which is used to detect duplicate bindings.
+    user_map: HashMap<Vec<Key>, String>,
+
+    /// Key sequences bound within a scope via [`bind_scoped()`](Self::bind_scoped).
+    scoped_map: HashMap<Scope, HashMap<Vec<Key>, String>>,
+
+    /// Prefixes of key sequences bound within a scope.
+    scoped_prefixes: HashMap<Scope, HashSet<Vec<Key>>>,
+
+    /// Conflicts detected during binding.
+    conflicts: Vec<Conflict>,
+}
+
+/// A context in which key bindings apply in preference to those that apply in all
+/// contexts.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// The editor in the active window is _readonly_.
+    Readonly,
+
+    /// A question is being solicited.
+    Question,
+
+    /// The editor in the active window has a selection.
+    Selection,
+
+    /// The editor in the active window uses the syntax with the given name.
+    Syntax(String),
+}
+
+/// A snapshot of the environment against which scoped key bindings are resolved.
+#[derive(Default)]
+pub struct Context {
+    pub readonly: bool,
+    pub question: bool,
+    pub selection: bool,
+    pub syntax: String,
+}
+
+/// A conflict among key bindings, which is reported by [`Bindings::audit()`].
+///
+/// Each conflict carries the scope of the bindings involved, which is `None` for
+/// bindings that apply in all contexts.
+#[derive(Clone)]
+pub enum Conflict {
+    /// A key sequence bound more than once to different operations, where the
+    /// latter binding prevails.
+    Duplicate {
+        scope: Option<Scope>,
+        keys: Vec<Key>,
+        prior_op: String,
+        op: String,
+    },
+
+    /// A key sequence that can never be reached because one of its prefixes is also
+    /// bound to an operation.
+    Shadowed {
+        scope: Option<Scope>,
+        keys: Vec<Key>,
+        op: String,
+        prefix: Vec<Key>,
+        prefix_op: String,
+    },
+
+    /// A key sequence bound to an operation that does not exist, which is ignored.
+    UnknownOp {
+        scope: Option<Scope>,
+        keys: Vec<Key>,
+        op: String,
+    },
 }
 
 impl Bindings {
@@ -32,6 +111,10 @@ impl Bindings {
             bind_map: HashMap::new(),
             bind_prefixes: HashSet::new(),
             restricted_keys: Self::init_restricted_keys(),
+            user_map: HashMap::new(),
+            scoped_map: HashMap::new(),
+            scoped_prefixes: HashMap::new(),
+            conflicts: Vec::new(),
         };
 
         for (key_seq, op) in bindings {
@@ -44,11 +127,72 @@ impl Bindings {
     /// override an existing binding with an identical key sequence.
     ///
     /// Attempting to bind to any of the restricted key sequences will result in an
-    /// error.
+    /// error. However, binding to an editing operation that does not exist, or
+    /// binding the same key sequence more than once to different operations, is
+    /// recorded as a [`Conflict`] rather than an error, and in the former case, the
+    /// binding is ignored.
     pub fn bind(&mut self, key_seq: &str, op: &str) -> Result<()> {
+        let keys = self.to_keys(key_seq, true)?;
+        if !self.op_map.contains_key(op) {
+            self.conflicts.push(Conflict::UnknownOp {
+                scope: None,
+                keys,
+                op: op.to_string(),
+            });
+            return Ok(());
+        }
+        if let Some(prior_op) = self.user_map.insert(keys.clone(), op.to_string()) {
+            if prior_op != op {
+                self.conflicts.push(Conflict::Duplicate {
+                    scope: None,
+                    keys,
+                    prior_op,
+                    op: op.to_string(),
+                });
+            }
+        }
         self.bind_internal(key_seq, op, true)
     }
 
+    /// Binds the key sequence `key_seq` to the editing operation `op` within the
+    /// scope named by `context`, which is one of `readonly`, `question`, `selection`
+    /// or `syntax:<name>`.
+    ///
+    /// Errors and conflicts are handled in the same manner as [`bind()`](Self::bind),
+    /// though an unrecognized `context` is also an error.
+    pub fn bind_scoped(&mut self, context: &str, key_seq: &str, op: &str) -> Result<()> {
+        let scope = Scope::parse(context)?;
+        let keys = self.to_keys(key_seq, true)?;
+        let Some((op, _)) = self.op_map.get_key_value(op) else {
+            self.conflicts.push(Conflict::UnknownOp {
+                scope: Some(scope),
+                keys,
+                op: op.to_string(),
+            });
+            return Ok(());
+        };
+        let prefixes = self.scoped_prefixes.entry(scope.clone()).or_default();
+        for n in 1..keys.len() {
+            prefixes.insert(keys[0..n].to_vec());
+        }
+        let prior_op = self
+            .scoped_map
+            .entry(scope.clone())
+            .or_default()
+            .insert(keys.clone(), op.to_string());
+        if let Some(prior_op) = prior_op {
+            if prior_op != *op {
+                self.conflicts.push(Conflict::Duplicate {
+                    scope: Some(scope),
+                    keys,
+                    prior_op,
+                    op: op.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Internal binding function that prohibits binding to restricted key sequences
     /// when `strict` is `true`.
     fn bind_internal(&mut self, key_seq: &str, op: &str, strict: bool) -> Result<()> {
@@ -121,17 +265,83 @@ impl Bindings {
         &self.bind_map
     }
 
-    /// Returns the function pointer bound to `keys`, otherwise `None`.
-    pub fn find(&self, keys: &Vec<Key>) -> Option<&OpFn> {
-        self.bind_map
-            .get(keys)
-            .and_then(|op| self.op_map.get(op as &str))
+    /// Returns a reference to the bindings within each scope.
+    pub fn scoped_bindings(&self) -> &HashMap<Scope, HashMap<Vec<Key>, String>> {
+        &self.scoped_map
+    }
+
+    /// Returns the function pointer bound to `keys` in `context`, otherwise `None`.
+    pub fn find(&self, keys: &Vec<Key>, context: &Context) -> Option<&OpFn> {
+        self.find_op(keys, context)
+            .and_then(|op| self.op_map.get(op))
+    }
+
+    /// Returns the name of the operation bound to `keys` in `context`, otherwise
+    /// `None`.
+    ///
+    /// Bindings within scopes that apply to `context` take precedence over those that
+    /// apply in all contexts, though only bindings within the question scope are
+    /// consulted while a question is being solicited, since keys are otherwise
+    /// consumed by the question.
+    pub fn find_op(&self, keys: &Vec<Key>, context: &Context) -> Option<&str> {
+        context
+            .scopes()
+            .iter()
+            .find_map(|scope| self.scoped_map.get(scope).and_then(|map| map.get(keys)))
+            .or_else(|| {
+                if context.question {
+                    None
+                } else {
+                    self.bind_map.get(keys)
+                }
+            })
+            .map(|op| op.as_str())
     }
 
     /// Returns `true` if `keys` is a prefix of at least one key sequence bound to a
-    /// function pointer.
-    pub fn is_prefix(&self, keys: &Vec<Key>) -> bool {
-        self.bind_prefixes.contains(keys)
+    /// function pointer in `context`.
+    pub fn is_prefix(&self, keys: &Vec<Key>, context: &Context) -> bool {
+        context.scopes().iter().any(|scope| {
+            self.scoped_prefixes
+                .get(scope)
+                .is_some_and(|prefixes| prefixes.contains(keys))
+        }) || (!context.question && self.bind_prefixes.contains(keys))
+    }
+
+    /// Returns `true` if `keys` is restricted from being rebound.
+    pub fn is_restricted(&self, keys: &Vec<Key>) -> bool {
+        self.restricted_keys.contains(keys)
+    }
+
+    /// Returns all conflicts among the current bindings, which includes those
+    /// detected during binding as well as key sequences shadowed by bound prefixes,
+    /// both in all contexts and within each scope.
+    pub fn audit(&self) -> Vec<Conflict> {
+        let mut shadowed = Self::shadowed(None, &self.bind_map);
+        for (scope, map) in &self.scoped_map {
+            shadowed.extend(Self::shadowed(Some(scope), map));
+        }
+        shadowed.sort_by_key(|conflict| conflict.to_string());
+        self.conflicts.iter().cloned().chain(shadowed).collect()
+    }
+
+    /// Returns the key sequences in `map` that are shadowed by a prefix also bound in
+    /// `map`, where `scope` is the scope of the bindings.
+    fn shadowed(scope: Option<&Scope>, map: &HashMap<Vec<Key>, String>) -> Vec<Conflict> {
+        map.iter()
+            .filter_map(|(keys, op)| {
+                (1..keys.len()).find_map(|n| {
+                    let prefix = &keys[0..n];
+                    map.get(prefix).map(|prefix_op| Conflict::Shadowed {
+                        scope: scope.cloned(),
+                        keys: keys.clone(),
+                        op: op.to_string(),
+                        prefix: prefix.to_vec(),
+                        prefix_op: prefix_op.to_string(),
+                    })
+                })
+            })
+            .collect()
     }
 
     /// Returns the set of restricted keys.
@@ -153,3 +363,95 @@ impl Bindings {
         &[Key::Control(127)], // C-? (del)
     ];
 }
+
+impl Scope {
+    /// Prefix of context names that scope bindings to a syntax.
+    const SYNTAX_PREFIX: &str = "syntax:";
+
+    /// Returns the scope named by `context`.
+    pub fn parse(context: &str) -> Result<Scope> {
+        match context {
+            "readonly" => Ok(Scope::Readonly),
+            "question" => Ok(Scope::Question),
+            "selection" => Ok(Scope::Selection),
+            _ => match context.strip_prefix(Self::SYNTAX_PREFIX) {
+                Some(name) if !name.is_empty() => Ok(Scope::Syntax(name.to_string())),
+                _ => Err(Error::invalid_context(context)),
+            },
+        }
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Readonly => write!(f, "readonly"),
+            Self::Question => write!(f, "question"),
+            Self::Selection => write!(f, "selection"),
+            Self::Syntax(name) => write!(f, "{}{name}", Self::SYNTAX_PREFIX),
+        }
+    }
+}
+
+impl Context {
+    /// Returns the scopes that apply to this context in order of precedence, noting
+    /// that only the question scope applies while a question is being solicited.
+    fn scopes(&self) -> Vec<Scope> {
+        if self.question {
+            return vec![Scope::Question];
+        }
+        let mut scopes = Vec::new();
+        if self.selection {
+            scopes.push(Scope::Selection);
+        }
+        if self.readonly {
+            scopes.push(Scope::Readonly);
+        }
+        scopes.push(Scope::Syntax(self.syntax.clone()));
+        scopes
+    }
+}
+
+impl Conflict {
+    /// Returns the key sequence `keys` in conflict, qualified by its scope if any.
+    fn label(scope: &Option<Scope>, keys: String) -> String {
+        match scope {
+            Some(scope) => format!("{keys} [{scope}]"),
+            None => keys,
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Duplicate {
+                scope,
+                keys,
+                prior_op,
+                op,
+            } => write!(
+                f,
+                "{}: bound to both {prior_op} and {op}, where {op} prevails",
+                Self::label(scope, key::pretty(keys))
+            ),
+            Self::Shadowed {
+                scope,
+                keys,
+                op,
+                prefix,
+                prefix_op,
+            } => write!(
+                f,
+                "{}: {op} is unreachable since {} is bound to {prefix_op}",
+                Self::label(scope, key::pretty(keys)),
+                key::pretty(prefix)
+            ),
+            Self::UnknownOp { scope, keys, op } => write!(
+                f,
+                "{}: {op} is not a valid operation",
+                Self::label(scope, key::pretty(keys))
+            ),
+        }
+    }
+}
@@ -64,6 +64,7 @@ impl Buffer {
     const INIT_CAPACITY: usize = 65_536;
     const GROW_CAPACITY: usize = 65_536;
     const MAX_CAPACITY: usize = 2_147_483_648;
+    const SHRINK_FACTOR: usize = 4;
 
     pub fn new() -> Buffer {
         Buffer::with_capacity(Self::INIT_CAPACITY)
@@ -95,6 +96,12 @@ impl Buffer {
         self.size
     }
 
+    /// Returns the number of characters the buffer can hold without reallocation.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Returns the gap position, which is the point of insertion and removal
     /// operations.
     #[inline]
@@ -207,6 +214,24 @@ impl Buffer {
         }
     }
 
+    /// Shrinks the capacity of the buffer if it exceeds the capacity required by its
+    /// contents by a wide margin, returning `true` if the buffer was reallocated.
+    ///
+    /// The resulting capacity is a multiple of the growth increment that leaves room
+    /// for at least one increment of growth.
+    pub fn compact(&mut self) -> bool {
+        let capacity = cmp::max(
+            (self.size / Self::GROW_CAPACITY + 1) * Self::GROW_CAPACITY,
+            Self::INIT_CAPACITY,
+        );
+        if self.capacity > capacity * Self::SHRINK_FACTOR {
+            self.realloc(capacity);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Returns a vector of characters in the range of [`from_pos`, `to_pos`).
     ///
     /// There is no ordering requirement in relation to `from_pos` and `to_pos`, so it
@@ -454,10 +479,15 @@ impl Buffer {
             (self.capacity + need + Self::GROW_CAPACITY - 1) / Self::GROW_CAPACITY
                 * Self::GROW_CAPACITY
         };
+        self.realloc(capacity);
+    }
 
+    /// Reallocates the buffer with `capacity`, which must be at least `size`, such
+    /// that the gap absorbs the change in capacity.
+    fn realloc(&mut self, capacity: usize) {
         // Allocate new buffer and copy contents of old buffer.
         let buf = Buffer::alloc(capacity);
-        let gap_len = self.gap_len + (capacity - self.capacity);
+        let gap_len = capacity - self.size;
         unsafe {
             // Copy left of gap.
             NonNull::copy_to_nonoverlapping(self.ptr_at(0), buf, self.gap);
@@ -655,6 +685,24 @@ mod tests {
         assert_eq!(buf.size, CAP + 1);
     }
 
+    #[test]
+    fn compact_buffer() {
+        const CAP: usize = Buffer::GROW_CAPACITY * 8;
+        const TEXT: &str = "abcdefghij";
+
+        let mut buf = Buffer::with_capacity(CAP);
+        buf.insert_str(TEXT);
+        buf.insert_str(TEXT);
+        let _ = buf.set_pos(TEXT.len());
+        assert!(buf.compact());
+        assert_eq!(buf.capacity, Buffer::INIT_CAPACITY);
+        assert_eq!(buf.size, TEXT.len() * 2);
+        assert_eq!(buf.gap, TEXT.len());
+        assert_eq!(buf.gap_len, buf.capacity - buf.size);
+        assert_eq!(buf.copy_as_string(0, buf.size), TEXT.repeat(2));
+        assert!(!buf.compact());
+    }
+
     #[test]
     fn insert_char() {
         let mut buf = Buffer::new();
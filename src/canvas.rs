@@ -12,6 +12,7 @@
 use crate::color::Color;
 use crate::grid::{Cell, Grid};
 use crate::size::{Point, Size};
+use crate::unicode;
 use crate::writer::Writer;
 use std::cell::RefCell;
 use std::cmp;
@@ -79,6 +80,31 @@ impl Canvas {
         self.back.set_cell(row, col, cell);
     }
 
+    /// Sets the cell at (`row`, `col`) to the value `cell`, whose character is wide and
+    /// thus also covers the cell that follows.
+    pub fn set_wide_cell(&mut self, row: u32, col: u32, cell: Cell) {
+        self.set_cell(row, col, cell);
+        self.set_cell(row, col + 1, Cell::covered(cell.color));
+    }
+
+    /// Attaches the combining character `mark` to the cell at (`row`, `col`), or the
+    /// wide character covering that cell, unless a mark is already attached.
+    pub fn set_mark(&mut self, row: u32, col: u32, mark: char) {
+        debug_assert!(row < self.size.rows);
+        debug_assert!(col < self.size.cols);
+        let mut cell = self.back.get_cell(row, col);
+        let col = if cell.is_covered() && col > 0 {
+            cell = self.back.get_cell(row, col - 1);
+            col - 1
+        } else {
+            col
+        };
+        if cell.mark.is_none() {
+            cell.mark = Some(mark);
+            self.back.set_cell(row, col, cell);
+        }
+    }
+
     /// Sets the cell at (`row`, `col`) to the value `c` using `color`.
     #[inline]
     pub fn set(&mut self, row: u32, col: u32, c: char, color: Color) {
@@ -170,8 +196,12 @@ impl Canvas {
             let mut hint = None;
             self.writer.hide_cursor();
             for (p, cell) in changes {
-                self.draw_cell(p, cell, hint);
-                hint = Some((p, cell));
+                // Cells covered by wide characters are skipped, since the terminal
+                // fills them when drawing the preceding cell.
+                if !cell.is_covered() {
+                    self.draw_cell(p, cell, hint);
+                    hint = Some((p, cell));
+                }
             }
             self.writer.show_cursor().send();
         }
@@ -186,11 +216,12 @@ impl Canvas {
     /// Draws `cell` at point `p`.
     ///
     /// An optional `hint` is used to optimize the output, where the hint is the last
-    /// cell drawn.
+    /// cell drawn, noting that the terminal advances the cursor by the width of its
+    /// character.
     fn draw_cell(&mut self, p: Point, cell: Cell, hint: Option<(Point, Cell)>) {
         match hint {
             Some((prev_p, prev_cell)) => {
-                if p.row != prev_p.row || p.col != prev_p.col + 1 {
+                if p.row != prev_p.row || p.col != prev_p.col + unicode::width(prev_cell.value) {
                     self.writer.set_cursor(p);
                 }
                 if cell.color != prev_cell.color {
@@ -202,5 +233,8 @@ impl Canvas {
             }
         }
         self.writer.write(cell.value);
+        if let Some(mark) = cell.mark {
+            self.writer.write(mark);
+        }
     }
 }
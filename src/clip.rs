@@ -0,0 +1,86 @@
+//! Exchange of text with the system clipboard.
+//!
+//! The clipboard maintained by the environment is always used for copying and
+//! pasting, though depending on the [`Clipboard`] setting, copied text is also sent
+//! to the system clipboard and pasted text is taken from the system clipboard if it
+//! was changed by another application.
+//!
+//! With [`Clipboard::Osc52`], copied text is sent to the terminal using the OSC 52
+//! escape sequence, which terminals interpret by setting the system clipboard. This
+//! works even when connected to a remote machine, though reading the system clipboard
+//! is not supported.
+//!
+//! With [`Clipboard::System`], text is exchanged using commands found on the system,
+//! which are `pbcopy` and `pbpaste` on macOS, `wl-copy` and `wl-paste` under Wayland,
+//! and `xclip` otherwise.
+
+use crate::config::Clipboard;
+use crate::error::Result;
+use crate::job;
+use std::env;
+use std::io::{self, Write};
+
+/// Sends `text` to the system clipboard according to `mode`.
+pub fn copy(mode: Clipboard, text: &str) -> Result<()> {
+    match mode {
+        Clipboard::Internal => Ok(()),
+        Clipboard::Osc52 => {
+            print!("\x1b]52;c;{}\x07", encode(text.as_bytes()));
+            let _ = io::stdout().flush();
+            Ok(())
+        }
+        Clipboard::System => job::pipe(commands().0, text.to_string()),
+    }
+}
+
+/// Returns the contents of the system clipboard according to `mode`, or `None` if
+/// `mode` does not support reading the system clipboard.
+pub fn paste(mode: Clipboard) -> Result<Option<String>> {
+    match mode {
+        Clipboard::Internal | Clipboard::Osc52 => Ok(None),
+        Clipboard::System => {
+            let text = job::filter(commands().1, Vec::new(), &[])?;
+            Ok(Some(String::from_utf8_lossy(&text).to_string()))
+        }
+    }
+}
+
+/// Returns a tuple containing the commands that copy to and paste from the system
+/// clipboard.
+///
+/// Both `wl-copy` and `xclip` leave a process running in the background to serve
+/// the clipboard, so its _stderr_ is discarded, otherwise waiting for the output of
+/// the command would never finish.
+fn commands() -> (&'static str, &'static str) {
+    if cfg!(target_os = "macos") {
+        ("pbcopy", "pbpaste")
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("wl-copy 2>/dev/null", "wl-paste --no-newline")
+    } else {
+        (
+            "xclip -selection clipboard 2>/dev/null",
+            "xclip -selection clipboard -out",
+        )
+    }
+}
+
+/// Returns the base64 encoding of `bytes`, which is required by OSC 52.
+fn encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -23,6 +23,7 @@ pub struct Colors {
 
 /// A color value representing either a _number_ in the range of `0` to `255`, or as a
 /// _string_ that refers to a named color.
+#[derive(Clone)]
 pub enum ColorValue {
     Number(u8),
     Name(String),
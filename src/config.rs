@@ -23,8 +23,9 @@ use crate::bind::Bindings;
 use crate::color::{ColorValue, Colors};
 use crate::error::{Error, Result};
 use crate::opt::Options;
-use crate::syntax::Registry;
+use crate::syntax::{Registry, TokenColors};
 use crate::sys::{self, AsString};
+use indexmap::IndexMap;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -48,6 +49,10 @@ pub struct Configuration {
 
     /// A registry of syntax configurations.
     pub registry: Registry,
+
+    /// A map of syntax names to overrides of token colors, which are applied to the
+    /// registry when loaded.
+    pub syntax_colors: TokenColors,
 }
 
 pub type ConfigurationRef = Rc<Configuration>;
@@ -55,9 +60,165 @@ pub type ConfigurationRef = Rc<Configuration>;
 pub struct Settings {
     pub spotlight: bool,
     pub lines: bool,
+    pub relative_lines: bool,
     pub eol: bool,
+    pub wrap: bool,
+    pub virtual_edit: bool,
+    pub auto_indent: bool,
     pub tab_hard: bool,
     pub tab_size: u32,
+    pub print_command: String,
+    pub print_lines: bool,
+    pub print_header: bool,
+    pub diff_tool: String,
+    pub middle_paste: bool,
+    pub clipboard: Clipboard,
+    pub page_overlap: u32,
+    pub cursor_shape: CursorShape,
+    pub cursor_blink: bool,
+    pub readonly_cursor_shape: CursorShape,
+    pub dim_inactive: bool,
+    pub echo_timeout: u32,
+    pub check_interval: u32,
+    pub title: bool,
+    pub fill_column: u32,
+    pub trash_dir: Option<String>,
+    pub templates: bool,
+    pub sticky_header: bool,
+    pub selection_info: bool,
+    pub key_timeout: u32,
+    pub undo_memory: u32,
+    pub open_preview: bool,
+    pub ignore: Vec<String>,
+    pub quit_ephemeral: QuitEphemeral,
+    pub bom: ByteOrderMark,
+    pub save_session: bool,
+    pub audit_log: bool,
+    pub io_retries: u32,
+    pub io_backoff: u32,
+    pub io_timeout: u32,
+    pub crypt_extensions: Vec<String>,
+    pub decrypt_command: String,
+    pub encrypt_command: String,
+    pub control_chars: ControlChars,
+    pub markers: Vec<String>,
+    pub highlight_markers: bool,
+    pub show_keys: bool,
+    pub locale: Option<String>,
+    pub ascii_only: bool,
+    pub glyphs: Glyphs,
+}
+
+/// Shapes of the terminal cursor.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorShape {
+    Block,
+    Underline,
+    Bar,
+}
+
+/// Treatment of ephemeral editors with content when quitting.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuitEphemeral {
+    /// Prompts for a path to which each editor is saved.
+    Prompt,
+
+    /// Discards the content of each editor.
+    Discard,
+
+    /// Saves each editor to the state directory without prompting.
+    Save,
+}
+
+/// Exchange of text between the clipboard and the system clipboard.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Clipboard {
+    /// Never exchanges text with the system clipboard.
+    Internal,
+
+    /// Sends copied text to the terminal using the OSC 52 escape sequence, which also
+    /// works over remote connections, though text cannot be pasted from the system.
+    Osc52,
+
+    /// Exchanges text using a system command, such as `pbcopy` and `pbpaste`.
+    System,
+}
+
+/// Treatment of the UTF-8 byte order mark when saving files.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteOrderMark {
+    /// Writes the byte order mark only if present when the file was opened.
+    Preserve,
+
+    /// Never writes the byte order mark.
+    Strip,
+
+    /// Always writes the byte order mark.
+    Add,
+}
+
+/// Display of ASCII control characters other than `\n` and `\t`.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ControlChars {
+    /// Shows all control characters using the same special character.
+    Glyph,
+
+    /// Shows each control character in caret notation occupying two columns, such as
+    /// `^[` for `ESC` and `^?` for `DEL`.
+    Caret,
+}
+
+/// Special characters shown in place of characters that are otherwise invisible or
+/// concealed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Glyphs {
+    /// Shown for `\n` when visible.
+    pub eol: char,
+
+    /// Shown for `\t`.
+    pub tab: char,
+
+    /// Shown for all other ASCII control characters.
+    pub control: char,
+
+    /// Shown for all other characters when concealed.
+    pub redact: char,
+
+    /// Shown at the edges of lines truncated when line wrapping is disabled.
+    pub truncate: char,
+}
+
+impl ControlChars {
+    /// Returns `true` if `c` is shown in caret notation, which excludes `\n` and `\t`.
+    #[inline]
+    pub fn is_caret(&self, c: char) -> bool {
+        *self == ControlChars::Caret && c.is_ascii_control() && c != '\n' && c != '\t'
+    }
+}
+
+impl Glyphs {
+    /// Default glyphs drawn from the Unicode character set.
+    pub const UNICODE: Glyphs = Glyphs {
+        eol: '\u{21b2}',
+        tab: '\u{2192}',
+        control: '\u{00bf}',
+        redact: '\u{2022}',
+        truncate: '\u{2026}',
+    };
+
+    /// Default glyphs when the `ascii-only` setting is enabled.
+    pub const ASCII: Glyphs = Glyphs {
+        eol: '$',
+        tab: '>',
+        control: '?',
+        redact: '*',
+        truncate: '+',
+    };
 }
 
 pub struct Theme {
@@ -65,13 +226,18 @@ pub struct Theme {
     pub text_bg: u8,
     pub select_bg: u8,
     pub spotlight_bg: u8,
+    pub highlight_bg: u8,
+    pub bracket_bg: u8,
     pub whitespace_fg: u8,
     pub accent_fg: u8,
     pub echo_fg: u8,
+    pub warn_fg: u8,
+    pub error_fg: u8,
     pub prompt_fg: u8,
     pub banner_fg: u8,
     pub active_bg: u8,
     pub inactive_bg: u8,
+    pub inactive_fg: u8,
     pub margin_fg: u8,
     pub margin_bg: u8,
 }
@@ -82,7 +248,13 @@ struct ExternalConfiguration {
     settings: Option<ExternalSettings>,
     colors: Option<HashMap<String, u8>>,
     theme: Option<ExternalTheme>,
-    bindings: Option<HashMap<String, String>>,
+    bindings: Option<IndexMap<String, String>>,
+
+    #[serde(rename = "context-bindings")]
+    context_bindings: Option<IndexMap<String, IndexMap<String, String>>>,
+
+    #[serde(rename = "syntax-colors")]
+    syntax_colors: Option<HashMap<String, IndexMap<String, ColorValue>>>,
 }
 
 #[derive(Deserialize)]
@@ -90,13 +262,148 @@ struct ExternalConfiguration {
 struct ExternalSettings {
     spotlight: Option<bool>,
     lines: Option<bool>,
+
+    #[serde(rename = "relative-lines")]
+    relative_lines: Option<bool>,
     eol: Option<bool>,
+    wrap: Option<bool>,
+
+    #[serde(rename = "virtual-edit")]
+    virtual_edit: Option<bool>,
+
+    #[serde(rename = "auto-indent")]
+    auto_indent: Option<bool>,
 
     #[serde(rename = "tab-hard")]
     tab_hard: Option<bool>,
 
     #[serde(rename = "tab-size")]
     tab_size: Option<u32>,
+
+    #[serde(rename = "print-command")]
+    print_command: Option<String>,
+
+    #[serde(rename = "print-lines")]
+    print_lines: Option<bool>,
+
+    #[serde(rename = "print-header")]
+    print_header: Option<bool>,
+
+    #[serde(rename = "diff-tool")]
+    diff_tool: Option<String>,
+
+    #[serde(rename = "middle-paste")]
+    middle_paste: Option<bool>,
+
+    clipboard: Option<Clipboard>,
+
+    #[serde(rename = "page-overlap")]
+    page_overlap: Option<u32>,
+
+    #[serde(rename = "cursor-shape")]
+    cursor_shape: Option<CursorShape>,
+
+    #[serde(rename = "cursor-blink")]
+    cursor_blink: Option<bool>,
+
+    #[serde(rename = "readonly-cursor-shape")]
+    readonly_cursor_shape: Option<CursorShape>,
+
+    #[serde(rename = "dim-inactive")]
+    dim_inactive: Option<bool>,
+
+    #[serde(rename = "echo-timeout")]
+    echo_timeout: Option<u32>,
+
+    #[serde(rename = "check-interval")]
+    check_interval: Option<u32>,
+
+    #[serde(rename = "title")]
+    title: Option<bool>,
+
+    #[serde(rename = "fill-column")]
+    fill_column: Option<u32>,
+
+    #[serde(rename = "trash-dir")]
+    trash_dir: Option<String>,
+    templates: Option<bool>,
+
+    #[serde(rename = "sticky-header")]
+    sticky_header: Option<bool>,
+
+    #[serde(rename = "selection-info")]
+    selection_info: Option<bool>,
+
+    #[serde(rename = "key-timeout")]
+    key_timeout: Option<u32>,
+
+    #[serde(rename = "undo-memory")]
+    undo_memory: Option<u32>,
+
+    #[serde(rename = "open-preview")]
+    open_preview: Option<bool>,
+
+    ignore: Option<Vec<String>>,
+
+    #[serde(rename = "quit-ephemeral")]
+    quit_ephemeral: Option<QuitEphemeral>,
+
+    bom: Option<ByteOrderMark>,
+
+    #[serde(rename = "save-session")]
+    save_session: Option<bool>,
+
+    #[serde(rename = "audit-log")]
+    audit_log: Option<bool>,
+
+    #[serde(rename = "io-retries")]
+    io_retries: Option<u32>,
+
+    #[serde(rename = "io-backoff")]
+    io_backoff: Option<u32>,
+
+    #[serde(rename = "io-timeout")]
+    io_timeout: Option<u32>,
+
+    #[serde(rename = "crypt-extensions")]
+    crypt_extensions: Option<Vec<String>>,
+
+    #[serde(rename = "decrypt-command")]
+    decrypt_command: Option<String>,
+
+    #[serde(rename = "encrypt-command")]
+    encrypt_command: Option<String>,
+
+    #[serde(rename = "control-chars")]
+    control_chars: Option<ControlChars>,
+
+    markers: Option<Vec<String>>,
+
+    #[serde(rename = "highlight-markers")]
+    highlight_markers: Option<bool>,
+
+    #[serde(rename = "show-keys")]
+    show_keys: Option<bool>,
+
+    locale: Option<String>,
+
+    #[serde(rename = "ascii-only")]
+    ascii_only: Option<bool>,
+
+    #[serde(rename = "eol-char")]
+    eol_char: Option<char>,
+
+    #[serde(rename = "tab-char")]
+    tab_char: Option<char>,
+
+    #[serde(rename = "control-char")]
+    control_char: Option<char>,
+
+    #[serde(rename = "redact-char")]
+    redact_char: Option<char>,
+
+    #[serde(rename = "truncate-char")]
+    truncate_char: Option<char>,
 }
 
 #[derive(Deserialize)]
@@ -114,6 +421,12 @@ struct ExternalTheme {
     #[serde(rename = "spotlight-bg")]
     spotlight_bg: Option<ColorValue>,
 
+    #[serde(rename = "highlight-bg")]
+    highlight_bg: Option<ColorValue>,
+
+    #[serde(rename = "bracket-bg")]
+    bracket_bg: Option<ColorValue>,
+
     #[serde(rename = "whitespace-fg")]
     whitespace_fg: Option<ColorValue>,
 
@@ -123,6 +436,12 @@ struct ExternalTheme {
     #[serde(rename = "echo-fg")]
     echo_fg: Option<ColorValue>,
 
+    #[serde(rename = "warn-fg")]
+    warn_fg: Option<ColorValue>,
+
+    #[serde(rename = "error-fg")]
+    error_fg: Option<ColorValue>,
+
     #[serde(rename = "prompt-fg")]
     prompt_fg: Option<ColorValue>,
 
@@ -135,6 +454,9 @@ struct ExternalTheme {
     #[serde(rename = "inactive-bg")]
     inactive_bg: Option<ColorValue>,
 
+    #[serde(rename = "inactive-fg")]
+    inactive_fg: Option<ColorValue>,
+
     #[serde(rename = "margin-fg")]
     margin_fg: Option<ColorValue>,
 
@@ -143,14 +465,84 @@ struct ExternalTheme {
 }
 
 impl Settings {
+    /// Returns a `gpg` command that performs `action` using the passphrase contained
+    /// in the `PED_PASSPHRASE` environment variable, which is prepended to _stdin_
+    /// since `gpg` reads the passphrase from the first line when `--passphrase-fd 0`
+    /// is given.
+    fn gpg_command(action: &str) -> String {
+        format!(
+            "{{ printf '%s\\n' \"$PED_PASSPHRASE\"; cat; }} | \
+            gpg --batch --quiet --yes --pinentry-mode loopback --passphrase-fd 0 {action}"
+        )
+    }
+
     /// Applies the external settings `ext` on top of `self`.
     fn apply(&mut self, ext: Option<ExternalSettings>) {
         if let Some(ext) = ext {
             self.spotlight = ext.spotlight.unwrap_or(self.spotlight);
             self.lines = ext.lines.unwrap_or(self.lines);
+            self.relative_lines = ext.relative_lines.unwrap_or(self.relative_lines);
             self.eol = ext.eol.unwrap_or(self.eol);
+            self.wrap = ext.wrap.unwrap_or(self.wrap);
+            self.virtual_edit = ext.virtual_edit.unwrap_or(self.virtual_edit);
+            self.auto_indent = ext.auto_indent.unwrap_or(self.auto_indent);
             self.tab_hard = ext.tab_hard.unwrap_or(self.tab_hard);
             self.tab_size = ext.tab_size.unwrap_or(self.tab_size);
+            self.print_command = ext.print_command.unwrap_or(self.print_command.clone());
+            self.print_lines = ext.print_lines.unwrap_or(self.print_lines);
+            self.print_header = ext.print_header.unwrap_or(self.print_header);
+            self.diff_tool = ext.diff_tool.unwrap_or(self.diff_tool.clone());
+            self.middle_paste = ext.middle_paste.unwrap_or(self.middle_paste);
+            self.clipboard = ext.clipboard.unwrap_or(self.clipboard);
+            self.page_overlap = ext.page_overlap.unwrap_or(self.page_overlap);
+            self.cursor_shape = ext.cursor_shape.unwrap_or(self.cursor_shape);
+            self.cursor_blink = ext.cursor_blink.unwrap_or(self.cursor_blink);
+            self.readonly_cursor_shape = ext
+                .readonly_cursor_shape
+                .unwrap_or(self.readonly_cursor_shape);
+            self.dim_inactive = ext.dim_inactive.unwrap_or(self.dim_inactive);
+            self.echo_timeout = ext.echo_timeout.unwrap_or(self.echo_timeout);
+            self.check_interval = ext.check_interval.unwrap_or(self.check_interval);
+            self.title = ext.title.unwrap_or(self.title);
+            self.fill_column = ext.fill_column.unwrap_or(self.fill_column);
+            self.trash_dir = ext.trash_dir.or(self.trash_dir.clone());
+            self.templates = ext.templates.unwrap_or(self.templates);
+            self.sticky_header = ext.sticky_header.unwrap_or(self.sticky_header);
+            self.selection_info = ext.selection_info.unwrap_or(self.selection_info);
+            self.key_timeout = ext.key_timeout.unwrap_or(self.key_timeout);
+            self.undo_memory = ext.undo_memory.unwrap_or(self.undo_memory);
+            self.open_preview = ext.open_preview.unwrap_or(self.open_preview);
+            self.ignore = ext.ignore.unwrap_or(self.ignore.clone());
+            self.quit_ephemeral = ext.quit_ephemeral.unwrap_or(self.quit_ephemeral);
+            self.bom = ext.bom.unwrap_or(self.bom);
+            self.save_session = ext.save_session.unwrap_or(self.save_session);
+            self.audit_log = ext.audit_log.unwrap_or(self.audit_log);
+            self.io_retries = ext.io_retries.unwrap_or(self.io_retries);
+            self.io_backoff = ext.io_backoff.unwrap_or(self.io_backoff);
+            self.io_timeout = ext.io_timeout.unwrap_or(self.io_timeout);
+            self.crypt_extensions = ext
+                .crypt_extensions
+                .unwrap_or(self.crypt_extensions.clone());
+            self.decrypt_command = ext.decrypt_command.unwrap_or(self.decrypt_command.clone());
+            self.encrypt_command = ext.encrypt_command.unwrap_or(self.encrypt_command.clone());
+            self.control_chars = ext.control_chars.unwrap_or(self.control_chars);
+            self.markers = ext.markers.unwrap_or(self.markers.clone());
+            self.highlight_markers = ext.highlight_markers.unwrap_or(self.highlight_markers);
+            self.show_keys = ext.show_keys.unwrap_or(self.show_keys);
+            self.locale = ext.locale.or(self.locale.clone());
+            self.ascii_only = ext.ascii_only.unwrap_or(self.ascii_only);
+            let glyphs = if self.ascii_only {
+                Glyphs::ASCII
+            } else {
+                self.glyphs
+            };
+            self.glyphs = Glyphs {
+                eol: ext.eol_char.unwrap_or(glyphs.eol),
+                tab: ext.tab_char.unwrap_or(glyphs.tab),
+                control: ext.control_char.unwrap_or(glyphs.control),
+                redact: ext.redact_char.unwrap_or(glyphs.redact),
+                truncate: ext.truncate_char.unwrap_or(glyphs.truncate),
+            };
         }
     }
 
@@ -169,9 +561,56 @@ impl Default for Settings {
         Settings {
             spotlight: true,
             lines: true,
+            relative_lines: false,
             eol: false,
+            wrap: true,
+            virtual_edit: false,
+            auto_indent: true,
             tab_hard: false,
             tab_size: 4,
+            print_command: "lpr".to_string(),
+            print_lines: false,
+            print_header: false,
+            diff_tool: "vimdiff".to_string(),
+            middle_paste: true,
+            clipboard: Clipboard::Internal,
+            page_overlap: 0,
+            cursor_shape: CursorShape::Block,
+            cursor_blink: true,
+            readonly_cursor_shape: CursorShape::Underline,
+            dim_inactive: false,
+            echo_timeout: 5,
+            check_interval: 2,
+            title: true,
+            fill_column: 80,
+            trash_dir: None,
+            templates: true,
+            sticky_header: true,
+            selection_info: true,
+            key_timeout: 0,
+            undo_memory: 8,
+            open_preview: false,
+            ignore: vec![".git/".to_string()],
+            quit_ephemeral: QuitEphemeral::Prompt,
+            bom: ByteOrderMark::Preserve,
+            save_session: true,
+            audit_log: false,
+            io_retries: 3,
+            io_backoff: 250,
+            io_timeout: 30,
+            crypt_extensions: Vec::new(),
+            decrypt_command: Self::gpg_command("--decrypt"),
+            encrypt_command: Self::gpg_command("--symmetric"),
+            control_chars: ControlChars::Glyph,
+            markers: vec![
+                r"\b(?:TODO|FIXME|XXX)\b".to_string(),
+                r"^(?:<{7}|\|{7}|={7}|>{7})(?:\s|$)".to_string(),
+            ],
+            highlight_markers: false,
+            show_keys: false,
+            locale: None,
+            ascii_only: false,
+            glyphs: Glyphs::UNICODE,
         }
     }
 }
@@ -181,13 +620,18 @@ impl Theme {
     const TEXT_BG: u8 = 233;
     const SELECT_BG: u8 = 88;
     const SPOTLIGHT_BG: u8 = 234;
+    const HIGHLIGHT_BG: u8 = 58;
+    const BRACKET_BG: u8 = 24;
     const WHITSPACE_FG: u8 = 243;
     const ACCENT_FG: u8 = 180;
     const ECHO_FG: u8 = 208;
+    const WARN_FG: u8 = 220;
+    const ERROR_FG: u8 = 196;
     const PROMPT_FG: u8 = 102;
     const BANNER_FG: u8 = 254;
     const ACTIVE_BG: u8 = 60;
     const INACTIVE_BG: u8 = 237;
+    const INACTIVE_FG: u8 = 245;
     const MARGIN_FG: u8 = 61;
     const MARGIN_BG: u8 = 234;
 
@@ -210,13 +654,18 @@ impl Theme {
             self.text_bg = resolve(self.text_bg, &ext.text_bg, colors)?;
             self.select_bg = resolve(self.select_bg, &ext.select_bg, colors)?;
             self.spotlight_bg = resolve(self.spotlight_bg, &ext.spotlight_bg, colors)?;
+            self.highlight_bg = resolve(self.highlight_bg, &ext.highlight_bg, colors)?;
+            self.bracket_bg = resolve(self.bracket_bg, &ext.bracket_bg, colors)?;
             self.whitespace_fg = resolve(self.whitespace_fg, &ext.whitespace_fg, colors)?;
             self.accent_fg = resolve(self.accent_fg, &ext.accent_fg, colors)?;
             self.echo_fg = resolve(self.echo_fg, &ext.echo_fg, colors)?;
+            self.warn_fg = resolve(self.warn_fg, &ext.warn_fg, colors)?;
+            self.error_fg = resolve(self.error_fg, &ext.error_fg, colors)?;
             self.prompt_fg = resolve(self.prompt_fg, &ext.prompt_fg, colors)?;
             self.banner_fg = resolve(self.banner_fg, &ext.banner_fg, colors)?;
             self.active_bg = resolve(self.active_bg, &ext.active_bg, colors)?;
             self.inactive_bg = resolve(self.inactive_bg, &ext.inactive_bg, colors)?;
+            self.inactive_fg = resolve(self.inactive_fg, &ext.inactive_fg, colors)?;
             self.margin_fg = resolve(self.margin_fg, &ext.margin_fg, colors)?;
             self.margin_bg = resolve(self.margin_bg, &ext.margin_bg, colors)?;
         }
@@ -231,13 +680,18 @@ impl Default for Theme {
             text_bg: Self::TEXT_BG,
             select_bg: Self::SELECT_BG,
             spotlight_bg: Self::SPOTLIGHT_BG,
+            highlight_bg: Self::HIGHLIGHT_BG,
+            bracket_bg: Self::BRACKET_BG,
             whitespace_fg: Self::WHITSPACE_FG,
             accent_fg: Self::ACCENT_FG,
             echo_fg: Self::ECHO_FG,
+            warn_fg: Self::WARN_FG,
+            error_fg: Self::ERROR_FG,
             prompt_fg: Self::PROMPT_FG,
             banner_fg: Self::BANNER_FG,
             active_bg: Self::ACTIVE_BG,
             inactive_bg: Self::INACTIVE_BG,
+            inactive_fg: Self::INACTIVE_FG,
             margin_fg: Self::MARGIN_FG,
             margin_bg: Self::MARGIN_BG,
         }
@@ -294,6 +748,26 @@ impl Configuration {
                 self.bindings.bind(&key_seq, &op)?;
             }
         }
+        if let Some(context_bindings) = ext.context_bindings {
+            for (context, bindings) in context_bindings {
+                for (key_seq, op) in bindings {
+                    self.bindings.bind_scoped(&context, &key_seq, &op)?;
+                }
+            }
+        }
+        if let Some(syntax_colors) = ext.syntax_colors {
+            for (name, tokens) in syntax_colors {
+                let mut colors = Vec::new();
+                for (token_ref, color) in tokens {
+                    let color = self
+                        .colors
+                        .lookup_value(&color)
+                        .ok_or_else(|| Error::invalid_color(&color.to_string()))?;
+                    colors.push((token_ref, color));
+                }
+                self.syntax_colors.insert(name, colors);
+            }
+        }
         Ok(())
     }
 
@@ -311,7 +785,7 @@ impl Configuration {
         Bindings::new(&bindings).unwrap_or_else(|e| panic!("{e}: default bindings failed"))
     }
 
-    const DEFAULT_BINDINGS: [(&'static str, &'static str); 92] = [
+    const DEFAULT_BINDINGS: [(&'static str, &'static str); 170] = [
         // --- exit and cancellation ---
         ("C-q", "quit"),
         // --- help ---
@@ -319,7 +793,16 @@ impl Configuration {
         ("M-h:k", "help-keys"),
         ("M-h:o", "help-ops"),
         ("M-h:b", "help-bindings"),
+        ("M-h:a", "audit-bindings"),
+        ("M-h:e", "export-bindings"),
         ("M-h:c", "help-colors"),
+        ("M-h:t", "preview-theme"),
+        ("M-h:y", "debug-syntax"),
+        ("M-h:m", "memory-report"),
+        ("M-h:p", "perf-stats"),
+        ("M-h:l", "show-audit"),
+        ("M-h:d", "describe-key"),
+        ("M-h:s", "search-help"),
         // --- navigation and selection ---
         ("C-b", "move-backward"),
         ("left", "move-backward"),
@@ -338,6 +821,8 @@ impl Configuration {
         ("S-pg_up", "move-up-page-select"),
         ("M-n", "move-down-page"),
         ("pg_down", "move-down-page"),
+        ("M-C-p", "move-up-half-page"),
+        ("M-C-n", "move-down-half-page"),
         ("S-pg_down", "move-down-page-select"),
         ("C-a", "move-start"),
         ("home", "move-start"),
@@ -365,34 +850,75 @@ impl Configuration {
         ("S-C-down", "scroll-down-select"),
         ("C-l", "scroll-center"),
         ("C-@", "set-mark"),
+        ("M-C-@", "set-mark-column"),
         ("C-_", "goto-line"),
+        ("M-C-_", "goto-column"),
+        ("M-C-]", "match-bracket"),
         // --- insertion and removal ---
         ("ret", "insert-line"),
         ("tab", "insert-tab"),
         ("C-d", "remove-after"),
         ("del", "remove-before"),
+        ("M-del", "remove-backward-word"),
+        ("M-d", "remove-forward-word"),
         ("C-j", "remove-start"),
         ("C-k", "remove-end"),
+        ("M-l:d", "duplicate-line"),
+        ("M-l:p", "move-line-up"),
+        ("M-l:n", "move-line-down"),
+        ("M-l:j", "join-lines"),
+        ("M-l:s", "sort-lines"),
+        ("M-l:u", "upcase"),
+        ("M-l:l", "downcase"),
         ("C-u", "undo"),
         ("C-r", "redo"),
+        ("M-q", "hard-wrap-region"),
+        ("M-Q", "unwrap-region"),
+        ("M-C-q", "reflow-paragraph"),
+        ("M-i:i", "reindent-region"),
+        ("M-i:c", "toggle-comment"),
+        ("M-i:t", "insert-template-for-type"),
+        ("M-i:p", "expand-pair"),
         // --- selection actions ---
         ("C-c", "copy"),
         ("C-v", "paste"),
+        ("M-v", "paste-adjust-indent"),
+        ("M-C-v", "paste-block"),
         ("C-x", "cut"),
+        ("M-C-c", "copy-append"),
+        ("M-C-x", "cut-append"),
         // --- search next ---
         ("C-\\", "search"),
         ("M-C-\\", "search-case"),
         ("M-\\", "search-regex"),
         ("M-M-\\", "search-regex-case"),
         ("C-]", "search-next"),
+        ("M-]", "search-prev"),
+        ("M-c:p", "add-cursor-up"),
+        ("M-c:n", "add-cursor-down"),
+        ("M-c:m", "add-cursor-at-next-match"),
+        ("M-c:c", "clear-cursors"),
+        ("M-c:s", "sequence-insert"),
+        ("M-r", "replace"),
+        ("M-M-r", "replace-regex"),
+        ("M-R", "replace-all"),
+        ("M-/", "occur"),
+        ("M-M-/", "search-all"),
+        ("M-m:n", "next-marker"),
+        ("M-m:p", "prev-marker"),
         // --- file handling ---
         ("C-o", "open-file"),
         ("M-o:a", "open-file-top"),
         ("M-o:e", "open-file-bottom"),
         ("M-o:p", "open-file-above"),
         ("M-o:n", "open-file-below"),
+        ("M-o:b", "open-file-left"),
+        ("M-o:f", "open-file-right"),
         ("C-s", "save-file"),
         ("M-s", "save-file-as"),
+        ("M-o:d", "delete-file"),
+        ("M-o:u", "undelete-file"),
+        ("M-o:r", "rename-file"),
         // --- editor handling ---
         ("C-y", "select-editor"),
         ("M-y:a", "select-editor-top"),
@@ -401,6 +927,9 @@ impl Configuration {
         ("M-y:n", "select-editor-below"),
         ("M-,", "prev-editor"),
         ("M-.", "next-editor"),
+        ("M-y:d", "next-dirty-editor"),
+        ("M-^", "switch-last-editor"),
+        ("M-;", "command-palette"),
         // --- window handling ---
         ("C-w", "kill-window"),
         ("M-w:0", "close-window"),
@@ -411,9 +940,33 @@ impl Configuration {
         ("M-<", "prev-window"),
         ("M-w:n", "next-window"),
         ("M->", "next-window"),
+        ("M-w:r", "toggle-ruler"),
+        ("M-w:+", "grow-window"),
+        ("M-w:-", "shrink-window"),
+        ("M-w:=", "balance-windows"),
+        ("M-w:k", "scroll-lock"),
+        ("M-w:s", "save-layout"),
+        ("M-w:l", "restore-layout"),
+        ("M-w:w", "session-save"),
+        ("M-w:o", "session-load"),
         // --- behaviors ---
         ("C-t", "describe-editor"),
         ("M-t:t", "tab-mode"),
+        ("M-t:l", "set-local"),
+        ("M-t:o", "toggle-readonly"),
+        ("M-t:k", "lock-editing"),
+        ("M-t:r", "redact-mode"),
+        ("M-t:w", "wrap-mode"),
+        ("M-t:v", "virtual-mode"),
+        ("M-t:i", "indent-mode"),
+        ("M-t:b", "bom-mode"),
+        ("M-t:c", "describe-char"),
+        ("M-t:u", "inspect-char"),
+        // --- external commands ---
+        ("M-x:x", "run-command"),
+        ("M-x:r", "rerun-last-command"),
+        ("M-x:p", "print-buffer"),
+        ("M-x:d", "difftool"),
     ];
 }
 
@@ -425,6 +978,7 @@ impl Default for Configuration {
             theme: Theme::default(),
             bindings: Self::init_bindings(),
             registry: Registry::default(),
+            syntax_colors: TokenColors::new(),
         }
     }
 }
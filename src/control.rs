@@ -7,20 +7,27 @@
 //!
 //! The controller is essentially a loop that runs until a _quit_ directive is given.
 
-use crate::config::ConfigurationRef;
-use crate::echo::Echo;
+use crate::audit::Audit;
+use crate::bind::Context;
+use crate::config::{ConfigurationRef, CursorShape};
+use crate::crypt;
+use crate::echo::{Echo, Severity};
 use crate::editor::{Align, ImmutableEditor};
-use crate::env::{Environment, Focus};
+use crate::env::{Environment, Focus, Usage};
 use crate::error::Result;
 use crate::etc::{PACKAGE_NAME, PACKAGE_VERSION};
 use crate::input::{Directive, InputEditor};
+use crate::io::FileSpec;
 use crate::key::{self, Key, Keyboard, Shift, CTRL_G};
-use crate::op::{self, Action};
+use crate::keyline::KeyLine;
+use crate::op::{self, Action, OpFn, ScriptOp};
+use crate::session::Session;
 use crate::size::Point;
 use crate::sys::{self, AsString};
 use crate::term;
-use crate::user::Inquirer;
+use crate::user::{Inquirer, Intercept};
 use crate::workspace::{Placement, Workspace};
+use crate::writer::Writer;
 use std::time::Instant;
 
 /// The primary control point for coordinating user interaction and editing operations.
@@ -37,6 +44,10 @@ pub struct Controller {
     /// A sequence of keys resulting from continuations.
     key_seq: Vec<Key>,
 
+    /// An optional time of the last key appended to a pending key sequence or `None`
+    /// if no key sequence is pending.
+    last_key: Option<Instant>,
+
     /// A means of echoing arbitrary text.
     echo: Echo,
 
@@ -46,11 +57,39 @@ pub struct Controller {
     /// A means of soliciting input.
     input: InputEditor,
 
+    /// A means of showing keys and operations, which is only visible if the
+    /// workspace allocates a key region.
+    keyline: KeyLine,
+
     /// An optional question solicited by an editing function or `None` otherwise.
     question: Option<Box<dyn Inquirer>>,
 
     /// An optional time capturing the last terminal size change event.
     term_changed: Option<Instant>,
+
+    /// The cursor shape last sent to the terminal or `None` if not yet sent.
+    cursor_shape: Option<CursorShape>,
+
+    /// The terminal title last sent to the terminal or `None` if not yet sent.
+    title: Option<String>,
+
+    /// The time of the last maintenance pass that releases nonessential memory.
+    last_compact: Instant,
+
+    /// The time of the last key read from the keyboard, which determines whether the
+    /// user is idle.
+    last_input: Instant,
+
+    /// The id of the editor last observed in the active window or `None` if not yet
+    /// observed.
+    editor_id: Option<u32>,
+
+    /// The time of the last check for files changed in storage, or `None` if a check
+    /// is due at the next opportunity, such as when focus moves to another editor.
+    last_check: Option<Instant>,
+
+    /// An audit trail of operations, which records nothing unless enabled.
+    audit: Audit,
 }
 
 enum Step {
@@ -63,74 +102,296 @@ impl Controller {
     /// change.
     const TERM_CHANGE_DELAY: u128 = 100;
 
+    /// Minimum number of milliseconds between maintenance passes.
+    const COMPACT_INTERVAL: u128 = 60_000;
+
+    /// Number of milliseconds without keyboard input after which the user is
+    /// considered idle, allowing maintenance passes to run.
+    const IDLE_DELAY: u128 = 5_000;
+
+    /// Name under which the execution time of inserting characters is recorded,
+    /// since insertion bypasses the binding of keys to operations.
+    const INSERT_CHAR_OP: &str = "insert-char";
+
     pub fn new(keyboard: Keyboard, workspace: Workspace) -> Controller {
         let config = workspace.config().clone();
         let workspace = workspace.to_ref();
         let env = Environment::new(workspace.clone());
         let echo = Echo::new(workspace.clone());
         let input = InputEditor::new(workspace.clone());
+        let keyline = KeyLine::new(workspace.clone());
 
         Controller {
             config,
             keyboard,
             env,
             key_seq: Vec::new(),
+            last_key: None,
             echo,
             last_echo: None,
             input,
+            keyline,
             question: None,
             term_changed: None,
+            cursor_shape: None,
+            title: None,
+            last_compact: Instant::now(),
+            last_input: Instant::now(),
+            editor_id: None,
+            last_check: None,
+            audit: Audit::new(),
         }
     }
 
     /// Opens the collection of `files`, placing each successive editor at the bottom
-    /// of the workspace.
-    pub fn open(&mut self, files: &Vec<String>) -> Result<()> {
+    /// of the workspace, and moving the cursor to the line and column if given.
+    ///
+    /// Editors are still opened when the workspace is unable to accommodate more
+    /// windows, though such editors remain unattached and the line and column are
+    /// ignored.
+    ///
+    /// Encrypted files are deferred until a passphrase is solicited for each, after
+    /// which they are opened at the bottom of the workspace without regard to the line
+    /// number.
+    pub fn open(&mut self, files: &[FileSpec]) -> Result<()> {
         let view_id = self.env.get_active_view_id();
-        for (i, path) in files.iter().enumerate() {
+        let mut encrypted = Vec::new();
+        let mut first = true;
+        for (path, line, col) in files.iter() {
             let path = sys::canonicalize(sys::working_dir().join(path)).as_string();
+            if crypt::is_encrypted(&path, &self.config.settings) {
+                encrypted.push(path);
+                continue;
+            }
             let editor = op::open_editor(self.config.clone(), &path)?;
-            if i == 0 {
-                self.env.set_editor(editor, Align::Auto);
+            let attached = if first {
+                self.env.set_editor(editor.clone(), Align::Auto);
+                first = false;
+                true
+            } else if self
+                .env
+                .open_editor(editor.clone(), Placement::Bottom, Align::Auto)
+                .is_some()
+            {
+                true
             } else {
-                self.env.open_editor(editor, Placement::Bottom, Align::Auto);
+                self.env.add_editor(editor.clone());
+                false
+            };
+            if let (true, Some(line)) = (attached, line) {
+                let line = line.saturating_sub(1);
+                let mut editor = editor.borrow_mut();
+                if let Some(col) = col {
+                    editor.move_line_col(line, col.saturating_sub(1), Align::Center);
+                } else {
+                    editor.move_line(line, Align::Center);
+                }
             }
         }
         self.env.set_active(Focus::To(view_id));
+        if !encrypted.is_empty() {
+            self.set_question(op::decrypt_question(encrypted));
+        }
+        Ok(())
+    }
+
+    /// Runs each operation in `script`, which is expected to follow
+    /// [`open`](Self::open), returning `true` if an operation requested to quit.
+    ///
+    /// An operation that poses a question is given its answer, if any, as though
+    /// typed by the user. Otherwise, the question is left for the user to answer,
+    /// and the remainder of the script is abandoned, which is also the case when a
+    /// question is already pending or the answer is not accepted.
+    pub fn exec(&mut self, script: &[ScriptOp]) -> bool {
+        for ScriptOp { op, op_fn, answer } in script {
+            if self.question.is_some() {
+                break;
+            }
+            let action = match (op_fn(&mut self.env), answer) {
+                (Some(Action::Question(mut inquirer)), Some(answer)) => {
+                    let _ = inquirer.react(&mut self.env, answer, &Key::None);
+                    match inquirer.completer().accept(answer) {
+                        Some(value) => inquirer.respond(&mut self.env, Some(&value)),
+                        None => {
+                            let _ = inquirer.respond(&mut self.env, None);
+                            let text = format!("{op}: answer not accepted: {answer}");
+                            self.set_echo(&text, Severity::Warn);
+                            break;
+                        }
+                    }
+                }
+                (action, _) => action,
+            };
+            match action {
+                Some(Action::Quit) => return true,
+                Some(Action::Echo(text, severity)) => {
+                    self.set_echo(text.as_str(), severity);
+                }
+                Some(Action::Question(inquirer)) => {
+                    self.clear_echo();
+                    self.set_question(inquirer);
+                }
+                None => (),
+            }
+        }
+        false
+    }
+
+    /// Restores the session saved when last quitting, if any, which is expected to
+    /// precede [`open`](Self::open).
+    pub fn restore(&mut self) -> Result<()> {
+        if let Some(session) = Session::load()? {
+            op::restore_session(&mut self.env, &session)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the current session if enabled, though only if at least one file is open
+    /// so that quitting an empty editor does not discard a prior session, which is
+    /// meaningful after the main processing loop returns.
+    pub fn save_session(&self) -> Result<()> {
+        if self.config.settings.save_session {
+            let session = op::capture_session(&self.env);
+            if !session.editors.is_empty() {
+                session.save()?;
+            }
+        }
         Ok(())
     }
 
+    /// Returns `true` if any editor contains unsaved changes, which is meaningful
+    /// after the main processing loop returns.
+    pub fn is_dirty(&self) -> bool {
+        op::has_dirty_editors(&self.env)
+    }
+
     /// Runs the main processing loop.
     ///
     /// This loop orchestrates the entire editing experience, reading sequences of
     /// [keys](Key) and calling their corresponding editing functions until instructed to
     /// quit.
     pub fn run(&mut self) {
-        self.set_echo(&format!(
-            "{PACKAGE_NAME} {PACKAGE_VERSION} | type C-h for help, C-q to quit"
-        ));
+        // Greeting is withheld if a question was posed while opening files, such as
+        // soliciting the passphrase of an encrypted file, or if a script echoed a
+        // message or posed a question.
+        if self.question.is_some() {
+            self.input.show_cursor();
+        } else if self.last_echo.is_none() {
+            self.set_echo(
+                &format!("{PACKAGE_NAME} {PACKAGE_VERSION} | type C-h for help, C-q to quit"),
+                Severity::Info,
+            );
+        }
+        if self.config.settings.audit_log {
+            match Audit::open() {
+                Ok(audit) => self.audit = audit,
+                Err(e) => self.set_echo(e.to_string().as_str(), Severity::Error),
+            }
+        }
+        self.keyline.draw();
         self.show_cursor();
+        self.update_title();
+        self.track_editor();
         loop {
             let key = self.keyboard.read().unwrap_or(Key::None);
             if key == Key::None {
-                self.process_background();
+                if let Step::Quit = self.process_background() {
+                    break;
+                }
             } else {
+                let frame = Instant::now();
+                self.last_input = frame;
                 if let Step::Quit = self.process_key(key) {
                     break;
                 } else {
+                    self.warn_protected();
                     self.show_cursor();
+                    self.update_title();
+                    self.track_editor();
                 }
+                self.env.perf_mut().record_frame(frame.elapsed());
             }
         }
     }
 
+    /// Records a use of the editor in the active window if it changed since last
+    /// observed, which also makes a check for changed files due.
+    fn track_editor(&mut self) {
+        let editor_id = self.env.get_active_editor_id();
+        if self.editor_id != Some(editor_id) {
+            self.editor_id = Some(editor_id);
+            self.env.touch(Usage::Editor(editor_id));
+            self.last_check = None;
+        }
+    }
+
+    /// Warns the user upon the first modification of the editor in the active window
+    /// if its file was not writable when opened.
+    fn warn_protected(&mut self) {
+        let protected = self.env.get_active_editor().borrow_mut().take_protected();
+        if protected {
+            self.set_echo(
+                "file is not writable: changes may not be saved",
+                Severity::Warn,
+            );
+        }
+    }
+
     fn show_cursor(&mut self) {
         if self.question.is_none() {
-            self.env.get_active_editor().borrow_mut().show_cursor();
+            let editor = self.env.get_active_editor().clone();
+            let mut editor = editor.borrow_mut();
+            self.set_cursor_shape(editor.is_readonly());
+            editor.show_cursor();
+        }
+    }
+
+    /// Sets the terminal title to the label of the active editor, including a marker
+    /// if the editor is dirty and the number of dirty editors, though only if it
+    /// differs from the current title.
+    fn update_title(&mut self) {
+        if self.config.settings.title {
+            let title = {
+                let editor = self.env.get_active_editor().borrow();
+                let dirty = match op::dirty_count(&self.env) {
+                    0 => String::new(),
+                    n => format!(" ({n} unsaved)"),
+                };
+                format!(
+                    "{}{} - {PACKAGE_NAME}{dirty}",
+                    editor.label(),
+                    if editor.is_dirty() { "*" } else { "" }
+                )
+            };
+            if self.title.as_ref() != Some(&title) {
+                Writer::new(Point::ORIGIN).set_title(&title).send();
+                self.title = Some(title);
+            }
+        }
+    }
+
+    /// Changes the shape of the cursor depending on whether it will be positioned in
+    /// a _readonly_ editor, though only if it differs from the current shape.
+    fn set_cursor_shape(&mut self, readonly: bool) {
+        let settings = &self.config.settings;
+        let shape = if readonly {
+            settings.readonly_cursor_shape
+        } else {
+            settings.cursor_shape
+        };
+        if self.cursor_shape != Some(shape) {
+            Writer::new(Point::ORIGIN)
+                .set_cursor_shape(shape, settings.cursor_blink)
+                .send();
+            self.cursor_shape = Some(shape);
         }
     }
 
     fn process_key(&mut self, key: Key) -> Step {
+        // Keys typed in response to masked questions are never shown.
+        if !self.question.as_ref().is_some_and(|q| q.masked()) {
+            self.keyline.push_key(&key);
+        }
         if self.question.is_some() {
             self.process_question(key)
         } else {
@@ -139,18 +400,37 @@ impl Controller {
     }
 
     fn process_normal(&mut self, key: Key) -> Step {
-        if let Some(c) = self.possible_char(&key) {
+        if let Some((op, op_fn)) = self
+            .possible_char(&key)
+            .and_then(|c| op::readonly_op(&self.env, c))
+        {
+            // Characters cannot be inserted into readonly editors, so some are
+            // repurposed as quick access to searching.
+            self.keyline.set_op(op);
+            self.env.touch(Usage::Op(op.to_string()));
+            return self.run_op(op_fn, Some(op.to_string()));
+        } else if let Some(c) = self.possible_char(&key) {
             // Inserting text is statistically most prevalent scenario, so this short
             // circuits detection and bypasses normal indirection of key binding.
-            match op::insert_char(&mut self.env, c) {
-                Some(Action::Echo(text)) => self.set_echo(text.as_str()),
+            let audited = self.audit_op(Self::INSERT_CHAR_OP);
+            let time = Instant::now();
+            let action = op::insert_char(&mut self.env, c);
+            self.env
+                .perf_mut()
+                .record_op(Self::INSERT_CHAR_OP, time.elapsed());
+            match action {
+                Some(Action::Echo(text, severity)) => self.set_echo(text.as_str(), severity),
                 _ => self.clear_echo(),
             }
+            if let Err(e) = audited {
+                self.set_echo(e.to_string().as_str(), Severity::Error);
+            }
         } else if key == CTRL_G {
             self.clear_echo();
             if !self.clear_keys() {
                 let mut editor = self.env.get_active_editor().borrow_mut();
-                if let Some(_) = editor.clear_mark() {
+                let cursors = editor.clear_cursors();
+                if editor.clear_mark().is_some() || cursors > 0 {
                     editor.render();
                 }
             }
@@ -164,28 +444,27 @@ impl Controller {
             op::track_forward(&mut self.env, Point::new(row, col), shift == Shift::On);
         } else if let Key::ButtonPress(row, col) = key {
             op::set_focus(&mut self.env, Point::new(row, col));
+        } else if let Key::MiddlePress(row, col) = key {
+            if self.config.settings.middle_paste {
+                match op::paste_at(&mut self.env, Point::new(row, col)) {
+                    Some(Action::Echo(text, severity)) => self.set_echo(text.as_str(), severity),
+                    _ => self.clear_echo(),
+                }
+            }
         } else if let Key::ButtonRelease(_, _) = key {
             // Absorb since this event serve no purpose at this time.
         } else {
             self.key_seq.push(key.clone());
-            if let Some(op_fn) = self.config.bindings.find(&self.key_seq) {
-                match op_fn(&mut self.env) {
-                    Some(Action::Quit) => return Step::Quit,
-                    Some(Action::Echo(text)) => {
-                        self.set_echo(text.as_str());
-                    }
-                    Some(Action::Question(inquirer)) => {
-                        self.clear_echo();
-                        self.set_question(inquirer);
-                    }
-                    None => {
-                        self.clear_echo();
-                    }
-                }
+            let context = self.context();
+            if let Some(op_fn) = self.config.bindings.find(&self.key_seq, &context).copied() {
+                let op = self.touch_op(&self.key_seq.clone(), &context);
                 self.clear_keys();
-            } else if self.config.bindings.is_prefix(&self.key_seq) {
+                return self.run_op(op_fn, op);
+            } else if self.config.bindings.is_prefix(&self.key_seq, &context) {
                 // Current keys form a prefix of at least one sequence bound to an
                 // editing function.
+                self.last_key = Some(Instant::now());
+                self.keyline.set_pending(&self.key_seq);
                 self.show_keys();
             } else {
                 // Current keys are not bound to an editing function, nor do they
@@ -198,38 +477,60 @@ impl Controller {
     }
 
     fn process_question(&mut self, key: Key) -> Step {
+        // Keys bound within the question scope take precedence over the question
+        // itself, which remains in place unless replaced by the operation.
+        let context = self.context();
+        let keys = vec![key.clone()];
+        if let Some(op_fn) = self.config.bindings.find(&keys, &context).copied() {
+            let op = self.touch_op(&keys, &context);
+            return self.run_op(op_fn, op);
+        }
+
         let inquirer = self.question.as_mut().unwrap();
         let action = if key == CTRL_G {
             let action = inquirer.respond(&mut self.env, None);
             self.clear_question();
             action
         } else {
-            match self.input.process_key(&key) {
-                Directive::Continue => {
-                    let value = self.input.value();
-                    if let Some(hint) = inquirer.react(&mut self.env, &value, &key) {
+            match inquirer.intercept(&mut self.env, &key) {
+                Intercept::Pass => match self.input.process_key(&key) {
+                    Directive::Continue => {
+                        let value = self.input.value();
+                        if let Some(hint) = inquirer.react(&mut self.env, &value, &key) {
+                            self.input.set_hint(hint);
+                        }
+                        self.input.show_cursor();
+                        None
+                    }
+                    Directive::Ignore => None,
+                    Directive::Accept => {
+                        let value = self.input.value();
+                        let action = inquirer.respond(&mut self.env, Some(&value));
+                        self.clear_question();
+                        action
+                    }
+                    Directive::Cancel => {
+                        self.clear_question();
+                        None
+                    }
+                },
+                Intercept::Consume(hint) => {
+                    if let Some(hint) = hint {
                         self.input.set_hint(hint);
                     }
                     self.input.show_cursor();
                     None
                 }
-                Directive::Ignore => None,
-                Directive::Accept => {
-                    let value = self.input.value();
-                    let action = inquirer.respond(&mut self.env, Some(&value));
+                Intercept::Conclude(action) => {
                     self.clear_question();
                     action
                 }
-                Directive::Cancel => {
-                    self.clear_question();
-                    None
-                }
             }
         };
         match action {
             Some(Action::Quit) => return Step::Quit,
-            Some(Action::Echo(text)) => {
-                self.set_echo(text.as_str());
+            Some(Action::Echo(text, severity)) => {
+                self.set_echo(text.as_str(), severity);
             }
             Some(Action::Question(inquirer)) => {
                 self.clear_echo();
@@ -253,12 +554,47 @@ impl Controller {
                 self.env.resize();
                 self.resize_echo();
                 self.resize_question();
+                self.keyline.resize();
                 None
             } else {
                 // Keep waiting.
                 Some(time)
             }
         } else {
+            // Output from a running job may be rendered in a window other than the
+            // active one, so cursor must be restored to where it belongs.
+            // Loading of large files and printing continue in the same manner.
+            let loaded = match op::poll_load(&mut self.env) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    self.set_echo(e.to_string().as_str(), Severity::Error);
+                    false
+                }
+            };
+            let printed = match op::poll_print(&mut self.env) {
+                Some(Ok(message)) => {
+                    self.set_echo(&message, Severity::Info);
+                    true
+                }
+                Some(Err(e)) => {
+                    self.set_echo(e.to_string().as_str(), Severity::Error);
+                    true
+                }
+                None => false,
+            };
+            if op::poll_job(&mut self.env) || loaded || printed {
+                if self.question.is_some() {
+                    self.input.show_cursor();
+                } else {
+                    self.show_cursor();
+                }
+            }
+            if self.expire_keys() {
+                return Step::Quit;
+            }
+            self.expire_echo();
+            self.compact();
+            self.check_files();
             let mut editor = self.env.get_active_editor().borrow_mut();
             if editor.tokenize() {
                 editor.render();
@@ -283,15 +619,126 @@ impl Controller {
         }
     }
 
+    /// Returns a snapshot of the environment against which key bindings are resolved.
+    fn context(&self) -> Context {
+        Context {
+            question: self.question.is_some(),
+            ..self.env.context()
+        }
+    }
+
+    /// Appends `op` to the audit trail along with the source of the editor in the
+    /// active window, which is the editor affected by most operations.
+    ///
+    /// An error is returned only once, since recording stops after the first
+    /// failure.
+    fn audit_op(&mut self, op: &str) -> Result<()> {
+        if self.audit.is_open() {
+            let source = self.env.get_active_editor().borrow().source().to_string();
+            self.audit.record(op, &source)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Records a use of the operation bound to `keys`, which is also shown as the last
+    /// operation run, and returns its name.
+    fn touch_op(&mut self, keys: &Vec<Key>, context: &Context) -> Option<String> {
+        let op = self.config.bindings.find_op(keys, context)?.to_string();
+        self.keyline.set_op(&op);
+        self.env.touch(Usage::Op(op.clone()));
+        Some(op)
+    }
+
+    /// Calls the editing function `op_fn` and handles the resulting action, where the
+    /// execution time is recorded under the name `op` if given.
+    fn run_op(&mut self, op_fn: OpFn, op: Option<String>) -> Step {
+        let audited = op.as_ref().map_or(Ok(()), |op| self.audit_op(op));
+        let time = Instant::now();
+        let action = op_fn(&mut self.env);
+        if let Some(op) = op {
+            self.env.perf_mut().record_op(&op, time.elapsed());
+        }
+        match action {
+            Some(Action::Quit) => return Step::Quit,
+            Some(Action::Echo(text, severity)) => {
+                self.set_echo(text.as_str(), severity);
+            }
+            Some(Action::Question(inquirer)) => {
+                self.clear_echo();
+                self.set_question(inquirer);
+            }
+            None => {
+                self.clear_echo();
+            }
+        }
+        if let Err(e) = audited {
+            self.set_echo(e.to_string().as_str(), Severity::Error);
+        }
+        Step::Continue
+    }
+
     fn clear_keys(&mut self) -> bool {
         let cleared = self.key_seq.len() > 0;
         self.key_seq.clear();
+        self.last_key = None;
+        if cleared {
+            self.keyline.set_pending(&[]);
+        }
         cleared
     }
 
+    /// Abandons a pending key sequence if no key was typed within the configured
+    /// timeout, in which case each key is processed individually as though the
+    /// sequence was never started, returning `true` if the editor should quit.
+    ///
+    /// Keys bound to editing functions call those functions, characters are inserted,
+    /// and all other keys are ignored.
+    fn expire_keys(&mut self) -> bool {
+        let timeout = self.config.settings.key_timeout as u128;
+        if let Some(time) = self.last_key {
+            if timeout > 0 && time.elapsed().as_millis() >= timeout {
+                let keys = self.key_seq.clone();
+                self.clear_keys();
+                self.clear_echo();
+                for key in keys {
+                    let context = self.context();
+                    if let Some(op_fn) = self
+                        .config
+                        .bindings
+                        .find(&vec![key.clone()], &context)
+                        .copied()
+                    {
+                        let op = self.touch_op(&vec![key.clone()], &context);
+                        if let Step::Quit = self.run_op(op_fn, op) {
+                            return true;
+                        }
+                        if self.question.is_some() {
+                            break;
+                        }
+                    } else if let Key::Char(c) = key {
+                        if let Err(e) = self.audit_op(Self::INSERT_CHAR_OP) {
+                            self.set_echo(e.to_string().as_str(), Severity::Error);
+                        }
+                        if let Some(Action::Echo(text, severity)) =
+                            op::insert_char(&mut self.env, c)
+                        {
+                            self.set_echo(text.as_str(), severity);
+                        }
+                    }
+                }
+                if self.question.is_none() {
+                    self.show_cursor();
+                }
+                self.update_title();
+            }
+        }
+        false
+    }
+
     fn show_keys(&mut self) {
         let text = key::pretty(&self.key_seq);
-        self.set_echo(text.as_str());
+        self.set_echo(text.as_str(), Severity::Info);
     }
 
     fn show_undefined_keys(&mut self) {
@@ -305,33 +752,94 @@ impl Controller {
                 "key sequence"
             }
         );
-        self.set_echo(text.as_str());
+        self.set_echo(text.as_str(), Severity::Warn);
     }
 
-    fn set_echo(&mut self, text: &str) {
-        self.echo.set(text);
+    /// Sets the echo to `text` with the given `severity`, which temporarily expands the
+    /// shared region of the workspace if `text` needs more than a single row.
+    fn set_echo(&mut self, text: &str, severity: Severity) {
+        let rows = self.echo.rows_for(text);
+        self.env.set_shared_rows(rows);
+        self.echo.set(text, severity);
         self.last_echo = Some(Instant::now());
     }
 
+    /// Clears the echo, which also collapses the shared region of the workspace if
+    /// previously expanded.
     fn clear_echo(&mut self) {
         if let Some(_) = self.last_echo.take() {
             self.echo.clear();
+            self.env.set_shared_rows(1);
+        }
+    }
+
+    /// Releases nonessential memory held by the environment once the user is idle,
+    /// though only if the maintenance interval has elapsed since the last pass and
+    /// keys were read since then, as nothing worth releasing accumulates otherwise.
+    fn compact(&mut self) {
+        if self.last_input.elapsed().as_millis() >= Self::IDLE_DELAY
+            && self.last_compact.elapsed().as_millis() >= Self::COMPACT_INTERVAL
+            && self.last_compact < self.last_input
+        {
+            self.env.compact();
+            self.last_compact = Instant::now();
+        }
+    }
+
+    /// Checks for files changed in storage by other programs, though only if the
+    /// configured interval has elapsed since the last check, or the check is due
+    /// because focus moved to another editor.
+    ///
+    /// Checking is deferred while a question or key sequence is pending so that a
+    /// question asking to reload the active editor does not interrupt the user.
+    fn check_files(&mut self) {
+        let interval = self.config.settings.check_interval as u64;
+        if interval == 0 || self.question.is_some() || !self.key_seq.is_empty() {
+            return;
+        }
+        let (due, focused) = match self.last_check {
+            Some(time) => (time.elapsed().as_secs() >= interval, false),
+            None => (true, true),
+        };
+        if due {
+            self.last_check = Some(Instant::now());
+            if let Some(Action::Question(inquirer)) = op::poll_stale(&mut self.env, focused) {
+                self.clear_echo();
+                self.set_question(inquirer);
+            }
+        }
+    }
+
+    /// Clears the echo if displayed longer than the configured timeout, though not
+    /// while a key sequence is pending since the echo shows the keys typed thus far.
+    fn expire_echo(&mut self) {
+        let timeout = self.config.settings.echo_timeout as u64;
+        if let Some(time) = self.last_echo {
+            if timeout > 0 && self.key_seq.is_empty() && time.elapsed().as_secs() >= timeout {
+                self.clear_echo();
+                self.show_cursor();
+            }
         }
     }
 
     fn resize_echo(&mut self) {
         if let Some(_) = self.last_echo {
+            let rows = self.echo.rows();
+            self.env.set_shared_rows(rows);
             self.echo.resize();
         }
     }
 
     fn set_question(&mut self, inquirer: Box<dyn Inquirer>) {
-        self.input.enable(&inquirer.prompt(), inquirer.completer());
+        self.set_cursor_shape(false);
+        self.input
+            .enable(&inquirer.prompt(), inquirer.completer(), inquirer.masked());
         self.question = Some(inquirer);
     }
 
     fn clear_question(&mut self) {
         if let Some(_) = self.question.take() {
+            self.env.set_preview(None);
             self.input.disable();
         }
     }
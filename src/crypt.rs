@@ -0,0 +1,83 @@
+//! Editing of encrypted files by piping content through external commands.
+//!
+//! A file is considered _encrypted_ if its extension matches one of the extensions in
+//! the `crypt-extensions` setting. The content of such a file is piped through the
+//! `decrypt-command` when opened, and the content of the buffer is piped through the
+//! `encrypt-command` when saved, so plaintext is never written to storage.
+//!
+//! The passphrase solicited from the user is made available to both commands via the
+//! environment variable [`PASSPHRASE_VAR`], which keeps it out of the command line
+//! visible to other processes.
+
+use crate::buffer::Buffer;
+use crate::config::Settings;
+use crate::error::{Error, Result};
+use crate::io::{self, Stamp};
+use crate::job;
+use std::fs;
+use std::path::Path;
+
+/// Name of the environment variable containing the passphrase.
+pub const PASSPHRASE_VAR: &str = "PED_PASSPHRASE";
+
+/// A means of decrypting and encrypting the content of an editor, which is retained
+/// by the editor for the purpose of saving.
+#[derive(Clone)]
+pub struct Crypt {
+    passphrase: String,
+    decrypt_command: String,
+    encrypt_command: String,
+}
+
+impl Crypt {
+    pub fn new(settings: &Settings, passphrase: &str) -> Crypt {
+        Crypt {
+            passphrase: passphrase.to_string(),
+            decrypt_command: settings.decrypt_command.clone(),
+            encrypt_command: settings.encrypt_command.clone(),
+        }
+    }
+
+    /// Returns the encryption of the contents of `buf`, preceded by the byte order
+    /// mark if `bom` is `true`.
+    pub fn encrypt(&self, buf: &Buffer, bom: bool) -> Result<Vec<u8>> {
+        let text = io::encode(buf, bom);
+        job::filter(&self.encrypt_command, text, &self.vars())
+    }
+
+    /// Reads the file at `path`, decrypting its contents and inserting the result into
+    /// `buf`, returning the [`Stamp`] of the file whose digest is computed from the
+    /// encrypted contents.
+    pub fn read_file<P: AsRef<Path>>(&self, path: P, buf: &mut Buffer) -> Result<Stamp> {
+        let path = path.as_ref();
+        let time = io::get_time(path)?;
+        let cipher = fs::read(path).map_err(|e| Error::io(&path.to_string_lossy(), e))?;
+        let digest = io::hash_bytes(&cipher);
+        let text = job::filter(&self.decrypt_command, cipher, &self.vars())?;
+        let _ = buf
+            .read(&mut text.as_slice())
+            .map_err(|e| Error::io(&path.to_string_lossy(), e))?;
+        Ok(Stamp {
+            time,
+            digest: Some(digest),
+        })
+    }
+
+    fn vars(&self) -> [(&str, &str); 1] {
+        [(PASSPHRASE_VAR, &self.passphrase)]
+    }
+}
+
+/// Returns `true` if the extension of `path` matches one of the extensions in the
+/// `crypt-extensions` setting.
+pub fn is_encrypted<P: AsRef<Path>>(path: P, settings: &Settings) -> bool {
+    path.as_ref()
+        .extension()
+        .map(|ext| {
+            settings
+                .crypt_extensions
+                .iter()
+                .any(|crypt_ext| ext == crypt_ext.as_str())
+        })
+        .unwrap_or(false)
+}
@@ -1,29 +1,51 @@
 //! Echo messages to the shared region of a workspace.
+//!
+//! Messages that do not fit on a single row are wrapped, and the shared region may
+//! be expanded to accommodate a limited number of rows, though this is a decision
+//! left to the caller.
 
 use crate::color::Color;
+use crate::size::Size;
 use crate::workspace::WorkspaceRef;
 use crate::writer::Writer;
 
+/// Severity of a message, which determines the color in which it is echoed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
 pub struct Echo {
     workspace: WorkspaceRef,
-    echo_color: Color,
-    text: Option<String>,
+    info_color: Color,
+    warn_color: Color,
+    error_color: Color,
+    text: Option<(String, Severity)>,
 }
 
 impl Echo {
+    /// Maximum number of rows to which text will wrap.
+    const MAX_ROWS: u32 = 4;
+
     pub fn new(workspace: WorkspaceRef) -> Echo {
         let config = workspace.borrow().config().clone();
-        let echo_color = Color::new(config.theme.echo_fg, config.theme.text_bg);
+        let info_color = Color::new(config.theme.echo_fg, config.theme.text_bg);
+        let warn_color = Color::new(config.theme.warn_fg, config.theme.text_bg);
+        let error_color = Color::new(config.theme.error_fg, config.theme.text_bg);
 
         Echo {
             workspace,
-            echo_color,
+            info_color,
+            warn_color,
+            error_color,
             text: None,
         }
     }
 
-    pub fn set(&mut self, text: &str) {
-        self.text = Some(text.to_string());
+    pub fn set(&mut self, text: &str, severity: Severity) {
+        self.text = Some((text.to_string(), severity));
         self.draw();
     }
 
@@ -32,19 +54,44 @@ impl Echo {
         self.draw();
     }
 
+    /// Returns the number of rows needed to show the current text without clipping,
+    /// which is never more than [`MAX_ROWS`](Self::MAX_ROWS).
+    pub fn rows(&self) -> u32 {
+        self.text
+            .as_ref()
+            .map(|(text, _)| self.rows_for(text))
+            .unwrap_or(1)
+    }
+
+    /// Returns the number of rows needed to show `text` without clipping, which is
+    /// never more than [`MAX_ROWS`](Self::MAX_ROWS).
+    pub fn rows_for(&self, text: &str) -> u32 {
+        let (_, size) = self.workspace.borrow().shared_region();
+        let rows = Self::wrap(text, size.cols).len() as u32;
+        rows.clamp(1, Self::MAX_ROWS)
+    }
+
     pub fn draw(&mut self) {
-        if let Some(ref text) = self.text {
+        if let Some((ref text, severity)) = self.text {
             let (origin, size) = self.workspace.borrow().shared_region();
+            let color = match severity {
+                Severity::Info => self.info_color,
+                Severity::Warn => self.warn_color,
+                Severity::Error => self.error_color,
+            };
 
-            // Possibly clip text to fit size constraint of viewable region.
-            let chars = text.chars().take(size.cols as usize).collect::<Vec<_>>();
-            let blank_cols = size.cols - chars.len() as u32;
+            // Possibly clip wrapped text to fit size constraint of viewable region.
+            let lines = Self::wrap(text, size.cols);
+            for row in 0..size.rows {
+                let chars = lines.get(row as usize).map(|l| l.as_slice()).unwrap_or(&[]);
+                let blank_cols = size.cols - chars.len() as u32;
 
-            Writer::new_at(origin)
-                .set_color(self.echo_color)
-                .write_str(chars.into_iter().collect::<String>().as_str())
-                .write_str(" ".repeat(blank_cols as usize).as_str())
-                .send();
+                Writer::new_at(origin + Size::rows(row))
+                    .set_color(color)
+                    .write_str(chars.iter().collect::<String>().as_str())
+                    .write_str(" ".repeat(blank_cols as usize).as_str())
+                    .send();
+            }
         } else {
             self.workspace.borrow_mut().clear_shared();
         }
@@ -53,4 +100,22 @@ impl Echo {
     pub fn resize(&mut self) {
         self.draw();
     }
+
+    /// Breaks `text` into lines of at most `cols` characters, honoring any `\n`
+    /// characters embedded in `text`.
+    fn wrap(text: &str, cols: u32) -> Vec<Vec<char>> {
+        text.lines()
+            .flat_map(|line| {
+                let chars = line.chars().collect::<Vec<_>>();
+                if chars.is_empty() {
+                    vec![chars]
+                } else {
+                    chars
+                        .chunks(cols as usize)
+                        .map(|chunk| chunk.to_vec())
+                        .collect()
+                }
+            })
+            .collect()
+    }
 }
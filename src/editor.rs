@@ -7,19 +7,24 @@
 use crate::buffer::{Buffer, BufferRef};
 use crate::canvas::{Canvas, CanvasRef};
 use crate::color::Color;
-use crate::config::ConfigurationRef;
+use crate::config::{ByteOrderMark, ConfigurationRef, ControlChars};
+use crate::crypt::Crypt;
+use crate::error::Result;
 use crate::grid::Cell;
-use crate::search::Pattern;
+use crate::io::{Loader, Stamp};
+use crate::lane::{Hint, Lane, Lanes, LanesRef, Style};
 use crate::size::{Point, Size};
 use crate::source::Source;
 use crate::syntax::Syntax;
 use crate::token::{Cursor, Tokenizer, TokenizerRef};
-use crate::window::{Banner, BannerRef, Window, WindowRef};
+use crate::unicode;
+use crate::window::{Banner, BannerRef, RulerRef, Window, WindowRef};
 use std::cell::{Ref, RefCell, RefMut};
 use std::cmp;
+use std::mem;
 use std::ops::Range;
 use std::rc::Rc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
 /// An editing session containing a [`kernel`](EditorKernel) that carries out most
 /// operations.
@@ -29,6 +34,26 @@ pub struct Editor {
 
     /// A value of `true` implies that _mutable_ operations are not allowed.
     readonly: bool,
+
+    /// A value of `true` implies that editing is locked across all editors, which
+    /// disallows _mutable_ operations regardless of `readonly`.
+    locked: bool,
+
+    /// A value of `true` implies that the source file was not writable when opened
+    /// and the user has not yet been warned about modifying it.
+    protected: bool,
+
+    /// A means of encrypting the buffer when saved if the source is an encrypted
+    /// file, otherwise `None`.
+    crypt: Option<Crypt>,
+
+    /// Reads the remainder of a large source file that is still loading, otherwise
+    /// `None`.
+    loader: Option<Loader>,
+
+    /// The modification time of the source file when it was found to have changed
+    /// in storage since last read or written, otherwise `None`.
+    stale: Option<SystemTime>,
 }
 
 pub type EditorRef = Rc<RefCell<Editor>>;
@@ -54,6 +79,14 @@ pub trait ImmutableEditor {
     /// Changes the `source` associated with this editor.
     fn assume(&mut self, source: Source);
 
+    /// Returns the shortest name that distinguishes this editor from other editors,
+    /// which defaults to the [`source`](Self::source) if not otherwise set.
+    fn label(&self) -> String;
+
+    /// Sets the shortest name that distinguishes this editor from other editors to
+    /// `label`, or `None` to revert to the default.
+    fn set_label(&mut self, label: Option<String>);
+
     /// Returns a reference to the underlying buffer.
     fn buffer(&self) -> Ref<'_, Buffer>;
 
@@ -92,6 +125,72 @@ pub trait ImmutableEditor {
     /// Sets the tab mode based on the value of `hard`.
     fn set_tab(&mut self, hard: bool);
 
+    /// Returns the width of tab stops in number of columns.
+    fn get_tab_size(&self) -> u32;
+
+    /// Sets the width of tab stops to `cols`, which applies only when the tab mode is
+    /// _soft_.
+    fn set_tab_size(&mut self, cols: u32);
+
+    /// Returns the column at which lines are broken when hard-wrapping text.
+    fn get_fill(&self) -> u32;
+
+    /// Sets the column at which lines are broken when hard-wrapping text to `cols`.
+    fn set_fill(&mut self, cols: u32);
+
+    /// Returns `true` if inserted lines are automatically indented.
+    fn get_indent(&self) -> bool;
+
+    /// Sets the auto-indent mode based on the value of `auto`.
+    fn set_indent(&mut self, auto: bool);
+
+    /// Replaces the syntax configuration with `syntax` and tokenizes the buffer.
+    fn set_syntax(&mut self, syntax: Syntax);
+
+    /// Returns `true` if characters are concealed when rendered on the display.
+    fn get_redact(&self) -> bool;
+
+    /// Conceals characters when rendered on the display based on the value of
+    /// `redact`, though the contents of the buffer remain unchanged.
+    fn set_redact(&mut self, redact: bool);
+
+    /// Returns `true` if lines longer than the width of the display are wrapped onto
+    /// subsequent rows, and `false` if truncated and scrolled horizontally.
+    fn get_wrap(&self) -> bool;
+
+    /// Sets the line wrap mode based on the value of `wrap`.
+    fn set_wrap(&mut self, wrap: bool);
+
+    /// Returns `true` if the cursor may move past the end of lines into empty space.
+    fn get_virtual(&self) -> bool;
+
+    /// Sets the virtual editing mode based on the value of `virtual_edit`, where
+    /// padding spaces are inserted only when text is inserted at a virtual position.
+    fn set_virtual(&mut self, virtual_edit: bool);
+
+    /// Returns `true` if the buffer is preceded by a byte order mark when saved.
+    fn get_bom(&self) -> bool;
+
+    /// Sets whether the buffer is preceded by a byte order mark when saved based on
+    /// the value of `bom`.
+    fn set_bom(&mut self, bom: bool);
+
+    /// Annotates each of `ranges` of buffer positions in `lane`, which are rendered
+    /// using `style` over syntax colors according to `priority`.
+    ///
+    /// Annotations are not rendered until the next call to [`render`](Self::render).
+    fn annotate(&mut self, lane: Lane, ranges: Vec<Range<usize>>, priority: u8, style: Style);
+
+    /// Anchors the virtual `text` of a hint in `lane` to the buffer position `pos`,
+    /// which is rendered using `style` after the end of the line containing `pos`.
+    ///
+    /// Hints occupy display cells but no buffer positions, so they never affect the
+    /// placement of the cursor.
+    fn add_hint(&mut self, lane: Lane, pos: usize, text: &str, style: Style);
+
+    /// Removes all annotations and hints in `lane`.
+    fn clear_annotations(&mut self, lane: Lane);
+
     /// Sets the cursor location and corresponding buffer position to `cursor`, though
     /// the final cursor location is constrained by end-of-line and end-of-buffer
     /// boundaries.
@@ -109,6 +208,10 @@ pub trait ImmutableEditor {
     /// Detaches the existing window from this editor.
     fn detach(&mut self);
 
+    /// Changes the rendering of this editor based on whether its window is the
+    /// _active_ window indicated by `active`.
+    fn set_active(&mut self, active: bool);
+
     /// Sets the position of the cursor based on the alignment objective `align`.
     fn align_cursor(&mut self, align: Align);
 
@@ -119,11 +222,12 @@ pub trait ImmutableEditor {
     fn show_cursor(&mut self);
 
     /// Tries to move the cursor _backward_ from the current buffer position by `len`
-    /// characters.
+    /// characters, where virtual columns past the end of the line are consumed first.
     fn move_backward(&mut self, len: usize);
 
     /// Tries to move the cursor _forward_ from the current buffer position by `len`
-    /// characters.
+    /// characters, though when virtual editing is enabled and the cursor is at the
+    /// end of the line, it moves into the empty space beyond instead.
     fn move_forward(&mut self, len: usize);
 
     /// Tries to move the cursor _backward_ by one word from the current buffer
@@ -168,6 +272,14 @@ pub trait ImmutableEditor {
     /// cursor on the display according to the `align` objective.
     fn move_line(&mut self, line: u32, align: Align);
 
+    /// Moves the buffer position to the character at `col` on `line`, both of which
+    /// are `0`-based, and places the cursor on the display according to the `align`
+    /// objective.
+    ///
+    /// The buffer position is constrained by the end of the line, and `col` is
+    /// measured in characters rather than display columns.
+    fn move_line_col(&mut self, line: u32, col: u32, align: Align);
+
     /// Moves the current buffer position to `pos` and places the cursor on the
     /// display according to the `align` objective.
     ///
@@ -182,6 +294,14 @@ pub trait ImmutableEditor {
     ///   row below the current line, though not to extend beyond the borrom row
     fn move_to(&mut self, pos: usize, align: Align);
 
+    /// Returns the buffer position of the bracket matching the bracket at `pos`, or
+    /// `None` if the character at `pos` is not a bracket or no match is found within
+    /// `limit` characters.
+    ///
+    /// Brackets are pairs of `()`, `[]` and `{}`, where nested pairs of the same kind
+    /// are skipped while scanning.
+    fn find_bracket(&self, pos: usize, limit: usize) -> Option<usize>;
+
     /// Tries scrolling _up_ the contents of the display by the specified number of
     /// `try_rows` while preserving the cursor position, which also means the cursor
     /// moves _up_ as the contents scroll.
@@ -220,9 +340,41 @@ pub trait ImmutableEditor {
     /// Clears and returns the mark.
     fn clear_mark(&mut self) -> Option<Mark>;
 
+    /// Sets a _hard_ mark at the current buffer position that selects a [`Block`]
+    /// of columns rather than a contiguous span of text, and returns the previous
+    /// mark if set.
+    fn set_block_mark(&mut self) -> Option<Mark>;
+
+    /// Returns the block of columns between the mark and the cursor if the mark was
+    /// set using [`set_block_mark`](Self::set_block_mark), otherwise `None`.
+    fn get_block(&self) -> Option<Block>;
+
+    /// Moves the cursor to the top-left corner of `block` and adds secondary cursors
+    /// at the same column on every other line of the block, though lines that end
+    /// before that column are skipped.
+    fn add_block_cursors(&mut self, block: &Block);
+
+    /// Adds a secondary cursor at buffer position `pos` unless it coincides with the
+    /// current buffer position or an existing secondary cursor.
+    ///
+    /// Characters inserted and removed using [`MutableEditor::insert_char`],
+    /// [`MutableEditor::remove_before`] and [`MutableEditor::remove_after`] apply at
+    /// every cursor.
+    fn add_cursor(&mut self, pos: usize);
+
+    /// Removes all secondary cursors and returns the number removed.
+    fn clear_cursors(&mut self) -> usize;
+
+    /// Returns the buffer positions of secondary cursors in ascending order.
+    fn cursors(&self) -> &[usize];
+
     /// Returns the text between the current buffer position and `mark`.
     fn copy_mark(&self, mark: Mark) -> Vec<char>;
 
+    /// Returns the text within the columns of `block` on each of its lines, which
+    /// are separated by `\n`.
+    fn copy_block(&self, block: &Block) -> Vec<char>;
+
     /// Returns the text of the line on which the current buffer position rests.
     fn copy_line(&self) -> Vec<char>;
 
@@ -264,13 +416,6 @@ pub trait ImmutableEditor {
 
     /// Renders the contents of the editor.
     fn render(&mut self);
-
-    /// Sets the last match from a prior search, where `pos` is the starting positon of
-    /// the match and `pattern` is the applicable search pattern.
-    fn set_last_match(&mut self, pos: usize, pattern: Box<dyn Pattern>);
-
-    /// Takes the last match from a prior search.
-    fn take_last_match(&mut self) -> Option<(usize, Box<dyn Pattern>)>;
 }
 
 /// A collection of _mutable_ operations that can be performed on an [`Editor`].
@@ -281,7 +426,8 @@ pub trait ImmutableEditor {
 /// This trait also inherits immutable operations, as these operations are typically
 /// interleaved with mutable operations.
 pub trait MutableEditor: ImmutableEditor {
-    /// Inserts the character `c` at the current buffer position.
+    /// Inserts the character `c` at the current buffer position as well as at every
+    /// secondary cursor.
     fn insert_char(&mut self, c: char);
 
     /// Inserts the string slice `str` at the current buffer position.
@@ -293,21 +439,55 @@ pub trait MutableEditor: ImmutableEditor {
     /// Inserts the array of `text` at the current buffer position.
     fn insert(&mut self, text: &[char]);
 
+    /// Inserts each of `texts` at successive cursors ordered from the top of the
+    /// buffer, where cursors include the current buffer position and every secondary
+    /// cursor, recording all insertions as a single change.
+    ///
+    /// Cursors beyond the number of `texts` are unaffected.
+    fn insert_each(&mut self, texts: &[Vec<char>]);
+
     /// Removes and returns the character before the current buffer position.
     ///
     /// An empty vector is returned if the current position is already at the top
-    /// of the buffer.
+    /// of the buffer, or if secondary cursors exist, in which case the character
+    /// before every cursor is removed. If the cursor is at a virtual position past
+    /// the end of the line, it simply moves backward by one column.
     fn remove_before(&mut self) -> Vec<char>;
 
     /// Removes and returns the character after the current buffer position.
     ///
     /// An empty vector is returned if the current position is already at the
-    /// bottom of the buffer.
+    /// bottom of the buffer, or if secondary cursors exist, in which case the
+    /// character after every cursor is removed.
     fn remove_after(&mut self) -> Vec<char>;
 
+    /// Removes and returns the text between the start of the word preceding the current
+    /// buffer position and the current buffer position.
+    ///
+    /// An empty vector is returned if the current position is already at the top
+    /// of the buffer.
+    fn remove_backward_word(&mut self) -> Vec<char>;
+
+    /// Removes and returns the text between the current buffer position and the start
+    /// of the word following the current buffer position.
+    ///
+    /// An empty vector is returned if the current position is already at the
+    /// bottom of the buffer.
+    fn remove_forward_word(&mut self) -> Vec<char>;
+
     /// Removes and returns the text between the current buffer position and `mark`.
     fn remove_mark(&mut self, mark: Mark) -> Vec<char>;
 
+    /// Removes and returns the text within the columns of `block` on each of its
+    /// lines, which are separated by `\n`, leaving the cursor at the top-left corner
+    /// of the block.
+    fn remove_block(&mut self, block: &Block) -> Vec<char>;
+
+    /// Inserts each line of `text` at the column of the current buffer position on
+    /// successive lines, padding short lines with spaces and appending lines to the
+    /// end of the buffer as necessary.
+    fn insert_block(&mut self, text: &[char]);
+
     /// Removes and returns the text of the line on which the current buffer position
     /// rests.
     fn remove_line(&mut self) -> Vec<char>;
@@ -328,6 +508,34 @@ pub trait MutableEditor: ImmutableEditor {
     ///
     /// This function will return an empty vector if `pos` is equal to `cur_pos`.
     fn remove(&mut self, pos: usize) -> Vec<char>;
+
+    /// Replaces the text between the current buffer position and `pos` with `text`,
+    /// returning the text removed, where the removal and insertion are recorded in the
+    /// undo stack as a single change.
+    ///
+    /// The range of characters removed is formed in the same manner as
+    /// [`remove`](Self::remove), and the cursor follows the inserted text.
+    fn replace(&mut self, pos: usize, text: &[char]) -> Vec<char>;
+
+    /// Inserts a copy of the lines spanned by the selection, or the current line if
+    /// no mark is set, immediately below those lines, moving the cursor and mark to
+    /// the copy.
+    fn duplicate_lines(&mut self);
+
+    /// Exchanges the lines spanned by the selection, or the current line if no mark
+    /// is set, with the line above if `up` is `true`, otherwise the line below, such
+    /// that the cursor and mark move with the lines.
+    ///
+    /// Returns `false` if there is no line above or below, in which case nothing
+    /// changes.
+    fn move_lines(&mut self, up: bool) -> bool;
+
+    /// Joins the lines spanned by the selection, or the current line and the line
+    /// below if no mark is set, replacing each line break and its surrounding
+    /// whitespace with a single space, and clearing the mark.
+    ///
+    /// Returns `false` if there are no lines to join, in which case nothing changes.
+    fn join_lines(&mut self) -> bool;
 }
 
 /// An editing kernel with an underlying [`Buffer`] and an attachable [`Window`].
@@ -338,6 +546,9 @@ struct EditorKernel {
     /// The source of the buffer.
     source: Source,
 
+    /// An optional name that distinguishes this editor from other editors.
+    label: Option<String>,
+
     /// Buffer containing the contents of this editor.
     buffer: BufferRef,
 
@@ -347,12 +558,21 @@ struct EditorKernel {
     /// A stack containing changes to the buffer that can be _undone_.
     undo: Vec<Change>,
 
+    /// The number of characters contained in all changes of the _undo_ stack.
+    undo_chars: usize,
+
+    /// The approximate number of bytes held by all changes of the _undo_ stack.
+    undo_memory: usize,
+
     /// A stack containing changes to the buffer that can be _redone_.
     redo: Vec<Change>,
 
     /// Tokenizes the buffer for syntax coloring.
     tokenizer: TokenizerRef,
 
+    /// Annotations of buffer positions maintained in parallel with the buffer.
+    lanes: LanesRef,
+
     /// The number of milliseconds spent performing the last tokenization.
     tokenize_cost: u128,
 
@@ -384,12 +604,21 @@ struct EditorKernel {
     /// An optional mark used when selecting text.
     mark: Option<Mark>,
 
+    /// Indicates whether the mark selects a block of columns.
+    block: bool,
+
+    /// Buffer positions of secondary cursors in ascending order.
+    cursors: Vec<usize>,
+
     /// Canvas associated with the window.
     canvas: CanvasRef,
 
     /// Banner associated with the window.
     banner: BannerRef,
 
+    /// Ruler associated with the window, if shown.
+    ruler: Option<RulerRef>,
+
     /// Number of rows available for text.
     rows: u32,
 
@@ -405,8 +634,47 @@ struct EditorKernel {
     /// The width of tab stops in number of columns.
     tab_cols: u32,
 
-    /// An optional last match from a prior search.
-    last_match: Option<(usize, Box<dyn Pattern>)>,
+    /// The column at which lines are broken when hard-wrapping text.
+    fill_cols: u32,
+
+    /// Indicates whether inserted lines are automatically indented.
+    auto_indent: bool,
+
+    /// Indicates whether characters are concealed when rendered.
+    redact: bool,
+
+    /// Indicates whether lines longer than the width of the display are wrapped.
+    wrap: bool,
+
+    /// Number of columns scrolled horizontally when lines are not wrapped.
+    hscroll: u32,
+
+    /// Indicates whether the cursor may move past the end of lines.
+    virtual_edit: bool,
+
+    /// Number of columns of empty space between the end of the current row and the
+    /// cursor, which is always `0` unless virtual editing is enabled.
+    virtual_cols: u32,
+
+    /// Indicates whether the buffer is preceded by a byte order mark when saved.
+    bom: bool,
+
+    /// Indicates whether the editor is _readonly_, which is shown in the banner.
+    readonly: bool,
+
+    /// Indicates whether the source file has changed in storage, which is shown in
+    /// the banner.
+    stale: bool,
+
+    /// Activity and percentage of the source file processed so far while loading or
+    /// saving, which is shown in the banner, or `None` if neither is in progress.
+    progress: Option<(&'static str, u8)>,
+
+    /// The most recently found section shown as the sticky header.
+    header: Option<Header>,
+
+    /// Indicates whether the attached window is the _active_ window.
+    active: bool,
 }
 
 /// The distinct types of changes to a buffer recorded in the _undo_ and _redo_ stacks.
@@ -428,6 +696,18 @@ enum Change {
     /// - text removed
     RemoveAfter(usize, Vec<char>),
 
+    /// Represents the removal of words that come before the cursor, where values are
+    /// defined as:
+    /// - buffer position prior to removal
+    /// - text removed
+    RemoveWordBefore(usize, Vec<char>),
+
+    /// Represents the removal of words that come after the cursor, where values are
+    /// defined as:
+    /// - buffer position prior to removal
+    /// - text removed
+    RemoveWordAfter(usize, Vec<char>),
+
     /// Represents the removal of selected text that comes before the cursor, where
     /// values are defined as:
     /// - buffer position prior to removal
@@ -439,6 +719,11 @@ enum Change {
     /// - buffer position prior to removal
     /// - text removed
     RemoveSelectionAfter(usize, Vec<char>, bool),
+
+    /// Represents changes made at multiple cursors as a single change, which are
+    /// ordered from the bottom of the buffer to the top so that the position of each
+    /// change is unaffected by those that follow.
+    Group(Vec<Change>),
 }
 
 /// Indicates how a [`Change`] should be logged.
@@ -446,6 +731,12 @@ enum Log {
     /// Indicates that no selection was active when the change was made.
     Normal,
 
+    /// Indicates that a word was removed, which is distinguished from [`Normal`]
+    /// changes so that successive removals of words are combined.
+    ///
+    /// [`Normal`]: Log::Normal
+    Word,
+
     /// Indicates that a selection was active when the change was made, where the
     /// value is `true` if it was a _soft_ mark and `false` if a _hard_ mark.
     Selection(bool),
@@ -465,6 +756,10 @@ struct Line {
     /// Length of the display line, including the `\n` if one exists.
     row_len: usize,
 
+    /// Number of columns occupied by the display line, which may be less than the
+    /// width of the display if the line wraps before a wide character.
+    row_cols: u32,
+
     /// Buffer position corresponding to the first character of the buffer line,
     /// which is always less than or equal to `row_pos`.
     line_pos: usize,
@@ -479,6 +774,40 @@ struct Line {
     line_bottom: bool,
 }
 
+/// The nearest section at or above the top row of the display, which is retained to
+/// avoid searching the buffer on every render.
+#[derive(Clone)]
+struct Header {
+    /// Value of the editor clock at the time of the search.
+    clock: u64,
+
+    /// The `0`-based number of the buffer line at the top row at the time of the
+    /// search.
+    top: u32,
+
+    /// The `0`-based line number and text of the section, if found.
+    section: Option<(u32, String)>,
+}
+
+/// A summary of memory held by an editor, where quantities are expressed in terms of
+/// elements rather than bytes.
+pub struct Usage {
+    /// Number of characters in the buffer.
+    pub size: usize,
+
+    /// Number of characters the buffer can hold without reallocation.
+    pub capacity: usize,
+
+    /// Number of changes and characters in the _undo_ stack.
+    pub undo: (usize, usize),
+
+    /// Number of changes and characters in the _redo_ stack.
+    pub redo: (usize, usize),
+
+    /// Number of spans produced by tokenization.
+    pub spans: usize,
+}
+
 /// Cursor alignment directives.
 pub enum Align {
     /// Try aligning the cursor based on its contextual use.
@@ -504,6 +833,22 @@ pub enum Align {
 #[derive(Copy, Clone)]
 pub struct Mark(pub usize, pub bool);
 
+/// A rectangular selection of columns spanning a range of lines, where both ranges
+/// are `0`-based and bounded _inclusively below_ and _exclusively above_.
+#[derive(Clone)]
+pub struct Block {
+    pub lines: Range<u32>,
+    pub cols: Range<u32>,
+}
+
+impl Block {
+    /// Returns `true` if the column `col` of `line` falls within this block.
+    #[inline]
+    pub fn contains(&self, line: u32, col: u32) -> bool {
+        self.lines.contains(&line) && self.cols.contains(&col)
+    }
+}
+
 /// A means of capturing the visual state of an editor for the purpose of possible
 /// restoration.
 pub struct Capture {
@@ -523,35 +868,130 @@ struct Draw {
     /// Color of text with no special treatment.
     text_color: Color,
 
+    /// Color of the sticky header.
+    header_color: Color,
+
     /// Current cursor position.
     cursor: Point,
 
     /// Range in the buffer containing selected text, if applicable, otherwise this
     /// span is assumed to be `0`..`0`.
     select_span: Range<usize>,
+
+    /// Block of columns containing selected text, which takes precedence over
+    /// [`select_span`](Self::select_span) if applicable.
+    select_block: Option<Block>,
+
+    /// Buffer positions of secondary cursors in ascending order.
+    cursors: Vec<usize>,
+
+    /// Buffer position of the bracket matching the bracket under the cursor, if
+    /// applicable.
+    bracket_pos: Option<usize>,
+
+    /// Indicates whether text should be dimmed because the window is not active.
+    dim: bool,
+
+    /// Indicates whether characters should be concealed.
+    redact: bool,
 }
 
 /// A rendering context that captures state information for rendering functions.
 struct Render {
     pos: usize,
+    line_pos: usize,
     row: u32,
     col: u32,
     line: u32,
     line_wrapped: bool,
     tokenizer: TokenizerRef,
     syntax_cursor: Cursor,
+    lanes: LanesRef,
+
+    /// Location on the canvas of the most recently drawn character, to which
+    /// characters of zero width are attached.
+    base: Option<Point>,
 }
 
 impl Change {
+    /// Returns the number of characters contained in this change.
+    fn size(&self) -> usize {
+        use Change::*;
+
+        match self {
+            Insert(_, text)
+            | RemoveBefore(_, text)
+            | RemoveAfter(_, text)
+            | RemoveWordBefore(_, text)
+            | RemoveWordAfter(_, text)
+            | RemoveSelectionBefore(_, text, _)
+            | RemoveSelectionAfter(_, text, _) => text.len(),
+            Group(changes) => changes.iter().map(|c| c.size()).sum(),
+        }
+    }
+
+    /// Returns the approximate number of bytes of memory held by this change, which
+    /// includes the overhead of the change itself in addition to its characters.
+    fn memory(&self) -> usize {
+        let overhead = mem::size_of::<Change>();
+        match self {
+            Change::Group(changes) => overhead + changes.iter().map(|c| c.memory()).sum::<usize>(),
+            _ => overhead + self.size() * mem::size_of::<char>(),
+        }
+    }
+
+    /// Returns the net number of characters added to the buffer by this change, which
+    /// is negative for removals.
+    fn delta(&self) -> isize {
+        use Change::*;
+
+        match self {
+            Insert(_, text) => text.len() as isize,
+            Group(changes) => changes.iter().map(|c| c.delta()).sum(),
+            _ => -(self.size() as isize),
+        }
+    }
+
+    /// Returns a copy of this change with its position offset by `delta`, or `None`
+    /// if this change is not a single-character insertion or removal.
+    fn shift(&self, delta: isize) -> Option<Change> {
+        use Change::{Insert, RemoveAfter, RemoveBefore};
+
+        let shift = |pos: usize| (pos as isize + delta) as usize;
+        match self {
+            Insert(pos, text) => Some(Insert(shift(*pos), text.clone())),
+            RemoveBefore(pos, text) => Some(RemoveBefore(shift(*pos), text.clone())),
+            RemoveAfter(pos, text) => Some(RemoveAfter(shift(*pos), text.clone())),
+            _ => None,
+        }
+    }
+
     /// Returns a new change if `self` can be combined with `prior`, otherwise `None`.
     ///
     /// In general, this function is used to optimize changes that involve a single
     /// character being inserted or removed. If the change described by `self` is
     /// adjacent to `prior`, then both changes are combined into a single change.
+    /// Removals of words are combined in the same manner, though only with prior
+    /// removals of words.
     fn possibly_combine(&self, prior: &Change) -> Option<Change> {
-        use Change::{Insert, RemoveAfter, RemoveBefore};
+        use Change::{Group, Insert, RemoveAfter, RemoveBefore, RemoveWordAfter, RemoveWordBefore};
 
         match self {
+            // Groups are combined when each of their changes can be combined, though
+            // positions of changes in this group must first be shifted to account for
+            // changes in the prior group that precede them in the buffer.
+            Group(changes) => match prior {
+                Group(p_changes) if p_changes.len() == changes.len() => {
+                    let mut combined = Vec::new();
+                    for (i, (change, p_change)) in changes.iter().zip(p_changes).enumerate() {
+                        let delta = p_changes[i + 1..].iter().map(|c| c.delta()).sum::<isize>();
+                        let change = change.shift(-delta)?;
+                        combined.push(change.possibly_combine(p_change)?);
+                    }
+                    Some(Group(combined))
+                }
+                _ => None,
+            },
             Insert(pos, text) if text.len() == 1 => match prior {
                 Insert(p_pos, p_text) if p_pos + p_text.len() == *pos => {
                     let mut p_text = p_text.clone();
@@ -576,6 +1016,22 @@ impl Change {
                 }
                 _ => None,
             },
+            RemoveWordBefore(pos, text) => match prior {
+                RemoveWordBefore(p_pos, p_text) if pos + p_text.len() == *p_pos => {
+                    let mut text = text.clone();
+                    text.extend(p_text);
+                    Some(RemoveWordBefore(*p_pos, text))
+                }
+                _ => None,
+            },
+            RemoveWordAfter(pos, text) => match prior {
+                RemoveWordAfter(p_pos, p_text) if *p_pos == *pos => {
+                    let mut p_text = p_text.clone();
+                    p_text.extend(text);
+                    Some(RemoveWordAfter(*p_pos, p_text))
+                }
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -591,7 +1047,7 @@ impl Line {
     /// Returns `true` if the row of this line points to the bottom of the buffer,
     /// where `cols` is the width of the display.
     fn is_bottom(&self, cols: u32) -> bool {
-        self.line_bottom && self.row_len < cols as usize
+        self.line_bottom && !self.does_wrap() && self.row_cols < cols
     }
 
     /// Returns `true` if the row of this line wraps at least to the next row,
@@ -608,62 +1064,6 @@ impl Line {
         self.row_pos > self.line_pos
     }
 
-    /// Returns a possibly smaller value of `col` if it extends beyond the end of
-    /// the row, where `cols` is the width of the display.
-    ///
-    /// In most cases, the right-most column aligns to the last character of the row,
-    /// which is usually `\n` but may also be any other character if the row wraps.
-    /// However, if this is the bottom-most row in the buffer, there is no terminating
-    /// `\n`, and thus the right-most column is right of the last character.
-    #[inline]
-    fn snap_col(&self, col: u32, cols: u32) -> u32 {
-        if self.row_len == 0 {
-            0
-        } else if self.is_bottom(cols) {
-            cmp::min(col, self.row_len as u32)
-        } else {
-            cmp::min(col, self.row_len as u32 - 1)
-        }
-    }
-
-    /// Returns the buffer position of `col` relative to the starting position of
-    /// the row.
-    #[inline]
-    fn pos_of(&self, col: u32) -> usize {
-        self.row_pos + col as usize
-    }
-
-    /// Returns the column number of `pos` relative to the starting position of the
-    /// row, though be advised that the resulting column may extend beyond the end
-    /// of the row.
-    #[inline]
-    fn col_of(&self, pos: usize) -> u32 {
-        (pos - self.row_pos) as u32
-    }
-
-    /// Returns the right-most column number of this row, where `cols` is the width of
-    /// the display.
-    ///
-    /// See [`snap_col`](Line::snap_col) for further details on calculating the
-    /// right-most column.
-    #[inline]
-    fn end_col(&self, cols: u32) -> u32 {
-        if self.row_len == 0 {
-            0
-        } else if self.is_bottom(cols) {
-            self.row_len as u32
-        } else {
-            (self.row_len - 1) as u32
-        }
-    }
-
-    /// Returns the column number of `col` relative to the buffer line, which may be
-    /// larger than the width of the display.
-    #[inline]
-    fn line_col(&self, col: u32) -> u32 {
-        (self.row_pos - self.line_pos) as u32 + col
-    }
-
     /// Returns the buffer position at the end of the row.
     #[inline]
     fn end_pos(&self) -> usize {
@@ -680,6 +1080,7 @@ impl Default for Line {
         Line {
             row_pos: 0,
             row_len: 0,
+            row_cols: 0,
             line_pos: 0,
             line_len: 0,
             line: 0,
@@ -689,19 +1090,16 @@ impl Default for Line {
 }
 
 impl Draw {
-    // Special character shown for \n (newline) when visible.
-    const EOL_CHAR: char = '\u{21b2}';
-
-    // Special character shown for \t (tab).
-    const TAB_CHAR: char = '\u{2192}';
-
-    // Special character shown for all other ASCII control characters.
-    const CTRL_CHAR: char = '\u{00bf}';
+    // Maximum number of characters scanned when searching for the bracket matching
+    // the bracket under the cursor, which bounds the cost of rendering.
+    const BRACKET_LIMIT: usize = 65_536;
 
     fn new(editor: &EditorKernel) -> Draw {
         let config = editor.config.clone();
         let margin_color = Color::new(config.theme.margin_fg, config.theme.margin_bg);
         let text_color = Color::new(config.theme.text_fg, config.theme.text_bg);
+        let header_color = Color::new(config.theme.banner_fg, config.theme.inactive_bg);
+        let dim = !editor.active && config.settings.dim_inactive;
 
         let select_span = editor
             .mark
@@ -714,12 +1112,24 @@ impl Draw {
             })
             .unwrap_or(0..0);
 
+        let bracket_pos = if editor.active {
+            editor.find_bracket(editor.cur_pos, Self::BRACKET_LIMIT)
+        } else {
+            None
+        };
+
         Draw {
             config,
             margin_color,
             text_color,
+            header_color,
             cursor: editor.cursor(),
             select_span,
+            select_block: editor.get_block(),
+            cursors: editor.cursors.clone(),
+            bracket_pos,
+            dim,
+            redact: editor.redact,
         }
     }
 
@@ -735,40 +1145,115 @@ impl Draw {
         Cell::new(' ', self.text_color)
     }
 
+    /// Formats `c` using the header color.
+    #[inline]
+    fn as_header(&self, c: char) -> Cell {
+        Cell::new(self.convert_char(c), self.header_color)
+    }
+
     /// Formats `c` using a color depending on the current rendering context.
     fn as_text(&self, c: char, render: &Render) -> Cell {
+        let style = render.lane_style();
         let fg = if (c == '\n' && self.config.settings.eol) || c.is_ascii_control() {
             self.config.theme.whitespace_fg
+        } else if self.dim {
+            self.config.theme.inactive_fg
+        } else if self.redact {
+            self.config.theme.text_fg
+        } else if let Some(fg) = style.fg {
+            fg
         } else if let Some(fg) = render.syntax_cursor.color() {
             fg
         } else {
             self.config.theme.text_fg
         };
 
-        let bg = if self.select_span.contains(&render.pos) {
+        let selected = if let Some(block) = &self.select_block {
+            block.contains(render.line - 1, (render.pos - render.line_pos) as u32)
+        } else {
+            self.select_span.contains(&render.pos)
+        };
+        let bg = if selected {
             self.config.theme.select_bg
-        } else if self.config.settings.spotlight && render.row == self.cursor.row {
+        } else if self.bracket_pos == Some(render.pos) {
+            self.config.theme.bracket_bg
+        } else if let Some(bg) = style.bg {
+            bg
+        } else if self.config.settings.spotlight && !self.dim && render.row == self.cursor.row {
             self.config.theme.spotlight_bg
         } else {
             self.config.theme.text_bg
         };
 
-        Cell::new(self.convert_char(c), Color::new(fg, bg))
+        // Secondary cursors are shown in reverse video, since only the primary cursor
+        // is shown by the terminal.
+        let color = if self.cursors.binary_search(&render.pos).is_ok() {
+            Color::new(self.config.theme.text_bg, self.config.theme.text_fg)
+        } else {
+            Color::new(fg, bg)
+        };
+        Cell::new(self.convert_char(c), color)
+    }
+
+    /// Formats the glyph indicating a truncated line using the whitespace color over
+    /// the background of text.
+    fn as_truncate(&self, render: &Render) -> Cell {
+        let color = self.as_text(' ', render).color;
+        let fg = if self.dim {
+            self.config.theme.inactive_fg
+        } else {
+            self.config.theme.whitespace_fg
+        };
+        Cell::new(
+            self.config.settings.glyphs.truncate,
+            Color::new(fg, color.bg),
+        )
+    }
+
+    /// Formats `c` of a hint using `style`, though the foreground defaults to the
+    /// whitespace color and the background to that of text at the end of the line.
+    fn as_hint(&self, c: char, render: &Render, style: &Style) -> Cell {
+        let color = self.as_text(' ', render).color;
+        let fg = if self.dim {
+            self.config.theme.inactive_fg
+        } else {
+            style.fg.unwrap_or(self.config.theme.whitespace_fg)
+        };
+        let c = if c.is_control() { ' ' } else { c };
+        Cell::new(c, Color::new(fg, style.bg.unwrap_or(color.bg)))
+    }
+
+    /// Returns the character following `^` when the ASCII control character `c` is
+    /// shown in caret notation, such as `[` for `ESC`, otherwise `None`.
+    ///
+    /// The caret itself is the result of [`convert_char`](Self::convert_char).
+    #[inline]
+    fn caret_of(&self, c: char) -> Option<char> {
+        if !self.redact && self.config.settings.control_chars.is_caret(c) {
+            Some((c as u8 ^ 0x40) as char)
+        } else {
+            None
+        }
     }
 
     /// Possibly converts `c` to an alternate display character.
     #[inline]
     fn convert_char(&self, c: char) -> char {
+        let glyphs = &self.config.settings.glyphs;
         match c {
             '\n' => {
                 if self.config.settings.eol {
-                    Self::EOL_CHAR
+                    glyphs.eol
                 } else {
                     ' '
                 }
             }
-            '\t' => Self::TAB_CHAR,
-            c if c.is_ascii_control() => Self::CTRL_CHAR,
+            '\t' => glyphs.tab,
+            c if c.is_ascii_control() => match self.config.settings.control_chars {
+                ControlChars::Glyph => glyphs.control,
+                ControlChars::Caret => '^',
+            },
+            _ if self.redact => glyphs.redact,
             c => c,
         }
     }
@@ -779,20 +1264,24 @@ impl Render {
     fn new(editor: &EditorKernel) -> Render {
         Render {
             pos: editor.top_line.row_pos,
+            line_pos: editor.top_line.line_pos,
             row: 0,
             col: 0,
             line: editor.top_line.line + 1,
             line_wrapped: false,
             tokenizer: editor.tokenizer.clone(),
             syntax_cursor: editor.syntax_cursor,
+            lanes: editor.lanes.clone(),
+            base: None,
         }
     }
 
-    /// Returns a new rendering context representing a transition to the next column.
-    fn next_col(self) -> Render {
+    /// Returns a new rendering context representing a transition past a character
+    /// occupying `width` columns.
+    fn next_col(self, width: u32) -> Render {
         Render {
             pos: self.pos + 1,
-            col: self.col + 1,
+            col: self.col + width,
             syntax_cursor: self.syntax_forward(1),
             ..self
         }
@@ -811,20 +1300,59 @@ impl Render {
         }
     }
 
+    /// Returns a new rendering context representing a transition to the next row
+    /// without moving past the current character, which happens when a wide character
+    /// would otherwise straddle the right edge of the display.
+    fn wrap_row(self) -> Render {
+        Render {
+            row: self.row + 1,
+            col: 0,
+            line_wrapped: true,
+            ..self
+        }
+    }
+
     /// Returns a new rendering context representing a transition to the next line,
     /// which is also the next row.
     fn next_line(self) -> Render {
         Render {
             pos: self.pos + 1,
+            line_pos: self.pos + 1,
             row: self.row + 1,
             col: 0,
             line: self.line + 1,
             line_wrapped: false,
             syntax_cursor: self.syntax_forward(1),
+            base: None,
             ..self
         }
     }
 
+    /// Returns the style of annotations at the current position.
+    fn lane_style(&self) -> Style {
+        let lanes = self.lanes.borrow();
+        if lanes.is_empty() {
+            Style::default()
+        } else {
+            lanes.style_at(self.pos)
+        }
+    }
+
+    /// Returns the hints anchored to the current line, which applies only when the
+    /// rendering context is at the end of the line.
+    fn hints(&self) -> Vec<Hint> {
+        let lanes = self.lanes.borrow();
+        if lanes.is_empty() {
+            Vec::new()
+        } else {
+            lanes
+                .hints_in(self.line_pos..self.pos + 1)
+                .into_iter()
+                .cloned()
+                .collect()
+        }
+    }
+
     /// Returns a new syntax cursor moved forward by `n` characters.
     fn syntax_forward(&self, n: usize) -> Cursor {
         self.tokenizer.borrow().forward(self.syntax_cursor, n)
@@ -832,11 +1360,27 @@ impl Render {
 }
 
 impl Editor {
+    /// Activities whose progress is shown in the banner.
+    const LOADING: &str = "loading";
+    const SAVING: &str = "saving";
+
     /// Creates a readonly editor using `source` and `buffer`.
     ///
     /// A readonly editor is not permitted to obtain a mutable interface.
     pub fn readonly(config: ConfigurationRef, source: Source, buffer: Buffer) -> Editor {
-        Self::new(config, source, Some(buffer), true)
+        Self::new(config, source, Some(buffer), None, true)
+    }
+
+    /// Creates a readonly editor using `source` and `buffer`, though unlike
+    /// [`readonly`](Self::readonly), tokenizes the buffer using `syntax` rather than
+    /// the syntax configuration associated with `source`.
+    pub fn readonly_with_syntax(
+        config: ConfigurationRef,
+        source: Source,
+        buffer: Buffer,
+        syntax: Syntax,
+    ) -> Editor {
+        Self::new(config, source, Some(buffer), Some(syntax), true)
     }
 
     /// Creates a mutable editor using `source` and an optional `buffer`, which if
@@ -844,18 +1388,26 @@ impl Editor {
     ///
     /// A mutable editor is permitted to obtain a mutable interface.
     pub fn mutable(config: ConfigurationRef, source: Source, buffer: Option<Buffer>) -> Editor {
-        Self::new(config, source, buffer, false)
+        Self::new(config, source, buffer, None, false)
     }
 
     fn new(
         config: ConfigurationRef,
         source: Source,
         buffer: Option<Buffer>,
+        syntax: Option<Syntax>,
         readonly: bool,
     ) -> Editor {
+        let mut kernel = EditorKernel::new(config, source, buffer, syntax);
+        kernel.readonly = readonly;
         Editor {
-            kernel: EditorKernel::new(config, source, buffer),
+            kernel,
             readonly,
+            locked: false,
+            protected: false,
+            crypt: None,
+            loader: None,
+            stale: None,
         }
     }
 
@@ -864,49 +1416,250 @@ impl Editor {
         Rc::new(RefCell::new(self))
     }
 
-    /// Returns a mutable editor if not classified as _readonly_, otherwise `None`.
+    /// Returns a mutable editor if not classified as _readonly_ and editing is not
+    /// locked, otherwise `None`.
     pub fn modify(&mut self) -> Option<&mut dyn MutableEditor> {
-        if self.readonly {
+        if self.readonly || self.locked {
             None
         } else {
             Some(&mut self.kernel)
         }
     }
-}
 
-impl ImmutableEditor for Editor {
-    #[inline]
-    fn clone_as(&self, source: Source) -> Editor {
-        self.kernel.clone_as(source)
+    /// Returns `true` if the editor is classified as _readonly_ or editing is locked.
+    pub fn is_readonly(&self) -> bool {
+        self.readonly || self.locked
     }
 
-    #[inline]
-    fn source(&self) -> &Source {
-        self.kernel.source()
+    /// Returns `true` if the editor is _readonly_ only because editing is locked.
+    pub fn is_locked(&self) -> bool {
+        self.locked && !self.readonly
     }
 
-    #[inline]
-    fn assume(&mut self, source: Source) {
-        self.kernel.assume(source);
+    /// Returns the configuration used by the editor.
+    pub fn config(&self) -> &ConfigurationRef {
+        &self.kernel.config
     }
 
-    #[inline]
-    fn buffer(&self) -> Ref<'_, Buffer> {
-        self.kernel.buffer()
+    /// Returns the means of encrypting the buffer if the source is an encrypted file.
+    pub fn crypt(&self) -> Option<&Crypt> {
+        self.crypt.as_ref()
     }
 
-    #[inline]
-    fn is_dirty(&self) -> bool {
-        self.kernel.is_dirty()
+    /// Sets the means of encrypting the buffer when saved.
+    pub fn set_crypt(&mut self, crypt: Option<Crypt>) {
+        self.crypt = crypt;
     }
 
-    #[inline]
-    fn clear_dirty(&mut self) {
-        self.kernel.clear_dirty();
+    /// Classifies the editor as _readonly_ based on the value of `readonly`, which is
+    /// indicated in the banner.
+    pub fn set_readonly(&mut self, readonly: bool) {
+        self.readonly = readonly;
+        self.kernel.set_readonly(readonly || self.locked);
     }
 
-    #[inline]
-    fn cursor(&self) -> Point {
+    /// Locks or unlocks editing based on the value of `locked`, where a locked editor
+    /// is indicated in the banner as _readonly_ regardless of its classification.
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+        self.kernel.set_readonly(self.readonly || locked);
+    }
+
+    /// Classifies the editor as _readonly_ because its source file is not writable,
+    /// though the user may still choose to modify the editor.
+    pub fn set_protected(&mut self) {
+        self.protected = true;
+        self.set_readonly(true);
+    }
+
+    /// Returns `true` exactly once if the editor was classified as _protected_ and
+    /// has since been modified, which is when the user should be warned that the
+    /// source file is not writable.
+    pub fn take_protected(&mut self) -> bool {
+        if self.protected && !self.readonly && self.kernel.is_dirty() {
+            self.protected = false;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns a copy of the syntax configuration used to tokenize the buffer.
+    pub fn syntax(&self) -> Syntax {
+        self.kernel.tokenizer().syntax().clone()
+    }
+
+    /// Returns the name of the syntax configuration used to tokenize the buffer.
+    pub fn syntax_name(&self) -> String {
+        self.kernel.tokenizer().syntax().name.clone()
+    }
+
+    /// Appends `text` to the end of the buffer regardless of whether the editor is
+    /// _readonly_, which is intended for editors whose content is produced by the
+    /// system rather than the user.
+    ///
+    /// The cursor follows appended text if positioned at the end of the buffer,
+    /// otherwise it remains in place. The change is neither recorded in the undo
+    /// stack nor does it make the editor dirty.
+    pub fn append(&mut self, text: &str) {
+        self.kernel.append(&text.chars().collect::<Vec<_>>());
+    }
+
+    /// Continues loading the source file with `loader`, which reads the remainder of
+    /// the file in chunks as the editor is [loaded](Self::load).
+    pub fn set_loader(&mut self, loader: Loader) {
+        self.kernel
+            .set_progress(Some((Self::LOADING, loader.progress())));
+        self.loader = Some(loader);
+    }
+
+    /// Returns `true` if the source file is still loading.
+    pub fn is_loading(&self) -> bool {
+        self.loader.is_some()
+    }
+
+    /// Returns the modification time of the source file if it was found to have
+    /// changed in storage, otherwise `None`.
+    pub fn stale(&self) -> Option<SystemTime> {
+        self.stale
+    }
+
+    /// Marks the source file as having changed in storage at the modification
+    /// `time`, or `None` to clear the mark, which is indicated in the banner.
+    pub fn set_stale(&mut self, time: Option<SystemTime>) {
+        self.stale = time;
+        self.kernel.set_stale(time.is_some());
+    }
+
+    /// Replaces the entire buffer with `text` even if the editor is _readonly_,
+    /// keeping the cursor on the same line and column where possible.
+    ///
+    /// The replacement is recorded in the undo stack as a single change.
+    pub fn reload(&mut self, text: &[char]) {
+        let loc = self.kernel.location();
+        let row = self.kernel.cursor().row;
+        let end_pos = self.kernel.buffer().size();
+        self.kernel.move_to(0, Align::Auto);
+        let _ = self.kernel.replace(end_pos, text);
+        self.kernel.move_line_col(loc.row, loc.col, Align::Row(row));
+    }
+
+    /// Shows the percentage of the source file written so far in the banner, or
+    /// `None` once writing finishes.
+    ///
+    /// Since saving blocks the processing of keys, the banner is drawn immediately
+    /// rather than waiting for the editor to be rendered.
+    pub fn show_saving(&mut self, progress: Option<u8>) {
+        self.kernel
+            .set_progress(progress.map(|progress| (Self::SAVING, progress)));
+        self.kernel.show_banner();
+    }
+
+    /// Appends chunks of the source file that is still loading until either `budget`
+    /// is exhausted or the end of file is reached, returning `true` if anything was
+    /// appended.
+    ///
+    /// Loading stops if an error occurs, leaving the buffer with whatever content was
+    /// loaded up to that point.
+    pub fn load(&mut self, budget: Duration) -> Result<bool> {
+        let timer = Instant::now();
+        let mut loaded = false;
+        while let Some(loader) = self.loader.as_mut() {
+            match loader.read_chunk() {
+                Ok(Some(text)) => {
+                    let progress = loader.progress();
+                    self.append(&text);
+                    self.kernel.set_progress(Some((Self::LOADING, progress)));
+                    loaded = true;
+                    if timer.elapsed() >= budget {
+                        break;
+                    }
+                }
+                Ok(None) => {
+                    // Digest of the file is now known for detecting changes made by
+                    // other programs.
+                    let digest = loader.digest();
+                    if let Source::File(path, Some(stamp)) = self.source().clone() {
+                        let stamp = Stamp {
+                            digest: Some(digest),
+                            ..stamp
+                        };
+                        self.assume(Source::as_file(&path, Some(stamp)));
+                    }
+                    self.loader = None;
+                    self.kernel.set_progress(None);
+                }
+                Err(e) => {
+                    self.loader = None;
+                    self.kernel.set_progress(None);
+                    return Err(e);
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Releases memory held by this editor that is not essential, which trims the
+    /// _undo_ stack if its changes are oversized, drops the cached sticky header, and
+    /// shrinks the capacity of the buffer if it is much larger than its contents.
+    ///
+    /// Nothing is released while the source file is still loading, since capacity of
+    /// the buffer was reserved for the entire file.
+    pub fn compact(&mut self) {
+        if self.loader.is_none() {
+            self.kernel.compact();
+        }
+    }
+
+    /// Returns a summary of memory held by this editor.
+    pub fn usage(&self) -> Usage {
+        self.kernel.usage()
+    }
+}
+
+impl ImmutableEditor for Editor {
+    #[inline]
+    fn clone_as(&self, source: Source) -> Editor {
+        self.kernel.clone_as(source)
+    }
+
+    #[inline]
+    fn source(&self) -> &Source {
+        self.kernel.source()
+    }
+
+    #[inline]
+    fn assume(&mut self, source: Source) {
+        self.kernel.assume(source);
+    }
+
+    #[inline]
+    fn label(&self) -> String {
+        self.kernel.label()
+    }
+
+    #[inline]
+    fn set_label(&mut self, label: Option<String>) {
+        self.kernel.set_label(label);
+    }
+
+    #[inline]
+    fn buffer(&self) -> Ref<'_, Buffer> {
+        self.kernel.buffer()
+    }
+
+    #[inline]
+    fn is_dirty(&self) -> bool {
+        self.kernel.is_dirty()
+    }
+
+    #[inline]
+    fn clear_dirty(&mut self) {
+        self.kernel.clear_dirty();
+    }
+
+    #[inline]
+    fn cursor(&self) -> Point {
         self.kernel.cursor()
     }
 
@@ -940,6 +1693,96 @@ impl ImmutableEditor for Editor {
         self.kernel.set_tab(hard);
     }
 
+    #[inline]
+    fn get_tab_size(&self) -> u32 {
+        self.kernel.get_tab_size()
+    }
+
+    #[inline]
+    fn set_tab_size(&mut self, cols: u32) {
+        self.kernel.set_tab_size(cols);
+    }
+
+    #[inline]
+    fn get_fill(&self) -> u32 {
+        self.kernel.get_fill()
+    }
+
+    #[inline]
+    fn set_fill(&mut self, cols: u32) {
+        self.kernel.set_fill(cols);
+    }
+
+    #[inline]
+    fn get_indent(&self) -> bool {
+        self.kernel.get_indent()
+    }
+
+    #[inline]
+    fn set_indent(&mut self, auto: bool) {
+        self.kernel.set_indent(auto);
+    }
+
+    #[inline]
+    fn set_syntax(&mut self, syntax: Syntax) {
+        self.kernel.set_syntax(syntax);
+    }
+
+    #[inline]
+    fn get_redact(&self) -> bool {
+        self.kernel.get_redact()
+    }
+
+    #[inline]
+    fn set_redact(&mut self, redact: bool) {
+        self.kernel.set_redact(redact);
+    }
+
+    #[inline]
+    fn get_wrap(&self) -> bool {
+        self.kernel.get_wrap()
+    }
+
+    #[inline]
+    fn set_wrap(&mut self, wrap: bool) {
+        self.kernel.set_wrap(wrap);
+    }
+
+    #[inline]
+    fn get_virtual(&self) -> bool {
+        self.kernel.get_virtual()
+    }
+
+    #[inline]
+    fn set_virtual(&mut self, virtual_edit: bool) {
+        self.kernel.set_virtual(virtual_edit);
+    }
+
+    #[inline]
+    fn get_bom(&self) -> bool {
+        self.kernel.get_bom()
+    }
+
+    #[inline]
+    fn set_bom(&mut self, bom: bool) {
+        self.kernel.set_bom(bom);
+    }
+
+    #[inline]
+    fn annotate(&mut self, lane: Lane, ranges: Vec<Range<usize>>, priority: u8, style: Style) {
+        self.kernel.annotate(lane, ranges, priority, style);
+    }
+
+    #[inline]
+    fn add_hint(&mut self, lane: Lane, pos: usize, text: &str, style: Style) {
+        self.kernel.add_hint(lane, pos, text, style);
+    }
+
+    #[inline]
+    fn clear_annotations(&mut self, lane: Lane) {
+        self.kernel.clear_annotations(lane);
+    }
+
     #[inline]
     fn set_focus(&mut self, cursor: Point) {
         self.kernel.set_focus(cursor);
@@ -955,6 +1798,11 @@ impl ImmutableEditor for Editor {
         self.kernel.detach();
     }
 
+    #[inline]
+    fn set_active(&mut self, active: bool) {
+        self.kernel.set_active(active);
+    }
+
     #[inline]
     fn align_cursor(&mut self, align: Align) {
         self.kernel.align_cursor(align);
@@ -1025,11 +1873,21 @@ impl ImmutableEditor for Editor {
         self.kernel.move_line(line, align);
     }
 
+    #[inline]
+    fn move_line_col(&mut self, line: u32, col: u32, align: Align) {
+        self.kernel.move_line_col(line, col, align);
+    }
+
     #[inline]
     fn move_to(&mut self, pos: usize, align: Align) {
         self.kernel.move_to(pos, align);
     }
 
+    #[inline]
+    fn find_bracket(&self, pos: usize, limit: usize) -> Option<usize> {
+        self.kernel.find_bracket(pos, limit)
+    }
+
     #[inline]
     fn scroll_up(&mut self, try_rows: u32) {
         self.kernel.scroll_up(try_rows);
@@ -1065,11 +1923,46 @@ impl ImmutableEditor for Editor {
         self.kernel.clear_mark()
     }
 
+    #[inline]
+    fn set_block_mark(&mut self) -> Option<Mark> {
+        self.kernel.set_block_mark()
+    }
+
+    #[inline]
+    fn get_block(&self) -> Option<Block> {
+        self.kernel.get_block()
+    }
+
+    #[inline]
+    fn add_block_cursors(&mut self, block: &Block) {
+        self.kernel.add_block_cursors(block)
+    }
+
+    #[inline]
+    fn add_cursor(&mut self, pos: usize) {
+        self.kernel.add_cursor(pos)
+    }
+
+    #[inline]
+    fn clear_cursors(&mut self) -> usize {
+        self.kernel.clear_cursors()
+    }
+
+    #[inline]
+    fn cursors(&self) -> &[usize] {
+        self.kernel.cursors()
+    }
+
     #[inline]
     fn copy_mark(&self, mark: Mark) -> Vec<char> {
         self.kernel.copy_mark(mark)
     }
 
+    #[inline]
+    fn copy_block(&self, block: &Block) -> Vec<char> {
+        self.kernel.copy_block(block)
+    }
+
     #[inline]
     fn copy_line(&self) -> Vec<char> {
         self.kernel.copy_line()
@@ -1109,16 +2002,6 @@ impl ImmutableEditor for Editor {
     fn render(&mut self) {
         self.kernel.render();
     }
-
-    #[inline]
-    fn set_last_match(&mut self, pos: usize, pattern: Box<dyn Pattern>) {
-        self.kernel.set_last_match(pos, pattern);
-    }
-
-    #[inline]
-    fn take_last_match(&mut self) -> Option<(usize, Box<dyn Pattern>)> {
-        self.kernel.take_last_match()
-    }
 }
 
 impl ImmutableEditor for EditorKernel {
@@ -1126,6 +2009,11 @@ impl ImmutableEditor for EditorKernel {
         Editor {
             kernel: self.clone_kernel(source),
             readonly: false,
+            locked: false,
+            protected: false,
+            crypt: None,
+            loader: None,
+            stale: None,
         }
     }
 
@@ -1137,6 +2025,21 @@ impl ImmutableEditor for EditorKernel {
         self.source = source;
     }
 
+    fn label(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| self.source.to_string())
+    }
+
+    fn set_label(&mut self, label: Option<String>) {
+        if label != self.label {
+            self.label = label;
+            if self.rows > 0 {
+                self.show_banner();
+            }
+        }
+    }
+
     #[inline]
     fn buffer(&self) -> Ref<'_, Buffer> {
         self.buffer.borrow()
@@ -1153,12 +2056,14 @@ impl ImmutableEditor for EditorKernel {
 
     #[inline]
     fn cursor(&self) -> Point {
-        self.cursor
+        let col = self.cursor.col + self.virtual_cols;
+        Point::new(self.cursor.row, col.saturating_sub(self.hscroll))
     }
 
     #[inline]
     fn location(&self) -> Point {
-        Point::new(self.cur_line.line, self.cur_line.line_col(self.cursor.col))
+        let col = (self.cur_pos - self.cur_line.line_pos) as u32 + self.virtual_cols;
+        Point::new(self.cur_line.line, col)
     }
 
     fn rows(&self) -> u32 {
@@ -1181,6 +2086,112 @@ impl ImmutableEditor for EditorKernel {
         self.tab_hard = hard;
     }
 
+    fn get_tab_size(&self) -> u32 {
+        self.tab_cols
+    }
+
+    fn set_tab_size(&mut self, cols: u32) {
+        self.tab_cols = cmp::max(cols, 1);
+    }
+
+    fn get_fill(&self) -> u32 {
+        self.fill_cols
+    }
+
+    fn set_fill(&mut self, cols: u32) {
+        self.fill_cols = cmp::max(cols, 1);
+    }
+
+    fn get_indent(&self) -> bool {
+        self.auto_indent
+    }
+
+    fn set_indent(&mut self, auto: bool) {
+        self.auto_indent = auto;
+    }
+
+    fn set_syntax(&mut self, syntax: Syntax) {
+        *self.tokenizer_mut() = Tokenizer::new(syntax);
+        self.header = None;
+        self.possibly_tokenize(true);
+        if self.rows > 0 {
+            self.render();
+        }
+    }
+
+    fn get_redact(&self) -> bool {
+        self.redact
+    }
+
+    fn set_redact(&mut self, redact: bool) {
+        if redact != self.redact {
+            // Concealed characters always occupy a single column, so rows may wrap
+            // differently.
+            self.redact = redact;
+            if self.rows > 0 {
+                self.align_cursor(Align::Auto);
+                self.render();
+            }
+        }
+    }
+
+    fn get_wrap(&self) -> bool {
+        self.wrap
+    }
+
+    fn set_wrap(&mut self, wrap: bool) {
+        if wrap != self.wrap {
+            self.wrap = wrap;
+            self.hscroll = 0;
+            if self.rows > 0 {
+                self.align_cursor(Align::Auto);
+                self.render();
+            }
+        }
+    }
+
+    fn get_virtual(&self) -> bool {
+        self.virtual_edit
+    }
+
+    fn set_virtual(&mut self, virtual_edit: bool) {
+        if virtual_edit != self.virtual_edit {
+            self.virtual_edit = virtual_edit;
+            if self.virtual_cols > 0 {
+                self.virtual_cols = 0;
+                if self.rows > 0 {
+                    self.render();
+                }
+            }
+        }
+    }
+
+    fn get_bom(&self) -> bool {
+        self.bom
+    }
+
+    fn set_bom(&mut self, bom: bool) {
+        if bom != self.bom {
+            self.bom = bom;
+            self.banner.borrow_mut().set_bom(bom).draw();
+        }
+    }
+
+    fn annotate(&mut self, lane: Lane, ranges: Vec<Range<usize>>, priority: u8, style: Style) {
+        let mut lanes = self.lanes.borrow_mut();
+        for range in ranges {
+            lanes.add(lane, range, priority, style);
+        }
+    }
+
+    fn add_hint(&mut self, lane: Lane, pos: usize, text: &str, style: Style) {
+        self.lanes.borrow_mut().add_hint(lane, pos, text, style);
+    }
+
+    fn clear_annotations(&mut self, lane: Lane) {
+        self.lanes.borrow_mut().clear(lane);
+    }
+
     fn set_focus(&mut self, cursor: Point) {
         // Ensure target cursor is bounded by effective area of canvas, which takes
         // into account left margin if enabled.
@@ -1188,16 +2199,17 @@ impl ImmutableEditor for EditorKernel {
         let try_col = if cursor.col < self.margin_cols {
             0
         } else {
-            cmp::min(cursor.col - self.margin_cols, self.cols)
+            cmp::min(cursor.col - self.margin_cols, self.cols) + self.hscroll
         };
 
         // Find effective cursor location and buffer position by moving down from
         // top line of display.
         self.cur_line = self.top_line.clone();
         let row = self.down_cur_line(try_row);
-        let col = self.cur_line.snap_col(try_col, self.cols);
-        self.snap_col = Some(col);
-        self.cur_pos = self.cur_line.pos_of(col);
+        let (pos, col) = self.snap_col(&self.cur_line, try_col);
+        self.virtual_cols = self.virtual_cols_of(try_col, col);
+        self.snap_col = Some(col + self.virtual_cols);
+        self.cur_pos = pos;
         self.cursor = Point::new(row, col);
     }
 
@@ -1205,6 +2217,7 @@ impl ImmutableEditor for EditorKernel {
         let is_zombie = window.borrow().is_zombie();
         self.canvas = window.borrow().canvas().clone();
         self.banner = window.borrow().banner().clone();
+        self.ruler = window.borrow().ruler().cloned();
 
         // Allocate leftmost columns of window to line numbers, but only if enabled and
         // total width of window is large enough to reasonably accommodate.
@@ -1227,6 +2240,15 @@ impl ImmutableEditor for EditorKernel {
         self.attach(Window::zombie().to_ref(), Align::Auto);
     }
 
+    fn set_active(&mut self, active: bool) {
+        if active != self.active {
+            self.active = active;
+            if self.rows > 0 {
+                self.render();
+            }
+        }
+    }
+
     fn align_cursor(&mut self, align: Align) {
         // Determine ideal row where cursor would like to be focused, though this should
         // be considered a hint.
@@ -1242,28 +2264,36 @@ impl ImmutableEditor for EditorKernel {
         // of row to top of buffer.
         self.cur_line = self.find_line(self.cur_pos);
         let row = self.set_top_line(try_row);
-        let col = self.cur_line.col_of(self.cur_pos);
+        let col = self.col_of(&self.cur_line, self.cur_pos);
         self.snap_col = None;
+        self.virtual_cols = 0;
         self.align_syntax();
         self.cursor = Point::new(row, col);
     }
 
     fn draw(&mut self) {
         self.canvas.borrow_mut().clear();
+        if let Some(ruler) = &self.ruler {
+            ruler.borrow_mut().redraw();
+        }
         self.show_banner();
         self.render();
     }
 
     fn show_cursor(&mut self) {
         let cursor = if self.margin_cols > 0 {
-            self.cursor + Size::cols(self.margin_cols)
+            self.cursor() + Size::cols(self.margin_cols)
         } else {
-            self.cursor
+            self.cursor()
         };
         self.canvas.borrow_mut().set_cursor(cursor);
     }
 
     fn move_backward(&mut self, len: usize) {
+        let n = cmp::min(len, self.virtual_cols as usize);
+        self.virtual_cols -= n as u32;
+        self.snap_col = None;
+        let len = len - n;
         let pos = self.cur_pos - cmp::min(len, self.cur_pos);
         if pos < self.cur_pos {
             self.move_to(pos, Align::Auto);
@@ -1271,9 +2301,17 @@ impl ImmutableEditor for EditorKernel {
     }
 
     fn move_forward(&mut self, len: usize) {
-        let pos = cmp::min(self.cur_pos + len, self.buffer().size());
-        if pos > self.cur_pos {
-            self.move_to(pos, Align::Auto);
+        if self.is_virtual_end() {
+            // Cursor moves into empty space past end of line, though never beyond the
+            // right edge of the display when lines are wrapped.
+            let limit = self.line_cols() - 1 - self.cursor.col;
+            self.virtual_cols = cmp::min(self.virtual_cols.saturating_add(len as u32), limit);
+            self.snap_col = None;
+        } else {
+            let pos = cmp::min(self.cur_pos + len, self.buffer().size());
+            if pos > self.cur_pos {
+                self.move_to(pos, Align::Auto);
+            }
         }
     }
 
@@ -1313,10 +2351,14 @@ impl ImmutableEditor for EditorKernel {
                     self.cursor.row - rows
                 }
             };
-            let try_col = self.snap_col.take().unwrap_or(self.cursor.col);
+            let try_col = self
+                .snap_col
+                .take()
+                .unwrap_or(self.cursor.col + self.virtual_cols);
             self.snap_col = Some(try_col);
-            let col = self.cur_line.snap_col(try_col, self.cols);
-            self.cur_pos = self.cur_line.pos_of(col);
+            let (pos, col) = self.snap_col(&self.cur_line, try_col);
+            self.cur_pos = pos;
+            self.virtual_cols = self.virtual_cols_of(try_col, col);
             self.align_syntax();
             self.cursor = Point::new(row, col);
         }
@@ -1339,10 +2381,14 @@ impl ImmutableEditor for EditorKernel {
                     self.set_top_line(self.rows - 1)
                 }
             };
-            let try_col = self.snap_col.take().unwrap_or(self.cursor.col);
+            let try_col = self
+                .snap_col
+                .take()
+                .unwrap_or(self.cursor.col + self.virtual_cols);
             self.snap_col = Some(try_col);
-            let col = self.cur_line.snap_col(try_col, self.cols);
-            self.cur_pos = self.cur_line.pos_of(col);
+            let (pos, col) = self.snap_col(&self.cur_line, try_col);
+            self.cur_pos = pos;
+            self.virtual_cols = self.virtual_cols_of(try_col, col);
             self.align_syntax();
             self.cursor = Point::new(row, col);
         }
@@ -1354,15 +2400,17 @@ impl ImmutableEditor for EditorKernel {
             self.cursor.col = 0;
         }
         self.snap_col = None;
+        self.virtual_cols = 0;
     }
 
     fn move_end(&mut self) {
-        let end_col = self.cur_line.end_col(self.cols);
+        let (end_pos, end_col) = self.end_col(&self.cur_line);
         if self.cursor.col < end_col {
-            self.cur_pos = self.cur_line.pos_of(end_col);
+            self.cur_pos = end_pos;
             self.cursor.col = end_col;
         }
         self.snap_col = None;
+        self.virtual_cols = 0;
     }
 
     fn move_top(&mut self) {
@@ -1379,6 +2427,16 @@ impl ImmutableEditor for EditorKernel {
         self.move_to(pos, align);
     }
 
+    fn move_line_col(&mut self, line: u32, col: u32, align: Align) {
+        let line_pos = self.buffer().find_line(line);
+        let end_pos = match self.buffer().find_next_line(line_pos) {
+            (pos, false) => pos - 1,
+            (pos, true) => pos,
+        };
+        let pos = cmp::min(line_pos + col as usize, end_pos);
+        self.move_to(pos, align);
+    }
+
     fn move_to(&mut self, pos: usize, align: Align) {
         let row = if pos < self.top_line.row_pos {
             self.find_up_cur_line(pos);
@@ -1428,12 +2486,59 @@ impl ImmutableEditor for EditorKernel {
             self.set_top_line(row)
         };
         self.cur_pos = pos;
-        let col = self.cur_line.col_of(self.cur_pos);
+        let col = self.col_of(&self.cur_line, self.cur_pos);
         self.snap_col = None;
+        self.virtual_cols = 0;
         self.align_syntax();
         self.cursor = Point::new(row, col);
     }
 
+    fn find_bracket(&self, pos: usize, limit: usize) -> Option<usize> {
+        let (open, close, forward) = match self.buffer().get_char(pos)? {
+            '(' => ('(', ')', true),
+            '[' => ('[', ']', true),
+            '{' => ('{', '}', true),
+            ')' => ('(', ')', false),
+            ']' => ('[', ']', false),
+            '}' => ('{', '}', false),
+            _ => return None,
+        };
+
+        // Depth is incremented for each bracket that opens a nested pair in the
+        // direction of scanning, so a match is found when depth returns to zero.
+        let (nest, unnest) = if forward {
+            (open, close)
+        } else {
+            (close, open)
+        };
+        let mut depth = 0;
+        let mut is_match = |c: char| {
+            if c == nest {
+                depth += 1;
+            } else if c == unnest {
+                depth -= 1;
+            }
+            depth == 0
+        };
+
+        let buffer = self.buffer();
+        if forward {
+            buffer
+                .forward(pos)
+                .index()
+                .take(limit)
+                .find(|(_, c)| is_match(*c))
+                .map(|(pos, _)| pos)
+        } else {
+            buffer
+                .backward(pos + 1)
+                .index()
+                .take(limit)
+                .find(|(_, c)| is_match(*c))
+                .map(|(pos, _)| pos)
+        }
+    }
+
     fn scroll_up(&mut self, try_rows: u32) {
         let rows = self.down_top_line(try_rows);
         if rows > 0 {
@@ -1441,10 +2546,14 @@ impl ImmutableEditor for EditorKernel {
                 // Cursor would have moved beyond top of display, which means current
                 // buffer position changes accordingly.
                 self.cur_line = self.top_line.clone();
-                let try_col = self.snap_col.take().unwrap_or(self.cursor.col);
+                let try_col = self
+                    .snap_col
+                    .take()
+                    .unwrap_or(self.cursor.col + self.virtual_cols);
                 self.snap_col = Some(try_col);
-                let col = self.cur_line.snap_col(try_col, self.cols);
-                self.cur_pos = self.cur_line.pos_of(col);
+                let (pos, col) = self.snap_col(&self.cur_line, try_col);
+                self.cur_pos = pos;
+                self.virtual_cols = self.virtual_cols_of(try_col, col);
                 (0, col)
             } else {
                 // Cursor still visible on display.
@@ -1466,10 +2575,14 @@ impl ImmutableEditor for EditorKernel {
                 // Cursor would have moved beyond bottom of display, which means current
                 // buffer position changes accordingly.
                 self.up_cur_line(row - self.rows + 1);
-                let try_col = self.snap_col.take().unwrap_or(self.cursor.col);
+                let try_col = self
+                    .snap_col
+                    .take()
+                    .unwrap_or(self.cursor.col + self.virtual_cols);
                 self.snap_col = Some(try_col);
-                let col = self.cur_line.snap_col(try_col, self.cols);
-                self.cur_pos = self.cur_line.pos_of(col);
+                let (pos, col) = self.snap_col(&self.cur_line, try_col);
+                self.cur_pos = pos;
+                self.virtual_cols = self.virtual_cols_of(try_col, col);
                 (self.rows - 1 as u32, col)
             };
             self.align_syntax();
@@ -1478,6 +2591,7 @@ impl ImmutableEditor for EditorKernel {
     }
 
     fn set_hard_mark(&mut self) -> Option<Mark> {
+        self.block = false;
         self.mark.replace(Mark(self.cur_pos, false))
     }
 
@@ -1486,6 +2600,7 @@ impl ImmutableEditor for EditorKernel {
             if soft {
                 None
             } else {
+                self.block = false;
                 self.mark = Some(Mark(self.cur_pos, true));
                 Some(mark)
             }
@@ -1501,6 +2616,7 @@ impl ImmutableEditor for EditorKernel {
             if soft {
                 None
             } else {
+                self.block = false;
                 self.mark = Some(Mark(pos, true));
                 Some(mark)
             }
@@ -1519,14 +2635,79 @@ impl ImmutableEditor for EditorKernel {
     }
 
     fn clear_mark(&mut self) -> Option<Mark> {
+        self.block = false;
         self.mark.take()
     }
 
+    fn set_block_mark(&mut self) -> Option<Mark> {
+        let mark = self.set_hard_mark();
+        self.block = true;
+        mark
+    }
+
+    fn get_block(&self) -> Option<Block> {
+        match self.mark {
+            Some(Mark(pos, _)) if self.block => {
+                let line = self.buffer().line_of(pos);
+                let col = (pos - self.buffer().find_start_line(pos)) as u32;
+                let cur = self.location();
+                Some(Block {
+                    lines: cmp::min(line, cur.row)..cmp::max(line, cur.row) + 1,
+                    cols: cmp::min(col, cur.col)..cmp::max(col, cur.col),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn add_block_cursors(&mut self, block: &Block) {
+        let col = block.cols.start as usize;
+        let mut ranges = self.block_ranges(block).into_iter();
+        if let Some((_, range)) = ranges.next() {
+            self.move_to(range.start, Align::Auto);
+        }
+        for (line_pos, range) in ranges {
+            if range.start == line_pos + col {
+                self.add_cursor(range.start);
+            }
+        }
+    }
+
+    fn add_cursor(&mut self, pos: usize) {
+        let pos = cmp::min(pos, self.buffer().size());
+        if pos != self.cur_pos {
+            if let Err(i) = self.cursors.binary_search(&pos) {
+                self.cursors.insert(i, pos);
+            }
+        }
+    }
+
+    fn clear_cursors(&mut self) -> usize {
+        let n = self.cursors.len();
+        self.cursors.clear();
+        n
+    }
+
+    fn cursors(&self) -> &[usize] {
+        &self.cursors
+    }
+
     fn copy_mark(&self, mark: Mark) -> Vec<char> {
         let Range { start, end } = self.get_mark_range(mark);
         self.copy(start, end)
     }
 
+    fn copy_block(&self, block: &Block) -> Vec<char> {
+        let mut text = Vec::new();
+        for (i, (_, Range { start, end })) in self.block_ranges(block).into_iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            text.extend(self.copy(start, end));
+        }
+        text
+    }
+
     fn copy_line(&self) -> Vec<char> {
         let Range { start, end } = self.cur_line.line_range();
         self.copy(start, end)
@@ -1538,6 +2719,8 @@ impl ImmutableEditor for EditorKernel {
 
     fn undo(&mut self) -> bool {
         if let Some(change) = self.undo.pop() {
+            self.undo_chars -= change.size();
+            self.undo_memory -= change.memory();
             self.undo_change(&change);
             self.redo.push(change);
             true
@@ -1549,6 +2732,8 @@ impl ImmutableEditor for EditorKernel {
     fn redo(&mut self) -> bool {
         if let Some(change) = self.redo.pop() {
             self.redo_change(&change);
+            self.undo_chars += change.size();
+            self.undo_memory += change.memory();
             self.undo.push(change);
             true
         } else {
@@ -1572,11 +2757,15 @@ impl ImmutableEditor for EditorKernel {
         } else {
             self.mark = None;
         }
+        self.block = false;
     }
 
     fn tokenize(&mut self) -> bool {
         if self.tokenize_clock < self.clock {
-            self.possibly_tokenize(true);
+            self.possibly_tokenize(false);
+            true
+        } else if self.tokenizer().is_rescanning() {
+            self.rescan();
             true
         } else {
             false
@@ -1585,6 +2774,7 @@ impl ImmutableEditor for EditorKernel {
 
     fn render(&mut self) {
         // Renders visible buffer content.
+        self.scroll_cursor();
         let draw = Draw::new(&self);
         let render = Render::new(&self);
         let rest = self
@@ -1595,6 +2785,18 @@ impl ImmutableEditor for EditorKernel {
         if let Some(render) = rest {
             self.render_rest(&draw, render);
         }
+
+        // Overlays top row with enclosing section, but only if the cursor would not be
+        // obscured.
+        if self.active
+            && self.config.settings.sticky_header
+            && self.cursor.row > 0
+            && self.tokenizer().syntax().has_outline()
+        {
+            if let Some((line, text)) = self.find_header() {
+                self.render_header(&draw, line, &text);
+            }
+        }
         self.canvas.borrow_mut().draw();
 
         // Renders additional information.
@@ -1602,21 +2804,30 @@ impl ImmutableEditor for EditorKernel {
             .borrow_mut()
             .set_dirty(self.dirty)
             .set_location(self.location())
+            .set_selection(self.selection_info())
             .draw();
-    }
-
-    fn set_last_match(&mut self, pos: usize, pattern: Box<dyn Pattern>) {
-        self.last_match = Some((pos, pattern));
-    }
-
-    fn take_last_match(&mut self) -> Option<(usize, Box<dyn Pattern>)> {
-        self.last_match.take()
+        if let Some(ruler) = &self.ruler {
+            ruler
+                .borrow_mut()
+                .set_margin(self.margin_cols)
+                .set_offset(self.hscroll)
+                .set_column(self.cursor().col)
+                .draw();
+        }
     }
 }
 
 impl MutableEditor for EditorKernel {
     fn insert_char(&mut self, c: char) {
-        self.insert_normal(&[c])
+        if self.cursors.is_empty() {
+            self.insert_normal(&[c])
+        } else {
+            self.edit_cursors(|this| {
+                let pos = this.cur_pos;
+                this.insert_internal(&[c], None);
+                Some(Change::Insert(pos, vec![c]))
+            })
+        }
     }
 
     fn insert_str(&mut self, text: &str) {
@@ -1636,25 +2847,148 @@ impl MutableEditor for EditorKernel {
         self.insert_normal(text);
     }
 
+    fn insert_each(&mut self, texts: &[Vec<char>]) {
+        // Cursors are visited from the bottom of the buffer, so texts are consumed in
+        // reverse order starting with the last cursor.
+        let mut index = self.cursors.len() + 1;
+        self.edit_cursors(|this| {
+            index -= 1;
+            match texts.get(index) {
+                Some(text) if !text.is_empty() => {
+                    let pos = this.cur_pos;
+                    this.insert_internal(text, None);
+                    Some(Change::Insert(pos, text.clone()))
+                }
+                _ => None,
+            }
+        })
+    }
+
     fn remove_before(&mut self) -> Vec<char> {
-        if self.cur_pos > 0 {
+        if self.virtual_cols > 0 && self.cursors.is_empty() {
+            self.virtual_cols -= 1;
+            vec![]
+        } else if !self.cursors.is_empty() {
+            self.edit_cursors(|this| {
+                let pos = this.cur_pos;
+                if pos > 0 {
+                    let text = this.remove_internal(pos - 1, None);
+                    Some(Change::RemoveBefore(pos, text))
+                } else {
+                    None
+                }
+            });
+            vec![]
+        } else if self.cur_pos > 0 {
             self.remove(self.cur_pos - 1)
         } else {
             vec![]
         }
-    }
-
-    fn remove_after(&mut self) -> Vec<char> {
-        if self.cur_pos < self.buffer().size() {
-            self.remove(self.cur_pos + 1)
-        } else {
-            vec![]
+    }
+
+    fn remove_after(&mut self) -> Vec<char> {
+        if !self.cursors.is_empty() {
+            self.edit_cursors(|this| {
+                let pos = this.cur_pos;
+                if pos < this.buffer().size() {
+                    let text = this.remove_internal(pos + 1, None);
+                    Some(Change::RemoveAfter(pos, text))
+                } else {
+                    None
+                }
+            });
+            vec![]
+        } else if self.cur_pos < self.buffer().size() {
+            self.remove(self.cur_pos + 1)
+        } else {
+            vec![]
+        }
+    }
+
+    fn remove_backward_word(&mut self) -> Vec<char> {
+        let pos = self.find_word_before(self.cur_pos);
+        self.remove_internal(pos, Some(Log::Word))
+    }
+
+    fn remove_forward_word(&mut self) -> Vec<char> {
+        let pos = self.find_word_after(self.cur_pos);
+        self.remove_internal(pos, Some(Log::Word))
+    }
+
+    fn remove_mark(&mut self, mark: Mark) -> Vec<char> {
+        let Mark(pos, soft) = mark;
+        self.remove_internal(pos, Some(Log::Selection(soft)))
+    }
+
+    fn remove_block(&mut self, block: &Block) -> Vec<char> {
+        let text = self.copy_block(block);
+        let ranges = self.block_ranges(block);
+
+        // Removals proceed from the bottom of the block to the top so that positions
+        // of lines yet to be visited are unaffected.
+        let mut changes = Vec::new();
+        for (_, Range { start, end }) in ranges.iter().cloned().rev() {
+            if start < end {
+                self.move_to(start, Align::Auto);
+                let text = self.remove_internal(end, None);
+                changes.push(Change::RemoveAfter(start, text));
+            }
+        }
+        if let Some((_, range)) = ranges.first() {
+            self.move_to(range.start, Align::Auto);
+        }
+        if !changes.is_empty() {
+            self.log(Change::Group(changes));
+        }
+        text
+    }
+
+    fn insert_block(&mut self, text: &[char]) {
+        let col = self.location().col as usize;
+        let top_pos = self.cur_line.line_pos + col;
+        let mut rows = text.split(|c| *c == '\n');
+
+        // Determine insertion position and text for each line, noting that lines
+        // shorter than the column are padded with spaces. Lines beyond the end of the
+        // buffer are combined into a single insertion.
+        let mut inserts = Vec::new();
+        let mut line_pos = self.cur_line.line_pos;
+        for row in rows.by_ref() {
+            let (next_pos, bottom) = self.buffer().find_next_line(line_pos);
+            let end_pos = if bottom { next_pos } else { next_pos - 1 };
+            let pos = cmp::min(line_pos + col, end_pos);
+            let mut text = vec![' '; line_pos + col - pos];
+            text.extend_from_slice(row);
+            inserts.push((pos, text));
+            if bottom {
+                break;
+            }
+            line_pos = next_pos;
+        }
+        let mut tail = Vec::new();
+        for row in rows {
+            tail.push('\n');
+            tail.extend(" ".repeat(col).chars());
+            tail.extend_from_slice(row);
+        }
+        if let Some((_, text)) = inserts.last_mut() {
+            text.extend(tail);
         }
-    }
 
-    fn remove_mark(&mut self, mark: Mark) -> Vec<char> {
-        let Mark(pos, soft) = mark;
-        self.remove_internal(pos, Some(Log::Selection(soft)))
+        // Insertions proceed from the bottom of the buffer to the top so that
+        // positions of lines yet to be visited are unaffected.
+        let mut changes = Vec::new();
+        for (pos, text) in inserts.iter().rev() {
+            if !text.is_empty() {
+                self.move_to(*pos, Align::Auto);
+                self.insert_internal(text, None);
+                changes.push(Change::Insert(*pos, text.clone()));
+            }
+        }
+        if !changes.is_empty() {
+            self.move_to(top_pos, Align::Auto);
+            self.log(Change::Group(changes));
+        }
     }
 
     fn remove_line(&mut self) -> Vec<char> {
@@ -1678,6 +3012,119 @@ impl MutableEditor for EditorKernel {
     fn remove(&mut self, pos: usize) -> Vec<char> {
         self.remove_internal(pos, Some(Log::Normal))
     }
+
+    fn replace(&mut self, pos: usize, text: &[char]) -> Vec<char> {
+        let removed = self.remove_internal(pos, None);
+        let start_pos = self.cur_pos;
+        self.insert_internal(text, None);
+
+        let mut changes = Vec::new();
+        if !removed.is_empty() {
+            changes.push(Change::RemoveAfter(start_pos, removed.clone()));
+        }
+        if !text.is_empty() {
+            changes.push(Change::Insert(start_pos, text.to_vec()));
+        }
+        if !changes.is_empty() {
+            self.log(Change::Group(changes));
+        }
+        removed
+    }
+
+    fn duplicate_lines(&mut self) {
+        let Range { start, end } = self.line_span();
+        let mut text = self.copy(start, end);
+
+        // Lines at the bottom of the buffer without a trailing line break are
+        // separated from the copy by inserting one.
+        if text.last() != Some(&'\n') {
+            text.insert(0, '\n');
+        }
+        let (cur_pos, mark) = (self.cur_pos, self.mark);
+        self.move_to(end, Align::Auto);
+        self.insert_internal(&text, None);
+        self.log(Change::Insert(end, text.clone()));
+        self.follow_lines(cur_pos, mark, text.len() as isize);
+    }
+
+    fn move_lines(&mut self, up: bool) -> bool {
+        let Range { start, end } = self.line_span();
+        let mut lines = self.copy(start, end);
+        let (from, to, text, delta) = if up {
+            if start == 0 {
+                return false;
+            }
+            let from = self.buffer().find_start_line(start - 1);
+            let mut above = self.copy(from, start);
+            let delta = -(above.len() as isize);
+            if lines.last() != Some(&'\n') {
+                lines.push('\n');
+                above.pop();
+            }
+            lines.extend(above);
+            (from, end, lines, delta)
+        } else {
+            if end >= self.buffer().size() {
+                return false;
+            }
+            let (to, _) = self.buffer().find_next_line(end);
+            let mut below = self.copy(end, to);
+            if below.last() != Some(&'\n') {
+                below.push('\n');
+                lines.pop();
+            }
+            let delta = below.len() as isize;
+            below.extend(lines);
+            (start, to, below, delta)
+        };
+        let (cur_pos, mark) = (self.cur_pos, self.mark);
+        self.move_to(from, Align::Auto);
+        self.replace(to, &text);
+        self.follow_lines(cur_pos, mark, delta);
+        true
+    }
+
+    fn join_lines(&mut self) -> bool {
+        let Range { start, mut end } = self.line_span();
+        if self.mark.is_none() && end < self.buffer().size() {
+            end = self.buffer().find_next_line(end).0;
+        }
+        let mut text = self.copy(start, end);
+        let newline = text.last() == Some(&'\n');
+        if newline {
+            text.pop();
+        }
+        let mut lines = text.split(|c| *c == '\n');
+        let mut joined = lines.next().map(|line| line.to_vec()).unwrap_or_default();
+        let mut join_pos = None;
+        for line in lines {
+            while joined.last().is_some_and(|c| c.is_whitespace()) {
+                joined.pop();
+            }
+            let line = match line.iter().position(|c| !c.is_whitespace()) {
+                Some(n) => &line[n..],
+                None => &[],
+            };
+            join_pos = Some(joined.len());
+            if !joined.is_empty() && !line.is_empty() {
+                joined.push(' ');
+            }
+            joined.extend_from_slice(line);
+        }
+
+        if let Some(join_pos) = join_pos {
+            if newline {
+                joined.push('\n');
+            }
+            self.clear_mark();
+            self.move_to(start, Align::Auto);
+            self.replace(end, &joined);
+            self.move_to(start + join_pos, Align::Auto);
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl EditorKernel {
@@ -1687,22 +3134,50 @@ impl EditorKernel {
     /// Exclusive upper bound on line numbers that can be displayed in the margin.
     const LINE_LIMIT: u32 = 10_u32.pow(Self::MARGIN_COLS - 1);
 
-    /// An upper bound on the tolerable number of milliseconds to tokenize the
-    /// buffer in real-time, otherwise the operation is deferred.
+    /// Maximum number of lines searched above the top row of the display for the
+    /// section shown as the sticky header.
+    const SECTION_SCAN_LINES: u32 = 5_000;
+
+    /// An upper bound on the tolerable number of milliseconds to tokenize the entire
+    /// buffer between keystrokes, otherwise only regions affected by changes are
+    /// tokenized.
     const TOKENIZE_COST_LIMIT: u128 = 50;
 
+    /// Maximum number of characters of the first line of a file considered when
+    /// detecting its syntax configuration.
+    const FIRST_LINE_LIMIT: usize = 1024;
+
+    /// Number of columns at which lines are wrapped when line wrapping is disabled,
+    /// which is large enough to be practically unbounded.
+    const NOWRAP_COLS: u32 = u32::MAX / 2;
+
     /// Creates a new editor using `source` and an optional `buffer`, which if `None`
     /// automatically creates an empty buffer.
-    fn new(config: ConfigurationRef, source: Source, buffer: Option<Buffer>) -> EditorKernel {
+    ///
+    /// If `syntax` is `None`, then the syntax configuration is derived from `source`.
+    fn new(
+        config: ConfigurationRef,
+        source: Source,
+        buffer: Option<Buffer>,
+        syntax: Option<Syntax>,
+    ) -> EditorKernel {
         let buffer = buffer.unwrap_or_else(|| Buffer::new()).to_ref();
         let cur_pos = buffer.borrow().get_pos();
 
         // Constructs syntax configuration based on type of buffer and file extension,
-        // if applicable.
-        let syntax = if let Source::File(path, _) = &source {
+        // if applicable, otherwise the first line of files.
+        let syntax = if let Some(syntax) = syntax {
+            syntax
+        } else if let Source::File(path, _) = &source {
+            let first_line = {
+                let buffer = buffer.borrow();
+                let (end_pos, _) = buffer.find_next_line(0);
+                let text = buffer.copy_as_string(0, cmp::min(end_pos, Self::FIRST_LINE_LIMIT));
+                text.trim_end_matches(['\n', '\r']).to_string()
+            };
             config
                 .registry
-                .find(path)
+                .detect(path, &first_line)
                 .map(|syntax| syntax.clone())
                 .unwrap_or_else(|| Syntax::default())
         } else if let Source::Ephemeral(_) = &source {
@@ -1724,15 +3199,24 @@ impl EditorKernel {
         // Additional settings.
         let tab_hard = config.settings.tab_hard;
         let tab_cols = config.settings.tab_size as u32;
+        let fill_cols = config.settings.fill_column;
+        let auto_indent = config.settings.auto_indent;
+        let wrap = config.settings.wrap;
+        let virtual_edit = config.settings.virtual_edit;
+        let bom = config.settings.bom == ByteOrderMark::Add;
 
         EditorKernel {
             config,
             source,
+            label: None,
             buffer,
             clock: 0,
             undo: Vec::new(),
+            undo_chars: 0,
+            undo_memory: 0,
             redo: Vec::new(),
             tokenizer: tokenizer.to_ref(),
+            lanes: Lanes::new().to_ref(),
             tokenize_cost,
             tokenize_clock: 0,
             syntax_cursor,
@@ -1743,14 +3227,50 @@ impl EditorKernel {
             snap_col: None,
             cursor: Point::ORIGIN,
             mark: None,
+            block: false,
+            cursors: Vec::new(),
             canvas: Canvas::zero().to_ref(),
             banner: Banner::none().to_ref(),
+            ruler: None,
             rows: 0,
             cols: 0,
             margin_cols: 0,
             tab_hard,
             tab_cols,
-            last_match: None,
+            fill_cols,
+            auto_indent,
+            redact: false,
+            wrap,
+            hscroll: 0,
+            virtual_edit,
+            virtual_cols: 0,
+            bom,
+            readonly: false,
+            stale: false,
+            progress: None,
+            header: None,
+            active: false,
+        }
+    }
+
+    fn set_readonly(&mut self, readonly: bool) {
+        if readonly != self.readonly {
+            self.readonly = readonly;
+            self.banner.borrow_mut().set_readonly(readonly).draw();
+        }
+    }
+
+    fn set_stale(&mut self, stale: bool) {
+        if stale != self.stale {
+            self.stale = stale;
+            self.banner.borrow_mut().set_stale(stale).draw();
+        }
+    }
+
+    fn set_progress(&mut self, progress: Option<(&'static str, u8)>) {
+        if progress != self.progress {
+            self.progress = progress;
+            self.banner.borrow_mut().set_progress(progress);
         }
     }
 
@@ -1758,8 +3278,13 @@ impl EditorKernel {
     fn clone_kernel(&self, source: Source) -> EditorKernel {
         let mut buffer = self.buffer().clone();
         buffer.set_pos(self.cur_pos);
-        let mut editor = Self::new(self.config.clone(), source, Some(buffer));
+        let mut editor = Self::new(self.config.clone(), source, Some(buffer), None);
         editor.cursor = self.cursor;
+        editor.redact = self.redact;
+        editor.wrap = self.wrap;
+        editor.hscroll = self.hscroll;
+        editor.virtual_edit = self.virtual_edit;
+        editor.bom = self.bom;
         editor
     }
 
@@ -1792,11 +3317,49 @@ impl EditorKernel {
             .borrow_mut()
             .set_dirty(self.dirty)
             .set_source(self.source.clone())
+            .set_label(self.label())
             .set_syntax(self.tokenizer().syntax().name.clone())
+            .set_bom(self.bom)
+            .set_readonly(self.readonly)
+            .set_stale(self.stale)
+            .set_progress(self.progress)
             .set_location(self.location())
+            .set_selection(self.selection_info())
             .draw();
     }
 
+    /// Returns a tuple containing the number of characters and lines spanned by the
+    /// selection, or `None` if no selection is active or selection statistics are
+    /// disabled.
+    fn selection_info(&self) -> Option<(usize, usize)> {
+        if self.config.settings.selection_info {
+            if let Some(block) = self.get_block() {
+                let chars = self
+                    .block_ranges(&block)
+                    .iter()
+                    .map(|(_, range)| range.len())
+                    .sum();
+                return Some((chars, block.lines.len()));
+            }
+            self.mark.map(|Mark(mark_pos, _)| {
+                let (start, end) = if mark_pos < self.cur_pos {
+                    (mark_pos, self.cur_pos)
+                } else {
+                    (self.cur_pos, mark_pos)
+                };
+                let lines = self
+                    .buffer()
+                    .forward(start)
+                    .take(end - start)
+                    .filter(|c| *c == '\n')
+                    .count();
+                (end - start, lines + 1)
+            })
+        } else {
+            None
+        }
+    }
+
     /// Returns the position of the word that comes before `pos`.
     fn find_word_before(&self, pos: usize) -> usize {
         self.buffer()
@@ -1821,9 +3384,99 @@ impl EditorKernel {
             .unwrap_or(self.buffer().size())
     }
 
+    /// Appends `text` to the end of the buffer, where the cursor only follows if
+    /// already positioned at the end.
+    fn append(&mut self, text: &[char]) {
+        let end_pos = self.buffer().size();
+        let dirty = self.dirty;
+        if self.rows > 0 && self.cur_pos == end_pos {
+            self.insert_internal(text, None);
+        } else {
+            // Either the editor is detached or the cursor remains in place, so only
+            // buffer and tokenizer are updated, though lines on the display are
+            // refreshed in case the bottom line was extended.
+            self.buffer_mut().set_pos(end_pos);
+            self.buffer_mut().insert(text);
+            self.lanes.borrow_mut().insert(end_pos, text.len());
+            self.syntax_cursor = {
+                let mut tokenizer = self.tokenizer_mut();
+                let cursor = tokenizer.find(self.syntax_cursor, end_pos);
+                tokenizer.insert(cursor, text.len())
+            };
+            if self.rows > 0 {
+                self.cur_line = self.update_line(&self.cur_line);
+                self.top_line = self.update_line(&self.top_line);
+            } else if self.cur_pos == end_pos {
+                self.cur_pos += text.len();
+            }
+            self.clock += 1;
+        }
+        self.dirty = dirty;
+    }
+
+    /// Applies `edit` at the current buffer position and at every secondary cursor,
+    /// recording the changes returned by `edit` in the undo stack as a single change.
+    ///
+    /// Cursors are visited from the bottom of the buffer to the top so that the edit
+    /// at each cursor leaves positions of the cursors yet to be visited unaffected.
+    /// Cursors that coincide as a result of the edits are merged.
+    fn edit_cursors<F>(&mut self, mut edit: F)
+    where
+        F: FnMut(&mut EditorKernel) -> Option<Change>,
+    {
+        let cur_pos = self.cur_pos;
+        let mut cursors = mem::take(&mut self.cursors);
+        cursors.push(cur_pos);
+        cursors.sort();
+        cursors.dedup();
+
+        // Apply edit at each cursor, noting the resulting position of the cursor and
+        // the number of characters added or removed.
+        let mut changes = Vec::new();
+        let mut edits = Vec::new();
+        for &pos in cursors.iter().rev() {
+            self.move_to(pos, Align::Auto);
+            let size = self.buffer().size();
+            changes.extend(edit(self));
+            let delta = self.buffer().size() as isize - size as isize;
+            edits.push((self.cur_pos, delta, pos == cur_pos));
+        }
+
+        // Final position of each cursor is shifted by edits at all cursors above it,
+        // which were applied afterwards.
+        let mut cur_pos = cur_pos;
+        let mut shift = 0;
+        for (pos, delta, primary) in edits.into_iter().rev() {
+            let pos = (pos as isize + shift) as usize;
+            if primary {
+                cur_pos = pos;
+            } else {
+                self.cursors.push(pos);
+            }
+            shift += delta;
+        }
+        self.cursors.dedup();
+        self.cursors.retain(|&pos| pos != cur_pos);
+        self.move_to(cur_pos, Align::Auto);
+
+        if !changes.is_empty() {
+            self.log(Change::Group(changes));
+        }
+    }
+
     /// Inserts `text` such that the change is recorded in the undo stack.
+    ///
+    /// If the cursor is at a virtual position past the end of the line, the empty
+    /// space is first materialized as padding spaces that are recorded as part of
+    /// the same change.
     fn insert_normal(&mut self, text: &[char]) {
-        self.insert_internal(text, Some(Log::Normal));
+        if self.virtual_cols > 0 && !text.is_empty() {
+            let mut padded = vec![' '; self.virtual_cols as usize];
+            padded.extend_from_slice(text);
+            self.insert_internal(&padded, Some(Log::Normal));
+        } else {
+            self.insert_internal(text, Some(Log::Normal));
+        }
     }
 
     /// An internal workhorse to which all _insertion_ functions delegate.
@@ -1846,7 +3499,11 @@ impl EditorKernel {
                 self.log(Change::Insert(self.cur_pos, text.to_vec()));
             }
 
-            // Update tokenizer with insertion range.
+            // Update tokenizer, annotations and secondary cursors with insertion range.
+            self.lanes.borrow_mut().insert(self.cur_pos, text.len());
+            for pos in self.cursors.iter_mut().filter(|pos| **pos >= self.cur_pos) {
+                *pos += text.len();
+            }
             self.syntax_cursor = {
                 let mut tokenizer = self.tokenizer_mut();
                 let cursor = tokenizer.find(self.syntax_cursor, self.cur_pos);
@@ -1858,9 +3515,15 @@ impl EditorKernel {
             // so need to find new current line. Top line must also be updated even if
             // new cursor location is still visible because insertion may have changed
             // its attributes as well.
+            //
+            // The current line shifts to the prior row when an insertion at the start
+            // of a wrapped row allows characters to move up, though the cursor stays
+            // on the top row if already there, since the top line shifts as well.
+            let row_pos = self.cur_line.row_pos;
             self.cur_line = self.update_line(&self.cur_line);
+            let shift = self.cur_line.row_pos < row_pos && self.cursor.row > 0;
             let rows = self.find_down_cur_line(cur_pos);
-            let row = self.cursor.row + rows;
+            let row = self.cursor.row + rows - shift as u32;
             let row = if row < self.rows {
                 self.top_line = self.update_line(&self.top_line);
                 row
@@ -1868,12 +3531,13 @@ impl EditorKernel {
                 self.set_top_line(self.rows - 1)
             };
             self.cur_pos = cur_pos;
-            let col = self.cur_line.col_of(self.cur_pos);
+            let col = self.col_of(&self.cur_line, self.cur_pos);
             self.snap_col = None;
+            self.virtual_cols = 0;
             self.cursor = Point::new(row, col);
             self.dirty = true;
             self.clock += 1;
-            self.possibly_tokenize(false);
+            self.retokenize();
         }
     }
 
@@ -1930,6 +3594,13 @@ impl EditorKernel {
                             Change::RemoveAfter(self.cur_pos, text.clone())
                         });
                     }
+                    Log::Word => {
+                        self.log(if pos < self.cur_pos {
+                            Change::RemoveWordBefore(self.cur_pos, text.clone())
+                        } else {
+                            Change::RemoveWordAfter(self.cur_pos, text.clone())
+                        });
+                    }
                     Log::Selection(soft) => {
                         self.log(if pos < self.cur_pos {
                             Change::RemoveSelectionBefore(self.cur_pos, text.clone(), soft)
@@ -1940,7 +3611,12 @@ impl EditorKernel {
                 }
             }
 
-            // Update tokenizer with removal range.
+            // Update tokenizer, annotations and secondary cursors with removal range.
+            self.lanes.borrow_mut().remove_text(from_pos, text.len());
+            for pos in self.cursors.iter_mut().filter(|pos| **pos > from_pos) {
+                *pos = cmp::max(*pos - cmp::min(*pos, text.len()), from_pos);
+            }
+            self.cursors.dedup();
             self.syntax_cursor = {
                 let mut tokenizer = self.tokenizer_mut();
                 let cursor = tokenizer.find(self.syntax_cursor, from_pos);
@@ -1948,22 +3624,33 @@ impl EditorKernel {
             };
 
             // Removal of text requires current and top lines to be updated since may
-            // have changed.
+            // have changed. The current line shifts to the prior row when a removal at
+            // the start of a wrapped row allows characters to move up, though the
+            // cursor stays on the top row if already there, since the top line shifts
+            // as well.
+            let row_pos = self.cur_line.row_pos;
             self.cur_line = self.update_line(&self.cur_line);
             self.top_line = self.update_line(&self.top_line);
+            let row = if self.cur_line.row_pos < row_pos && row > 0 {
+                row - 1
+            } else {
+                row
+            };
             self.cur_pos = from_pos;
-            let col = self.cur_line.col_of(self.cur_pos);
+            let col = self.col_of(&self.cur_line, self.cur_pos);
             self.snap_col = None;
+            self.virtual_cols = 0;
             self.cursor = Point::new(row, col);
             self.dirty = true;
             self.clock += 1;
-            self.possibly_tokenize(false);
+            self.retokenize();
             text
         }
     }
 
-    /// Tokenizes the buffer if either the prior tokenization fell below the real-time
-    /// limit or `force` is `true`, otherwise the operation is not performed.
+    /// Tokenizes the entire buffer if either the prior tokenization of the entire
+    /// buffer fell below the real-time limit or `force` is `true`, otherwise only the
+    /// region affected by changes since the prior tokenization is tokenized.
     fn possibly_tokenize(&mut self, force: bool) {
         if force || self.tokenize_cost < Self::TOKENIZE_COST_LIMIT {
             self.syntax_cursor = {
@@ -1972,11 +3659,90 @@ impl EditorKernel {
                 self.tokenize_cost = timer.elapsed().as_millis();
                 cursor
             };
-            self.tokenize_clock = self.clock;
+            self.align_syntax();
+        } else {
+            self.retokenize();
+        }
+        self.tokenize_clock = self.clock;
+    }
+
+    /// Tokenizes the entire buffer in chunks until either finished or the real-time
+    /// limit is reached, in which case it resumes at the next opportunity, which
+    /// eventually corrects tokens that incremental tokenization does not recognize,
+    /// such as a multi-line comment closed after the point at which rescanning
+    /// started.
+    fn rescan(&mut self) {
+        let timer = Instant::now();
+        while timer.elapsed().as_millis() < Self::TOKENIZE_COST_LIMIT {
+            let cursor = self.tokenizer_mut().rescan(&self.buffer());
+            if let Some(cursor) = cursor {
+                self.syntax_cursor = cursor;
+                self.align_syntax();
+                break;
+            }
+        }
+    }
+
+    /// Tokenizes only the region of the buffer affected by changes since the prior
+    /// tokenization, which is cheap enough to perform as each change is made.
+    fn retokenize(&mut self) {
+        let cursor = self.tokenizer_mut().retokenize(&self.buffer());
+        if let Some(cursor) = cursor {
+            self.syntax_cursor = cursor;
         }
         self.align_syntax();
     }
 
+    /// Returns the range of whole lines spanned by the current buffer position and the
+    /// mark if set, otherwise the range of the current line, which includes the
+    /// trailing line break if present.
+    ///
+    /// A selection ending at the start of a line does not include that line.
+    fn line_span(&self) -> Range<usize> {
+        let buffer = self.buffer();
+        let (start, end) = match self.mark {
+            Some(Mark(pos, _)) => (cmp::min(pos, self.cur_pos), cmp::max(pos, self.cur_pos)),
+            None => (self.cur_pos, self.cur_pos),
+        };
+        let start = buffer.find_start_line(start);
+        let end = if end > start && buffer.get_char(end - 1) == Some('\n') {
+            end
+        } else {
+            buffer.find_next_line(end).0
+        };
+        start..end
+    }
+
+    /// Moves the cursor and `mark`, if set, to positions offset by `delta` from
+    /// `cur_pos` and the position of `mark`, respectively, which is how both follow
+    /// lines that were moved or copied.
+    fn follow_lines(&mut self, cur_pos: usize, mark: Option<Mark>, delta: isize) {
+        let shift = |pos: usize| pos.saturating_add_signed(delta);
+        self.move_to(shift(cur_pos), Align::Auto);
+        self.mark = mark.map(|Mark(pos, soft)| Mark(shift(pos), soft));
+        self.block = false;
+    }
+
+    /// Returns the position of each line in `block` paired with the range of positions
+    /// within the columns of the block, which is truncated at the end of lines
+    /// shorter than the block.
+    fn block_ranges(&self, block: &Block) -> Vec<(usize, Range<usize>)> {
+        let mut ranges = Vec::new();
+        let mut line_pos = self.buffer().find_line(block.lines.start);
+        for _ in block.lines.clone() {
+            let (next_pos, bottom) = self.buffer().find_next_line(line_pos);
+            let end_pos = if bottom { next_pos } else { next_pos - 1 };
+            let start = cmp::min(line_pos + block.cols.start as usize, end_pos);
+            let end = cmp::min(line_pos + block.cols.end as usize, end_pos);
+            ranges.push((line_pos, start..end));
+            if bottom {
+                break;
+            }
+            line_pos = next_pos;
+        }
+        ranges
+    }
+
     fn get_mark_range(&self, mark: Mark) -> Range<usize> {
         let Mark(pos, _) = mark;
         if pos < self.cur_pos {
@@ -1994,12 +3760,12 @@ impl EditorKernel {
                 self.move_to(*pos, Align::Auto);
                 self.remove_internal(pos + text.len(), None);
             }
-            Change::RemoveBefore(pos, text) => {
+            Change::RemoveBefore(pos, text) | Change::RemoveWordBefore(pos, text) => {
                 self.clear_mark();
                 self.move_to(pos - text.len(), Align::Auto);
                 self.insert_internal(text, None);
             }
-            Change::RemoveAfter(pos, text) => {
+            Change::RemoveAfter(pos, text) | Change::RemoveWordAfter(pos, text) => {
                 self.clear_mark();
                 self.move_to(*pos, Align::Auto);
                 self.insert_internal(text, None);
@@ -2024,6 +3790,11 @@ impl EditorKernel {
                 }
                 self.move_to(*pos, Align::Auto);
             }
+            Change::Group(changes) => {
+                for change in changes.iter().rev() {
+                    self.undo_change(change);
+                }
+            }
         }
     }
 
@@ -2035,12 +3806,12 @@ impl EditorKernel {
                 self.move_to(*pos, Align::Auto);
                 self.insert_internal(text, None);
             }
-            Change::RemoveBefore(pos, text) => {
+            Change::RemoveBefore(pos, text) | Change::RemoveWordBefore(pos, text) => {
                 self.clear_mark();
                 self.move_to(*pos, Align::Auto);
                 self.remove_internal(pos - text.len(), None);
             }
-            Change::RemoveAfter(pos, text) => {
+            Change::RemoveAfter(pos, text) | Change::RemoveWordAfter(pos, text) => {
                 self.clear_mark();
                 self.move_to(*pos, Align::Auto);
                 self.remove_internal(pos + text.len(), None);
@@ -2055,31 +3826,76 @@ impl EditorKernel {
                 self.move_to(*pos, Align::Auto);
                 self.remove_internal(pos + text.len(), None);
             }
+            Change::Group(changes) => {
+                for change in changes {
+                    self.redo_change(change);
+                }
+            }
         }
     }
 
+    fn usage(&self) -> Usage {
+        let chars_of = |changes: &Vec<Change>| changes.iter().map(|c| c.size()).sum();
+        let buffer = self.buffer();
+        Usage {
+            size: buffer.size(),
+            capacity: buffer.capacity(),
+            undo: (self.undo.len(), self.undo_chars),
+            redo: (self.redo.len(), chars_of(&self.redo)),
+            spans: self.tokenizer().span_count(),
+        }
+    }
+
+    fn compact(&mut self) {
+        self.trim_undo();
+        self.undo.shrink_to_fit();
+        self.redo.shrink_to_fit();
+        self.header = None;
+        self.buffer_mut().compact();
+    }
+
     /// Logs `change` by pushing it onto the _undo_ stack and clearing the _redo_
     /// stack.
     fn log(&mut self, change: Change) {
-        const UNDO_SOFT_LIMIT: usize = 1024;
-        const UNDO_HARD_LIMIT: usize = 1280;
-
         if let Some(top) = self.undo.pop() {
             if let Some(combined) = change.possibly_combine(&top) {
+                self.undo_chars += combined.size() - top.size();
+                self.undo_memory += combined.memory() - top.memory();
                 self.undo.push(combined);
             } else {
+                self.undo_chars += change.size();
+                self.undo_memory += change.memory();
                 self.undo.push(top);
                 self.undo.push(change);
             }
         } else {
+            self.undo_chars += change.size();
+            self.undo_memory += change.memory();
             self.undo.push(change);
         }
         self.redo.clear();
+        self.trim_undo();
+    }
 
-        // Trim undo stack to soft limit once size exceeds hard limit, as this avoids
-        // repeatedly trimming with every change.
-        if self.undo.len() > UNDO_HARD_LIMIT {
-            let n = self.undo.len() - UNDO_SOFT_LIMIT;
+    /// Discards the oldest changes in the _undo_ stack once the memory held by its
+    /// changes exceeds the budget prescribed by the `undo-memory` setting, though the
+    /// most recent change is always retained.
+    ///
+    /// Changes are discarded until the memory falls to three quarters of the budget,
+    /// as this avoids repeatedly trimming the stack with every change.
+    fn trim_undo(&mut self) {
+        let limit = self.config.settings.undo_memory as usize * 1_048_576;
+        if self.undo_memory > limit {
+            let target = limit / 4 * 3;
+            let mut n = 0;
+            for change in &self.undo[..self.undo.len() - 1] {
+                if self.undo_memory <= target {
+                    break;
+                }
+                self.undo_chars -= change.size();
+                self.undo_memory -= change.memory();
+                n += 1;
+            }
             self.undo.drain(0..n);
         }
     }
@@ -2142,9 +3958,179 @@ impl EditorKernel {
         try_rows
     }
 
+    /// Returns the number of columns at which lines are wrapped, which is effectively
+    /// unbounded when line wrapping is disabled.
+    #[inline]
+    fn line_cols(&self) -> u32 {
+        if self.wrap {
+            self.cols
+        } else {
+            Self::NOWRAP_COLS
+        }
+    }
+
+    /// Returns the number of columns occupied by `c` on the display, which is always
+    /// `1` when characters are concealed since all are shown as the same glyph, and
+    /// `2` for control characters shown in caret notation.
+    #[inline]
+    fn char_width(&self, c: char) -> u32 {
+        if self.redact {
+            1
+        } else if self.config.settings.control_chars.is_caret(c) {
+            2
+        } else {
+            unicode::width(c)
+        }
+    }
+
+    /// Returns a tuple containing the length and number of columns of the row that
+    /// starts at `row_pos`, where `end_pos` is the end of the buffer line.
+    ///
+    /// Characters are added to the row until the next character would extend beyond
+    /// the right edge of the display, which means that a wide character straddling
+    /// the edge is pushed to the next row. The first character of a row is always
+    /// accepted, so the row is never empty unless `row_pos` is at `end_pos`.
+    fn find_row(&self, row_pos: usize, end_pos: usize) -> (usize, u32) {
+        let cols = self.line_cols();
+        let mut row_len = 0;
+        let mut row_cols = 0;
+        for c in self.buffer().forward(row_pos).take(end_pos - row_pos) {
+            let width = self.char_width(c);
+            if row_cols >= cols || (row_cols > 0 && row_cols + width > cols) {
+                break;
+            }
+            row_len += 1;
+            row_cols += width;
+        }
+        (row_len, row_cols)
+    }
+
+    /// Returns a tuple containing the position, length and number of columns of the
+    /// row containing `pos`, where `line_pos` and `end_pos` are the bounds of the
+    /// buffer line.
+    ///
+    /// Rows are found by scanning forward from `line_pos`, since the point at which a
+    /// line wraps depends on the width of each character that precedes it. If `pos` is
+    /// at the end of the buffer and the last row is full, then the resulting row is
+    /// empty.
+    fn find_row_at(&self, line_pos: usize, end_pos: usize, pos: usize) -> (usize, usize, u32) {
+        let mut row_pos = line_pos;
+        loop {
+            let (row_len, row_cols) = self.find_row(row_pos, end_pos);
+            let next_pos = row_pos + row_len;
+            if pos < next_pos
+                || row_len == 0
+                || (next_pos == end_pos && row_cols < self.line_cols())
+            {
+                break (row_pos, row_len, row_cols);
+            }
+            row_pos = next_pos;
+        }
+    }
+
+    /// Returns a tuple containing the buffer position and column of the character on
+    /// the row of `line` that occupies `col`, or the right-most position and column of
+    /// the row if `col` extends beyond its end.
+    ///
+    /// In most cases, the right-most column aligns to the last character of the row,
+    /// which is usually `\n` but may also be any other character if the row wraps.
+    /// However, if this is the bottom-most row in the buffer, there is no terminating
+    /// `\n`, and thus the right-most column is right of the last character.
+    ///
+    /// A column in the middle of a wide character snaps to the start of that character,
+    /// and characters of zero width are never chosen, since those are always drawn
+    /// with the preceding character.
+    fn snap_col(&self, line: &Line, col: u32) -> (usize, u32) {
+        let mut end = (line.row_pos, 0);
+        let mut row_cols = 0;
+        for (pos, c) in self
+            .buffer()
+            .forward(line.row_pos)
+            .index()
+            .take(line.row_len)
+        {
+            let width = self.char_width(c);
+            if width > 0 {
+                if row_cols + width > col {
+                    return (pos, row_cols);
+                }
+                end = (pos, row_cols);
+                row_cols += width;
+            }
+        }
+        if line.is_bottom(self.line_cols()) {
+            (line.end_pos(), row_cols)
+        } else {
+            end
+        }
+    }
+
+    /// Returns a tuple containing the right-most buffer position and column of the row
+    /// of `line`.
+    ///
+    /// See [`snap_col`](Self::snap_col) for further details on calculating the
+    /// right-most column.
+    #[inline]
+    fn end_col(&self, line: &Line) -> (usize, u32) {
+        self.snap_col(line, u32::MAX)
+    }
+
+    /// Returns the column of `pos` relative to the start of the row of `line`, though
+    /// be advised that the resulting column may extend beyond the end of the row.
+    fn col_of(&self, line: &Line, pos: usize) -> u32 {
+        self.buffer()
+            .forward(line.row_pos)
+            .take(pos - line.row_pos)
+            .map(|c| self.char_width(c))
+            .sum()
+    }
+
+    /// Returns `true` if virtual editing is enabled and the cursor is at or beyond
+    /// the end of a line, which excludes rows that wrap.
+    fn is_virtual_end(&self) -> bool {
+        self.virtual_edit
+            && !self.cur_line.does_wrap()
+            && self.cursor.col >= self.end_col(&self.cur_line).1
+    }
+
+    /// Returns the number of virtual columns between `col`, which was snapped to the
+    /// current row, and the desired column `try_col`, which applies only when `col`
+    /// is the right-most column of the row.
+    fn virtual_cols_of(&self, try_col: u32, col: u32) -> u32 {
+        if self.virtual_edit && !self.cur_line.does_wrap() && col == self.end_col(&self.cur_line).1
+        {
+            try_col.saturating_sub(col)
+        } else {
+            0
+        }
+    }
+
+    /// Scrolls the display horizontally, if necessary, so that the cursor remains
+    /// visible when line wrapping is disabled.
+    ///
+    /// A column is kept in reserve on both sides of the cursor, or the wide character
+    /// under the cursor, since those columns may be occupied by continuation
+    /// indicators.
+    fn scroll_cursor(&mut self) {
+        if !self.wrap && self.cols > 2 {
+            let col = self.cursor.col + self.virtual_cols;
+            let width = if self.virtual_cols > 0 {
+                1
+            } else {
+                let c = self.buffer().get_char(self.cur_pos);
+                c.map_or(1, |c| cmp::max(self.char_width(c), 1))
+            };
+            if col <= self.hscroll {
+                self.hscroll = col.saturating_sub(1);
+            } else if col + width >= self.hscroll + self.cols {
+                self.hscroll = col + width + 1 - self.cols;
+            }
+        }
+    }
+
     fn find_down_cur_line(&mut self, pos: usize) -> u32 {
         let mut rows = 0;
-        while pos >= self.cur_line.end_pos() && !self.cur_line.is_bottom(self.cols) {
+        while pos >= self.cur_line.end_pos() && !self.cur_line.is_bottom(self.line_cols()) {
             self.cur_line = self.next_line_unchecked(&self.cur_line);
             rows += 1;
         }
@@ -2154,14 +4140,13 @@ impl EditorKernel {
     /// Finds and returns the display line corresponding to `pos`.
     fn find_line(&self, pos: usize) -> Line {
         let (line_pos, next_pos, line_bottom) = self.find_line_bounds(pos);
-        let line_len = next_pos - line_pos;
-        let row_pos = pos - ((pos - line_pos) % self.cols as usize);
-        let row_len = cmp::min(line_len - (row_pos - line_pos), self.cols as usize);
+        let (row_pos, row_len, row_cols) = self.find_row_at(line_pos, next_pos, pos);
         Line {
             row_pos,
             row_len,
+            row_cols,
             line_pos,
-            line_len,
+            line_len: next_pos - line_pos,
             line: self.buffer().line_of(line_pos),
             line_bottom,
         }
@@ -2170,22 +4155,28 @@ impl EditorKernel {
     /// Returns an updated `line` based on the assumption of underlying changes to
     /// the buffer.
     ///
-    /// Note that none of `line_pos`, `row_pos`, and `line` are modified as part of
-    /// this update, as those are presumed to have not changed.
+    /// Note that neither `line_pos` nor `line` are modified as part of this update,
+    /// as those are presumed to have not changed.
     ///
     /// The rationale for this function is that an insertion or deletion of text is
     /// always relative to the current line, and that such a change would never
-    /// alter the values noted above.
+    /// alter the values noted above. The same is true of `row_pos` unless the row has
+    /// wrapped, since a change at the start of the row may allow characters to move
+    /// to the prior row when a wide character no longer needs to be pushed to this
+    /// row, in which case the row containing the original `row_pos` is returned.
     fn update_line(&self, line: &Line) -> Line {
         let (next_pos, line_bottom) = self.buffer().find_next_line(line.line_pos);
-        let line_len = next_pos - line.line_pos;
-        let row_len = cmp::min(
-            line_len - (line.row_pos - line.line_pos),
-            self.cols as usize,
-        );
+        let (row_pos, row_len, row_cols) = if line.has_wrapped() {
+            self.find_row_at(line.line_pos, next_pos, line.row_pos)
+        } else {
+            let (row_len, row_cols) = self.find_row(line.row_pos, next_pos);
+            (line.row_pos, row_len, row_cols)
+        };
         Line {
+            row_pos,
             row_len,
-            line_len,
+            row_cols,
+            line_len: next_pos - line.line_pos,
             line_bottom,
             ..*line
         }
@@ -2197,23 +4188,26 @@ impl EditorKernel {
         if line.is_top() {
             None
         } else if line.has_wrapped() {
+            let end_pos = line.line_pos + line.line_len;
+            let (row_pos, row_len, row_cols) =
+                self.find_row_at(line.line_pos, end_pos, line.row_pos - 1);
             let l = Line {
-                row_pos: line.row_pos - self.cols as usize,
-                row_len: self.cols as usize,
+                row_pos,
+                row_len,
+                row_cols,
                 ..*line
             };
             Some(l)
         } else {
             let pos = line.line_pos - 1;
             let (line_pos, next_pos, line_bottom) = self.find_line_bounds(pos);
-            let line_len = next_pos - line_pos;
-            let row_pos = pos - ((pos - line_pos) % self.cols as usize);
-            let row_len = cmp::min(line_len - (row_pos - line_pos), self.cols as usize);
+            let (row_pos, row_len, row_cols) = self.find_row_at(line_pos, next_pos, pos);
             let l = Line {
                 row_pos,
                 row_len,
+                row_cols,
                 line_pos,
-                line_len,
+                line_len: next_pos - line_pos,
                 line: line.line - 1,
                 line_bottom,
             };
@@ -2231,30 +4225,28 @@ impl EditorKernel {
     /// Returns the line following `line`, or `None` if `line` is already at the
     /// bottom of the buffer.
     fn next_line(&self, line: &Line) -> Option<Line> {
-        if line.is_bottom(self.cols) {
+        if line.is_bottom(self.line_cols()) {
             None
         } else if line.does_wrap() {
             let row_pos = line.row_pos + line.row_len;
-            let row_len = cmp::min(
-                line.line_len - (row_pos - line.line_pos),
-                self.cols as usize,
-            );
+            let (row_len, row_cols) = self.find_row(row_pos, line.line_pos + line.line_len);
             let l = Line {
                 row_pos,
                 row_len,
+                row_cols,
                 ..*line
             };
             Some(l)
         } else {
             let line_pos = line.line_pos + line.line_len;
             let (next_pos, line_bottom) = self.buffer().find_next_line(line_pos);
-            let line_len = next_pos - line_pos;
-            let row_len = cmp::min(line_len, self.cols as usize);
+            let (row_len, row_cols) = self.find_row(line_pos, next_pos);
             let l = Line {
                 row_pos: line_pos,
                 row_len,
+                row_cols,
                 line_pos,
-                line_len,
+                line_len: next_pos - line_pos,
                 line: line.line + 1,
                 line_bottom,
             };
@@ -2283,17 +4275,88 @@ impl EditorKernel {
     /// Renders an individual cell for the character `c`, returning the next rendering
     /// context or `None` if rendering has finished.
     fn render_cell(&self, draw: &Draw, render: Render, c: char) -> Option<Render> {
+        // A wide character that would straddle the right edge of the display is pushed
+        // to the next row, leaving the remaining column empty.
+        let width = self.char_width(c);
+        let render = if render.col > 0 && render.col + width > self.line_cols() {
+            let col = render.col + self.margin_cols;
+            let cell = draw.as_text(' ', &render);
+            self.canvas.borrow_mut().set_cell(render.row, col, cell);
+            let render = render.wrap_row();
+            if render.row < self.rows {
+                render
+            } else {
+                return None;
+            }
+        } else {
+            render
+        };
+
         self.render_margin(draw, &render);
         let mut canvas = self.canvas.borrow_mut();
-        let (row, col) = (render.row, render.col + self.margin_cols);
+        let row = render.row;
+        let col = render.col.saturating_sub(self.hscroll) + self.margin_cols;
+        let end_col = self.hscroll + self.cols;
         let render = if c == '\n' {
-            canvas.set_cell(row, col, draw.as_text(c, &render));
-            canvas.fill_cell_from(row, col + 1, draw.as_text(' ', &render));
+            if render.col < self.hscroll {
+                canvas.fill_cell_from(row, self.margin_cols, draw.as_text(' ', &render));
+            } else if render.col < end_col {
+                canvas.set_cell(row, col, draw.as_text(c, &render));
+                canvas.fill_cell_from(row, col + 1, draw.as_text(' ', &render));
+                self.render_hints(&mut canvas, draw, &render, col + 2);
+            }
             render.next_line()
         } else {
-            canvas.set_cell(row, col, draw.as_text(c, &render));
-            if render.col + 1 < self.cols {
-                render.next_col()
+            let base = if width == 0 {
+                // Characters of zero width, such as combining marks, are drawn with the
+                // preceding character, if visible.
+                if let Some(p) = render.base {
+                    canvas.set_mark(p.row, p.col, c);
+                }
+                render.base
+            } else if render.col < self.hscroll {
+                // Columns outside of the display are only possible when line wrapping
+                // is disabled, in which case continuation indicators are shown at the
+                // edges of truncated lines, including wide characters that straddle
+                // either edge.
+                if render.col + width > self.hscroll {
+                    canvas.set_cell(row, self.margin_cols, draw.as_truncate(&render));
+                }
+                None
+            } else if render.col == self.hscroll && self.hscroll > 0 {
+                canvas.set_cell(row, col, draw.as_truncate(&render));
+                if width > 1 && render.col + width <= end_col {
+                    canvas.set_cell(row, col + 1, draw.as_text(' ', &render));
+                }
+                None
+            } else if render.col + width <= end_col {
+                let cell = draw.as_text(c, &render);
+                if let Some(caret) = draw.caret_of(c) {
+                    canvas.set_cell(row, col, cell);
+                    canvas.set_cell(row, col + 1, Cell::new(caret, cell.color));
+                } else if width > 1 {
+                    canvas.set_wide_cell(row, col, cell);
+                } else {
+                    canvas.set_cell(row, col, cell);
+                }
+                Some(Point::new(row, col))
+            } else if render.col < end_col {
+                canvas.set_cell(row, col, draw.as_truncate(&render));
+                None
+            } else {
+                if render.col == end_col {
+                    // A wide character occupying the last two columns is removed
+                    // entirely, since the indicator would otherwise cover half of it.
+                    if render.base == Some(Point::new(row, col - 2)) {
+                        canvas.set_cell(row, col - 2, draw.as_text(' ', &render));
+                    }
+                    canvas.set_cell(row, col - 1, draw.as_truncate(&render));
+                }
+                None
+            };
+            let render = Render { base, ..render };
+            if render.col + width < self.line_cols() {
+                render.next_col(width)
             } else {
                 render.next_row()
             }
@@ -2313,9 +4376,14 @@ impl EditorKernel {
         self.render_margin(draw, &render);
         let mut canvas = self.canvas.borrow_mut();
 
-        // Blank out rest of existing row.
-        let (row, col) = (render.row, render.col + self.margin_cols);
-        canvas.fill_cell_from(row, col, draw.as_text(' ', &render));
+        // Blank out rest of existing row, unless the end of buffer lies beyond the
+        // right edge of the display.
+        let row = render.row;
+        let col = render.col.saturating_sub(self.hscroll) + self.margin_cols;
+        if render.col < self.hscroll + self.cols {
+            canvas.fill_cell_from(row, col, draw.as_text(' ', &render));
+            self.render_hints(&mut canvas, draw, &render, col + 1);
+        }
 
         // Blank out remaining rows.
         for row in (render.row + 1)..self.rows {
@@ -2326,6 +4394,144 @@ impl EditorKernel {
         }
     }
 
+    /// Renders hints anchored to the current line starting at `col` of the current row,
+    /// though hints are truncated at the right edge of the display and never rendered
+    /// when characters are concealed.
+    fn render_hints(&self, canvas: &mut Canvas, draw: &Draw, render: &Render, col: u32) {
+        if !draw.redact {
+            let hints = render.hints();
+            let cells = hints
+                .iter()
+                .enumerate()
+                .flat_map(|(i, hint)| {
+                    let sep = if i > 0 { "  " } else { "" };
+                    sep.chars()
+                        .chain(hint.text.chars())
+                        .map(move |c| draw.as_hint(c, render, &hint.style))
+                })
+                .take(
+                    self.margin_cols
+                        .saturating_add(self.cols)
+                        .saturating_sub(col) as usize,
+                );
+            for (n, cell) in cells.enumerate() {
+                canvas.set_cell(render.row, col + n as u32, cell);
+            }
+        }
+    }
+
+    /// Returns the `0`-based line number and text of the nearest line at or above the
+    /// top row of the display that matches the outline of the syntax configuration,
+    /// or `None` if no such line exists or the line is already visible.
+    fn find_header(&mut self) -> Option<(u32, String)> {
+        let top = self.top_line.line;
+        let cached = self
+            .header
+            .as_ref()
+            .filter(|header| header.clock == self.clock);
+
+        // Unless the buffer changed, a prior search is reused, though if scrolled
+        // down, only lines between the prior and current top rows need to be searched.
+        // If scrolled up, the prior section remains correct as long as it starts at
+        // or above the current top row.
+        let section = match cached {
+            Some(header) if header.top == top => header.section.clone(),
+            Some(header) if header.top < top && top - header.top <= Self::SECTION_SCAN_LINES => {
+                self.find_section(header.top + 1)
+                    .or_else(|| header.section.clone())
+            }
+            Some(Header {
+                section: Some((line, text)),
+                ..
+            }) if *line <= top => Some((*line, text.clone())),
+            _ => self.find_section(top.saturating_sub(Self::SECTION_SCAN_LINES)),
+        };
+        self.header = Some(Header {
+            clock: self.clock,
+            top,
+            section: section.clone(),
+        });
+
+        // Section is already visible when it starts on the top row.
+        section.filter(|(line, _)| *line != top || self.top_line.row_pos > self.top_line.line_pos)
+    }
+
+    /// Searches backward from the buffer line at the top row of the display for the
+    /// nearest line matching the outline of the syntax configuration, though not
+    /// beyond the line numbered `stop`.
+    fn find_section(&self, stop: u32) -> Option<(u32, String)> {
+        let tokenizer = self.tokenizer();
+        let syntax = tokenizer.syntax();
+        let buffer = self.buffer();
+        let mut line = self.top_line.line;
+        let mut end = self.top_line.line_pos + self.top_line.line_len;
+        while end > 0 && line >= stop {
+            let start = buffer.find_start_line(end - 1);
+            let text = buffer.copy_as_string(start, end);
+            if syntax.is_outline(&text) {
+                return Some((line, text));
+            }
+            end = start;
+            if line == 0 {
+                break;
+            }
+            line -= 1;
+        }
+        None
+    }
+
+    /// Renders the section of `line` and `text` on the top row of the display.
+    fn render_header(&self, draw: &Draw, line: u32, text: &str) {
+        let mut canvas = self.canvas.borrow_mut();
+        if self.margin_cols > 0 {
+            // Lines always displayed as 1-based, hence adjustment.
+            let line = self.margin_line(line + 1);
+            let s = if line < Self::LINE_LIMIT {
+                format!("{:>cols$} ", line, cols = Self::MARGIN_COLS as usize - 1)
+            } else {
+                format!("{} ", "-".repeat(Self::MARGIN_COLS as usize - 1))
+            };
+            for (col, c) in s.chars().enumerate() {
+                canvas.set_cell(0, col as u32, draw.as_margin(c));
+            }
+        }
+        let mut col = 0;
+        for c in text.trim_end_matches('\n').chars() {
+            let width = self.char_width(c);
+            if width == 0 {
+                if col > 0 {
+                    canvas.set_mark(0, self.margin_cols + col - 1, c);
+                }
+            } else if col + width <= self.cols {
+                let cell = draw.as_header(c);
+                if let Some(caret) = draw.caret_of(c) {
+                    canvas.set_cell(0, self.margin_cols + col, cell);
+                    canvas.set_cell(0, self.margin_cols + col + 1, Cell::new(caret, cell.color));
+                } else if width > 1 {
+                    canvas.set_wide_cell(0, self.margin_cols + col, cell);
+                } else {
+                    canvas.set_cell(0, self.margin_cols + col, cell);
+                }
+                col += width;
+            } else {
+                break;
+            }
+        }
+        canvas.fill_cell_from(0, self.margin_cols + col, draw.as_header(' '));
+    }
+
+    /// Returns the number shown in the margin for the `1`-based `line`, which is the
+    /// distance from the cursor line if relative numbering is enabled, except on the
+    /// cursor line itself.
+    fn margin_line(&self, line: u32) -> u32 {
+        let cur_line = self.cur_line.line + 1;
+        if self.config.settings.relative_lines && line != cur_line {
+            line.abs_diff(cur_line)
+        } else {
+            line
+        }
+    }
+
     /// Renders the margin if line numbering is enabled and the rendering context is
     /// on the first column of any row.
     fn render_margin(&self, draw: &Draw, render: &Render) {
@@ -2336,7 +4542,7 @@ impl EditorKernel {
             } else if render.line < Self::LINE_LIMIT {
                 let s = format!(
                     "{:>cols$} ",
-                    render.line,
+                    self.margin_line(render.line),
                     cols = Self::MARGIN_COLS as usize - 1
                 );
                 for (col, c) in s.char_indices() {
@@ -2349,3 +4555,98 @@ impl EditorKernel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+
+    #[test]
+    fn caret_cursor_cols() {
+        let mut editor = build_editor(ControlChars::Caret, "a\x01b\x1b\x7fc\nxyz\n", 20);
+        let mut cols = Vec::new();
+        for _ in 0..6 {
+            cols.push(editor.cursor().col);
+            editor.move_forward(1);
+        }
+        assert_eq!(cols, [0, 1, 3, 4, 6, 8]);
+
+        // Cursor moving onto the second column of caret notation snaps to its start.
+        editor.move_forward(3);
+        assert_eq!(editor.cursor(), Point::new(1, 2));
+        editor.move_up(1, false);
+        assert_eq!(editor.cursor(), Point::new(0, 1));
+        assert_eq!(editor.kernel.cur_pos, 1);
+    }
+
+    #[test]
+    fn caret_wraps_as_unit() {
+        // Caret notation straddling the right edge is pushed to the next row.
+        let mut editor = build_editor(ControlChars::Caret, "abc\x01d\n", 4);
+        editor.move_forward(3);
+        assert_eq!(editor.cursor(), Point::new(1, 0));
+        editor.move_forward(1);
+        assert_eq!(editor.cursor(), Point::new(1, 2));
+
+        // Otherwise each control character occupies a single column.
+        let mut editor = build_editor(ControlChars::Glyph, "abc\x01d\n", 4);
+        editor.move_forward(4);
+        assert_eq!(editor.cursor(), Point::new(1, 0));
+    }
+
+    #[test]
+    fn hints_occupy_no_positions() {
+        let mut editor = build_editor(ControlChars::Glyph, "abcdefgh\nxyz", 10);
+        editor.add_hint(
+            Lane::Diagnostic,
+            2,
+            "wider than the window",
+            Style::default(),
+        );
+        editor.add_hint(Lane::Marker, 12, "at end of buffer", Style::default());
+        editor.render();
+
+        // Hints neither wrap the line nor extend the columns available to the cursor.
+        editor.move_end();
+        assert_eq!(editor.cursor(), Point::new(0, 8));
+        assert_eq!(editor.kernel.cur_pos, 8);
+        editor.move_forward(1);
+        assert_eq!(editor.cursor(), Point::new(1, 0));
+        editor.move_end();
+        assert_eq!(editor.cursor(), Point::new(1, 3));
+        assert_eq!(editor.kernel.cur_pos, 12);
+        editor.move_up(1, false);
+        assert_eq!(editor.cursor(), Point::new(0, 3));
+        assert_eq!(editor.kernel.cur_pos, 3);
+    }
+
+    #[test]
+    fn hints_follow_edits() {
+        let mut editor = build_editor(ControlChars::Glyph, "abc\nxyz\n", 10);
+        editor.add_hint(Lane::Diagnostic, 5, "hint", Style::default());
+        editor.modify().unwrap().insert_str("123\n");
+        editor.render();
+        assert_eq!(editor.cursor(), Point::new(1, 0));
+
+        let lanes = editor.kernel.lanes.borrow();
+        assert!(lanes.hints_in(0..9).is_empty());
+        assert_eq!(lanes.hints_in(9..10)[0].text, "hint");
+    }
+
+    /// Returns an editor containing `text` that is attached to a window of `cols`
+    /// columns, showing control characters according to `control_chars`.
+    fn build_editor(control_chars: ControlChars, text: &str, cols: u32) -> Editor {
+        let mut config = Configuration::default();
+        config.settings.lines = false;
+        config.settings.control_chars = control_chars;
+        let config = config.to_ref();
+        let mut buffer = Buffer::new();
+        buffer.insert_str(text);
+        buffer.set_pos(0);
+        let mut editor =
+            Editor::mutable(config.clone(), Source::as_ephemeral("test"), Some(buffer));
+        let window = Window::new(Point::ORIGIN, Size::new(10, cols), config, false);
+        editor.attach(window.to_ref(), Align::Top);
+        editor
+    }
+}
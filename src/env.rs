@@ -4,12 +4,19 @@
 //! to the core [`Editor`]. A restricted set of functions is necessary not only to
 //! simplify operations, but more importantly, to enforce certain invariants.
 
+use crate::bind::Context;
 use crate::editor::{Align, Editor, EditorRef, ImmutableEditor};
+use crate::job::{Job, Pipe};
+use crate::perf::Perf;
+use crate::recent::Recent;
+use crate::search::Pattern;
 use crate::source::Source;
+use crate::sys;
 use crate::window::{BannerRef, WindowRef};
 use crate::workspace::{Placement, Workspace, WorkspaceRef};
 use std::cell::{Ref, RefMut};
 use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
 /// Map of view ids to editor ids.
 pub type ViewMap = HashMap<u32, u32>;
@@ -17,6 +24,92 @@ pub type ViewMap = HashMap<u32, u32>;
 /// Map of editor ids to editors.
 pub type EditorMap = BTreeMap<u32, EditorRef>;
 
+/// Map of view ids to the last match from a prior search in that view.
+type MatchMap = HashMap<u32, LastMatch>;
+
+/// Map of view ids to the id of the editor most recently attached to that view prior
+/// to the editor currently attached.
+type HistoryMap = HashMap<u32, u32>;
+
+/// Map of view and editor ids to the cursor row of that editor when most recently
+/// detached from that view, which effectively captures the top line of the window.
+type ScrollMap = HashMap<(u32, u32), u32>;
+
+/// Map of layout names to saved window arrangements.
+type LayoutMap = BTreeMap<String, Layout>;
+
+/// A saved window arrangement, where `views` contains each window ordered from top to
+/// bottom, and `active` is the index of the _active_ window.
+///
+/// Note that only the number of rows allocated to each row of windows is retained,
+/// since the workspace always allocates an equal number of columns to windows sharing
+/// the same rows.
+#[derive(Clone)]
+struct Layout {
+    views: Vec<Pane>,
+    active: usize,
+}
+
+/// A window in an arrangement of windows, containing the id of the attached editor,
+/// the ruler preference, whether it shares the rows of the window that precedes it,
+/// and the number of rows allocated to its row of windows, where `0` implies an equal
+/// share.
+#[derive(Clone)]
+pub struct Pane {
+    pub editor_id: u32,
+    pub ruler: bool,
+    pub beside: bool,
+    pub rows: u32,
+}
+
+impl Pane {
+    /// Returns only those `panes` for which `keep` is `true`, though a pane that
+    /// follows a discarded pane starting a row of windows then starts a row itself.
+    pub fn retain<F>(panes: Vec<Pane>, keep: F) -> Vec<Pane>
+    where
+        F: Fn(&Pane) -> bool,
+    {
+        let mut kept: Vec<Pane> = Vec::new();
+        let mut row_start = false;
+        for pane in panes {
+            if keep(&pane) {
+                let beside = pane.beside && !row_start && !kept.is_empty();
+                kept.push(Pane { beside, ..pane });
+                row_start = false;
+            } else {
+                row_start = row_start || !pane.beside;
+            }
+        }
+        kept
+    }
+}
+
+/// The last match from a prior search, which is only relevant so long as the editor
+/// of `editor_id` remains attached to the view in which the search occurred.
+struct LastMatch {
+    editor_id: u32,
+    pos: usize,
+    pattern: Box<dyn Pattern>,
+}
+
+/// Matches of `pattern` gathered across all editors, where `locations` contains the
+/// editor id and `0`-based line number of each match in the order listed.
+pub struct Matches {
+    pub pattern: Box<dyn Pattern>,
+    pub locations: Vec<(u32, u32)>,
+}
+
+/// An item whose use is recorded for the purpose of ranking entries in the command
+/// palette.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Usage {
+    /// An editing operation by name.
+    Op(String),
+
+    /// An editor by id.
+    Editor(u32),
+}
+
 pub struct Environment {
     workspace: WorkspaceRef,
     editor_map: EditorMap,
@@ -24,6 +117,22 @@ pub struct Environment {
     view_map: ViewMap,
     active_view_id: u32,
     clipboard: Option<Vec<char>>,
+    clipboard_block: bool,
+    match_map: MatchMap,
+    history_map: HistoryMap,
+    scroll_map: ScrollMap,
+    layout_map: LayoutMap,
+    occur: Option<(u32, String)>,
+    search_all: Option<Matches>,
+    job: Option<(u32, Job)>,
+    print: Option<(String, Pipe)>,
+    last_command: Option<String>,
+    trash: Vec<(PathBuf, PathBuf)>,
+    preview: Option<EditorRef>,
+    recent: Recent<Usage>,
+    perf: Perf,
+    scroll_lock: Option<(u32, u32)>,
+    edit_lock: Option<String>,
 }
 
 pub enum Focus {
@@ -62,11 +171,14 @@ impl Environment {
         // reliably fetched as top view. Attach this view to first builtin editor.
         let active_view_id = workspace.borrow().top_view().id;
         let editor_id = Self::BUILTIN_EDITORS[0].0;
-        editor_map
-            .get(&editor_id)
-            .unwrap_or_else(|| panic!("expecting builtin editor id {editor_id}"))
-            .borrow_mut()
-            .attach(workspace.borrow().top_view().window.clone(), Align::Auto);
+        {
+            let mut editor = editor_map
+                .get(&editor_id)
+                .unwrap_or_else(|| panic!("expecting builtin editor id {editor_id}"))
+                .borrow_mut();
+            editor.set_active(true);
+            editor.attach(workspace.borrow().top_view().window.clone(), Align::Auto);
+        }
         let mut view_map = ViewMap::new();
         view_map.insert(active_view_id, editor_id);
 
@@ -77,6 +189,22 @@ impl Environment {
             view_map,
             active_view_id,
             clipboard: None,
+            clipboard_block: false,
+            match_map: MatchMap::new(),
+            history_map: HistoryMap::new(),
+            scroll_map: ScrollMap::new(),
+            layout_map: LayoutMap::new(),
+            occur: None,
+            search_all: None,
+            job: None,
+            print: None,
+            last_command: None,
+            trash: Vec::new(),
+            preview: None,
+            recent: Recent::new(),
+            perf: Perf::new(),
+            scroll_lock: None,
+            edit_lock: None,
         }
     }
 
@@ -95,6 +223,18 @@ impl Environment {
         self.get_view_editor_unchecked(self.active_view_id)
     }
 
+    /// Returns a snapshot of the _active_ editor against which key bindings are
+    /// resolved, where the absence of a question is assumed.
+    pub fn context(&self) -> Context {
+        let editor = self.get_active_editor().borrow();
+        Context {
+            readonly: editor.is_readonly(),
+            question: false,
+            selection: editor.capture().mark.is_some(),
+            syntax: editor.syntax_name(),
+        }
+    }
+
     /// Returns a reference to the editor attached to `view_id`.
     pub fn get_view_editor(&self, view_id: u32) -> &EditorRef {
         self.get_view_editor_unchecked(view_id)
@@ -120,6 +260,12 @@ impl Environment {
         self.active_view_id
     }
 
+    /// Returns the id of the editor most recently attached to the _active_ view prior
+    /// to the editor currently attached, or `None` if no such editor exists.
+    pub fn get_last_editor_id(&self) -> Option<u32> {
+        self.history_map.get(&self.active_view_id).cloned()
+    }
+
     /// Returns the view id attached to `editor_id` or `None` if unattached.
     pub fn find_editor_view_id(&self, editor_id: u32) -> Option<u32> {
         self.view_map
@@ -217,6 +363,18 @@ impl Environment {
         self.switch_editor_for(self.active_view_id, editor_id, align)
     }
 
+    /// Same as [`switch_editor`](Self::switch_editor), except that the scroll position
+    /// of the editor when last attached to the _active_ view is restored, falling back
+    /// to automatic alignment if the editor has never been attached to that view.
+    pub fn restore_editor(&mut self, editor_id: u32) -> u32 {
+        let align = self
+            .scroll_map
+            .get(&(self.active_view_id, editor_id))
+            .map(|row| Align::Row(*row))
+            .unwrap_or(Align::Auto);
+        self.switch_editor(editor_id, align)
+    }
+
     /// Closes the window of `view_id`, detaches the associated editor, and returns
     /// the id of the new _active_ view.
     ///
@@ -277,6 +435,38 @@ impl Environment {
     /// Sets the value of the clipboard to `text`.
     pub fn set_clipboard(&mut self, text: Vec<char>) {
         self.clipboard = Some(text);
+        self.clipboard_block = false;
+    }
+
+    /// Sets the value of the clipboard to `text` representing a block of columns,
+    /// where each line of the block is separated by `\n`.
+    pub fn set_block_clipboard(&mut self, text: Vec<char>) {
+        self.clipboard = Some(text);
+        self.clipboard_block = true;
+    }
+
+    /// Appends `text` to the value of the clipboard, separated by `\n` unless the
+    /// clipboard is empty or already ends with `\n`, where `block` indicates whether
+    /// `text` represents a block of columns.
+    ///
+    /// The clipboard continues to represent a block of columns only if it already did
+    /// and `text` is also a block.
+    pub fn append_clipboard(&mut self, text: Vec<char>, block: bool) {
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            if clipboard.last().is_some_and(|c| *c != '\n') {
+                clipboard.push('\n');
+            }
+            clipboard.extend(text);
+            self.clipboard_block = self.clipboard_block && block;
+        } else {
+            self.clipboard = Some(text);
+            self.clipboard_block = block;
+        }
+    }
+
+    /// Returns `true` if the clipboard contains a block of columns.
+    pub fn is_clipboard_block(&self) -> bool {
+        self.clipboard_block
     }
 
     /// Returns the value of the clipboard.
@@ -284,9 +474,140 @@ impl Environment {
         self.clipboard.as_ref()
     }
 
+    /// Sets the last match from a prior search in the window of `view_id`, where `pos`
+    /// is the starting position of the match and `pattern` is the applicable search
+    /// pattern.
+    ///
+    /// This function does nothing if `view_id` no longer exists, which may happen if
+    /// the workspace removed the view while resizing.
+    pub fn set_last_match_for(&mut self, view_id: u32, pos: usize, pattern: Box<dyn Pattern>) {
+        if let Some(editor_id) = self.view_map.get(&view_id).cloned() {
+            self.match_map.insert(
+                view_id,
+                LastMatch {
+                    editor_id,
+                    pos,
+                    pattern,
+                },
+            );
+        }
+    }
+
+    pub fn set_last_match(&mut self, pos: usize, pattern: Box<dyn Pattern>) {
+        self.set_last_match_for(self.active_view_id, pos, pattern);
+    }
+
+    /// Takes the last match from a prior search in the window of `view_id`, though
+    /// only if the window is still attached to the editor in which the search occurred.
+    pub fn take_last_match_for(&mut self, view_id: u32) -> Option<(usize, Box<dyn Pattern>)> {
+        let editor_id = self.view_map.get(&view_id).cloned();
+        self.match_map
+            .remove(&view_id)
+            .filter(|last_match| Some(last_match.editor_id) == editor_id)
+            .map(|last_match| (last_match.pos, last_match.pattern))
+    }
+
+    pub fn take_last_match(&mut self) -> Option<(usize, Box<dyn Pattern>)> {
+        self.take_last_match_for(self.active_view_id)
+    }
+
+    /// Sets the editor of `editor_id` and search `term` from which occurrences were
+    /// last gathered.
+    pub fn set_occur(&mut self, editor_id: u32, term: &str) {
+        self.occur = Some((editor_id, term.to_string()));
+    }
+
+    /// Returns the editor id and search term from which occurrences were last
+    /// gathered.
+    pub fn get_occur(&self) -> Option<&(u32, String)> {
+        self.occur.as_ref()
+    }
+
+    /// Sets the `pattern` from which matches across all editors were last gathered,
+    /// along with the editor id and line number of each match in `locations`.
+    pub fn set_search_all(&mut self, pattern: Box<dyn Pattern>, locations: Vec<(u32, u32)>) {
+        self.search_all = Some(Matches { pattern, locations });
+    }
+
+    /// Returns the pattern and locations of matches across all editors that were last
+    /// gathered.
+    pub fn get_search_all(&self) -> Option<&Matches> {
+        self.search_all.as_ref()
+    }
+
+    /// Sets the running `job` whose output is directed to the editor with
+    /// `editor_id`, which replaces and kills any job already running.
+    pub fn set_job(&mut self, editor_id: u32, job: Job) {
+        self.job = Some((editor_id, job));
+    }
+
+    /// Takes the running job, if any, along with the id of its editor.
+    pub fn take_job(&mut self) -> Option<(u32, Job)> {
+        self.job.take()
+    }
+
+    /// Returns `true` if a print command is running.
+    pub fn is_printing(&self) -> bool {
+        self.print.is_some()
+    }
+
+    /// Sets the running print `pipe` along with the `message` echoed once it
+    /// completes successfully.
+    pub fn set_print(&mut self, message: &str, pipe: Pipe) {
+        self.print = Some((message.to_string(), pipe));
+    }
+
+    /// Takes the running print command, if any, along with its message.
+    pub fn take_print(&mut self) -> Option<(String, Pipe)> {
+        self.print.take()
+    }
+
+    /// Sets the last command run to `command`.
+    pub fn set_last_command(&mut self, command: &str) {
+        self.last_command = Some(command.to_string());
+    }
+
+    /// Returns the last command run.
+    pub fn get_last_command(&self) -> Option<&String> {
+        self.last_command.as_ref()
+    }
+
+    /// Records a use of `usage`.
+    pub fn touch(&mut self, usage: Usage) {
+        self.recent.touch(usage);
+    }
+
+    /// Returns the record of recently used operations and editors.
+    pub fn recent(&self) -> &Recent<Usage> {
+        &self.recent
+    }
+
+    /// Returns the record of execution times of operations and frames.
+    pub fn perf(&self) -> &Perf {
+        &self.perf
+    }
+
+    /// Returns a mutable reference to the record of execution times of operations
+    /// and frames.
+    pub fn perf_mut(&mut self) -> &mut Perf {
+        &mut self.perf
+    }
+
+    /// Records that the file at `path` was moved to the trash at `trash_path`.
+    pub fn push_trash(&mut self, path: PathBuf, trash_path: PathBuf) {
+        self.trash.push((path, trash_path));
+    }
+
+    /// Removes and returns the original path and trash path of the file most recently
+    /// moved to the trash.
+    pub fn pop_trash(&mut self) -> Option<(PathBuf, PathBuf)> {
+        self.trash.pop()
+    }
+
     /// Resizes the workspace, which might remove a subset of views if resizing
     /// violates the minimum size constraint for windows.
     pub fn resize(&mut self) {
+        self.set_preview(None);
         let view_ids = self.workspace_mut().resize(self.active_view_id);
         self.workspace_mut().clear_shared();
 
@@ -298,6 +619,302 @@ impl Environment {
         }
     }
 
+    /// Temporarily attaches the window of the _active_ view to `editor`, which is not
+    /// added to the list of editors, or restores the window to its own editor if
+    /// `editor` is `None`.
+    pub fn set_preview(&mut self, editor: Option<EditorRef>) {
+        match self.preview.take() {
+            Some(preview) => preview.borrow_mut().detach(),
+            None if editor.is_none() => return,
+            None => self.get_active_editor().borrow_mut().detach(),
+        }
+        let window = self.window_of(self.active_view_id);
+        if let Some(editor) = editor {
+            {
+                let mut preview = editor.borrow_mut();
+                preview.set_active(true);
+                preview.attach(window, Align::Top);
+            }
+            self.preview = Some(editor);
+        } else {
+            self.get_active_editor()
+                .borrow_mut()
+                .attach(window, Align::Auto);
+        }
+    }
+
+    /// Assigns labels to all editors whose source is a _file_ such that each label is
+    /// the shortest path suffix distinguishing it from other editors.
+    ///
+    /// This function is called automatically when editors are added or removed, but
+    /// must be called explicitly when the source of an editor changes.
+    pub fn refresh_labels(&self) {
+        let editors = self
+            .editor_map
+            .values()
+            .filter_map(|editor| match editor.borrow().source() {
+                Source::File(path, _) => Some((editor.clone(), path.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let paths = editors.iter().map(|(_, path)| path).collect::<Vec<_>>();
+        for ((editor, _), label) in editors.iter().zip(sys::uniquify(&paths)) {
+            editor.borrow_mut().set_label(Some(label));
+        }
+    }
+
+    /// Changes the number of rows allocated to the shared region of the workspace to
+    /// `rows`, which resizes and redraws all windows if the number of rows changed.
+    pub fn set_shared_rows(&mut self, rows: u32) {
+        if self.workspace_mut().set_shared_rows(rows) {
+            self.reattach_views();
+            self.focus(self.active_view_id);
+        }
+    }
+
+    /// Toggles the ruler of the active window, which resizes and redraws all windows,
+    /// and returns `true` if the ruler is now shown.
+    pub fn toggle_ruler(&mut self) -> bool {
+        let ruler = !self.workspace().get_view(self.active_view_id).ruler;
+        self.workspace_mut().set_ruler(self.active_view_id, ruler);
+        self.reattach_views();
+        self.focus(self.active_view_id);
+        ruler
+    }
+
+    /// Grows the _active_ window by `rows`, taking rows from adjacent windows, which
+    /// resizes and redraws all windows, and returns `true` if the window was grown.
+    pub fn grow_window(&mut self, rows: u32) -> bool {
+        let grown = self.workspace_mut().grow_view(self.active_view_id, rows);
+        if grown {
+            self.reattach_views();
+            self.focus(self.active_view_id);
+        }
+        grown
+    }
+
+    /// Shrinks the _active_ window by `rows`, giving rows to an adjacent window, which
+    /// resizes and redraws all windows, and returns `true` if the window was shrunk.
+    pub fn shrink_window(&mut self, rows: u32) -> bool {
+        let shrunk = self.workspace_mut().shrink_view(self.active_view_id, rows);
+        if shrunk {
+            self.reattach_views();
+            self.focus(self.active_view_id);
+        }
+        shrunk
+    }
+
+    /// Redraws all windows, such as after the terminal was used by another program.
+    pub fn redraw(&mut self) {
+        self.set_preview(None);
+        self.workspace_mut().redraw_views();
+        self.reattach_views();
+        self.focus(self.active_view_id);
+    }
+
+    /// Allocates an equal number of rows to all windows, which resizes and redraws all
+    /// windows, and returns `true` if any window was resized.
+    pub fn balance_windows(&mut self) -> bool {
+        let balanced = self.workspace_mut().balance_views();
+        if balanced {
+            self.reattach_views();
+            self.focus(self.active_view_id);
+        }
+        balanced
+    }
+
+    /// Locks scrolling of the _active_ view together with the view below it, or the
+    /// top view if the active view is at the bottom, replacing any prior lock, and
+    /// returns the id of the other view.
+    ///
+    /// This function returns `None` if the active view is the only view.
+    pub fn set_scroll_lock(&mut self) -> Option<u32> {
+        let other_id = self.workspace().below_view(self.active_view_id).id;
+        if other_id == self.active_view_id {
+            None
+        } else {
+            self.clear_scroll_lock();
+            self.scroll_lock = Some((self.active_view_id, other_id));
+            self.show_scroll_lock(true);
+            Some(other_id)
+        }
+    }
+
+    /// Removes the scroll lock, if any, returning `true` if a lock was removed.
+    pub fn clear_scroll_lock(&mut self) -> bool {
+        if self.scroll_lock.is_some() {
+            self.show_scroll_lock(false);
+            self.scroll_lock = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the id of the view whose scrolling is locked together with `view_id`,
+    /// or `None` if `view_id` is not locked.
+    pub fn get_scroll_lock_for(&self, view_id: u32) -> Option<u32> {
+        match self.scroll_lock {
+            Some((a, b)) if a == view_id => Some(b),
+            Some((a, b)) if b == view_id => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Locks editing of all editors, including those added later, until unlocked,
+    /// where `keyword` is required to unlock unless empty.
+    pub fn lock_editing(&mut self, keyword: &str) {
+        for editor in self.editor_map.values() {
+            editor.borrow_mut().set_locked(true);
+        }
+        self.edit_lock = Some(keyword.to_string());
+    }
+
+    /// Unlocks editing of all editors, which restores their prior classification as
+    /// _readonly_ or not.
+    pub fn unlock_editing(&mut self) {
+        for editor in self.editor_map.values() {
+            editor.borrow_mut().set_locked(false);
+        }
+        self.edit_lock = None;
+    }
+
+    /// Returns the keyword required to unlock editing, which is empty if none was
+    /// given, or `None` if editing is not locked.
+    pub fn get_edit_lock(&self) -> Option<&str> {
+        self.edit_lock.as_deref()
+    }
+
+    /// Saves the current window arrangement as `name`, replacing any prior layout of
+    /// the same name.
+    pub fn save_layout(&mut self, name: &str) {
+        let (views, active) = self.get_arrangement();
+        self.layout_map
+            .insert(name.to_string(), Layout { views, active });
+    }
+
+    /// Returns the current window arrangement as a tuple containing each window ordered
+    /// from top to bottom, and the index of the _active_ window.
+    pub fn get_arrangement(&self) -> (Vec<Pane>, usize) {
+        let workspace = self.workspace();
+        let view_ids = workspace.view_ids();
+        let views = view_ids
+            .iter()
+            .map(|id| {
+                let view = workspace.get_view(*id);
+                Pane {
+                    editor_id: self.get_view_editor_id_unchecked(*id),
+                    ruler: view.ruler,
+                    beside: view.beside,
+                    rows: view.rows(),
+                }
+            })
+            .collect();
+        let active = view_ids
+            .iter()
+            .position(|id| *id == self.active_view_id)
+            .unwrap_or(0);
+        (views, active)
+    }
+
+    /// Returns the names of all saved layouts in lexicographic order.
+    pub fn layout_names(&self) -> Vec<String> {
+        self.layout_map.keys().cloned().collect()
+    }
+
+    /// Restores the window arrangement saved as `name`, returning `false` if no such
+    /// layout exists or none of its editors remain.
+    ///
+    /// Editors that were removed since the layout was saved are skipped, and windows
+    /// are possibly omitted if the workspace is no longer large enough to accommodate
+    /// all of them.
+    pub fn restore_layout(&mut self, name: &str) -> bool {
+        if let Some(layout) = self.layout_map.get(name).cloned() {
+            self.set_arrangement(layout.views, layout.active)
+        } else {
+            false
+        }
+    }
+
+    /// Arranges windows from top to bottom according to `views`, and makes the window
+    /// at index `active` the _active_ window, returning `false` if none of the editors
+    /// exist.
+    ///
+    /// Editors that do not exist are skipped, and windows are possibly omitted if the
+    /// workspace is not large enough to accommodate all of them.
+    pub fn set_arrangement(&mut self, views: Vec<Pane>, active: usize) -> bool {
+        let active_editor_id = views.get(active).map(|pane| pane.editor_id);
+
+        let panes = Pane::retain(views, |pane| self.editor_map.contains_key(&pane.editor_id));
+        let Some(first_id) = panes.first().map(|pane| pane.editor_id) else {
+            return false;
+        };
+
+        // Collapse workspace to active window only.
+        let view_ids = self.workspace().view_ids();
+        for view_id in view_ids {
+            if view_id != self.active_view_id {
+                self.workspace_mut().close_view(view_id);
+                self.remove_view(view_id);
+            }
+        }
+        self.workspace_mut().set_ruler(self.active_view_id, false);
+        self.reattach_views();
+        self.attach_to_editor(self.active_view_id, first_id, Align::Auto);
+
+        // Open remaining windows from top to bottom, where windows sharing the rows of
+        // the window that precedes them are placed to its right.
+        let mut view_ids = vec![(self.active_view_id, &panes[0])];
+        for pane in panes.iter().skip(1) {
+            let (last_id, _) = view_ids[view_ids.len() - 1];
+            let place = if pane.beside {
+                Placement::Right(last_id)
+            } else {
+                Placement::Bottom
+            };
+            let view_id = self.workspace_mut().open_view(place);
+            if let Some(view_id) = view_id {
+                self.reattach_views();
+                self.attach_to_editor(view_id, pane.editor_id, Align::Auto);
+                view_ids.push((view_id, pane));
+            } else if !pane.beside {
+                break;
+            }
+        }
+        for (view_id, pane) in view_ids.iter() {
+            if pane.ruler {
+                self.workspace_mut().set_ruler(*view_id, true);
+            }
+        }
+        let sizes = view_ids
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, pane))| *i == 0 || !pane.beside)
+            .map(|(_, (_, pane))| pane.rows)
+            .collect();
+        self.workspace_mut().set_view_rows(sizes);
+        self.reattach_views();
+
+        let active_id = view_ids
+            .iter()
+            .find(|(_, pane)| Some(pane.editor_id) == active_editor_id)
+            .map(|(view_id, _)| *view_id)
+            .unwrap_or(self.active_view_id);
+        self.set_active(Focus::To(active_id));
+        true
+    }
+
+    /// Releases memory that is not essential, which compacts all editors and discards
+    /// matches from prior searches that are no longer relevant.
+    pub fn compact(&mut self) {
+        for editor in self.editor_map.values() {
+            editor.borrow_mut().compact();
+        }
+        let view_map = &self.view_map;
+        self.match_map
+            .retain(|view_id, m| view_map.get(view_id) == Some(&m.editor_id));
+    }
+
     pub fn editor_map(&self) -> &EditorMap {
         &self.editor_map
     }
@@ -309,12 +926,23 @@ impl Environment {
     /// Attaches the window of `view_id` to the editor referenced by `editor_id`, and
     /// also detaches the window from its current editor if an association exists.
     fn attach_to_editor(&mut self, view_id: u32, editor_id: u32, align: Align) {
-        if let Some(id) = self.view_map.get(&view_id) {
-            self.get_editor_unchecked(*id).borrow_mut().detach();
+        if let Some(id) = self.view_map.get(&view_id).cloned() {
+            let row = {
+                let mut editor = self.get_editor_unchecked(id).borrow_mut();
+                let row = editor.cursor().row;
+                editor.detach();
+                row
+            };
+            self.scroll_map.insert((view_id, id), row);
+            if id != editor_id {
+                self.history_map.insert(view_id, id);
+            }
+        }
+        {
+            let mut editor = self.get_editor_unchecked(editor_id).borrow_mut();
+            editor.set_active(view_id == self.active_view_id);
+            editor.attach(self.window_of(view_id), align);
         }
-        self.get_editor_unchecked(editor_id)
-            .borrow_mut()
-            .attach(self.window_of(view_id), align);
         self.view_map.insert(view_id, editor_id);
     }
 
@@ -326,6 +954,24 @@ impl Environment {
                 .borrow_mut()
                 .attach(self.window_of(*view_id), Align::Auto);
         }
+        self.show_scroll_lock(true);
+    }
+
+    /// Indicates in the banners of views whose scrolling is locked, if any, whether
+    /// the lock is `shown`, though views already removed from the workspace are
+    /// ignored.
+    fn show_scroll_lock(&self, shown: bool) {
+        if let Some((a, b)) = self.scroll_lock {
+            for view_id in [a, b] {
+                let banner = self
+                    .workspace()
+                    .find_view(view_id)
+                    .map(|view| view.window.borrow().banner().clone());
+                if let Some(banner) = banner {
+                    banner.borrow_mut().set_locked(shown).draw();
+                }
+            }
+        }
     }
 
     /// Removes `view_id` and detaches the corresponding editor, returning the editor
@@ -335,13 +981,27 @@ impl Environment {
             .view_map
             .remove(&view_id)
             .unwrap_or_else(|| panic!("expecting view id {view_id}"));
+        self.match_map.remove(&view_id);
+        self.history_map.remove(&view_id);
+        self.scroll_map.retain(|(v_id, _), _| *v_id != view_id);
+        if self.get_scroll_lock_for(view_id).is_some() {
+            self.clear_scroll_lock();
+        }
         self.get_editor_unchecked(editor_id).borrow_mut().detach();
         editor_id
     }
 
-    fn add_editor(&mut self, editor: EditorRef) -> u32 {
+    /// Adds `editor` to the list of editors without attaching it to a window, and
+    /// returns the new editor id.
+    ///
+    /// If editing is locked, then `editor` is locked as well.
+    pub fn add_editor(&mut self, editor: EditorRef) -> u32 {
+        if self.edit_lock.is_some() {
+            editor.borrow_mut().set_locked(true);
+        }
         let editor_id = self.next_editor_id();
         self.editor_map.insert(editor_id, editor);
+        self.refresh_labels();
         editor_id
     }
 
@@ -352,9 +1012,15 @@ impl Environment {
     }
 
     fn remove_editor_unchecked(&mut self, editor_id: u32) -> EditorRef {
-        self.editor_map
+        let editor = self
+            .editor_map
             .remove(&editor_id)
-            .unwrap_or_else(|| panic!("expecting editor id {editor_id}"))
+            .unwrap_or_else(|| panic!("expecting editor id {editor_id}"));
+        self.history_map.retain(|_, id| *id != editor_id);
+        self.scroll_map.retain(|(_, e_id), _| *e_id != editor_id);
+        self.recent.forget(&Usage::Editor(editor_id));
+        self.refresh_labels();
+        editor
     }
 
     fn get_view_editor_id_unchecked(&self, view_id: u32) -> u32 {
@@ -378,10 +1044,16 @@ impl Environment {
 
     fn focus(&self, view_id: u32) {
         self.banner_of(view_id).borrow_mut().focus(true);
+        self.get_view_editor_unchecked(view_id)
+            .borrow_mut()
+            .set_active(true);
     }
 
     fn unfocus(&self, view_id: u32) {
         self.banner_of(view_id).borrow_mut().focus(false);
+        self.get_view_editor_unchecked(view_id)
+            .borrow_mut()
+            .set_active(false);
     }
 
     fn next_editor_id(&mut self) -> u32 {
@@ -40,6 +40,9 @@ pub enum Error {
     /// A `key_seq` is restricted from being rebound.
     RestrictedKey { key_seq: String },
 
+    /// A context `name` given for key bindings is not valid.
+    InvalidContext { name: String },
+
     /// An error occurred while parsing a configuration file referenced by `path`.
     Configuration { path: String, cause: String },
 
@@ -51,6 +54,21 @@ pub enum Error {
 
     /// The color `name` is not valid.
     InvalidColor { name: String },
+
+    /// A `token` referenced in a color override is not defined by `syntax`.
+    InvalidToken { syntax: String, token: String },
+
+    /// An external `command` failed for the given `cause`.
+    Command { command: String, cause: String },
+
+    /// An error occurred while reading or writing a session file referenced by `path`.
+    Session { path: String, cause: String },
+
+    /// The file referenced by `path` cannot be written because it is still loading.
+    Loading { path: String },
+
+    /// Writing the file referenced by `path` did not finish within `secs` seconds.
+    Timeout { path: String, secs: u32 },
 }
 
 impl error::Error for Error {}
@@ -101,6 +119,12 @@ impl Error {
         }
     }
 
+    pub fn invalid_context(name: &str) -> Error {
+        Error::InvalidContext {
+            name: name.to_string(),
+        }
+    }
+
     pub fn invalid_key(key: &str) -> Error {
         Error::InvalidKey {
             key: key.to_string(),
@@ -131,6 +155,13 @@ impl Error {
         }
     }
 
+    pub fn invalid_extends(path: &str, cause: &str) -> Error {
+        Error::Syntax {
+            path: path.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
     pub fn invalid_regex(pattern: &str, e: &regex_lite::Error) -> Error {
         Error::InvalidRegex {
             pattern: pattern.to_string(),
@@ -143,6 +174,62 @@ impl Error {
             name: name.to_string(),
         }
     }
+
+    pub fn invalid_token(syntax: &str, token: &str) -> Error {
+        Error::InvalidToken {
+            syntax: syntax.to_string(),
+            token: token.to_string(),
+        }
+    }
+
+    pub fn command(command: &str, cause: &str) -> Error {
+        Error::Command {
+            command: command.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn session(path: &str, cause: &str) -> Error {
+        Error::Session {
+            path: path.to_string(),
+            cause: cause.to_string(),
+        }
+    }
+
+    pub fn loading(path: &str) -> Error {
+        Error::Loading {
+            path: path.to_string(),
+        }
+    }
+
+    pub fn timeout(path: &str, secs: u32) -> Error {
+        Error::Timeout {
+            path: path.to_string(),
+            secs,
+        }
+    }
+
+    /// Returns `true` if the error is likely transient, in which case the operation
+    /// that caused it may succeed if tried again.
+    ///
+    /// Such errors are typical of network filesystems, which report stale file
+    /// handles, interruptions and timeouts when the server is briefly unavailable.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Os { cause } | Error::Io { cause, .. } => {
+                matches!(
+                    cause.kind(),
+                    io::ErrorKind::Interrupted
+                        | io::ErrorKind::TimedOut
+                        | io::ErrorKind::WouldBlock
+                ) || matches!(
+                    cause.raw_os_error(),
+                    Some(libc::EIO | libc::ESTALE | libc::ENOLCK | libc::EHOSTDOWN)
+                )
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Display for Error {
@@ -161,6 +248,7 @@ impl Display for Error {
             Error::RestrictedKey { key_seq } => {
                 write!(f, "{key_seq}: key sequence cannot be rebound")
             }
+            Error::InvalidContext { name } => write!(f, "{name}: invalid binding context"),
             Error::Configuration { path, cause } => {
                 write!(f, "{path}: configuration error: {cause}")
             }
@@ -173,6 +261,19 @@ impl Display for Error {
             Error::InvalidColor { name } => {
                 write!(f, "{name}: invalid color")
             }
+            Error::InvalidToken { syntax, token } => {
+                write!(f, "{token}: token not found in syntax {syntax}")
+            }
+            Error::Command { command, cause } => {
+                write!(f, "{command}: command failed: {cause}")
+            }
+            Error::Session { path, cause } => {
+                write!(f, "{path}: session error: {cause}")
+            }
+            Error::Loading { path } => write!(f, "{path}: file is still loading"),
+            Error::Timeout { path, secs } => {
+                write!(f, "{path}: write did not finish within {secs} seconds")
+            }
         }
     }
 }
@@ -16,6 +16,9 @@ pub struct Cell {
     /// A Unicode character.
     pub value: char,
 
+    /// A combining character, such as an accent, drawn over `value`.
+    pub mark: Option<char>,
+
     /// The foreground and background color of the character.
     pub color: Color,
 }
@@ -30,7 +33,27 @@ impl Cell {
     /// Creates a new cell from `value` and `color`.
     #[inline(always)]
     pub const fn new(value: char, color: Color) -> Cell {
-        Cell { value, color }
+        Cell {
+            value,
+            mark: None,
+            color,
+        }
+    }
+
+    /// Creates a cell covered by the right half of a wide character in the preceding
+    /// cell using `color`.
+    ///
+    /// Such cells share the `NULL` character with [`Cell::EMPTY`], since neither is
+    /// ever written to the terminal.
+    #[inline(always)]
+    pub const fn covered(color: Color) -> Cell {
+        Cell::new('\0', color)
+    }
+
+    /// Returns `true` if this cell is not written to the terminal.
+    #[inline(always)]
+    pub fn is_covered(&self) -> bool {
+        self.value == '\0'
     }
 }
 
@@ -64,6 +87,13 @@ impl Grid {
         self.content[(row * self.size.cols + col) as usize] = cell;
     }
 
+    /// Returns the `row`:`col` cell.
+    pub fn get_cell(&self, row: u32, col: u32) -> Cell {
+        debug_assert!(row < self.size.rows);
+        debug_assert!(col < self.size.cols);
+        self.content[(row * self.size.cols + col) as usize]
+    }
+
     /// Fills all cells in `row` in the range [`start_col`, `end_col`) to `cell`.
     pub fn fill_range(&mut self, row: u32, start_col: u32, end_col: u32, cell: Cell) {
         debug_assert!(row < self.size.rows);
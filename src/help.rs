@@ -4,22 +4,35 @@
 // check return values.
 #![allow(unused_must_use)]
 
+use crate::bind::{Bindings, Conflict};
 use crate::buffer::Buffer;
 use crate::config::{ConfigurationRef, Theme};
-use crate::editor::{Editor, EditorRef};
+use crate::editor::{Editor, EditorRef, Usage};
 use crate::etc;
 use crate::key::{self, Key, KEY_MAPPINGS};
 use crate::op::OP_MAPPINGS;
+use crate::perf::{Perf, Timing};
 use crate::source::Source;
+use crate::syntax::Syntax;
+use crate::token::Tokenizer;
 use indexmap::IndexMap;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt::Write;
+use std::mem;
+use std::time::Duration;
 
 pub const HELP_EDITOR_NAME: &str = "help";
 pub const KEYS_EDITOR_NAME: &str = "keys";
 pub const OPS_EDITOR_NAME: &str = "operations";
 pub const BINDINGS_EDITOR_NAME: &str = "bindings";
+pub const BINDINGS_AUDIT_EDITOR_NAME: &str = "bindings-audit";
+pub const EXPORT_BINDINGS_EDITOR_NAME: &str = "export-bindings";
 pub const COLORS_EDITOR_NAME: &str = "colors";
+pub const SEARCH_HELP_EDITOR_NAME: &str = "search-help";
+pub const THEME_EDITOR_NAME: &str = "theme";
+pub const SYNTAX_EDITOR_NAME: &str = "syntax";
+pub const MEMORY_EDITOR_NAME: &str = "memory";
+pub const PERF_EDITOR_NAME: &str = "perf";
 
 /// Returns an ephemeral editor, named `@help`, containing general help content.
 pub fn help_editor(config: ConfigurationRef) -> EditorRef {
@@ -123,36 +136,109 @@ fn prepare_ops() -> Vec<String> {
 
 /// Returns an ephemeral editor, named `@bindings`, containing a list of key bindings.
 pub fn bindings_editor(config: ConfigurationRef) -> EditorRef {
-    let buffer = bindings_buffer(config.bindings.bindings());
+    let buffer = bindings_buffer(&config.bindings);
     Editor::readonly(config, Source::as_ephemeral(BINDINGS_EDITOR_NAME), buffer).to_ref()
 }
 
-/// Returns a TOML-formatted list of key bindings.
-pub fn bindings_content(bindings: &HashMap<Vec<Key>, String>) -> String {
-    let bindings = prepare_bindings(bindings);
+/// Returns a TOML-formatted list of key bindings, preceded by comments describing
+/// any `conflicts`.
+pub fn bindings_content(bindings: &HashMap<Vec<Key>, String>, conflicts: &[Conflict]) -> String {
+    let bindings = bindings
+        .iter()
+        .map(|(keys, op)| (key::key_seq(keys), op))
+        .collect::<BTreeMap<_, _>>();
     let mut out = String::new();
+    for conflict in conflicts {
+        writeln!(out, "# conflict: {conflict}");
+    }
     for (key_seq, op) in bindings {
-        let key_seq = key_seq.replace(' ', ":");
-        writeln!(out, "\"{key_seq}\" = \"{op}\"");
+        writeln!(out, "{} = \"{op}\"", toml_string(&key_seq));
+    }
+    out
+}
+
+/// Returns an ephemeral editor, named `@export-bindings`, containing the current
+/// key bindings as a configuration fragment, which can be saved to a file.
+pub fn export_bindings_editor(config: ConfigurationRef) -> EditorRef {
+    let mut buffer = Buffer::new();
+    buffer.insert_str(&export_bindings_content(&config.bindings));
+    buffer.set_pos(0);
+    Editor::readonly(
+        config,
+        Source::as_ephemeral(EXPORT_BINDINGS_EDITOR_NAME),
+        buffer,
+    )
+    .to_ref()
+}
+
+/// Returns the current key bindings, which include default bindings merged with
+/// those from configuration files, as a `[bindings]` section suitable for pasting
+/// into a configuration file.
+///
+/// Restricted key sequences are omitted since these cannot be rebound.
+pub fn export_bindings_content(bindings: &Bindings) -> String {
+    let exported = bindings
+        .bindings()
+        .iter()
+        .filter(|(keys, _)| !bindings.is_restricted(keys))
+        .map(|(keys, op)| (keys.clone(), op.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut out = String::new();
+    writeln!(out, "# Key bindings exported by {}", etc::version());
+    writeln!(out, "[bindings]");
+    write!(out, "{}", bindings_content(&exported, &[]));
+    write!(out, "{}", scoped_bindings_content(bindings));
+    out
+}
+
+/// Returns the key bindings within each scope as `[context-bindings]` sections
+/// ordered by the name of the scope.
+pub fn scoped_bindings_content(bindings: &Bindings) -> String {
+    let scoped = bindings
+        .scoped_bindings()
+        .iter()
+        .map(|(scope, map)| (scope.to_string(), map))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut out = String::new();
+    for (scope, map) in scoped {
+        writeln!(out);
+        writeln!(out, "[context-bindings.{}]", toml_string(&scope));
+        write!(out, "{}", bindings_content(map, &[]));
     }
     out
 }
 
-fn bindings_buffer(bindings: &HashMap<Vec<Key>, String>) -> Buffer {
+/// Returns `s` as a TOML basic string, escaping `"` and `\` characters.
+fn toml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn bindings_buffer(bindings: &Bindings) -> Buffer {
     const HEADER_KEY: &str = "[Key]";
     const HEADER_OP: &str = "[Operation]";
 
-    // Prettify and sort key sequences.
-    let bindings = prepare_bindings(bindings);
+    // Prettify and sort key sequences, where those bound within scopes follow in
+    // order of scope name.
+    let scoped = bindings
+        .scoped_bindings()
+        .iter()
+        .map(|(scope, map)| (scope.to_string(), prepare_bindings(map)))
+        .collect::<BTreeMap<_, _>>();
+    let bindings = prepare_bindings(bindings.bindings());
 
     // Calculate maximum width of key sequences to align output.
-    let key_width = bindings.keys().fold(HEADER_KEY.len(), |width, k| {
-        if k.len() > width {
-            k.len()
-        } else {
-            width
-        }
-    });
+    let key_width = bindings
+        .keys()
+        .chain(scoped.values().flat_map(|map| map.keys()))
+        .fold(HEADER_KEY.len(), |width, k| {
+            if k.len() > width {
+                k.len()
+            } else {
+                width
+            }
+        });
 
     // Emit formatted bindings.
     let mut buf = Buffer::new();
@@ -160,6 +246,39 @@ fn bindings_buffer(bindings: &HashMap<Vec<Key>, String>) -> Buffer {
     for (key_seq, op) in bindings {
         writeln!(buf, "{key_seq:<key_width$}   {op}");
     }
+    for (scope, bindings) in scoped {
+        writeln!(buf);
+        writeln!(buf, "{:<key_width$}   [Operation in {scope}]", HEADER_KEY);
+        for (key_seq, op) in bindings {
+            writeln!(buf, "{key_seq:<key_width$}   {op}");
+        }
+    }
+    buf.set_pos(0);
+    buf
+}
+
+/// Returns an ephemeral editor, named `@bindings-audit`, containing a list of
+/// conflicts among key bindings.
+pub fn bindings_audit_editor(config: ConfigurationRef) -> EditorRef {
+    let buffer = bindings_audit_buffer(&config.bindings.audit());
+    Editor::readonly(
+        config,
+        Source::as_ephemeral(BINDINGS_AUDIT_EDITOR_NAME),
+        buffer,
+    )
+    .to_ref()
+}
+
+fn bindings_audit_buffer(conflicts: &[Conflict]) -> Buffer {
+    let mut buf = Buffer::new();
+    writeln!(buf, "[Conflicts]");
+    if !conflicts.is_empty() {
+        for conflict in conflicts {
+            writeln!(buf, "{conflict}");
+        }
+    } else {
+        writeln!(buf, "none detected");
+    }
     buf.set_pos(0);
     buf
 }
@@ -225,27 +344,731 @@ fn prepare_colors(colors: &HashMap<String, u8>) -> IndexMap<String, u8> {
         .collect::<IndexMap<_, _>>()
 }
 
+/// Returns an ephemeral editor, named `@search-help`, containing the keys, editing
+/// operations, and key bindings that match `term`, ignoring case.
+pub fn search_help_editor(config: ConfigurationRef, term: &str) -> EditorRef {
+    let buffer = search_help_buffer(config.bindings.bindings(), term);
+    Editor::readonly(
+        config,
+        Source::as_ephemeral(SEARCH_HELP_EDITOR_NAME),
+        buffer,
+    )
+    .to_ref()
+}
+
+fn search_help_buffer(bindings: &HashMap<Vec<Key>, String>, term: &str) -> Buffer {
+    let term = term.to_lowercase();
+    let is_match = |s: &str| s.to_lowercase().contains(&term);
+
+    // Operations match on either name or description.
+    let keys = prepare_keys()
+        .into_iter()
+        .filter(|key_name| is_match(key_name))
+        .collect::<Vec<_>>();
+    let ops = prepare_ops()
+        .into_iter()
+        .map(|op| {
+            let desc = describe_op(&op).unwrap_or("");
+            (op, desc)
+        })
+        .filter(|(op, desc)| is_match(op) || is_match(desc))
+        .collect::<Vec<_>>();
+    let bindings = prepare_bindings(bindings)
+        .into_iter()
+        .filter(|(key_seq, op)| is_match(key_seq) || is_match(op))
+        .collect::<Vec<_>>();
+
+    // Calculate maximum widths of operations and key sequences to align output.
+    let op_width = ops.iter().map(|(op, _)| op.len()).max().unwrap_or(0);
+    let key_width = bindings.iter().map(|(k, _)| k.len()).max().unwrap_or(0);
+
+    let mut buf = Buffer::new();
+    writeln!(buf, "[Keys]");
+    for key_name in keys {
+        writeln!(buf, "{key_name}");
+    }
+    writeln!(buf, "\n[Operations]");
+    for (op, desc) in ops {
+        writeln!(buf, "{op:<op_width$}   {desc}");
+    }
+    writeln!(buf, "\n[Bindings]");
+    for (key_seq, op) in bindings {
+        writeln!(buf, "{key_seq:<key_width$}   {op}");
+    }
+    buf.set_pos(0);
+    buf
+}
+
+/// Returns an ephemeral editor, named `@theme`, containing swatches of every theme
+/// color as well as the token colors of `syntax`, each rendered in its respective
+/// color.
+pub fn theme_editor(config: ConfigurationRef, syntax: &Syntax) -> EditorRef {
+    let (buffer, theme_syntax) = theme_buffer(&config.theme, syntax, config.settings.ascii_only);
+    Editor::readonly_with_syntax(
+        config,
+        Source::as_ephemeral(THEME_EDITOR_NAME),
+        buffer,
+        theme_syntax,
+    )
+    .to_ref()
+}
+
+/// Returns a buffer of theme and token colors along with a syntax configuration
+/// that renders each line in its respective color, where `ascii_only` indicates
+/// whether swatches are drawn using only ASCII characters.
+fn theme_buffer(theme: &Theme, syntax: &Syntax, ascii_only: bool) -> (Buffer, Syntax) {
+    const SWATCH: &str = "\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}\u{2588}";
+    const ASCII_SWATCH: &str = "########";
+    const SAMPLE: &str = "The quick brown fox jumps over the lazy dog";
+
+    // Background colors can only be shown as swatches since syntax tokens only
+    // affect the foreground color of text.
+    let entries = THEME_COLORS
+        .iter()
+        .map(|(name, t_fn)| (name.to_string(), t_fn(theme), SAMPLE))
+        .chain(
+            syntax
+                .tokens()
+                .into_iter()
+                .enumerate()
+                .map(|(i, (pattern, color))| (format!("token-{}", i + 1), color, pattern)),
+        )
+        .collect::<Vec<_>>();
+    let name_width = entries
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .max()
+        .unwrap_or(0);
+
+    let swatch = if ascii_only { ASCII_SWATCH } else { SWATCH };
+    let mut buf = Buffer::new();
+    let mut tokens = Vec::new();
+    writeln!(buf, "[Theme]");
+    for (i, (name, color, sample)) in entries.iter().enumerate() {
+        if i == THEME_COLORS.len() {
+            writeln!(buf, "\n[Syntax: {}]", syntax.name);
+        }
+        writeln!(
+            buf,
+            "{name:<name_width$}   {color:>3}   {swatch}   {sample}"
+        );
+
+        // Each line is uniquely identified by its name, which is padded with spaces.
+        let pattern = format!(
+            "^{} .*$",
+            regex_lite::escape(&format!("{name:<name_width$}"))
+        );
+        tokens.push((pattern, *color));
+    }
+    buf.set_pos(0);
+
+    let syntax = Syntax::new(THEME_EDITOR_NAME.to_string(), tokens).unwrap_or_default();
+    (buf, syntax)
+}
+
+/// Returns an ephemeral editor, named `@syntax`, containing a legend of the token
+/// definitions in `syntax` followed by every token found in `buffer`, each of which
+/// is annotated with its location, token name, and color.
+///
+/// The editor is positioned at the first token ending beyond `pos`.
+pub fn syntax_editor(
+    config: ConfigurationRef,
+    syntax: &Syntax,
+    buffer: &Buffer,
+    pos: usize,
+) -> EditorRef {
+    let (buffer, debug_syntax) = syntax_buffer(syntax, buffer, pos);
+    Editor::readonly_with_syntax(
+        config,
+        Source::as_ephemeral(SYNTAX_EDITOR_NAME),
+        buffer,
+        debug_syntax,
+    )
+    .to_ref()
+}
+
+/// Returns a buffer of tokens found in `buffer` along with a syntax configuration
+/// that renders each line in the color of its respective token.
+fn syntax_buffer(syntax: &Syntax, buffer: &Buffer, pos: usize) -> (Buffer, Syntax) {
+    let mut tokenizer = Tokenizer::new(syntax.clone());
+    tokenizer.tokenize(buffer);
+
+    // Locations are formatted as line:column, both of which are 1-based.
+    let tokens = tokenizer
+        .tokens()
+        .into_iter()
+        .map(|(id, range)| {
+            let line = buffer.line_of(range.start);
+            let col = range.start - buffer.find_line(line);
+            let loc = format!("{}:{}", line + 1, col + 1);
+            (id, range, loc)
+        })
+        .collect::<Vec<_>>();
+    let loc_width = tokens
+        .iter()
+        .map(|(_, _, loc)| loc.len())
+        .max()
+        .unwrap_or(0);
+
+    let mut buf = Buffer::new();
+    let mut lines = Vec::new();
+    writeln!(buf, "[Syntax: {}]", syntax.name);
+    for (i, (pattern, color)) in syntax.tokens().into_iter().enumerate() {
+        writeln!(buf, "token-{:<4} {color:>3}   {pattern}", i + 1);
+        lines.push((format!(r"^(?:\S+ +)?token-{}\b.*$", i + 1), color));
+    }
+
+    writeln!(buf, "\n[Tokens]");
+    let mut cur_pos = None;
+    for (id, range, loc) in tokens {
+        if cur_pos.is_none() && range.end > pos {
+            cur_pos = Some(buf.get_pos());
+        }
+        let text = buffer.copy_as_string(range.start, range.end);
+        let color = syntax.color(id).unwrap_or(0);
+        writeln!(
+            buf,
+            "{loc:<loc_width$}   token-{id:<4} {color:>3}   {text:?}"
+        );
+    }
+    buf.set_pos(cur_pos.unwrap_or(0));
+
+    let syntax = Syntax::new(SYNTAX_EDITOR_NAME.to_string(), lines).unwrap_or_default();
+    (buf, syntax)
+}
+
+/// Returns an ephemeral editor, named `@memory`, containing a report of memory held by
+/// each of `editors`, given as tuples of editor name and usage, and the number of
+/// characters in the clipboard.
+pub fn memory_editor(
+    config: ConfigurationRef,
+    editors: &[(String, Usage)],
+    clipboard: usize,
+) -> EditorRef {
+    Editor::readonly(
+        config,
+        Source::as_ephemeral(MEMORY_EDITOR_NAME),
+        memory_buffer(editors, clipboard),
+    )
+    .to_ref()
+}
+
+fn memory_buffer(editors: &[(String, Usage)], clipboard: usize) -> Buffer {
+    const HEADERS: [&str; 6] = ["size", "capacity", "undo", "redo", "spans", "KiB"];
+
+    // Memory is estimated from the number of characters held by each editor.
+    let kib_of = |chars: usize| (chars * mem::size_of::<char>()).div_ceil(1024);
+    let mut rows = editors
+        .iter()
+        .map(|(name, usage)| {
+            let chars = usage.capacity + usage.undo.1 + usage.redo.1;
+            (
+                name.clone(),
+                [
+                    usage.size.to_string(),
+                    usage.capacity.to_string(),
+                    format!("{}/{}", usage.undo.0, usage.undo.1),
+                    format!("{}/{}", usage.redo.0, usage.redo.1),
+                    usage.spans.to_string(),
+                    kib_of(chars).to_string(),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+    let total = editors
+        .iter()
+        .map(|(_, usage)| usage.capacity + usage.undo.1 + usage.redo.1)
+        .sum::<usize>();
+    rows.push((
+        "total".to_string(),
+        ["", "", "", "", "", &kib_of(total).to_string()].map(|s| s.to_string()),
+    ));
+
+    let name_width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let widths = (0..HEADERS.len())
+        .map(|i| {
+            rows.iter()
+                .map(|(_, cols)| cols[i].len())
+                .chain([HEADERS[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    let mut buf = Buffer::new();
+    writeln!(buf, "[Editors]");
+    write!(buf, "{:<name_width$}", "");
+    for (header, width) in HEADERS.iter().zip(&widths) {
+        write!(buf, "   {header:>width$}");
+    }
+    writeln!(buf);
+    for (name, cols) in rows {
+        write!(buf, "{name:<name_width$}");
+        for (col, width) in cols.iter().zip(&widths) {
+            write!(buf, "   {col:>width$}");
+        }
+        writeln!(buf);
+    }
+    writeln!(buf, "\n[Clipboard]");
+    writeln!(buf, "{clipboard} chars, {} KiB", kib_of(clipboard));
+    writeln!(buf, "\n[Legend]");
+    writeln!(buf, "size     : characters in buffer");
+    writeln!(buf, "capacity : characters allocated to buffer");
+    writeln!(buf, "undo     : changes/characters in undo stack");
+    writeln!(buf, "redo     : changes/characters in redo stack");
+    writeln!(buf, "spans    : spans produced by tokenizer");
+    writeln!(
+        buf,
+        "KiB      : estimate of memory held by buffer and stacks"
+    );
+    buf.set_pos(0);
+    buf
+}
+
+/// Returns an ephemeral editor, named `@perf`, containing a report of execution times
+/// of frames and the slowest operations recorded in `perf`.
+pub fn perf_editor(config: ConfigurationRef, perf: &Perf) -> EditorRef {
+    Editor::readonly(
+        config,
+        Source::as_ephemeral(PERF_EDITOR_NAME),
+        perf_buffer(perf),
+    )
+    .to_ref()
+}
+
+fn perf_buffer(perf: &Perf) -> Buffer {
+    const SLOWEST_OPS: usize = 20;
+    const HEADERS: [&str; 4] = ["count", "avg", "p95", "max"];
+
+    // Times are shown in milliseconds with microsecond precision.
+    let ms_of = |time: Duration| format!("{:.3}", time.as_secs_f64() * 1000.0);
+    let cols_of = |timing: &Timing| {
+        [
+            timing.count().to_string(),
+            ms_of(timing.average()),
+            ms_of(timing.percentile(0.95)),
+            ms_of(timing.max()),
+        ]
+    };
+    let mut rows = vec![("frame".to_string(), cols_of(perf.frames()))];
+    rows.extend(
+        perf.slowest(SLOWEST_OPS)
+            .into_iter()
+            .map(|(op, timing)| (op.to_string(), cols_of(timing))),
+    );
+
+    let name_width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    let widths = (0..HEADERS.len())
+        .map(|i| {
+            rows.iter()
+                .map(|(_, cols)| cols[i].len())
+                .chain([HEADERS[i].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+    let write_row = |buf: &mut Buffer, name: &str, cols: &[String]| {
+        write!(buf, "{name:<name_width$}");
+        for (col, width) in cols.iter().zip(&widths) {
+            write!(buf, "   {col:>width$}");
+        }
+        writeln!(buf);
+    };
+
+    let mut buf = Buffer::new();
+    writeln!(buf, "[Frames]");
+    write_row(&mut buf, "", &HEADERS.map(|s| s.to_string()));
+    write_row(&mut buf, &rows[0].0, &rows[0].1);
+    writeln!(buf, "\n[Frame histogram]");
+    let histogram = perf.frames().histogram();
+    let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (i, count) in histogram.iter().enumerate().filter(|(_, n)| **n > 0) {
+        let bound = if i == Timing::BUCKETS - 1 {
+            "max".to_string()
+        } else {
+            format!("< {}", ms_of(Duration::from_micros(Timing::bound_of(i))))
+        };
+        let bar = "#".repeat((*count as usize * 40).div_ceil(most as usize));
+        writeln!(buf, "{bound:>10} ms {count:>5} {bar}");
+    }
+    writeln!(buf, "\n[Slowest operations]");
+    write_row(&mut buf, "", &HEADERS.map(|s| s.to_string()));
+    for (name, cols) in &rows[1..] {
+        write_row(&mut buf, name, cols);
+    }
+    writeln!(buf, "\n[Legend]");
+    writeln!(
+        buf,
+        "frame : processing of key, including operation and rendering"
+    );
+    writeln!(buf, "count : executions since start");
+    writeln!(buf, "avg   : average of recent executions in milliseconds");
+    writeln!(
+        buf,
+        "p95   : 95th percentile of recent executions in milliseconds"
+    );
+    writeln!(buf, "max   : maximum of recent executions in milliseconds");
+    buf.set_pos(0);
+    buf
+}
+
+/// A function that extracts the value of a color from a theme.
+type ThemeColorFn = fn(&Theme) -> u8;
+
+/// Names of theme colors and functions that extract corresponding values.
+const THEME_COLORS: [(&str, ThemeColorFn); 18] = [
+    ("text-fg", |t| t.text_fg),
+    ("text-bg", |t| t.text_bg),
+    ("select-bg", |t| t.select_bg),
+    ("spotlight-bg", |t| t.spotlight_bg),
+    ("highlight-bg", |t| t.highlight_bg),
+    ("bracket-bg", |t| t.bracket_bg),
+    ("whitespace-fg", |t| t.whitespace_fg),
+    ("accent-fg", |t| t.accent_fg),
+    ("echo-fg", |t| t.echo_fg),
+    ("warn-fg", |t| t.warn_fg),
+    ("error-fg", |t| t.error_fg),
+    ("prompt-fg", |t| t.prompt_fg),
+    ("banner-fg", |t| t.banner_fg),
+    ("active-bg", |t| t.active_bg),
+    ("inactive-bg", |t| t.inactive_bg),
+    ("inactive-fg", |t| t.inactive_fg),
+    ("margin-fg", |t| t.margin_fg),
+    ("margin-bg", |t| t.margin_bg),
+];
+
 /// Returns a TOML-formatted list of theme color names and values.
 pub fn theme_content(theme: &Theme) -> String {
-    const COLORS: [(&str, fn(&Theme) -> u8); 13] = [
-        ("text-fg", |t| t.text_fg),
-        ("text-bg", |t| t.text_bg),
-        ("select-bg", |t| t.select_bg),
-        ("spotlight-bg", |t| t.spotlight_bg),
-        ("whitespace-fg", |t| t.whitespace_fg),
-        ("accent-fg", |t| t.accent_fg),
-        ("echo-fg", |t| t.echo_fg),
-        ("prompt-fg", |t| t.prompt_fg),
-        ("banner-fg", |t| t.banner_fg),
-        ("active-bg", |t| t.active_bg),
-        ("inactive-bg", |t| t.inactive_bg),
-        ("margin-fg", |t| t.margin_fg),
-        ("margin-bg", |t| t.margin_bg),
-    ];
-
     let mut out = String::new();
-    for (name, t_fn) in COLORS {
+    for (name, t_fn) in THEME_COLORS {
         writeln!(out, "{name} = {}", t_fn(theme));
     }
     out
 }
+
+/// Returns a short description of the editing operation `op`, or `None` if `op` is
+/// not recognized.
+pub fn describe_op(op: &str) -> Option<&'static str> {
+    OP_DESCRIPTIONS
+        .iter()
+        .find(|(name, _)| *name == op)
+        .map(|(_, desc)| *desc)
+}
+
+/// Short descriptions of editing operations, which are expected to correspond to
+/// those defined in [`OP_MAPPINGS`].
+const OP_DESCRIPTIONS: [(&str, &str); 154] = [
+    // --- exit and cancellation ---
+    ("quit", "Quit ped"),
+    // --- help ---
+    ("help", "Toggle @help window (general help)"),
+    ("help-keys", "Toggle @keys window (available keys)"),
+    (
+        "help-ops",
+        "Toggle @operations window (available operations)",
+    ),
+    ("help-bindings", "Toggle @bindings window (key bindings)"),
+    (
+        "audit-bindings",
+        "Toggle @bindings-audit window (binding conflicts)",
+    ),
+    (
+        "export-bindings",
+        "Toggle @export-bindings window (bindings as config)",
+    ),
+    ("help-colors", "Toggle @colors window (color names)"),
+    (
+        "preview-theme",
+        "Toggle @theme window (theme and token colors)",
+    ),
+    (
+        "debug-syntax",
+        "Show tokens of current buffer in @syntax window",
+    ),
+    (
+        "memory-report",
+        "Show memory held by editors in @memory window",
+    ),
+    ("perf-stats", "Show slowest operations in @perf window"),
+    (
+        "show-audit",
+        "Show audit log of operations in @audit window",
+    ),
+    ("describe-key", "Describe operation bound to key sequence"),
+    (
+        "search-help",
+        "List keys, operations, and bindings matching term",
+    ),
+    // --- navigation and selection ---
+    ("move-backward", "Move backward one character"),
+    ("move-backward-word", "Move backward one word"),
+    (
+        "move-backward-select",
+        "Move backward one character and select",
+    ),
+    (
+        "move-backward-word-select",
+        "Move backward one word and select",
+    ),
+    ("move-forward", "Move forward one character"),
+    ("move-forward-word", "Move forward one word"),
+    (
+        "move-forward-select",
+        "Move forward one character and select",
+    ),
+    (
+        "move-forward-word-select",
+        "Move forward one word and select",
+    ),
+    ("move-up", "Move up one line"),
+    ("move-up-select", "Move up one line and select"),
+    ("move-down", "Move down one line"),
+    ("move-down-select", "Move down one line and select"),
+    ("move-up-page", "Move up one page"),
+    ("move-up-page-select", "Move up one page and select"),
+    ("move-down-page", "Move down one page"),
+    ("move-down-page-select", "Move down one page and select"),
+    ("move-up-half-page", "Move up one half page"),
+    ("move-down-half-page", "Move down one half page"),
+    ("move-start", "Move to start of line"),
+    ("move-start-select", "Move to start of line and select"),
+    ("move-end", "Move to end of line"),
+    ("move-end-select", "Move to end of line and select"),
+    ("move-top", "Move to top of editor"),
+    ("move-top-select", "Move to top of editor and select"),
+    ("move-bottom", "Move to bottom of editor"),
+    ("move-bottom-select", "Move to bottom of editor and select"),
+    ("scroll-up", "Scroll up one line"),
+    ("scroll-up-select", "Scroll up one line and select"),
+    ("scroll-down", "Scroll down one line"),
+    ("scroll-down-select", "Scroll down one line and select"),
+    ("scroll-center", "Redraw window and center cursor"),
+    ("set-mark", "Set/Unset mark"),
+    ("set-mark-column", "Set/Unset mark for block selection"),
+    ("match-bracket", "Move to matching bracket"),
+    ("goto-line", "Go to line number or select range of lines"),
+    ("goto-column", "Go to column in line"),
+    // --- insertion and removal ---
+    ("insert-line", "Insert line break"),
+    ("insert-tab", "Insert tab"),
+    ("remove-before", "Remove character before cursor"),
+    ("remove-after", "Remove character after cursor"),
+    ("remove-backward-word", "Remove word before cursor"),
+    ("remove-forward-word", "Remove word after cursor"),
+    (
+        "remove-start",
+        "Remove characters from start of line to cursor",
+    ),
+    ("remove-end", "Remove characters from cursor to end of line"),
+    ("duplicate-line", "Duplicate selected lines or line"),
+    ("move-line-up", "Move selected lines or line up"),
+    ("move-line-down", "Move selected lines or line down"),
+    ("join-lines", "Join selected lines or line with next line"),
+    ("undo", "Undo last change"),
+    ("redo", "Redo last undo"),
+    (
+        "hard-wrap-region",
+        "Break lines of selection or paragraph at fill column",
+    ),
+    (
+        "unwrap-region",
+        "Join lines of each paragraph in selection or paragraph",
+    ),
+    (
+        "reflow-paragraph",
+        "Rewrap selection or paragraph at fill column",
+    ),
+    (
+        "reindent-region",
+        "Recompute indentation of selection or paragraph",
+    ),
+    (
+        "toggle-comment",
+        "Comment or uncomment selected lines or line",
+    ),
+    (
+        "sort-lines",
+        "Sort lines of selection or paragraph by locale",
+    ),
+    ("upcase", "Convert selection or word to uppercase by locale"),
+    (
+        "downcase",
+        "Convert selection or word to lowercase by locale",
+    ),
+    (
+        "insert-template-for-type",
+        "Insert template matching file extension at cursor",
+    ),
+    (
+        "expand-pair",
+        "Insert closing text of construct before cursor",
+    ),
+    // --- selection actions ---
+    ("copy", "Copy selection or line to clipboard"),
+    ("paste", "Paste contents of clipboard"),
+    (
+        "paste-adjust-indent",
+        "Paste contents of clipboard aligned with indentation at cursor",
+    ),
+    (
+        "paste-block",
+        "Paste lines of clipboard as block at column of cursor",
+    ),
+    ("cut", "Cut selection or line and copy to clipboard"),
+    (
+        "copy-append",
+        "Copy selection or line and append to clipboard",
+    ),
+    (
+        "cut-append",
+        "Cut selection or line and append to clipboard",
+    ),
+    // --- multiple cursors ---
+    ("add-cursor-up", "Add cursor and move to previous line"),
+    ("add-cursor-down", "Add cursor and move to next line"),
+    (
+        "add-cursor-at-next-match",
+        "Add cursor and move to next match of selection or word",
+    ),
+    ("clear-cursors", "Remove all cursors except the current one"),
+    (
+        "sequence-insert",
+        "Insert incrementing number at each cursor or selected line",
+    ),
+    // --- search ---
+    ("search", "Search using term (case-insensitive)"),
+    ("search-case", "Search using term (case-sensitive)"),
+    (
+        "search-regex",
+        "Search using regular expression (case-insensitive)",
+    ),
+    (
+        "search-regex-case",
+        "Search using regular expression (case-sensitive)",
+    ),
+    ("search-next", "Search for next match"),
+    ("search-prev", "Search for previous match"),
+    ("replace", "Replace matches of term, asking for each"),
+    (
+        "replace-regex",
+        "Replace matches of regular expression, asking for each",
+    ),
+    ("replace-all", "Replace all matches of term"),
+    (
+        "occur",
+        "List matching lines in @occur window (or refresh list)",
+    ),
+    (
+        "search-all",
+        "List matching lines of all editors in @matches window",
+    ),
+    ("next-marker", "Move to next marker, such as TODO"),
+    ("prev-marker", "Move to previous marker, such as TODO"),
+    // --- file handling ---
+    ("open-file", "Open file in current window"),
+    (
+        "open-file-top",
+        "Open file in new window at top of workspace",
+    ),
+    (
+        "open-file-bottom",
+        "Open file in new window at bottom of workspace",
+    ),
+    (
+        "open-file-above",
+        "Open file in new window above current window",
+    ),
+    (
+        "open-file-below",
+        "Open file in new window below current window",
+    ),
+    (
+        "open-file-left",
+        "Open file in new window left of current window",
+    ),
+    (
+        "open-file-right",
+        "Open file in new window right of current window",
+    ),
+    ("save-file", "Save file"),
+    ("save-file-as", "Save file as another name"),
+    ("delete-file", "Move file to trash and close editor"),
+    ("undelete-file", "Restore file most recently moved to trash"),
+    ("rename-file", "Rename file in storage"),
+    // --- editor handling ---
+    ("select-editor", "Switch to editor in current window"),
+    (
+        "select-editor-top",
+        "Switch to editor in new window at top of workspace",
+    ),
+    (
+        "select-editor-bottom",
+        "Switch to editor in new window at bottom of workspace",
+    ),
+    (
+        "select-editor-above",
+        "Switch to editor in new window above current window",
+    ),
+    (
+        "select-editor-below",
+        "Switch to editor in new window below current window",
+    ),
+    ("prev-editor", "Switch to previous editor in current window"),
+    ("next-editor", "Switch to next editor in current window"),
+    (
+        "next-dirty-editor",
+        "Switch to next editor with unsaved changes",
+    ),
+    (
+        "switch-last-editor",
+        "Switch to editor previously shown in current window",
+    ),
+    (
+        "command-palette",
+        "Switch to editor or run command by recent use",
+    ),
+    // --- window handling ---
+    ("kill-window", "Close window and editor"),
+    ("close-window", "Close window"),
+    ("close-other-windows", "Close all other windows"),
+    ("top-window", "Move to window at top of workspace"),
+    ("bottom-window", "Move to window at bottom of workspace"),
+    ("prev-window", "Move to window above current window"),
+    ("next-window", "Move to window below current window"),
+    ("toggle-ruler", "Toggle column ruler in current window"),
+    ("grow-window", "Grow current window by one row"),
+    ("shrink-window", "Shrink current window by one row"),
+    ("balance-windows", "Allocate equal rows to all windows"),
+    (
+        "scroll-lock",
+        "Toggle scrolling of window together with next",
+    ),
+    ("save-layout", "Save arrangement of windows under a name"),
+    (
+        "restore-layout",
+        "Restore arrangement of windows saved under a name",
+    ),
+    ("session-save", "Save editors and windows as session"),
+    ("session-load", "Restore editors and windows from session"),
+    // --- behaviors ---
+    ("describe-editor", "Show position and size of editor"),
+    ("tab-mode", "Toggle between soft/hard tab inserts"),
+    ("set-local", "Show or change setting local to editor"),
+    ("toggle-readonly", "Toggle readonly mode of editor"),
+    ("lock-editing", "Lock or unlock editing of all editors"),
+    ("redact-mode", "Toggle concealment of characters on display"),
+    ("wrap-mode", "Toggle between wrapped/truncated long lines"),
+    ("virtual-mode", "Toggle movement of cursor past end of line"),
+    ("indent-mode", "Toggle auto-indentation of inserted lines"),
+    ("bom-mode", "Toggle byte order mark written on save"),
+    ("describe-char", "Show code point of character at cursor"),
+    (
+        "inspect-char",
+        "Show Unicode details of character at cursor",
+    ),
+    // --- external commands ---
+    ("run-command", "Run command with output in @output window"),
+    ("rerun-last-command", "Run last command again"),
+    ("print-buffer", "Print editor using print command"),
+    ("difftool", "Compare editor with saved file using diff tool"),
+];
@@ -0,0 +1,258 @@
+//! Determines whether paths should be excluded when discovering files.
+//!
+//! An [`Ignore`] matcher is built for a particular directory and combines a global
+//! list of patterns, typically from the `ignore` setting, with the patterns found in
+//! `.gitignore` and `.ignore` files of that directory and its ancestors. Ancestors are
+//! visited up to and including the root of the enclosing git repository, if any.
+//!
+//! Patterns follow the conventions of `.gitignore` files. Patterns containing `/`,
+//! other than a trailing `/`, are anchored to the directory of the file in which
+//! they appear, whereas all other patterns match the file name at any depth. A
+//! trailing `/` restricts the pattern to directories, and a leading `!` negates the
+//! pattern such that a previously ignored path is included again. When more than one
+//! pattern matches, the last one wins, where patterns in `.ignore` files take
+//! precedence over those in `.gitignore` files, and patterns in nearer directories
+//! take precedence over those in more distant directories.
+//!
+//! Global patterns are never anchored, so those containing `/` match any suffix of a
+//! path formed from whole components.
+
+use crate::sys;
+use regex_lite::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Names of files containing ignore patterns in order of increasing precedence.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// A matcher that determines whether paths are ignored.
+pub struct Ignore {
+    rules: Vec<Rule>,
+}
+
+/// A single compiled pattern along with the directory to which it is relative, or
+/// `None` if the pattern is global.
+struct Rule {
+    base: Option<PathBuf>,
+    regex: Regex,
+    negate: bool,
+    dir_only: bool,
+}
+
+impl Ignore {
+    /// Creates a matcher for paths in `dir` using `global` patterns combined with
+    /// patterns found in ignore files of `dir` and its ancestors.
+    ///
+    /// Ignore files that cannot be read and patterns that cannot be compiled are
+    /// quietly discarded.
+    pub fn new<P: AsRef<Path>>(dir: P, global: &[String]) -> Ignore {
+        let mut rules = global
+            .iter()
+            .flat_map(|pattern| Rule::new(pattern, None))
+            .collect::<Vec<_>>();
+        for dir in Self::ancestors(dir.as_ref()).iter().rev() {
+            for file in IGNORE_FILES {
+                if let Ok(text) = fs::read_to_string(dir.join(file)) {
+                    rules.extend(text.lines().flat_map(|line| Rule::new(line, Some(dir))));
+                }
+            }
+        }
+        Ignore { rules }
+    }
+
+    /// Returns `true` if `path` is ignored, where `is_dir` indicates whether `path`
+    /// refers to a directory.
+    pub fn is_ignored<P: AsRef<Path>>(&self, path: P, is_dir: bool) -> bool {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .map(|rule| !rule.negate)
+            .unwrap_or(false)
+    }
+
+    /// Returns `dir` and its ancestors, nearest first, stopping at the root of the
+    /// enclosing git repository.
+    fn ancestors(dir: &Path) -> Vec<PathBuf> {
+        let dir = sys::canonicalize(dir);
+        let mut dirs = Vec::new();
+        for dir in dir.ancestors() {
+            dirs.push(dir.to_path_buf());
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        dirs
+    }
+}
+
+impl Rule {
+    /// Compiles `line` into a rule relative to `base`, returning `None` if `line` is
+    /// blank, a comment, or otherwise not a valid pattern.
+    fn new(line: &str, base: Option<&Path>) -> Option<Rule> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, pattern) = match line.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, line),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        let anchored = base.is_some() && pattern.contains('/');
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+        if pattern.is_empty() {
+            return None;
+        }
+        let regex = if anchored {
+            format!("^{}$", glob_to_regex(pattern))
+        } else {
+            format!("(?:^|/){}$", glob_to_regex(pattern))
+        };
+        Regex::new(&regex).ok().map(|regex| Rule {
+            base: base.map(|base| base.to_path_buf()),
+            regex,
+            negate,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            false
+        } else if let Some(base) = &self.base {
+            path.strip_prefix(base)
+                .ok()
+                .and_then(|path| path.to_str())
+                .map(|path| !path.is_empty() && self.regex.is_match(path))
+                .unwrap_or(false)
+        } else {
+            path.to_str()
+                .map(|path| self.regex.is_match(path))
+                .unwrap_or(false)
+        }
+    }
+}
+
+/// Translates the glob `pattern` into an equivalent regular expression, where `*`
+/// and `?` never match `/`, and `**` matches any number of directories.
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.next_if_eq(&'*').is_some() => {
+                if chars.next_if_eq(&'/').is_some() {
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let mut class = String::new();
+                if chars.next_if(|c| *c == '!' || *c == '^').is_some() {
+                    class.push('^');
+                }
+                while let Some(c) = chars.next_if(|c| *c != ']') {
+                    if c == '\\' || c == '[' {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                if chars.next_if_eq(&']').is_some() {
+                    regex.push_str(&format!("[{class}]"));
+                } else {
+                    regex.push_str(&regex_lite::escape(&format!("[{class}")));
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    regex.push_str(&regex_lite::escape(&c.to_string()));
+                }
+            }
+            c => regex.push_str(&regex_lite::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str) -> Rule {
+        Rule::new(pattern, Some(Path::new("/repo"))).unwrap()
+    }
+
+    #[test]
+    fn unanchored_pattern() {
+        let r = rule("*.o");
+        assert!(r.matches(Path::new("/repo/foo.o"), false));
+        assert!(r.matches(Path::new("/repo/src/foo.o"), false));
+        assert!(!r.matches(Path::new("/repo/foo.rs"), false));
+        assert!(!r.matches(Path::new("/other/foo.o"), false));
+    }
+
+    #[test]
+    fn anchored_pattern() {
+        let r = rule("/target");
+        assert!(r.matches(Path::new("/repo/target"), true));
+        assert!(!r.matches(Path::new("/repo/src/target"), true));
+
+        let r = rule("src/*.rs");
+        assert!(r.matches(Path::new("/repo/src/main.rs"), false));
+        assert!(!r.matches(Path::new("/repo/src/sub/main.rs"), false));
+
+        let r = rule("src/**/gen");
+        assert!(r.matches(Path::new("/repo/src/gen"), true));
+        assert!(r.matches(Path::new("/repo/src/a/b/gen"), true));
+    }
+
+    #[test]
+    fn dir_only_pattern() {
+        let r = rule("build/");
+        assert!(r.matches(Path::new("/repo/build"), true));
+        assert!(r.matches(Path::new("/repo/sub/build"), true));
+        assert!(!r.matches(Path::new("/repo/build"), false));
+    }
+
+    #[test]
+    fn invalid_lines() {
+        let base = Some(Path::new("/repo"));
+        assert!(Rule::new("", base).is_none());
+        assert!(Rule::new("   ", base).is_none());
+        assert!(Rule::new("# comment", base).is_none());
+        assert!(Rule::new("/", base).is_none());
+        assert!(Rule::new("\\#name", base).is_some());
+    }
+
+    #[test]
+    fn negated_pattern() {
+        let ignore = Ignore {
+            rules: vec![rule("*.log"), rule("!keep.log")],
+        };
+        assert!(ignore.is_ignored("/repo/debug.log", false));
+        assert!(!ignore.is_ignored("/repo/keep.log", false));
+    }
+
+    #[test]
+    fn global_pattern() {
+        let ignore = Ignore {
+            rules: ["node_modules", "a/b", "[!x]?.tmp"]
+                .iter()
+                .flat_map(|p| Rule::new(p, None))
+                .collect(),
+        };
+        assert!(ignore.is_ignored("/any/where/node_modules", true));
+        assert!(ignore.is_ignored("/any/a/b", false));
+        assert!(!ignore.is_ignored("/any/xa/b", false));
+        assert!(ignore.is_ignored("/any/ab.tmp", false));
+        assert!(!ignore.is_ignored("/any/xb.tmp", false));
+    }
+}
@@ -0,0 +1,300 @@
+//! Recomputation of indentation for lines of text.
+//!
+//! The indentation of each line is derived from the indentation of the first
+//! non-blank line and a _level_ that changes from one line to the next. By default,
+//! the level is determined by counting brackets, where lines that start with closing
+//! brackets are outdented and unbalanced opening brackets increase the level of
+//! subsequent lines. Brackets inside string and character literals or line comments
+//! are ignored.
+//!
+//! Alternatively, the level is determined by regular expressions, where lines that
+//! match _outdent_ patterns are outdented and lines that match _indent_ patterns
+//! increase the level of subsequent lines.
+//!
+//! Text may also be adjusted for insertion at a particular column such that its
+//! relative indentation is preserved, and the indentation of a newly inserted line
+//! may be derived from the line preceding it.
+
+use regex_lite::Regex;
+
+/// Characters that open a bracketed block.
+const OPEN_CHARS: [char; 3] = ['(', '[', '{'];
+
+/// Characters that close a bracketed block.
+const CLOSE_CHARS: [char; 3] = [')', ']', '}'];
+
+/// Returns `text` with the indentation of each line recomputed, where each level of
+/// indentation is represented by `unit`.
+///
+/// If `rules` is `None`, then bracket counting determines the level of each line,
+/// otherwise `rules` is a tuple of optional _indent_ and _outdent_ regular
+/// expressions. Bracket counting stops at any of the line comment prefixes in
+/// `comments`.
+///
+/// Blank lines are stripped of any whitespace.
+pub fn reindent(
+    text: &str,
+    unit: &str,
+    rules: Option<(Option<&Regex>, Option<&Regex>)>,
+    comments: &[String],
+) -> String {
+    let mut out = String::new();
+    let mut base: Option<&str> = None;
+    let mut level: usize = 0;
+    for line in text.split_inclusive('\n') {
+        let (body, eol) = match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        };
+        let trimmed = body.trim();
+        if trimmed.is_empty() {
+            out.push_str(eol);
+        } else {
+            let base = *base.get_or_insert_with(|| leading_space(body));
+            let (this_level, next_level) = match rules {
+                Some((indent, outdent)) => {
+                    let outdent = outdent.map(|re| re.is_match(trimmed)).unwrap_or(false);
+                    let this_level = if outdent {
+                        level.saturating_sub(1)
+                    } else {
+                        level
+                    };
+                    let indent = indent.map(|re| re.is_match(trimmed)).unwrap_or(false);
+                    (this_level, if indent { this_level + 1 } else { this_level })
+                }
+                None => {
+                    let (closers, delta) = count_brackets(trimmed, comments);
+                    let this_level = level.saturating_sub(closers);
+                    (this_level, (level as isize + delta).max(0) as usize)
+                }
+            };
+            out.push_str(base);
+            out.push_str(&unit.repeat(this_level));
+            out.push_str(trimmed);
+            out.push_str(eol);
+            level = next_level;
+        }
+    }
+    out
+}
+
+/// Returns `text` adjusted for insertion following `prefix`, which is the text from
+/// the start of the line to the insertion point, such that the first line aligns
+/// with the indentation implied by `prefix` and the indentation of subsequent lines
+/// relative to the first line is preserved.
+///
+/// If `prefix` is entirely whitespace, then the insertion point itself is the
+/// indentation, otherwise the leading whitespace of `prefix` is the indentation.
+/// In either case, the leading whitespace of the first line is removed, and blank
+/// lines are stripped of any whitespace.
+pub fn adjust(text: &str, prefix: &str) -> String {
+    let indent = if prefix.trim().is_empty() {
+        prefix
+    } else {
+        leading_space(prefix)
+    };
+    // Relative indentation is measured against first non-blank line.
+    let first_len = text
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| leading_space(line).chars().count())
+        .unwrap_or(0);
+
+    let mut lines = text.split_inclusive('\n');
+    let mut out = String::new();
+    if let Some(first) = lines.next() {
+        out.push_str(first.trim_start_matches(|c: char| c.is_whitespace() && c != '\n'));
+        for line in lines {
+            let (body, eol) = match line.strip_suffix('\n') {
+                Some(body) => (body, "\n"),
+                None => (line, ""),
+            };
+            if !body.trim().is_empty() {
+                let space = leading_space(body);
+                let space_len = space.chars().count();
+                if space_len >= first_len {
+                    out.push_str(indent);
+                    out.extend(space.chars().skip(first_len));
+                } else {
+                    let n = indent.chars().count().saturating_sub(first_len - space_len);
+                    out.extend(indent.chars().take(n));
+                }
+                out.push_str(body.trim_start());
+            }
+            out.push_str(eol);
+        }
+    }
+    out
+}
+
+/// Returns the indentation of a line inserted following `prefix`, which is the text
+/// from the start of the current line to the insertion point.
+///
+/// The leading whitespace of `prefix` is carried over to the new line, followed by
+/// `unit` if `prefix` increases the level of indentation as determined by `rules`,
+/// and `comments`, which are applied in the same manner as [`reindent`].
+pub fn next_line(
+    prefix: &str,
+    unit: &str,
+    rules: Option<(Option<&Regex>, Option<&Regex>)>,
+    comments: &[String],
+) -> String {
+    let base = leading_space(prefix);
+    let trimmed = prefix.trim();
+    let increase = if trimmed.is_empty() {
+        false
+    } else {
+        match rules {
+            Some((indent, _)) => indent.map(|re| re.is_match(trimmed)).unwrap_or(false),
+            None => {
+                let (closers, delta) = count_brackets(trimmed, comments);
+                delta + closers as isize > 0
+            }
+        }
+    };
+    if increase {
+        format!("{base}{unit}")
+    } else {
+        base.to_string()
+    }
+}
+
+/// Returns a tuple containing the number of closing brackets at the start of `line`
+/// and the net change of opening brackets over closing brackets.
+///
+/// Brackets inside string literals, character literals, or a line comment beginning
+/// with any of `comments` are ignored. A single quote only starts a character
+/// literal if a matching quote follows, as in `'{'` or `'\''`, so that lifetimes and
+/// apostrophes are left alone.
+fn count_brackets(line: &str, comments: &[String]) -> (usize, isize) {
+    let closers = line
+        .chars()
+        .take_while(|c| CLOSE_CHARS.contains(c) || c.is_whitespace())
+        .filter(|c| CLOSE_CHARS.contains(c))
+        .count();
+
+    let chars = line.char_indices().collect::<Vec<_>>();
+    let mut delta = 0;
+    let mut quote = false;
+    let mut escape = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if quote {
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == '"' {
+                quote = false;
+            }
+        } else if comments.iter().any(|p| line[pos..].starts_with(p.as_str())) {
+            break;
+        } else if c == '"' {
+            quote = true;
+        } else if c == '\'' {
+            i += char_literal(&chars[i + 1..]);
+        } else if OPEN_CHARS.contains(&c) {
+            delta += 1;
+        } else if CLOSE_CHARS.contains(&c) {
+            delta -= 1;
+        }
+        i += 1;
+    }
+    (closers, delta)
+}
+
+/// Returns the number of characters in `chars` that complete a character literal
+/// following its opening quote, including the closing quote, or `0` if `chars` does
+/// not complete a character literal.
+fn char_literal(chars: &[(usize, char)]) -> usize {
+    match chars {
+        [(_, '\\'), rest @ ..] => rest
+            .iter()
+            .skip(1)
+            .position(|&(_, c)| c == '\'')
+            .map(|n| n + 3)
+            .unwrap_or(0),
+        [(_, c), (_, '\''), ..] if *c != '\'' => 2,
+        _ => 0,
+    }
+}
+
+/// Returns the leading whitespace of `line`.
+fn leading_space(line: &str) -> &str {
+    let n = line.len() - line.trim_start().len();
+    &line[..n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comments() -> Vec<String> {
+        vec!["//".to_string()]
+    }
+
+    #[test]
+    fn reindent_by_brackets() {
+        let text = "  fn main() {\nif x {\n        y();\n    }\n\n}\n";
+        let out = reindent(text, "    ", None, &comments());
+        assert_eq!(
+            out,
+            "  fn main() {\n      if x {\n          y();\n      }\n\n  }\n"
+        );
+    }
+
+    #[test]
+    fn reindent_ignores_literals_and_comments() {
+        let text = "match c {\n'{' => x,\n\"}\" => y,\n}\n";
+        let out = reindent(text, "  ", None, &comments());
+        assert_eq!(out, "match c {\n  '{' => x,\n  \"}\" => y,\n}\n");
+
+        let text = "a {  // }\nb\n}";
+        let out = reindent(text, "  ", None, &comments());
+        assert_eq!(out, "a {  // }\n  b\n}");
+    }
+
+    #[test]
+    fn reindent_by_rules() {
+        let indent = Regex::new(":$").unwrap();
+        let outdent = Regex::new("^else:").unwrap();
+        let text = "if x:\ny\nelse:\nz\n";
+        let out = reindent(text, "  ", Some((Some(&indent), Some(&outdent))), &[]);
+        assert_eq!(out, "if x:\n  y\nelse:\n  z\n");
+    }
+
+    #[test]
+    fn count_brackets_literals() {
+        assert_eq!(count_brackets("} else {", &[]), (1, 0));
+        assert_eq!(count_brackets("f(\"(\\\"\", '(')", &[]), (0, 0));
+        assert_eq!(count_brackets("'\\'' {", &[]), (0, 1));
+        assert_eq!(count_brackets("'\\u{7b}'", &[]), (0, 0));
+        assert_eq!(count_brackets("fn f<'a>(x: &'a str) {", &[]), (0, 1));
+        assert_eq!(count_brackets("x { // (", &comments()), (0, 1));
+        assert_eq!(count_brackets("\"//\" {", &comments()), (0, 1));
+    }
+
+    #[test]
+    fn adjust_preserves_relative_indent() {
+        let text = "  if x {\n      y();\n\n    }\n";
+        assert_eq!(adjust(text, "    "), "if x {\n        y();\n\n      }\n");
+        assert_eq!(adjust(text, "  let a = "), "if x {\n      y();\n\n    }\n");
+    }
+
+    #[test]
+    fn adjust_shallower_lines() {
+        let text = "    a\n  b\nc";
+        assert_eq!(adjust(text, "      "), "a\n    b\n  c");
+        assert_eq!(adjust(text, " "), "a\nb\nc");
+    }
+
+    #[test]
+    fn next_line_indent() {
+        assert_eq!(next_line("    if x {", "  ", None, &[]), "      ");
+        assert_eq!(next_line("    x();", "  ", None, &[]), "    ");
+        assert_eq!(next_line("    } else {", "  ", None, &[]), "      ");
+        assert_eq!(next_line("  x // {", "  ", None, &comments()), "  ");
+        assert_eq!(next_line("   ", "  ", None, &[]), "   ");
+    }
+}
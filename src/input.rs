@@ -64,6 +64,13 @@ pub struct InputEditor {
 
     /// An optional _hint_ that is appended to the user-provided portion of `input`.
     hint: Option<String>,
+
+    /// Indicates whether the user-provided portion of `input` is masked when drawn.
+    masked: bool,
+
+    /// Indicates whether masked input is temporarily revealed, which only applies when
+    /// [`masked`](Self::masked) is `true`.
+    revealed: bool,
 }
 
 /// A directive produced after processing a [`key`](Key).
@@ -108,13 +115,26 @@ impl InputEditor {
             pos: 0,
             cursor: 0,
             hint: None,
+            masked: false,
+            revealed: false,
         }
     }
 
-    /// Enables the editor by associating a `prompt` and a `completer`.
-    pub fn enable(&mut self, prompt: &str, completer: Box<dyn Completer>) {
+    /// Enables the editor by associating a `prompt` and a `completer`, where `masked`
+    /// indicates whether input characters are drawn as `*`.
+    ///
+    /// When `masked` is `true`, `completer` is ignored in favor of a
+    /// [`null_completer`](user::null_completer) so that neither hints nor suggestions
+    /// can divulge any part of the input.
+    pub fn enable(&mut self, prompt: &str, completer: Box<dyn Completer>, masked: bool) {
         self.prompt = Some(prompt.to_string());
-        self.completer = completer;
+        self.completer = if masked {
+            user::null_completer()
+        } else {
+            completer
+        };
+        self.masked = masked;
+        self.revealed = false;
         self.set_sizes();
         self.set_input(None);
         let hint = self.completer.prepare();
@@ -123,9 +143,17 @@ impl InputEditor {
     }
 
     /// Disables the editor and clears the area on the workspace.
+    ///
+    /// If the input was masked, the input buffer is overwritten before being cleared
+    /// so that its value does not linger in memory.
     pub fn disable(&mut self) {
+        if self.masked {
+            self.input.fill('\0');
+        }
         self.prompt = None;
         self.completer = user::null_completer();
+        self.masked = false;
+        self.revealed = false;
         self.set_sizes();
         self.set_input(None);
         self.hint = None;
@@ -257,6 +285,17 @@ impl InputEditor {
                 self.suggest();
                 self.draw_input();
             }
+            CTRL_T => {
+                if self.masked {
+                    self.revealed = !self.revealed;
+                } else {
+                    self.toggle();
+                }
+                self.draw_input();
+            }
+            Key::ShiftTab => {
+                // Input is not changed, though inquirer may react to key.
+            }
             CTRL_M => {
                 if self.accept() {
                     return Directive::Accept;
@@ -292,6 +331,12 @@ impl InputEditor {
         }
     }
 
+    /// Calls the attached completer to toggle its alternate mode of operation.
+    fn toggle(&mut self) {
+        let hint = (self.completer).toggle(&self.value());
+        self.update_hint(hint);
+    }
+
     /// Calls the attached completer to accept or reject the input value in its
     /// form, returning `true` if the accepted and `false` otherwise.
     fn accept(&mut self) -> bool {
@@ -414,7 +459,12 @@ impl InputEditor {
         // since colors are distinct.
         let user_end = cmp::min(end, self.len);
         for (col, c) in self.input[start..user_end].iter().enumerate() {
-            self.canvas.set(0, col as u32, *c, self.input_color);
+            let c = if self.masked && !self.revealed {
+                '*'
+            } else {
+                *c
+            };
+            self.canvas.set(0, col as u32, c, self.input_color);
         }
         let hint_ofs = user_end - start;
         for (col, c) in self.input[user_end..end].iter().enumerate() {
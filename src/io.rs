@@ -1,32 +1,447 @@
 //! A collection of functions for reading and writing files to and from buffers.
 
 use crate::buffer::Buffer;
+use crate::config::Settings;
 use crate::error::{Error, Result};
-use crate::sys::AsString;
-use std::fs::{self, File};
-use std::io::{self, BufReader, BufWriter};
-use std::path::Path;
-use std::time::SystemTime;
+use crate::sys::{self, AsString};
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::fs::{self, File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::os::unix::fs::{MetadataExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 /// Suggested capacity of internal buffers for readers and writers.
 const BUFFER_SIZE: usize = 65_536;
 
+/// A file path with an optional `1`-based line and column at which the cursor is
+/// placed when opened.
+pub type FileSpec = (String, Option<u32>, Option<u32>);
+
+/// Byte order mark that may appear at the start of UTF-8 encoded files.
+pub const BOM: char = '\u{feff}';
+
+/// The state of a file in storage when it was last read or written, which is used to
+/// detect changes made by other programs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stamp {
+    /// Time of last modification.
+    pub time: SystemTime,
+
+    /// Hash of the file contents, or `None` if not yet known.
+    pub digest: Option<u64>,
+}
+
 /// Opens the file at `path` and reads the contents into `buf`, returning the
-/// number of bytes read.
-pub fn read_file<P: AsRef<Path>>(path: P, buf: &mut Buffer) -> Result<usize> {
+/// [`Stamp`] of the file whose digest is computed from the bytes as they are read.
+pub fn read_file<P: AsRef<Path>>(path: P, buf: &mut Buffer) -> Result<Stamp> {
     let path = path.as_ref();
     let file = open_file(path)?;
-    let mut reader = BufReader::with_capacity(BUFFER_SIZE, file);
-    buf.read(&mut reader).map_err(|e| to_error(e, path))
+    let time = file
+        .metadata()
+        .and_then(|info| info.modified())
+        .map_err(|e| to_error(e, path))?;
+    let mut reader = BufReader::with_capacity(BUFFER_SIZE, HashReader::new(file));
+    let _ = buf.read(&mut reader).map_err(|e| to_error(e, path))?;
+    Ok(Stamp {
+        time,
+        digest: Some(reader.get_ref().hasher.finish()),
+    })
+}
+
+/// A reader that hashes the bytes read from the underlying reader, such that the
+/// hash is the same as the result of [`hash_file`] once the end of file is reached.
+struct HashReader<R> {
+    inner: R,
+    hasher: DefaultHasher,
 }
 
-/// Creates a new file at `path` and writes the contents of `buf`, returning the
-/// number of bytes written.
-pub fn write_file<P: AsRef<Path>>(path: P, buf: &Buffer) -> Result<usize> {
+impl<R: Read> HashReader<R> {
+    fn new(inner: R) -> HashReader<R> {
+        HashReader {
+            inner,
+            hasher: DefaultHasher::new(),
+        }
+    }
+}
+
+impl<R: Read> Read for HashReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.write(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Files of at least this many bytes are loaded incrementally using a [`Loader`].
+pub const LARGE_FILE_SIZE: u64 = 16_777_216;
+
+/// Approximate number of bytes read by a [`Loader`] in each chunk.
+pub const LOAD_CHUNK_SIZE: usize = 1_048_576;
+
+/// Reads the contents of a large file in chunks of whole lines so that the file can
+/// be loaded incrementally.
+pub struct Loader {
+    path: PathBuf,
+    reader: BufReader<File>,
+    hasher: DefaultHasher,
+    time: SystemTime,
+    size: u64,
+    read: u64,
+}
+
+impl Loader {
+    /// Opens the file at `path` for incremental loading if its size is at least
+    /// [`LARGE_FILE_SIZE`] bytes, otherwise `None` is returned.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Option<Loader>> {
+        let path = path.as_ref();
+        let file = open_file(path)?;
+        let info = file.metadata().map_err(|e| to_error(e, path))?;
+        let size = info.len();
+        let loader = if size >= LARGE_FILE_SIZE {
+            Some(Loader {
+                path: path.to_path_buf(),
+                reader: BufReader::with_capacity(BUFFER_SIZE, file),
+                hasher: DefaultHasher::new(),
+                time: info.modified().map_err(|e| to_error(e, path))?,
+                size,
+                read: 0,
+            })
+        } else {
+            None
+        };
+        Ok(loader)
+    }
+
+    /// Reads whole lines until at least [`LOAD_CHUNK_SIZE`] bytes have been read or
+    /// the end of file is reached, returning `None` only if nothing remains.
+    pub fn read_chunk(&mut self) -> Result<Option<String>> {
+        let mut chunk = String::with_capacity(LOAD_CHUNK_SIZE + BUFFER_SIZE);
+        while chunk.len() < LOAD_CHUNK_SIZE {
+            let n = self
+                .reader
+                .read_line(&mut chunk)
+                .map_err(|e| to_error(e, &self.path))?;
+            if n == 0 {
+                break;
+            }
+        }
+        self.hasher.write(chunk.as_bytes());
+        self.read += chunk.len() as u64;
+        Ok(if chunk.is_empty() { None } else { Some(chunk) })
+    }
+
+    /// Returns a hash of the contents read so far, which is the same as the result of
+    /// [`hash_file`] once the end of file is reached.
+    pub fn digest(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    /// Returns the modification time of the file at the time it was opened.
+    pub fn time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Returns the size of the file in bytes at the time it was opened.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the percentage of the file read so far, which never reaches `100`
+    /// since the size of the file may have changed after it was opened.
+    pub fn progress(&self) -> u8 {
+        (self.read * 100 / self.size.max(1)).min(99) as u8
+    }
+}
+
+/// Writes the contents of `buf` to `file`, which was opened at `path`, preceded by
+/// the [`BOM`] if `bom` is `true`, returning the number of bytes written.
+pub fn write_to<P: AsRef<Path>>(file: File, path: P, buf: &Buffer, bom: bool) -> Result<usize> {
     let path = path.as_ref();
-    let file = create_file(path)?;
     let mut writer = BufWriter::with_capacity(BUFFER_SIZE, file);
-    buf.write(&mut writer).map_err(|e| to_error(e, path))
+    write_bom(&mut writer, bom)
+        .and_then(|n| buf.write(&mut writer).map(|count| n + count))
+        .and_then(|n| writer.flush().map(|_| n))
+        .map_err(|e| to_error(e, path))
+}
+
+/// Removes the [`BOM`] from the start of `buf` if present, returning `true` if it was
+/// removed.
+pub fn strip_bom(buf: &mut Buffer) -> bool {
+    if buf.get_char(0) == Some(BOM) {
+        buf.set_pos(0);
+        buf.remove_char();
+        true
+    } else {
+        false
+    }
+}
+
+/// Writes the UTF-8 encoding of the [`BOM`] to `writer` if `bom` is `true`, returning
+/// the number of bytes written.
+pub fn write_bom<W: Write>(writer: &mut W, bom: bool) -> io::Result<usize> {
+    if bom {
+        let mut bytes = [0; 4];
+        let encoding = BOM.encode_utf8(&mut bytes).as_bytes();
+        writer.write_all(encoding)?;
+        Ok(encoding.len())
+    } else {
+        Ok(0)
+    }
+}
+
+/// Returns the UTF-8 encoding of `buf`, preceded by the [`BOM`] if `bom` is `true`.
+pub fn encode(buf: &Buffer, bom: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(buf.size());
+    // Writing to a vector never fails.
+    let _ = write_bom(&mut bytes, bom).and_then(|_| buf.write(&mut bytes));
+    bytes
+}
+
+/// A policy for retrying reads and writes of files that fail with errors that are
+/// likely transient, such as those reported by network filesystems.
+#[derive(Clone, Copy, Debug)]
+pub struct Retry {
+    /// Number of times an operation is tried again after failing.
+    pub retries: u32,
+
+    /// Delay before the first retry, which doubles with each subsequent retry.
+    pub backoff: Duration,
+
+    /// Maximum time a write may take before it is abandoned, or `None` to wait
+    /// indefinitely.
+    pub timeout: Option<Duration>,
+}
+
+impl Retry {
+    pub fn new(settings: &Settings) -> Retry {
+        Retry {
+            retries: settings.io_retries,
+            backoff: Duration::from_millis(settings.io_backoff as u64),
+            timeout: match settings.io_timeout {
+                0 => None,
+                secs => Some(Duration::from_secs(secs as u64)),
+            },
+        }
+    }
+
+    /// Runs `f` and tries again after a delay if it fails with a transient error,
+    /// returning either the first success, the first error that is not transient,
+    /// or the last error once all retries are exhausted.
+    pub fn run<T, F>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut backoff = self.backoff;
+        let mut retries = self.retries;
+        loop {
+            match f() {
+                Err(e) if e.is_transient() && retries > 0 => {
+                    thread::sleep(backoff);
+                    backoff = backoff.saturating_mul(2);
+                    retries -= 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Interval at which the progress of [`write_bytes`] is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Writes `bytes` to the file at `path` on a separate thread, returning the number of
+/// bytes written or an error if writing did not finish within `timeout`.
+///
+/// While waiting for the write to finish, `progress` is called periodically with the
+/// percentage of bytes written so far, which only happens if the write is slow.
+///
+/// Bytes are written to a temporary file in the same directory, which then replaces
+/// the file at `path` by renaming, so the existing file is never left partially
+/// written. If `path` is a symbolic link, the file to which it refers is replaced.
+/// The permissions, owner and group of the existing file are preserved. Writing fails
+/// if the directory does not allow creation of the temporary file or if the owner or
+/// group cannot be preserved, rather than overwriting the existing file in place.
+///
+/// The file is synchronized with storage before being renamed, since network
+/// filesystems may otherwise defer reporting errors until the file is closed, at
+/// which point they would be ignored. A write that times out cannot be interrupted
+/// if blocked by the filesystem, so the thread is abandoned, though it stops writing
+/// at the next opportunity and removes the temporary file without replacing the
+/// file at `path`. However, if the timeout expires once renaming has started, then
+/// the outcome of renaming is awaited instead, so a timeout is only reported when
+/// the file at `path` was not replaced.
+pub fn write_bytes<P, F>(
+    path: P,
+    bytes: Arc<Vec<u8>>,
+    timeout: Option<Duration>,
+    mut progress: F,
+) -> Result<usize>
+where
+    P: AsRef<Path>,
+    F: FnMut(u8),
+{
+    let path = path.as_ref().to_path_buf();
+    let target = if path.exists() {
+        sys::canonicalize(&path)
+    } else {
+        path.clone()
+    };
+    let (temp_path, mut file) = create_staged_file(&target)?;
+    let written = Arc::new(AtomicUsize::new(0));
+    let state = Arc::new(AtomicU8::new(WRITING));
+    let (tx, rx) = mpsc::channel();
+
+    let file_bytes = bytes.clone();
+    let file_written = written.clone();
+    let file_state = state.clone();
+    thread::spawn(move || {
+        // Renaming only proceeds if the write was not abandoned in the meantime, and
+        // once started, prevents the write from being abandoned.
+        let result =
+            write_chunks(&mut file, &file_bytes, &file_written, &file_state).and_then(|n| {
+                match file_state.compare_exchange(
+                    WRITING,
+                    COMMITTING,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) => fs::rename(&temp_path, &target).map(|_| n),
+                    Err(_) => Err(io::Error::from(ErrorKind::Interrupted)),
+                }
+            });
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        let _ = tx.send(result);
+    });
+
+    let start = Instant::now();
+    loop {
+        match rx.recv_timeout(PROGRESS_INTERVAL) {
+            Ok(result) => return result.map_err(|e| to_error(e, &path)),
+            Err(RecvTimeoutError::Timeout) => match timeout {
+                Some(timeout) if start.elapsed() >= timeout => {
+                    if state
+                        .compare_exchange(WRITING, ABANDONED, Ordering::SeqCst, Ordering::SeqCst)
+                        .is_ok()
+                    {
+                        return Err(Error::timeout(&path.as_string(), timeout.as_secs() as u32));
+                    }
+                    // Renaming already started, so its outcome is reported instead.
+                    return match rx.recv() {
+                        Ok(result) => result.map_err(|e| to_error(e, &path)),
+                        Err(_) => Err(to_error(io::Error::from(ErrorKind::BrokenPipe), &path)),
+                    };
+                }
+                _ => {
+                    let n = written.load(Ordering::Relaxed);
+                    progress((n * 100 / bytes.len().max(1)).min(99) as u8);
+                }
+            },
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(to_error(io::Error::from(ErrorKind::BrokenPipe), &path));
+            }
+        }
+    }
+}
+
+/// States of a write performed by [`write_bytes`] that are shared between the waiting
+/// thread and the writing thread.
+const WRITING: u8 = 0;
+const ABANDONED: u8 = 1;
+const COMMITTING: u8 = 2;
+
+/// Writes `bytes` to `file` in chunks, counting the bytes in `written` and stopping
+/// as soon as `state` indicates that the write was abandoned, then synchronizes the
+/// file with storage.
+fn write_chunks(
+    file: &mut File,
+    bytes: &[u8],
+    written: &AtomicUsize,
+    state: &AtomicU8,
+) -> io::Result<usize> {
+    for chunk in bytes.chunks(BUFFER_SIZE) {
+        if state.load(Ordering::SeqCst) == ABANDONED {
+            return Err(io::Error::from(ErrorKind::Interrupted));
+        }
+        file.write_all(chunk)?;
+        written.fetch_add(chunk.len(), Ordering::Relaxed);
+    }
+    file.sync_all().map(|_| bytes.len())
+}
+
+/// Creates the file to which [`write_bytes`] writes in place of the file at `path`,
+/// returning the path of the temporary file along with the open file.
+///
+/// The owner and group of the existing file are given to the temporary file, which
+/// is an error if not permitted, since replacing the existing file would otherwise
+/// change its ownership.
+fn create_staged_file(path: &Path) -> Result<(PathBuf, File)> {
+    let meta = fs::metadata(path).ok();
+    if meta.is_some() {
+        // Existing file is opened for writing, though without truncation, only to
+        // ensure that renaming does not replace a file that could not be written.
+        let _ = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| to_error(e, path))?;
+    }
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mode = meta
+        .as_ref()
+        .map(|meta| meta.permissions().mode() & 0o7777)
+        .unwrap_or(0o666);
+    let (temp_path, file) = create_temp_file(&dir, &name, mode)?;
+    if let Some(meta) = meta {
+        // Permissions are set explicitly since the mode given at creation is
+        // restricted by the umask, and only after changing ownership since that may
+        // clear some of the permission bits.
+        let result = std::os::unix::fs::fchown(&file, Some(meta.uid()), Some(meta.gid()))
+            .and_then(|_| fs::set_permissions(&temp_path, meta.permissions()));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(to_error(e, path));
+        }
+    }
+    Ok((temp_path, file))
+}
+
+/// Reads at most `lines` lines from the start of the file at `path`, though never more
+/// than [`BUFFER_SIZE`] bytes, where bytes that are not valid UTF-8 are replaced rather
+/// than treated as an error.
+pub fn read_head<P: AsRef<Path>>(path: P, lines: usize) -> Result<String> {
+    let path = path.as_ref();
+    let file = open_file(path)?;
+    let mut reader = BufReader::with_capacity(BUFFER_SIZE, file).take(BUFFER_SIZE as u64);
+    let mut bytes = Vec::new();
+    for _ in 0..lines {
+        let n = reader
+            .read_until(b'\n', &mut bytes)
+            .map_err(|e| to_error(e, path))?;
+        if n == 0 {
+            break;
+        }
+    }
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Creates the directory at `path`, including any missing parent directories.
+pub fn create_dir<P: AsRef<Path>>(path: P) -> Result<()> {
+    let path = path.as_ref();
+    fs::create_dir_all(path).map_err(|e| to_error(e, path))
 }
 
 /// Opens the file at `path` for reading.
@@ -34,9 +449,80 @@ pub fn open_file<P: AsRef<Path>>(path: P) -> Result<File> {
     File::open(path.as_ref()).map_err(|e| to_error(e, path))
 }
 
-/// Creates a new file at `path` for writing.
-pub fn create_file<P: AsRef<Path>>(path: P) -> Result<File> {
-    File::create(path.as_ref()).map_err(|e| to_error(e, path))
+/// Creates a new file in `dir` whose name is `name` preceded by a random prefix and
+/// whose permissions are `mode`, subject to the umask, returning the path and the
+/// file opened for writing.
+///
+/// The file is created exclusively, so neither an existing file nor a symbolic link
+/// planted at the same path is ever written through.
+pub fn create_temp_file<P: AsRef<Path>>(dir: P, name: &str, mode: u32) -> Result<(PathBuf, File)> {
+    let dir = dir.as_ref();
+    let mut tries = 0;
+    loop {
+        let path = dir.join(format!(".ped-{:016x}-{name}", random()));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode)
+            .open(&path)
+        {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists && tries < 8 => tries += 1,
+            Err(e) => return Err(to_error(e, &path)),
+        }
+    }
+}
+
+/// Returns a random number that is not predictable by other processes.
+fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    hasher.write_u128(nanos);
+    hasher.finish()
+}
+
+/// Reads the list of files in `path`, one per line, returning a vector of file specs
+/// parsed using [`parse_file_spec`].
+///
+/// Leading and trailing whitespace is ignored, as are blank lines.
+pub fn read_file_list<P: AsRef<Path>>(path: P) -> Result<Vec<FileSpec>> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(|e| to_error(e, path))?;
+    let files = text
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(parse_file_spec)
+        .collect();
+    Ok(files)
+}
+
+/// Parses `spec` as a file path optionally suffixed with `:line` or `:line:col`, as
+/// commonly reported by compilers and other tools.
+///
+/// A suffix is recognized only if `spec` does not name an existing file, which
+/// preserves the rare file whose name happens to end with such a suffix.
+pub fn parse_file_spec(spec: &str) -> FileSpec {
+    if !Path::new(spec).exists() {
+        if let Some((rest, n)) = spec.rsplit_once(':') {
+            if let Ok(n) = n.parse::<u32>() {
+                if let Some((file, line)) = rest.rsplit_once(':') {
+                    if let Ok(line) = line.parse::<u32>() {
+                        if !file.is_empty() {
+                            return (file.to_string(), Some(line), Some(n));
+                        }
+                    }
+                }
+                if !rest.is_empty() {
+                    return (rest.to_string(), Some(n), None);
+                }
+            }
+        }
+    }
+    (spec.to_string(), None, None)
 }
 
 /// Returns the [modification timestamp](fs::Metadata::modified) of `path`.
@@ -47,7 +533,250 @@ pub fn get_time<P: AsRef<Path>>(path: P) -> Result<SystemTime> {
         .and_then(|info| info.modified().map_err(|e| to_error(e, path)))
 }
 
+/// Returns a hash of `bytes`, which is the same as the result of [`hash_file`] for a
+/// file containing `bytes`.
+pub fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Returns a hash of the contents of the file at `path`, which requires reading the
+/// entire file, so this is only necessary when checking whether the file changed
+/// since it was last read or written.
+pub fn hash_file<P: AsRef<Path>>(path: P) -> Result<u64> {
+    let path = path.as_ref();
+    let mut reader = BufReader::with_capacity(BUFFER_SIZE, open_file(path)?);
+    let mut hasher = DefaultHasher::new();
+    loop {
+        let bytes = reader.fill_buf().map_err(|e| to_error(e, path))?;
+        if bytes.is_empty() {
+            break;
+        }
+        hasher.write(bytes);
+        let n = bytes.len();
+        reader.consume(n);
+    }
+    Ok(hasher.finish())
+}
+
+/// Moves the file at `from` to `to`, which falls back to copying and then removing
+/// the file when `from` and `to` reside on different devices.
+pub fn move_file<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => {
+            fs::copy(from, to).map_err(|e| to_error(e, to))?;
+            fs::remove_file(from).map_err(|e| to_error(e, from))
+        }
+        Err(e) => Err(to_error(e, from)),
+    }
+}
+
+/// Moves the file at `path` into `trash_dir` and returns the path of the file in the
+/// trash.
+///
+/// The trash follows the layout prescribed by the FreeDesktop.org specification,
+/// where the file is moved to the `files` directory and a corresponding `.trashinfo`
+/// file, which records the original path and time of deletion, is written to the
+/// `info` directory. A numeric suffix is appended to the name of the file if the
+/// trash already contains a file of the same name.
+pub fn trash_file<P: AsRef<Path>>(path: P, trash_dir: &Path) -> Result<PathBuf> {
+    let path = sys::canonicalize(path);
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+    fs::create_dir_all(&files_dir).map_err(|e| to_error(e, &files_dir))?;
+    fs::create_dir_all(&info_dir).map_err(|e| to_error(e, &info_dir))?;
+
+    // Find name that does not conflict with existing files in trash.
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = (0..)
+        .map(|n| {
+            if n == 0 {
+                name.clone()
+            } else {
+                format!("{name}.{n}")
+            }
+        })
+        .find(|name| !files_dir.join(name).exists() && !info_dir.join(trash_info(name)).exists())
+        .unwrap_or(name);
+
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_path(&path),
+        sys::local_time()
+    );
+    let info_path = info_dir.join(trash_info(&name));
+    fs::write(&info_path, info).map_err(|e| to_error(e, &info_path))?;
+
+    let trash_path = files_dir.join(&name);
+    if let Err(e) = move_file(&path, &trash_path) {
+        let _ = fs::remove_file(&info_path);
+        Err(e)
+    } else {
+        Ok(trash_path)
+    }
+}
+
+/// Restores the file at `trash_path`, which was previously moved to the trash via
+/// [`trash_file`], to its original `path`.
+///
+/// An error is returned if a file already exists at `path`.
+pub fn restore_file<P: AsRef<Path>>(trash_path: &Path, path: P) -> Result<()> {
+    let path = path.as_ref();
+    if path.exists() {
+        Err(to_error(ErrorKind::AlreadyExists.into(), path))
+    } else {
+        move_file(trash_path, path)?;
+        if let (Some(dir), Some(name)) = (
+            trash_path.parent().and_then(|dir| dir.parent()),
+            trash_path.file_name(),
+        ) {
+            let _ = fs::remove_file(dir.join("info").join(trash_info(&name.to_string_lossy())));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the name of the `.trashinfo` file corresponding to `name`.
+fn trash_info(name: &str) -> String {
+    format!("{name}.trashinfo")
+}
+
+/// Returns `path` with all characters other than those considered unreserved in URIs
+/// percent-encoded, as required in `.trashinfo` files.
+fn encode_path(path: &Path) -> String {
+    path.as_string()
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-_.~/".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect()
+}
+
 /// Converts an I/O error into its corresponding `Error` adorned with `path`.
 fn to_error<P: AsRef<Path>>(e: io::Error, path: P) -> Error {
     Error::io(&path.as_ref().as_string(), e)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fresh directory under the system temporary directory unique to the
+    /// calling test.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ped-test-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        sys::canonicalize(dir)
+    }
+
+    #[test]
+    fn encode_reserved_chars() {
+        assert_eq!(encode_path(Path::new("/tmp/a-b_c.~d")), "/tmp/a-b_c.~d");
+        assert_eq!(
+            encode_path(Path::new("/tmp/a b/%#?.txt")),
+            "/tmp/a%20b/%25%23%3F.txt"
+        );
+        assert_eq!(encode_path(Path::new("/tmp/\u{fc}")), "/tmp/%C3%BC");
+    }
+
+    #[test]
+    fn trash_name_collisions() {
+        let dir = scratch_dir("trash-collisions");
+        let trash_dir = dir.join("trash");
+        fs::create_dir_all(dir.join("a")).unwrap();
+        fs::create_dir_all(dir.join("b")).unwrap();
+        let a = dir.join("a").join("x y.txt");
+        let b = dir.join("b").join("x y.txt");
+        fs::write(&a, "a").unwrap();
+        fs::write(&b, "b").unwrap();
+
+        let trash_a = trash_file(&a, &trash_dir).unwrap();
+        let trash_b = trash_file(&b, &trash_dir).unwrap();
+        assert_eq!(trash_a, trash_dir.join("files").join("x y.txt"));
+        assert_eq!(trash_b, trash_dir.join("files").join("x y.txt.1"));
+        assert!(!a.exists() && !b.exists());
+        assert_eq!(fs::read_to_string(&trash_b).unwrap(), "b");
+
+        let info = fs::read_to_string(trash_dir.join("info").join("x y.txt.1.trashinfo")).unwrap();
+        let expected = format!("Path={}\n", encode_path(&b));
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&expected));
+        assert!(expected.ends_with("/b/x%20y.txt\n"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restore_from_trash() {
+        let dir = scratch_dir("trash-restore");
+        let trash_dir = dir.join("trash");
+        let path = dir.join("x.txt");
+        fs::write(&path, "x").unwrap();
+
+        let trash_path = trash_file(&path, &trash_dir).unwrap();
+        let info_path = trash_dir.join("info").join("x.txt.trashinfo");
+        assert!(info_path.exists());
+
+        fs::write(&path, "y").unwrap();
+        assert!(restore_file(&trash_path, &path).is_err());
+        assert!(trash_path.exists());
+
+        fs::remove_file(&path).unwrap();
+        restore_file(&trash_path, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "x");
+        assert!(!trash_path.exists());
+        assert!(!info_path.exists());
+
+        // Name is reused once original entry is restored.
+        assert_eq!(trash_file(&path, &trash_dir).unwrap(), trash_path);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn digest_while_reading() {
+        let dir = scratch_dir("digest-read");
+        let path = dir.join("x.txt");
+        let text = "abc\n".repeat(BUFFER_SIZE);
+        fs::write(&path, &text).unwrap();
+
+        let mut buf = Buffer::new();
+        let stamp = read_file(&path, &mut buf).unwrap();
+        assert_eq!(buf.size(), text.len());
+        assert_eq!(stamp.digest, Some(hash_file(&path).unwrap()));
+        assert_eq!(stamp.digest, Some(hash_bytes(text.as_bytes())));
+        assert_eq!(stamp.time, get_time(&path).unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_replaces_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = scratch_dir("write-replace");
+        let path = dir.join("x.txt");
+        fs::write(&path, "old contents").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let bytes = Arc::new(b"new".to_vec());
+        let n = write_bytes(&path, bytes, Some(Duration::from_secs(5)), |_| {}).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+
+        // Temporary file no longer exists once renamed.
+        let names = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(names, 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
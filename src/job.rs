@@ -0,0 +1,224 @@
+//! Execution of external commands whose output is collected asynchronously.
+//!
+//! A job runs a command using the system shell and captures both _stdout_ and
+//! _stderr_ on background threads, which forward output line by line over a channel.
+//! This allows the controller to periodically [`poll`](Job::poll) for output without
+//! ever blocking the editing experience.
+//!
+//! Alternatively, text can be [`piped`](pipe) to a command whose output is of no
+//! interest, either waiting for the command or in the background as a [`Pipe`], or
+//! [`filtered`](filter) through a command whose output is returned. An interactive
+//! command can also be [`attached`](attach) to the terminal.
+
+use crate::error::{Error, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+/// A running external command.
+pub struct Job {
+    /// The process executing the command.
+    child: Child,
+
+    /// A channel over which lines of output are received.
+    output: Receiver<String>,
+}
+
+impl Job {
+    /// The shell used to execute commands.
+    const SHELL: &str = "sh";
+
+    /// Spawns `command` using the system shell and returns the corresponding job.
+    pub fn spawn(command: &str) -> Result<Job> {
+        let mut child = Command::new(Self::SHELL)
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| Error::io(command, e))?;
+
+        // Output from stdout and stderr is interleaved in the order in which lines
+        // arrive, though not necessarily the order in which they were written.
+        let (sender, output) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            Self::forward(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            Self::forward(stderr, sender);
+        }
+
+        Ok(Job { child, output })
+    }
+
+    /// Returns a tuple containing all output received since the prior call, which
+    /// may be empty, and the exit status of the process if it terminated.
+    ///
+    /// The exit status is only returned once all output has been received, after
+    /// which subsequent calls serve no purpose.
+    ///
+    /// This function never blocks.
+    pub fn poll(&mut self) -> (String, Option<ExitStatus>) {
+        let mut text = String::new();
+        loop {
+            match self.output.try_recv() {
+                Ok(line) => text.push_str(&line),
+                Err(TryRecvError::Empty) => return (text, None),
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        let status = self.child.try_wait().ok().flatten();
+        (text, status)
+    }
+
+    /// Spawns a thread that reads lines from `reader` and sends them to `sender`
+    /// until either the end of stream is reached or the receiver disconnects.
+    fn forward<R>(reader: R, sender: Sender<String>)
+    where
+        R: Read + Send + 'static,
+    {
+        thread::spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        let text = String::from_utf8_lossy(&line).to_string();
+                        if sender.send(text).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// An external command running in the background with text written to its _stdin_.
+pub struct Pipe {
+    /// The command being run.
+    command: String,
+
+    /// A channel over which the outcome of the command is received.
+    result: Receiver<Result<()>>,
+}
+
+impl Pipe {
+    /// Runs `command` on a background thread as described by [`pipe`] and returns
+    /// the corresponding pipe.
+    pub fn spawn(command: &str, input: String) -> Pipe {
+        let (sender, result) = mpsc::channel();
+        let command = command.to_string();
+        let cmd = command.clone();
+        thread::spawn(move || {
+            let _ = sender.send(pipe(&cmd, input));
+        });
+        Pipe { command, result }
+    }
+
+    /// Returns the outcome of the command if it terminated, otherwise `None`.
+    ///
+    /// The outcome is only returned once, after which subsequent calls serve no
+    /// purpose.
+    ///
+    /// This function never blocks.
+    pub fn poll(&self) -> Option<Result<()>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(Error::command(
+                &self.command,
+                "terminated unexpectedly",
+            ))),
+        }
+    }
+}
+
+/// Runs `command` using the system shell, writing `input` to its _stdin_ and waiting
+/// for the process to terminate.
+///
+/// Output written to _stdout_ is discarded, whereas output written to _stderr_ is
+/// returned as the cause of an error if the process exits unsuccessfully.
+pub fn pipe(command: &str, input: String) -> Result<()> {
+    run(command, input.into_bytes(), &[], Stdio::null()).map(|_| ())
+}
+
+/// Runs `command` using the system shell with the additional environment variables
+/// in `vars`, writing `input` to its _stdin_ and returning the output written to its
+/// _stdout_ once the process terminates.
+///
+/// Output written to _stderr_ is returned as the cause of an error if the process
+/// exits unsuccessfully.
+pub fn filter(command: &str, input: Vec<u8>, vars: &[(&str, &str)]) -> Result<Vec<u8>> {
+    run(command, input, vars, Stdio::piped())
+}
+
+/// Runs `command` using the system shell with `args` appended as its arguments,
+/// waiting for the process to terminate and returning its exit status.
+///
+/// The process inherits _stdin_, _stdout_ and _stderr_, so the terminal should be
+/// relinquished by the caller for the duration.
+pub fn attach(command: &str, args: &[&str]) -> Result<ExitStatus> {
+    Command::new(Job::SHELL)
+        .arg("-c")
+        .arg(format!("{command} \"$@\""))
+        .arg(Job::SHELL)
+        .args(args)
+        .status()
+        .map_err(|e| Error::io(command, e))
+}
+
+/// Runs `command` as described by [`pipe`] and [`filter`], where `stdout` determines
+/// whether output written to _stdout_ is captured and returned.
+fn run(command: &str, input: Vec<u8>, vars: &[(&str, &str)], stdout: Stdio) -> Result<Vec<u8>> {
+    let mut child = Command::new(Job::SHELL)
+        .arg("-c")
+        .arg(command)
+        .envs(vars.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(stdout)
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::io(command, e))?;
+
+    // Input is written on a separate thread to avoid a possible deadlock should the
+    // process fill the stdout or stderr pipe before consuming all input.
+    let writer = child.stdin.take().map(|mut stdin| {
+        thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        })
+    });
+    let output = child
+        .wait_with_output()
+        .map_err(|e| Error::io(command, e))?;
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let cause = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .unwrap_or_else(|| output.status.to_string());
+        Err(Error::command(command, &cause))
+    }
+}
+
+impl Drop for Job {
+    /// Kills the process if still running, which is necessary since dropping the
+    /// underlying [`Child`] leaves the process running.
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
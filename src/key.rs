@@ -29,6 +29,7 @@ pub enum Key {
     ScrollRight(Shift, u32, u32),
     ButtonPress(u32, u32),
     ButtonRelease(u32, u32),
+    MiddlePress(u32, u32),
 }
 
 /// Represents the state of the _SHIFT_ key for certain kinds of [`Key`]s.
@@ -56,6 +57,7 @@ pub const TAB: Key = Key::Control(9);
 pub const CTRL_J: Key = Key::Control(10);
 pub const CTRL_K: Key = Key::Control(11);
 pub const CTRL_M: Key = Key::Control(13);
+pub const CTRL_T: Key = Key::Control(20);
 pub const DELETE: Key = Key::Control(127);
 pub const LEFT: Key = Key::Left(Shift::Off, Ctrl::Off);
 pub const RIGHT: Key = Key::Right(Shift::Off, Ctrl::Off);
@@ -96,6 +98,7 @@ impl fmt::Display for Key {
             Key::ScrollRight(shift, row, col) => format!("{shift}sc_right({row},{col})"),
             Key::ButtonPress(row, col) => format!("bn_press({row},{col})"),
             Key::ButtonRelease(row, col) => format!("bn_release({row},{col})"),
+            Key::MiddlePress(row, col) => format!("bn_middle({row},{col})"),
         };
         write!(f, "{s}")
     }
@@ -295,7 +298,13 @@ impl Keyboard {
         let key = if let Some(b) = self.read_literal(&[b'M', b'm'])? {
             if button & 64 == 0 {
                 if b == b'M' {
-                    Key::ButtonPress(row, col)
+                    // Middle button is distinguished since it conventionally implies
+                    // pasting, whereas all other buttons are treated alike.
+                    if button & 3 == 1 {
+                        Key::MiddlePress(row, col)
+                    } else {
+                        Key::ButtonPress(row, col)
+                    }
                 } else {
                     Key::ButtonRelease(row, col)
                 }
@@ -486,7 +495,33 @@ pub fn pretty(keys: &Vec<Key>) -> String {
 /// Returns a vector of key names extracted from `keys`, wheressequences of
 /// `ESC` + `<key>` are replaced with `M-<key>`.
 pub fn pretty_keys(keys: &Vec<Key>) -> Vec<String> {
-    let mut keys = keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+    let keys = keys.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+    collapse_meta(keys)
+}
+
+/// Returns a key sequence formed by joining the names of `keys` with the `:`
+/// character, which is suitable for binding in a configuration file.
+///
+/// Unlike [`pretty_keys`], key names are guaranteed to be recognized when bound, as
+/// a few keys, such as `DEL`, are displayed differently than their bindable names.
+pub fn key_seq(keys: &[Key]) -> String {
+    let keys = keys
+        .iter()
+        .map(|key| {
+            let name = key.to_string();
+            KEY_MAPPINGS
+                .iter()
+                .filter(|(_, k)| k == key)
+                .max_by_key(|(n, _)| n.eq_ignore_ascii_case(&name))
+                .map(|(n, _)| n.to_string())
+                .unwrap_or(name)
+        })
+        .collect::<Vec<_>>();
+    collapse_meta(keys).join(":")
+}
+
+/// Replaces sequences of `ESC` + `<key>` in `keys` with `M-<key>`.
+fn collapse_meta(mut keys: Vec<String>) -> Vec<String> {
     if keys.len() > 1 {
         let mut i = keys.len() - 1;
         while i > 0 {
@@ -0,0 +1,141 @@
+//! Shows keys and operations in the key region of a workspace.
+//!
+//! The key line persistently shows the most recent keys typed on the left, and on the
+//! right, the prefix of an unfinished key sequence, if any, followed by the name of
+//! the last operation run. Its purpose is to make key bindings visible to viewers of
+//! screencasts and to users learning the bindings.
+
+use crate::color::Color;
+use crate::key::{self, Key};
+use crate::workspace::WorkspaceRef;
+use crate::writer::Writer;
+use std::collections::VecDeque;
+
+pub struct KeyLine {
+    workspace: WorkspaceRef,
+    keys_color: Color,
+    op_color: Color,
+    pending_suffix: &'static str,
+    keys: VecDeque<Key>,
+    pending: Vec<Key>,
+    last_op: Option<String>,
+}
+
+impl KeyLine {
+    /// Maximum number of recent keys retained, which is more than enough to fill
+    /// the width of most terminals.
+    const MAX_KEYS: usize = 64;
+
+    /// Suffix of pending keys indicating that the key sequence is unfinished.
+    const PENDING_SUFFIX: &str = "\u{2026}";
+
+    /// Equivalent of [`PENDING_SUFFIX`](Self::PENDING_SUFFIX) when only ASCII
+    /// characters are permitted.
+    const PENDING_ASCII_SUFFIX: &str = "...";
+
+    pub fn new(workspace: WorkspaceRef) -> KeyLine {
+        let config = workspace.borrow().config().clone();
+        let keys_color = Color::new(config.theme.prompt_fg, config.theme.text_bg);
+        let op_color = Color::new(config.theme.accent_fg, config.theme.text_bg);
+        let pending_suffix = if config.settings.ascii_only {
+            Self::PENDING_ASCII_SUFFIX
+        } else {
+            Self::PENDING_SUFFIX
+        };
+
+        KeyLine {
+            workspace,
+            keys_color,
+            op_color,
+            pending_suffix,
+            keys: VecDeque::new(),
+            pending: Vec::new(),
+            last_op: None,
+        }
+    }
+
+    /// Appends `key` to the recent keys, though mouse events are ignored.
+    pub fn push_key(&mut self, key: &Key) {
+        match key {
+            Key::None
+            | Key::ScrollUp(..)
+            | Key::ScrollDown(..)
+            | Key::ScrollLeft(..)
+            | Key::ScrollRight(..)
+            | Key::ButtonPress(..)
+            | Key::ButtonRelease(..)
+            | Key::MiddlePress(..) => (),
+            _ => {
+                if self.keys.len() == Self::MAX_KEYS {
+                    self.keys.pop_front();
+                }
+                self.keys.push_back(key.clone());
+                self.draw();
+            }
+        }
+    }
+
+    /// Sets the prefix of an unfinished key sequence to `keys`, which may be empty.
+    pub fn set_pending(&mut self, keys: &[Key]) {
+        self.pending = keys.to_vec();
+        self.draw();
+    }
+
+    /// Sets the name of the last operation run to `op`.
+    pub fn set_op(&mut self, op: &str) {
+        self.last_op = Some(op.to_string());
+        self.draw();
+    }
+
+    pub fn draw(&mut self) {
+        if let Some((origin, size)) = self.workspace.borrow().keys_region() {
+            let cols = size.cols as usize;
+
+            // Right side shows pending keys and last operation, which takes precedence
+            // over recent keys when space is limited.
+            let mut right = String::new();
+            if !self.pending.is_empty() {
+                right.push_str(&key::pretty(&self.pending));
+                right.push_str(self.pending_suffix);
+            }
+            if let Some(op) = &self.last_op {
+                if !right.is_empty() {
+                    right.push(' ');
+                }
+                right.push_str(op);
+            }
+            let right = right.chars().take(cols).collect::<String>();
+            let right_cols = right.chars().count();
+
+            // Left side shows as many of the most recent keys as will fit, leaving at
+            // least one column of separation from the right side.
+            let left_cols = cols.saturating_sub(right_cols + 1);
+            let keys = key::pretty_keys(&self.keys.iter().cloned().collect());
+            let mut left = String::new();
+            for k in keys.iter().rev() {
+                let n = left.chars().count() + k.chars().count() + 1;
+                if n > left_cols {
+                    break;
+                }
+                left = if left.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{k} {left}")
+                };
+            }
+            let blank_cols = cols - left.chars().count() - right_cols;
+
+            Writer::new_at(origin)
+                .set_color(self.keys_color)
+                .write_str(&left)
+                .write_str(&" ".repeat(blank_cols))
+                .set_color(self.op_color)
+                .write_str(&right)
+                .send();
+        }
+    }
+
+    pub fn resize(&mut self) {
+        self.draw();
+    }
+}
@@ -0,0 +1,378 @@
+//! Metadata lanes that annotate ranges of buffer positions.
+//!
+//! A lane is a collection of annotations owned by a single subsystem, such as search,
+//! diagnostics, or spell checking, each of which associates a range of buffer
+//! positions with a [`Style`] and a priority. Lanes are maintained in parallel with
+//! the buffer, meaning that insertions and removals are applied to lanes as well, so
+//! annotations remain attached to the text they describe rather than being
+//! recomputed from absolute positions.
+//!
+//! When annotations overlap, the style of each position is composed from the
+//! annotation of highest priority that defines a foreground color and the one that
+//! defines a background color. Ties are broken deterministically in favor of the
+//! lane declared last in [`Lane`], and then in favor of the annotation added last.
+//!
+//! Lanes may also contain _hints_, which are virtual text anchored to a buffer
+//! position and rendered after the end of the line containing that position. Hints
+//! occupy display cells but no buffer positions.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// The owners of annotations, each of which has its own lane.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Lane {
+    /// Markers of interest, such as notes or conflict markers.
+    Marker,
+
+    /// Transient highlights, such as matches of a search.
+    Highlight,
+
+    /// Diagnostics produced by external tools.
+    Diagnostic,
+}
+
+/// Colors applied over text, either of which may be absent.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    pub fg: Option<u8>,
+    pub bg: Option<u8>,
+}
+
+/// A collection of annotations organized by [`Lane`].
+pub struct Lanes {
+    lanes: BTreeMap<Lane, Annotations>,
+}
+
+pub type LanesRef = Rc<RefCell<Lanes>>;
+
+/// Annotations and hints of a single lane, each ordered by position.
+#[derive(Default)]
+struct Annotations {
+    annotations: Vec<Annotation>,
+    hints: Vec<Hint>,
+
+    /// Length of the longest range, which bounds the search for annotations
+    /// containing a given position.
+    max_len: usize,
+
+    /// Sequence number assigned to the next annotation.
+    next_seq: u64,
+}
+
+/// A range of buffer positions annotated with a style.
+#[derive(Clone, Debug, PartialEq)]
+struct Annotation {
+    range: Range<usize>,
+    priority: u8,
+    style: Style,
+    seq: u64,
+}
+
+/// Virtual text anchored to a buffer position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hint {
+    pub pos: usize,
+    pub text: String,
+    pub style: Style,
+}
+
+impl Style {
+    /// Returns a style that only defines the background color `bg`.
+    pub fn bg(bg: u8) -> Style {
+        Style {
+            fg: None,
+            bg: Some(bg),
+        }
+    }
+
+    /// Returns `true` if neither color is defined.
+    pub fn is_empty(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none()
+    }
+}
+
+impl Lanes {
+    pub fn new() -> Lanes {
+        Lanes {
+            lanes: BTreeMap::new(),
+        }
+    }
+
+    /// Turns the lanes into a [`LanesRef`].
+    pub fn to_ref(self) -> LanesRef {
+        Rc::new(RefCell::new(self))
+    }
+
+    /// Returns `true` if no annotations exist in any lane.
+    pub fn is_empty(&self) -> bool {
+        self.lanes.is_empty()
+    }
+
+    /// Annotates `range` of buffer positions in `lane` with `style` at the given
+    /// `priority`, which is ignored if `range` or `style` is empty.
+    pub fn add(&mut self, lane: Lane, range: Range<usize>, priority: u8, style: Style) {
+        if range.start < range.end && !style.is_empty() {
+            let lane = self.lanes.entry(lane).or_default();
+            let i = lane
+                .annotations
+                .partition_point(|a| a.range.start <= range.start);
+            lane.max_len = lane.max_len.max(range.len());
+            lane.annotations.insert(
+                i,
+                Annotation {
+                    range,
+                    priority,
+                    style,
+                    seq: lane.next_seq,
+                },
+            );
+            lane.next_seq += 1;
+        }
+    }
+
+    /// Anchors the virtual `text` of a hint in `lane` to `pos`, which is ignored if
+    /// `text` is empty.
+    pub fn add_hint(&mut self, lane: Lane, pos: usize, text: &str, style: Style) {
+        if !text.is_empty() {
+            let lane = self.lanes.entry(lane).or_default();
+            let i = lane.hints.partition_point(|h| h.pos <= pos);
+            lane.hints.insert(
+                i,
+                Hint {
+                    pos,
+                    text: text.to_string(),
+                    style,
+                },
+            );
+        }
+    }
+
+    /// Removes all annotations and hints in `lane`.
+    pub fn clear(&mut self, lane: Lane) {
+        self.lanes.remove(&lane);
+    }
+
+    /// Adjusts annotations to account for the insertion of `len` characters at `pos`.
+    ///
+    /// Annotations that start at or after `pos` are shifted, and annotations that
+    /// strictly contain `pos` are expanded. Hints anchored at or after `pos` are
+    /// shifted.
+    pub fn insert(&mut self, pos: usize, len: usize) {
+        for lane in self.lanes.values_mut() {
+            for hint in lane.hints.iter_mut().filter(|h| h.pos >= pos) {
+                hint.pos += len;
+            }
+            for annotation in lane.annotations.iter_mut() {
+                let range = &mut annotation.range;
+                if range.start >= pos {
+                    range.start += len;
+                    range.end += len;
+                } else if range.end > pos {
+                    range.end += len;
+                    lane.max_len = lane.max_len.max(range.len());
+                }
+            }
+        }
+    }
+
+    /// Adjusts annotations to account for the removal of `len` characters at `pos`.
+    ///
+    /// Annotations that fall entirely within the removed range are discarded, as are
+    /// hints anchored to removed characters.
+    pub fn remove_text(&mut self, pos: usize, len: usize) {
+        let end_pos = pos + len;
+        let adjust = |p: usize| {
+            if p <= pos {
+                p
+            } else if p >= end_pos {
+                p - len
+            } else {
+                pos
+            }
+        };
+        for lane in self.lanes.values_mut() {
+            for annotation in lane.annotations.iter_mut() {
+                let range = &mut annotation.range;
+                *range = adjust(range.start)..adjust(range.end);
+            }
+            lane.annotations.retain(|a| a.range.start < a.range.end);
+            lane.hints.retain(|h| h.pos < pos || h.pos >= end_pos);
+            for hint in lane.hints.iter_mut().filter(|h| h.pos >= end_pos) {
+                hint.pos -= len;
+            }
+        }
+        self.lanes.retain(|_, lane| !lane.is_empty());
+    }
+
+    /// Returns the style at `pos` composed from all annotations containing `pos`.
+    pub fn style_at(&self, pos: usize) -> Style {
+        let mut fg = None;
+        let mut bg = None;
+        for (lane, annotations) in self.lanes.iter() {
+            for a in annotations.containing(pos) {
+                let rank = (a.priority, *lane, a.seq);
+                if a.style.fg.is_some() && fg.is_none_or(|(r, _)| rank > r) {
+                    fg = Some((rank, a.style.fg));
+                }
+                if a.style.bg.is_some() && bg.is_none_or(|(r, _)| rank > r) {
+                    bg = Some((rank, a.style.bg));
+                }
+            }
+        }
+        Style {
+            fg: fg.and_then(|(_, c)| c),
+            bg: bg.and_then(|(_, c)| c),
+        }
+    }
+
+    /// Returns all hints anchored inside `range` ordered by lane and then by position.
+    pub fn hints_in(&self, range: Range<usize>) -> Vec<&Hint> {
+        self.lanes
+            .values()
+            .flat_map(|lane| {
+                let start = lane.hints.partition_point(|h| h.pos < range.start);
+                let end = lane.hints.partition_point(|h| h.pos < range.end);
+                &lane.hints[start..end]
+            })
+            .collect()
+    }
+}
+
+impl Annotations {
+    fn is_empty(&self) -> bool {
+        self.annotations.is_empty() && self.hints.is_empty()
+    }
+    /// Returns an iterator over annotations containing `pos`.
+    fn containing(&self, pos: usize) -> impl Iterator<Item = &Annotation> {
+        let from_pos = pos.saturating_sub(self.max_len);
+        let start = self
+            .annotations
+            .partition_point(|a| a.range.start < from_pos);
+        let end = self.annotations.partition_point(|a| a.range.start <= pos);
+        self.annotations[start..end]
+            .iter()
+            .filter(move |a| a.range.contains(&pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fg(fg: u8) -> Style {
+        Style {
+            fg: Some(fg),
+            bg: None,
+        }
+    }
+
+    #[test]
+    fn add_annotations() {
+        let mut lanes = Lanes::new();
+        assert!(lanes.is_empty());
+        lanes.add(Lane::Highlight, 5..5, 0, Style::bg(1));
+        lanes.add(Lane::Highlight, 5..10, 0, Style::default());
+        assert!(lanes.is_empty());
+        lanes.add(Lane::Highlight, 5..10, 0, Style::bg(1));
+        lanes.add(Lane::Diagnostic, 8..12, 0, Style::bg(2));
+        assert!(!lanes.is_empty());
+        assert_eq!(lanes.style_at(4), Style::default());
+        assert_eq!(lanes.style_at(5), Style::bg(1));
+        assert_eq!(lanes.style_at(8), Style::bg(2));
+        assert_eq!(lanes.style_at(11), Style::bg(2));
+        assert_eq!(lanes.style_at(12), Style::default());
+
+        lanes.clear(Lane::Diagnostic);
+        assert_eq!(lanes.style_at(8), Style::bg(1));
+    }
+
+    #[test]
+    fn compose_by_priority() {
+        let mut lanes = Lanes::new();
+        lanes.add(Lane::Highlight, 0..10, 1, Style::bg(1));
+        lanes.add(Lane::Marker, 0..10, 2, Style::bg(2));
+        lanes.add(Lane::Marker, 0..10, 0, fg(3));
+        assert_eq!(
+            lanes.style_at(5),
+            Style {
+                fg: Some(3),
+                bg: Some(2)
+            }
+        );
+
+        // Ties favor later lane, then later annotation.
+        lanes.add(Lane::Highlight, 0..10, 2, Style::bg(4));
+        assert_eq!(lanes.style_at(5).bg, Some(4));
+        lanes.add(Lane::Highlight, 0..10, 2, Style::bg(5));
+        assert_eq!(lanes.style_at(5).bg, Some(5));
+        lanes.add(Lane::Marker, 2..4, 2, Style::bg(6));
+        assert_eq!(lanes.style_at(3).bg, Some(5));
+    }
+
+    #[test]
+    fn maintain_hints() {
+        let mut lanes = Lanes::new();
+        lanes.add_hint(Lane::Diagnostic, 10, "", Style::default());
+        assert!(lanes.is_empty());
+        lanes.add_hint(Lane::Diagnostic, 10, "foo", Style::default());
+        lanes.add_hint(Lane::Marker, 20, "bar", Style::default());
+        lanes.add_hint(Lane::Diagnostic, 5, "baz", Style::default());
+        let texts = |lanes: &Lanes, range| {
+            lanes
+                .hints_in(range)
+                .iter()
+                .map(|h| h.text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts(&lanes, 0..30), vec!["bar", "baz", "foo"]);
+        assert_eq!(texts(&lanes, 6..20), vec!["foo"]);
+
+        lanes.insert(10, 5);
+        assert_eq!(lanes.hints_in(15..16)[0].pos, 15);
+        lanes.remove_text(0, 6);
+        assert_eq!(texts(&lanes, 0..30), vec!["bar", "foo"]);
+        assert_eq!(lanes.hints_in(9..10)[0].pos, 9);
+
+        lanes.clear(Lane::Marker);
+        lanes.clear(Lane::Diagnostic);
+        assert!(lanes.is_empty());
+    }
+
+    #[test]
+    fn insert_shifts_annotations() {
+        let mut lanes = Lanes::new();
+        lanes.add(Lane::Highlight, 5..10, 0, Style::bg(1));
+        lanes.insert(5, 3);
+        assert_eq!(lanes.style_at(5), Style::default());
+        assert_eq!(lanes.style_at(8), Style::bg(1));
+        assert_eq!(lanes.style_at(12), Style::bg(1));
+        assert_eq!(lanes.style_at(13), Style::default());
+
+        lanes.insert(10, 2);
+        assert_eq!(lanes.style_at(14), Style::bg(1));
+        assert_eq!(lanes.style_at(15), Style::default());
+
+        lanes.insert(15, 2);
+        assert_eq!(lanes.style_at(15), Style::default());
+    }
+
+    #[test]
+    fn remove_text_shrinks_annotations() {
+        let mut lanes = Lanes::new();
+        lanes.add(Lane::Highlight, 5..10, 0, Style::bg(1));
+        lanes.add(Lane::Highlight, 20..25, 0, Style::bg(2));
+        lanes.remove_text(0, 3);
+        assert_eq!(lanes.style_at(2), Style::bg(1));
+        assert_eq!(lanes.style_at(7), Style::default());
+
+        lanes.remove_text(5, 20);
+        assert_eq!(lanes.style_at(4), Style::bg(1));
+        assert_eq!(lanes.style_at(5), Style::default());
+
+        lanes.remove_text(2, 5);
+        assert!(lanes.is_empty());
+    }
+}
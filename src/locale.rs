@@ -0,0 +1,185 @@
+//! Locale-sensitive transformations of text, namely case mapping and collation.
+//!
+//! The locale is named by the `locale` setting, otherwise by the `LC_ALL` or `LANG`
+//! environment variables, such as `tr_TR.UTF-8`, and defaults to `C` when none are
+//! set.
+//!
+//! Case mapping follows the Unicode rules, which are tailored for Turkish and
+//! Azerbaijani where dotted and dotless `i` are distinct letters, such that `i`
+//! becomes `İ` and `I` becomes `ı`.
+//!
+//! Collation is delegated to the C library, which requires that the locale be
+//! installed on the system. Otherwise, text is compared ignoring case, with ties
+//! broken by code point.
+
+use std::cmp::Ordering;
+use std::env;
+use std::ffi::CString;
+use std::ptr;
+
+/// A locale used for transforming text.
+pub struct Locale {
+    /// Language code in lowercase, such as `tr`.
+    lang: String,
+
+    /// Locale of the C library used for collation, or `None` if not installed.
+    handle: Option<libc::locale_t>,
+}
+
+impl Locale {
+    /// Returns the locale named by `name` if present and not empty, otherwise the
+    /// locale named by the environment.
+    pub fn new(name: Option<&str>) -> Locale {
+        let name = name
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+            .or_else(|| Self::env_name("LC_ALL"))
+            .or_else(|| Self::env_name("LANG"))
+            .unwrap_or_else(|| "C".to_string());
+        let handle = CString::new(name.as_str()).ok().and_then(|c_name| {
+            let handle =
+                unsafe { libc::newlocale(libc::LC_COLLATE_MASK, c_name.as_ptr(), ptr::null_mut()) };
+            if handle.is_null() {
+                None
+            } else {
+                Some(handle)
+            }
+        });
+        Locale {
+            lang: Self::lang_of(&name),
+            handle,
+        }
+    }
+
+    /// Returns the value of the environment variable `var` if set and not empty.
+    fn env_name(var: &str) -> Option<String> {
+        env::var(var).ok().filter(|name| !name.is_empty())
+    }
+
+    /// Returns the language code of the locale `name`, which precedes the territory,
+    /// encoding, and modifier, if any.
+    fn lang_of(name: &str) -> String {
+        name.split(['_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+
+    /// Returns `true` if dotted and dotless `i` are distinct letters.
+    fn is_turkic(&self) -> bool {
+        self.lang == "tr" || self.lang == "az"
+    }
+
+    /// Returns `text` converted to uppercase.
+    pub fn to_upper(&self, text: &str) -> String {
+        if self.is_turkic() {
+            text.replace('i', "İ").to_uppercase()
+        } else {
+            text.to_uppercase()
+        }
+    }
+
+    /// Returns `text` converted to lowercase.
+    pub fn to_lower(&self, text: &str) -> String {
+        if self.is_turkic() {
+            text.replace('I', "ı").replace('İ', "i").to_lowercase()
+        } else {
+            text.to_lowercase()
+        }
+    }
+
+    /// Compares `a` and `b` according to the collation rules of the locale, where
+    /// text that collates equally is ordered by code point.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let order = match (self.handle, CString::new(a), CString::new(b)) {
+            (Some(handle), Ok(a), Ok(b)) => {
+                let n = unsafe {
+                    let prior = libc::uselocale(handle);
+                    let n = libc::strcoll(a.as_ptr(), b.as_ptr());
+                    libc::uselocale(prior);
+                    n
+                };
+                n.cmp(&0)
+            }
+            _ => self.to_lower(a).cmp(&self.to_lower(b)),
+        };
+        order.then_with(|| a.cmp(b))
+    }
+
+    /// Returns `text` with its lines sorted according to [`compare`](Self::compare),
+    /// where a final line without `\n` is sorted as though it had one.
+    pub fn sort_lines(&self, text: &str) -> String {
+        let (text, eol) = match text.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (text, ""),
+        };
+        let mut lines = text.split('\n').collect::<Vec<_>>();
+        lines.sort_by(|a, b| self.compare(a, b));
+        lines.join("\n") + eol
+    }
+}
+
+impl Drop for Locale {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            unsafe { libc::freelocale(handle) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turkic_case_mapping() {
+        let tr = fallback("tr_TR.UTF-8");
+        assert_eq!(tr.to_upper("istanbul ılık"), "İSTANBUL ILIK");
+        assert_eq!(tr.to_lower("İSTANBUL ILIK"), "istanbul ılık");
+
+        let az = fallback("az");
+        assert_eq!(az.to_upper("iI"), "İI");
+        assert_eq!(az.to_lower("iI"), "iı");
+
+        let en = fallback("en_US.UTF-8");
+        assert_eq!(en.to_upper("istanbul ılık"), "ISTANBUL ILIK");
+        assert_eq!(en.to_lower("İSTANBUL"), "i\u{307}stanbul");
+        assert_eq!(en.to_upper("straße"), "STRASSE");
+        assert_eq!(en.to_lower("ΟΔΟΣ"), "οδος");
+    }
+
+    #[test]
+    fn collate_without_system_locale() {
+        let en = fallback("en_US.UTF-8");
+        assert_eq!(en.compare("apple", "Banana"), Ordering::Less);
+        assert_eq!(en.compare("Apple", "apple"), Ordering::Less);
+        assert_eq!(en.compare("éclair", "éclair"), Ordering::Equal);
+        assert_eq!(
+            en.sort_lines("pear\nApple\nbanana\napple"),
+            "Apple\napple\nbanana\npear"
+        );
+        assert_eq!(en.sort_lines("b\na\n"), "a\nb\n");
+
+        // Dotless capital I is not the uppercase of i in Turkish.
+        let tr = fallback("tr");
+        assert_eq!(en.compare("Ia", "ib"), Ordering::Less);
+        assert_eq!(tr.compare("Ia", "ib"), Ordering::Greater);
+        assert_eq!(tr.compare("İa", "ib"), Ordering::Less);
+    }
+
+    #[test]
+    fn name_from_environment() {
+        assert_eq!(Locale::lang_of("tr_TR.UTF-8"), "tr");
+        assert_eq!(Locale::lang_of("AZ@latin"), "az");
+        assert_eq!(Locale::lang_of("C"), "c");
+        assert_eq!(Locale::new(Some("C")).compare("B", "a"), Ordering::Less);
+    }
+
+    /// Returns the locale `name` that collates without the C library.
+    fn fallback(name: &str) -> Locale {
+        Locale {
+            lang: Locale::lang_of(name),
+            handle: None,
+        }
+    }
+}
@@ -14,12 +14,15 @@
 //! See the License for the specific language governing permissions and
 //! limitations under the License.
 mod ansi;
+mod audit;
 mod bind;
 mod buffer;
 mod canvas;
+mod clip;
 mod color;
 mod config;
 mod control;
+mod crypt;
 mod echo;
 mod editor;
 mod env;
@@ -27,26 +30,40 @@ mod error;
 mod etc;
 mod grid;
 mod help;
+mod ignore;
+mod indent;
 mod input;
 mod io;
+mod job;
 mod key;
+mod keyline;
+mod lane;
+mod locale;
 mod op;
 mod opt;
+mod perf;
+mod recent;
 mod search;
+mod session;
 mod size;
 mod source;
 mod syntax;
 mod sys;
+mod template;
 mod term;
 mod token;
+mod unicode;
 mod user;
+mod var;
 mod window;
 mod workspace;
+mod wrap;
 mod writer;
 
 use crate::config::Configuration;
 use crate::control::Controller;
 use crate::error::Result;
+use crate::io::FileSpec;
 use crate::key::Keyboard;
 use crate::opt::Options;
 use crate::syntax::Registry;
@@ -57,6 +74,12 @@ use std::process::ExitCode;
 /// Usage documentation for display to terminal.
 const USAGE: &str = include_str!("include/usage.in");
 
+/// Exit code when quitting with unsaved changes under `--dirty-fail`.
+const EXIT_DIRTY: u8 = 2;
+
+/// Exit code when at least one file is not writable under `--readonly-check`.
+const EXIT_NOT_WRITABLE: u8 = 3;
+
 /// Used for restoring the terminal via [`Drop`] to its original state.
 struct RestoreTerminal;
 
@@ -72,33 +95,35 @@ fn main() -> ExitCode {
             println!("{e}");
             ExitCode::from(1)
         }
-        Ok(_) => ExitCode::SUCCESS,
+        Ok(code) => code,
     }
 }
 
-fn run() -> Result<()> {
+fn run() -> Result<ExitCode> {
     let opts = Options::parse(std::env::args().skip(1))?;
     if opts.help {
         println!("{USAGE}");
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else if opts.version {
         println!("{}", etc::version());
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else if opts.source {
         println!("{}", etc::SOURCE_URL);
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else if opts.keys {
         print!("{}", help::keys_content());
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else if opts.ops {
         print!("{}", help::ops_content());
-        Ok(())
+        Ok(ExitCode::SUCCESS)
+    } else if opts.readonly_check {
+        Ok(check_writable(&opts.files))
     } else {
         run_opts(&opts)
     }
 }
 
-fn run_opts(opts: &Options) -> Result<()> {
+fn run_opts(opts: &Options) -> Result<ExitCode> {
     // Load optional configuration from either standard location or path specified on
     // command line, and apply command line options afterwards since these override
     // all other settings.
@@ -115,41 +140,90 @@ fn run_opts(opts: &Options) -> Result<()> {
     config.registry = if opts.bare || opts.bare_syntax {
         Registry::default()
     } else if let Some(ref syntax_dir) = opts.syntax_dir {
-        Registry::load_dir(syntax_dir, &config.colors)?
+        Registry::load_dir(syntax_dir, &config.colors)?.with_colors(&config.syntax_colors)?
     } else {
-        Registry::load(&config.colors)?
+        Registry::load(&config.colors)?.with_colors(&config.syntax_colors)?
     };
 
     if opts.bindings {
-        print!("{}", help::bindings_content(config.bindings.bindings()));
-        Ok(())
+        print!(
+            "{}",
+            help::bindings_content(config.bindings.bindings(), &config.bindings.audit())
+        );
+        print!("{}", help::scoped_bindings_content(&config.bindings));
+        Ok(ExitCode::SUCCESS)
+    } else if opts.export_bindings {
+        print!("{}", help::export_bindings_content(&config.bindings));
+        Ok(ExitCode::SUCCESS)
     } else if opts.colors {
         print!("{}", help::colors_content(config.colors.colors()));
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else if opts.theme {
         print!("{}", help::theme_content(&config.theme));
-        Ok(())
+        Ok(ExitCode::SUCCESS)
     } else {
         run_config(opts, config)
     }
 }
 
-fn run_config(opts: &Options, config: Configuration) -> Result<()> {
+fn run_config(opts: &Options, config: Configuration) -> Result<ExitCode> {
+    // Gather files specified on command line, followed by files listed in manifests,
+    // and parse scripts before touching terminal so that errors are reported cleanly.
+    let mut files = opts.files.clone();
+    for path in &opts.files_from {
+        files.extend(io::read_file_list(path)?);
+    }
+    let mut script = Vec::new();
+    for exec in &opts.exec {
+        script.extend(op::parse_script(exec)?);
+    }
+
     // Prepare terminal but ensure original settings are restored upon return.
     prepare_term()?;
     let _restore = RestoreTerminal;
 
-    // Initialize main controller and open files specified on command line.
+    // Initialize main controller, restore prior session if requested, open files, and
+    // run script, which may quit before the main processing loop begins.
     let mut controller = Controller::new(Keyboard::new(), Workspace::new(config));
-    controller.open(&opts.files)?;
-    controller.run();
-    Ok(())
+    if opts.restore {
+        controller.restore()?;
+    }
+    controller.open(&files)?;
+    if !controller.exec(&script) {
+        controller.run();
+    }
+    controller.save_session()?;
+
+    // Signal failure to calling tools if any editors were left with unsaved changes.
+    if opts.dirty_fail && controller.is_dirty() {
+        Ok(ExitCode::from(EXIT_DIRTY))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Prints each of `files` that is not writable and returns an exit code indicating
+/// whether all files are writable.
+fn check_writable(files: &Vec<FileSpec>) -> ExitCode {
+    let mut writable = true;
+    for (file, _, _) in files {
+        if !sys::is_writable(file) {
+            println!("{file}: not writable");
+            writable = false;
+        }
+    }
+    if writable {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_NOT_WRITABLE)
+    }
 }
 
 fn prepare_term() -> Result<()> {
     term::init()?;
     print!(
-        "{}{}{}",
+        "{}{}{}{}",
+        ansi::save_title(),
         ansi::alt_screen(true),
         ansi::track_mouse(true),
         ansi::clear_screen()
@@ -159,10 +233,12 @@ fn prepare_term() -> Result<()> {
 
 fn restore_term() -> Result<()> {
     print!(
-        "{}{}{}",
+        "{}{}{}{}{}",
         ansi::clear_screen(),
+        ansi::reset_cursor_shape(),
         ansi::track_mouse(false),
-        ansi::alt_screen(false)
+        ansi::alt_screen(false),
+        ansi::restore_title()
     );
     term::restore()
 }
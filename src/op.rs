@@ -9,25 +9,46 @@
 //! See [`Bindings`](crate::bind::Bindings) for further details on binding keys
 //! at runtime.
 
+use crate::audit::Audit;
 use crate::buffer::Buffer;
-use crate::config::ConfigurationRef;
-use crate::editor::{Align, Capture, Editor, EditorRef, ImmutableEditor};
-use crate::env::{Environment, Focus};
+use crate::clip;
+use crate::config::{ByteOrderMark, Clipboard, ConfigurationRef, QuitEphemeral, Settings};
+use crate::crypt::{self, Crypt};
+use crate::echo::Severity;
+use crate::editor::{Align, Capture, Editor, EditorRef, ImmutableEditor, Mark};
+use crate::env::{Environment, Focus, Pane, Usage};
 use crate::error::{Error, Result};
 use crate::help;
-use crate::io;
-use crate::key::{Key, TAB};
-use crate::search::{self, Pattern};
+use crate::indent;
+use crate::io::{self, Loader, Retry, Stamp};
+use crate::job::{self, Job, Pipe};
+use crate::key::{self, Key, TAB};
+use crate::lane::{Lane, Style};
+use crate::locale::Locale;
+use crate::search::{self, Pattern, Results};
+use crate::session::{Session, SessionEditor, SessionWindow};
 use crate::size::{Point, Size};
 use crate::source::Source;
+use crate::syntax::Syntax;
 use crate::sys::{self, AsString};
-use crate::user::{self, Completer, Inquirer};
+use crate::template;
+use crate::term;
+use crate::unicode;
+use crate::user::{self, Checklist, Completer, Inquirer, Intercept};
+use crate::var::Variables;
 use crate::workspace::Placement;
-use regex_lite::RegexBuilder;
+use crate::wrap;
+use regex_lite::{Regex, RegexBuilder};
+use std::cmp;
 use std::collections::HashMap;
+use std::fs;
 use std::io::ErrorKind;
+use std::mem;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 use std::usize;
 
 /// A function type that implements an editing operation.
@@ -37,7 +58,7 @@ pub type OpFn = fn(&mut Environment) -> Option<Action>;
 /// calls to such functions.
 pub enum Action {
     Quit,
-    Echo(String),
+    Echo(String, Severity),
     Question(Box<dyn Inquirer>),
 }
 
@@ -50,7 +71,17 @@ impl Action {
     }
 
     fn as_echo<T: ToString + ?Sized>(text: &T) -> Option<Action> {
-        let action = Action::Echo(text.to_string());
+        let action = Action::Echo(text.to_string(), Severity::Info);
+        Some(action)
+    }
+
+    fn as_warn<T: ToString + ?Sized>(text: &T) -> Option<Action> {
+        let action = Action::Echo(text.to_string(), Severity::Warn);
+        Some(action)
+    }
+
+    fn as_error<T: ToString + ?Sized>(text: &T) -> Option<Action> {
+        let action = Action::Echo(text.to_string(), Severity::Error);
         Some(action)
     }
 
@@ -60,19 +91,19 @@ impl Action {
     }
 
     fn echo_readonly() -> Option<Action> {
-        Self::as_echo("editor is readonly")
+        Self::as_warn("editor is readonly")
     }
 
     fn echo_no_window() -> Option<Action> {
-        Self::as_echo("unable to create new window")
+        Self::as_warn("unable to create new window")
     }
 
     fn echo_cannot_close() -> Option<Action> {
-        Self::as_echo("cannot close only window")
+        Self::as_warn("cannot close only window")
     }
 
     fn echo_no_editors() -> Option<Action> {
-        Self::as_echo("no more editors")
+        Self::as_warn("no more editors")
     }
 }
 
@@ -82,57 +113,82 @@ fn quit(env: &mut Environment) -> Option<Action> {
 }
 
 /// An inquirer that orchestrates the _quit_ process, which may involve saving dirty
-/// editors.
+/// editors selected from a checklist.
 struct Quit {
-    /// List of dirty editors.
+    /// List of dirty editors, which may include ephemeral editors with content
+    /// depending on the `quit-ephemeral` setting.
     dirty: Vec<EditorRef>,
+
+    /// Checklist corresponding to `dirty` that indicates which editors are saved.
+    checklist: Checklist,
 }
 
 impl Quit {
     /// Starts the process of saving dirty editors before quitting.
+    ///
+    /// Ephemeral editors with content are either appended to the list of dirty
+    /// editors, quietly discarded, or saved to the state directory without prompting,
+    /// depending on the `quit-ephemeral` setting.
     fn start(env: &Environment) -> Option<Action> {
-        let dirty = dirty_editors(env);
+        let mut dirty = dirty_editors(env);
+        match env.workspace().config().settings.quit_ephemeral {
+            QuitEphemeral::Prompt => dirty.extend(ephemeral_editors(env)),
+            QuitEphemeral::Discard => (),
+            QuitEphemeral::Save => {
+                if let Err(e) = Self::save_ephemerals(env) {
+                    return Action::as_error(&e);
+                }
+            }
+        }
         if dirty.len() > 0 {
-            Action::as_question(Quit { dirty }.to_box())
+            let checklist = Checklist::new(dirty.iter().map(source_of).collect());
+            Action::as_question(Quit { dirty, checklist }.to_box())
         } else {
             Action::as_quit()
         }
     }
 
-    /// Continues the process of saving editors if `dirty` is not empty.
-    fn next(dirty: &Vec<EditorRef>) -> Option<Action> {
-        if dirty.len() > 1 {
-            let dirty = dirty[1..].to_vec();
-            Action::as_question(Quit { dirty }.to_box())
-        } else {
-            Action::as_quit()
-        }
+    /// Continues the process of saving editors that follow the first editor in
+    /// `dirty`.
+    fn next(env: &Environment, dirty: &[EditorRef]) -> Option<Action> {
+        Self::save_all(env, &dirty[1..])
     }
 
     fn to_box(self) -> Box<dyn Inquirer> {
         Box::new(self)
     }
 
-    /// Saves the first dirty editor and then continues to the next editor.
-    fn save_first(&mut self) -> Option<Action> {
-        let editor = &self.dirty[0];
-        match stale_editor(editor) {
-            Ok(true) => QuitOverride::question(self.dirty.clone()),
-            Ok(false) => {
-                if let Err(e) = save_editor(editor) {
-                    Action::as_echo(&e)
-                } else {
-                    Self::next(&self.dirty)
-                }
+    /// Writes the content of ephemeral editors to files in the state directory, each
+    /// named after the editor and the current time, though note that editors are not
+    /// changed as a consequence.
+    fn save_ephemerals(env: &Environment) -> Result<()> {
+        let editors = ephemeral_editors(env);
+        if !editors.is_empty() {
+            let dir = sys::state_dir();
+            io::create_dir(&dir)?;
+            let time = sys::local_time().replace(':', "");
+            for editor in editors {
+                let name = match editor.borrow().source() {
+                    Source::Ephemeral(name) => name.clone(),
+                    _ => continue,
+                };
+                let path = dir.join(format!("{name}-{time}.txt"));
+                write_editor(&editor, &path.as_string())?;
             }
-            Err(e) => Action::as_echo(&e),
         }
+        Ok(())
     }
 
-    /// Saves all dirty editors.
-    fn save_all(&mut self) -> Option<Action> {
-        let mut dirty_iter = self.dirty.iter();
+    /// Saves all editors in `dirty` and then quits, though a path is solicited for
+    /// each ephemeral editor.
+    fn save_all(env: &Environment, dirty: &[EditorRef]) -> Option<Action> {
+        let mut dirty_iter = dirty.iter();
         while let Some(editor) = dirty_iter.next() {
+            if is_ephemeral(editor) {
+                let mut dirty = vec![editor.clone()];
+                dirty.extend(dirty_iter.cloned());
+                return QuitSaveAs::question(env, dirty);
+            }
             match stale_editor(editor) {
                 Ok(true) => {
                     let mut dirty = vec![editor.clone()];
@@ -141,11 +197,14 @@ impl Quit {
                 }
                 Ok(false) => {
                     if let Err(e) = save_editor(editor) {
-                        return Action::as_echo(&e);
+                        let mut dirty = vec![editor.clone()];
+                        dirty.extend(dirty_iter.cloned());
+                        let path = path_of(editor).as_string();
+                        return save_failed(e, editor, &path, AfterSave::Quit(dirty));
                     }
                 }
                 Err(e) => {
-                    return Action::as_echo(&e);
+                    return Action::as_error(&e);
                 }
             }
         }
@@ -155,27 +214,37 @@ impl Quit {
 
 impl Inquirer for Quit {
     fn prompt(&self) -> String {
-        let source = source_of(&self.dirty[0]);
-        format!("{source}: save?")
+        "save before quitting:".to_string()
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::yes_no_all_completer()
+        user::hint_completer(self.checklist.hint())
     }
 
-    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
-        match value {
-            Some(yes_no) if yes_no == "y" => self.save_first(),
-            Some(yes_no) if yes_no == "a" => self.save_all(),
-            Some(yes_no) if yes_no == "n" => Self::next(&self.dirty),
-            Some(_) => Self::start(env),
-            None => None,
+    fn intercept(&mut self, env: &mut Environment, key: &Key) -> Intercept {
+        if *key == key::CTRL_M {
+            let dirty = self
+                .dirty
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| self.checklist.is_checked(*i))
+                .map(|(_, editor)| editor.clone())
+                .collect::<Vec<_>>();
+            Intercept::Conclude(Self::save_all(env, &dirty))
+        } else if self.checklist.process_key(key) {
+            Intercept::Consume(Some(self.checklist.hint()))
+        } else {
+            Intercept::Consume(None)
         }
     }
+
+    fn respond(&mut self, _: &mut Environment, _: Option<&str>) -> Option<Action> {
+        None
+    }
 }
 
 /// An inquirer spawned from [`Quit`] that orchestrates the saving of an editor whose
-/// corresponding file in storage is newer than its timestamp.
+/// corresponding file in storage was changed since it was last read or written.
 #[derive(Clone)]
 struct QuitOverride {
     /// List of dirty editors, where the first entry is pertinent to this flow.
@@ -195,11 +264,17 @@ impl QuitOverride {
         Box::new(self)
     }
 
-    fn save(&mut self) -> Option<Action> {
+    fn save(&mut self, env: &Environment) -> Option<Action> {
         if let Err(e) = save_editor(&self.dirty[0]) {
-            Action::as_echo(&e)
+            let path = path_of(&self.dirty[0]).as_string();
+            save_failed(
+                e,
+                &self.dirty[0],
+                &path,
+                AfterSave::Quit(self.dirty.clone()),
+            )
         } else {
-            Quit::next(&self.dirty)
+            Quit::next(env, &self.dirty)
         }
     }
 }
@@ -207,23 +282,91 @@ impl QuitOverride {
 impl Inquirer for QuitOverride {
     fn prompt(&self) -> String {
         let source = source_of(&self.dirty[0]);
-        format!("{source}: file in storage is newer, save anyway?")
+        format!("{source}: file in storage has changed, save anyway?")
     }
 
     fn completer(&self) -> Box<dyn Completer> {
         user::yes_no_completer()
     }
 
-    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
         match value {
-            Some(yes_no) if yes_no == "y" => self.save(),
-            Some(yes_no) if yes_no == "n" => Quit::next(&self.dirty),
+            Some("y") => self.save(env),
+            Some("n") => Quit::next(env, &self.dirty),
             Some(_) => self.again(),
             None => None,
         }
     }
 }
 
+/// An inquirer spawned from [`Quit`] that orchestrates the saving of an ephemeral
+/// editor by soliciting a path, where an empty path discards the editor.
+#[derive(Clone)]
+struct QuitSaveAs {
+    /// List of dirty editors, where the first entry is pertinent to this flow.
+    dirty: Vec<EditorRef>,
+
+    /// Patterns of files excluded from completion.
+    ignore: Vec<String>,
+
+    /// Path provided by the user that conflicts with an existing file, if any.
+    exists: Option<String>,
+}
+
+impl QuitSaveAs {
+    fn question(env: &Environment, dirty: Vec<EditorRef>) -> Option<Action> {
+        let ignore = env.workspace().config().settings.ignore.clone();
+        Action::as_question(Box::new(QuitSaveAs {
+            dirty,
+            ignore,
+            exists: None,
+        }))
+    }
+
+    fn again(&self, exists: &str) -> Option<Action> {
+        let mut this = self.clone();
+        this.exists = Some(exists.to_string());
+        Action::as_question(Box::new(this))
+    }
+}
+
+impl Inquirer for QuitSaveAs {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.dirty[0]);
+        if let Some(path) = &self.exists {
+            format!("{source}: {path} exists, save as:")
+        } else {
+            format!("{source}: save as:")
+        }
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::file_completer(sys::working_dir(), self.ignore.clone())
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(path) if !path.trim().is_empty() => {
+                let path = sys::expand_path(path.trim());
+                if Path::new(&path).exists() {
+                    self.again(&path)
+                } else if let Err(e) = save_editor_as(&self.dirty[0], Some(&path)) {
+                    save_failed(
+                        e,
+                        &self.dirty[0],
+                        &path,
+                        AfterSave::Quit(self.dirty.clone()),
+                    )
+                } else {
+                    Quit::next(env, &self.dirty)
+                }
+            }
+            Some(_) => Quit::next(env, &self.dirty),
+            None => None,
+        }
+    }
+}
+
 /// Operation: `help`
 fn help(env: &mut Environment) -> Option<Action> {
     toggle_help(env, help::HELP_EDITOR_NAME, |config| {
@@ -252,6 +395,20 @@ fn help_bindings(env: &mut Environment) -> Option<Action> {
     })
 }
 
+/// Operation: `audit-bindings`
+fn audit_bindings(env: &mut Environment) -> Option<Action> {
+    toggle_help(env, help::BINDINGS_AUDIT_EDITOR_NAME, |config| {
+        help::bindings_audit_editor(config)
+    })
+}
+
+/// Operation: `export-bindings`
+fn export_bindings(env: &mut Environment) -> Option<Action> {
+    toggle_help(env, help::EXPORT_BINDINGS_EDITOR_NAME, |config| {
+        help::export_bindings_editor(config)
+    })
+}
+
 /// Operation: `help-colors`
 fn help_colors(env: &mut Environment) -> Option<Action> {
     toggle_help(env, help::COLORS_EDITOR_NAME, |config| {
@@ -259,6 +416,192 @@ fn help_colors(env: &mut Environment) -> Option<Action> {
     })
 }
 
+/// Operation: `preview-theme`
+fn preview_theme(env: &mut Environment) -> Option<Action> {
+    let syntax = env.get_active_editor().borrow().syntax();
+    toggle_help(env, help::THEME_EDITOR_NAME, |config| {
+        help::theme_editor(config, &syntax)
+    })
+}
+
+/// Operation: `debug-syntax`
+fn debug_syntax(env: &mut Environment) -> Option<Action> {
+    let config = env.workspace().config().clone();
+    let editor = {
+        let editor = env.get_active_editor().borrow();
+        let buffer = editor.buffer();
+        help::syntax_editor(config, &editor.syntax(), &buffer, editor.pos())
+    };
+    if let Some((view_id, _)) = replace_editor(env, editor, Align::Center) {
+        env.set_active(Focus::To(view_id));
+        None
+    } else {
+        Action::echo_no_window()
+    }
+}
+
+/// Operation: `memory-report`
+fn memory_report(env: &mut Environment) -> Option<Action> {
+    let config = env.workspace().config().clone();
+    let editor = {
+        let editors = env
+            .editor_map()
+            .values()
+            .map(|editor| (label_of(editor), editor.borrow().usage()))
+            .collect::<Vec<_>>();
+        let clipboard = env.get_clipboard().map(|text| text.len()).unwrap_or(0);
+        help::memory_editor(config, &editors, clipboard)
+    };
+    if let Some((view_id, _)) = replace_editor(env, editor, Align::Top) {
+        env.set_active(Focus::To(view_id));
+        None
+    } else {
+        Action::echo_no_window()
+    }
+}
+
+/// Operation: `perf-stats`
+fn perf_stats(env: &mut Environment) -> Option<Action> {
+    let config = env.workspace().config().clone();
+    let editor = help::perf_editor(config, env.perf());
+    if let Some((view_id, _)) = replace_editor(env, editor, Align::Top) {
+        env.set_active(Focus::To(view_id));
+        None
+    } else {
+        Action::echo_no_window()
+    }
+}
+
+/// Operation: `show-audit`
+fn show_audit(env: &mut Environment) -> Option<Action> {
+    match Audit::load() {
+        Ok(Some(text)) => {
+            // Cursor is placed at the end of the buffer so the most recent entries are
+            // visible.
+            let mut buffer = Buffer::new();
+            buffer.insert_str(&text);
+            let config = env.workspace().config().clone();
+            let source = Source::as_ephemeral(AUDIT_EDITOR_NAME);
+            let editor = Editor::readonly(config, source, buffer).to_ref();
+            if let Some((view_id, _)) = replace_editor(env, editor, Align::Bottom) {
+                env.set_active(Focus::To(view_id));
+                None
+            } else {
+                Action::echo_no_window()
+            }
+        }
+        Ok(None) => Action::as_warn(&format!(
+            "{}: audit log not found",
+            sys::pretty_path(Audit::path())
+        )),
+        Err(e) => Action::as_error(&e),
+    }
+}
+
+const AUDIT_EDITOR_NAME: &str = "audit";
+
+/// Operation: `describe-key`
+fn describe_key(_: &mut Environment) -> Option<Action> {
+    DescribeKey::question()
+}
+
+/// An inquirer that waits for a key sequence and describes the editing operation
+/// bound to that sequence, if any.
+struct DescribeKey {
+    /// Keys gathered thus far, which form a prefix of at least one bound sequence.
+    keys: Vec<Key>,
+}
+
+impl DescribeKey {
+    const PROMPT: &str = "describe key:";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(DescribeKey { keys: Vec::new() }))
+    }
+}
+
+impl Inquirer for DescribeKey {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn intercept(&mut self, env: &mut Environment, key: &Key) -> Intercept {
+        match key {
+            Key::ScrollUp(..)
+            | Key::ScrollDown(..)
+            | Key::ScrollLeft(..)
+            | Key::ScrollRight(..)
+            | Key::ButtonPress(..)
+            | Key::ButtonRelease(..)
+            | Key::MiddlePress(..) => Intercept::Consume(None),
+            _ => {
+                self.keys.push(key.clone());
+                // Keys are described in the context of the active editor as though
+                // this question was not posed.
+                let config = env.workspace().config().clone();
+                let context = env.context();
+                let key_seq = key::pretty(&self.keys);
+                if let Some(op) = config.bindings.find_op(&self.keys, &context) {
+                    let text = if let Some(desc) = help::describe_op(op) {
+                        format!("{key_seq}: {op} ({desc})")
+                    } else {
+                        format!("{key_seq}: {op}")
+                    };
+                    Intercept::Conclude(Action::as_echo(&text))
+                } else if config.bindings.is_prefix(&self.keys, &context) {
+                    Intercept::Consume(Some(format!(" {key_seq}")))
+                } else {
+                    let text = format!("{key_seq}: not bound");
+                    Intercept::Conclude(Action::as_warn(&text))
+                }
+            }
+        }
+    }
+
+    fn respond(&mut self, _: &mut Environment, _: Option<&str>) -> Option<Action> {
+        None
+    }
+}
+
+/// Operation: `search-help`
+fn search_help(_: &mut Environment) -> Option<Action> {
+    SearchHelp::question()
+}
+
+/// An inquirer that gathers a term used to search keys, editing operations, and key
+/// bindings.
+struct SearchHelp;
+
+impl SearchHelp {
+    const PROMPT: &str = "search help (case-insensitive):";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(SearchHelp))
+    }
+}
+
+impl Inquirer for SearchHelp {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(term) if !term.is_empty() => {
+                let config = env.workspace().config().clone();
+                let editor = help::search_help_editor(config, term);
+                if let Some((view_id, _)) = replace_editor(env, editor, Align::Top) {
+                    env.set_active(Focus::To(view_id));
+                    None
+                } else {
+                    Action::echo_no_window()
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 fn toggle_help<F>(env: &mut Environment, editor_name: &str, editor_fn: F) -> Option<Action>
 where
     F: Fn(ConfigurationRef) -> EditorRef,
@@ -401,7 +744,7 @@ fn move_down_select(env: &mut Environment) -> Option<Action> {
 fn move_up_page(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
     editor.clear_soft_mark();
-    let rows = editor.rows();
+    let rows = page_rows(env, editor.rows());
     editor.move_up(rows, true);
     editor.render();
     None
@@ -411,7 +754,7 @@ fn move_up_page(env: &mut Environment) -> Option<Action> {
 fn move_up_page_select(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
     editor.set_soft_mark();
-    let rows = editor.rows();
+    let rows = page_rows(env, editor.rows());
     editor.move_up(rows, true);
     editor.render();
     None
@@ -421,7 +764,7 @@ fn move_up_page_select(env: &mut Environment) -> Option<Action> {
 fn move_down_page(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
     editor.clear_soft_mark();
-    let rows = editor.rows();
+    let rows = page_rows(env, editor.rows());
     editor.move_down(rows, true);
     editor.render();
     None
@@ -431,12 +774,39 @@ fn move_down_page(env: &mut Environment) -> Option<Action> {
 fn move_down_page_select(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
     editor.set_soft_mark();
-    let rows = editor.rows();
+    let rows = page_rows(env, editor.rows());
+    editor.move_down(rows, true);
+    editor.render();
+    None
+}
+
+/// Operation: `move-up-half-page`
+fn move_up_half_page(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    editor.clear_soft_mark();
+    let rows = cmp::max(editor.rows() / 2, 1);
+    editor.move_up(rows, true);
+    editor.render();
+    None
+}
+
+/// Operation: `move-down-half-page`
+fn move_down_half_page(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    editor.clear_soft_mark();
+    let rows = cmp::max(editor.rows() / 2, 1);
     editor.move_down(rows, true);
     editor.render();
     None
 }
 
+/// Returns the number of rows to move when paging through a window of `rows`, which
+/// accounts for the number of rows of context that should overlap between pages.
+fn page_rows(env: &Environment, rows: u32) -> u32 {
+    let overlap = env.workspace().config().settings.page_overlap;
+    cmp::max(rows.saturating_sub(overlap), 1)
+}
+
 /// Operation: `move-start`
 fn move_start(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
@@ -511,56 +881,89 @@ fn move_bottom_select(env: &mut Environment) -> Option<Action> {
 
 /// Operation: `scroll-up`
 fn scroll_up(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
+    {
+        let mut editor = env.get_active_editor().borrow_mut();
 
-    // Capture current buffer position before scrolling in case soft mark needs to
-    // be cleared.
-    let prior_pos = editor.pos();
-    editor.scroll_up(1);
+        // Capture current buffer position before scrolling in case soft mark needs
+        // to be cleared.
+        let prior_pos = editor.pos();
+        editor.scroll_up(1);
 
-    // Clear soft mark if buffer position moved as a result of scrolling.
-    if editor.pos() != prior_pos {
-        editor.clear_soft_mark();
+        // Clear soft mark if buffer position moved as a result of scrolling.
+        if editor.pos() != prior_pos {
+            editor.clear_soft_mark();
+        }
+        editor.render();
     }
-    editor.render();
+    scroll_locked(env, env.get_active_view_id(), |editor| editor.scroll_up(1));
     None
 }
 
 /// Operation: `scroll-up-select`
 fn scroll_up_select(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
-    editor.set_soft_mark();
-    editor.scroll_up(1);
-    editor.render();
+    {
+        let mut editor = env.get_active_editor().borrow_mut();
+        editor.set_soft_mark();
+        editor.scroll_up(1);
+        editor.render();
+    }
+    scroll_locked(env, env.get_active_view_id(), |editor| editor.scroll_up(1));
     None
 }
 
 /// Operation: `scroll-down`
 fn scroll_down(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
+    {
+        let mut editor = env.get_active_editor().borrow_mut();
 
-    // Capture current buffer position before scrolling in case soft mark needs to
-    // be cleared.
-    let prior_pos = editor.pos();
-    editor.scroll_down(1);
+        // Capture current buffer position before scrolling in case soft mark needs
+        // to be cleared.
+        let prior_pos = editor.pos();
+        editor.scroll_down(1);
 
-    // Clear soft mark if buffer position moved as a result of scrolling.
-    if editor.pos() != prior_pos {
-        editor.clear_soft_mark();
+        // Clear soft mark if buffer position moved as a result of scrolling.
+        if editor.pos() != prior_pos {
+            editor.clear_soft_mark();
+        }
+        editor.render();
     }
-    editor.render();
+    scroll_locked(env, env.get_active_view_id(), |editor| {
+        editor.scroll_down(1)
+    });
     None
 }
 
 /// Operation: `scroll-down-select`
 fn scroll_down_select(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
-    editor.set_soft_mark();
-    editor.scroll_down(1);
-    editor.render();
+    {
+        let mut editor = env.get_active_editor().borrow_mut();
+        editor.set_soft_mark();
+        editor.scroll_down(1);
+        editor.render();
+    }
+    scroll_locked(env, env.get_active_view_id(), |editor| {
+        editor.scroll_down(1)
+    });
     None
 }
 
+/// Scrolls the editor of the view whose scrolling is locked together with `view_id`,
+/// if any, using `scroll_fn`.
+fn scroll_locked<F>(env: &Environment, view_id: u32, scroll_fn: F)
+where
+    F: Fn(&mut Editor),
+{
+    if let Some(other_id) = env.get_scroll_lock_for(view_id) {
+        let mut editor = env.get_view_editor(other_id).borrow_mut();
+        let prior_pos = editor.pos();
+        scroll_fn(&mut editor);
+        if editor.pos() != prior_pos {
+            editor.clear_soft_mark();
+        }
+        editor.render();
+    }
+}
+
 /// Operation: `scroll-center`
 fn scroll_center(env: &mut Environment) -> Option<Action> {
     // Rotate through alignment based on current cursor position using following
@@ -596,15 +999,42 @@ fn set_mark(env: &mut Environment) -> Option<Action> {
     None
 }
 
+/// Operation: `set-mark-column`
+fn set_mark_column(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.clear_mark().is_some() {
+        editor.render();
+    } else {
+        editor.set_block_mark();
+    }
+    None
+}
+
+/// Operation: `match-bracket`
+fn match_bracket(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(pos) = editor.find_bracket(editor.pos(), usize::MAX) {
+        editor.clear_soft_mark();
+        editor.move_to(pos, Align::Auto);
+        editor.render();
+        None
+    } else {
+        Action::as_echo("no matching bracket")
+    }
+}
+
 /// Operation: `goto-line`
 fn goto_line(env: &mut Environment) -> Option<Action> {
     GotoLine::question(env.get_active_editor().clone())
 }
 
-/// An inquirer that orchestrates going to a specific line in an editor.
+/// An inquirer that orchestrates going to a specific line in an editor, optionally
+/// followed by a column as `line:col`, or selecting a range of lines given as
+/// `first,last`.
 struct GotoLine {
     editor: EditorRef,
     capture: Capture,
+    selected: bool,
 }
 
 impl GotoLine {
@@ -612,7 +1042,14 @@ impl GotoLine {
 
     fn question(editor: EditorRef) -> Option<Action> {
         let capture = editor.borrow().capture();
-        Action::as_question(GotoLine { editor, capture }.to_box())
+        Action::as_question(
+            GotoLine {
+                editor,
+                capture,
+                selected: false,
+            }
+            .to_box(),
+        )
     }
 
     fn to_box(self) -> Box<dyn Inquirer> {
@@ -623,6 +1060,47 @@ impl GotoLine {
         let mut editor = self.editor.borrow_mut();
         editor.restore(&self.capture);
         editor.render();
+        self.selected = false;
+    }
+
+    /// Returns the `1`-based line number in `value` as a `0`-based number.
+    fn parse_line(value: &str) -> Option<u32> {
+        value
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .map(|line| line.saturating_sub(1))
+    }
+
+    /// Moves to `line`, and also to `col` on that line if given, first restoring the
+    /// original selection if a range of lines was selected.
+    fn goto(&mut self, line: u32, col: Option<u32>) {
+        if self.selected {
+            self.restore();
+        }
+        let mut editor = self.editor.borrow_mut();
+        editor.move_line(line, Align::Center);
+        if let Some(col) = col {
+            move_column(&mut editor, col);
+        }
+        editor.render();
+    }
+
+    /// Selects the lines from `first` through `last` inclusive, placing the mark at
+    /// the start of `first` and the cursor at the start of the line following
+    /// `last`, which is how operations on selected lines recognize the range.
+    fn select(&mut self, first: u32, last: u32) {
+        let (first, last) = if first <= last {
+            (first, last)
+        } else {
+            (last, first)
+        };
+        let mut editor = self.editor.borrow_mut();
+        editor.move_line(first, Align::Center);
+        editor.set_hard_mark();
+        editor.move_line(last + 1, Align::Auto);
+        editor.render();
+        self.selected = true;
     }
 }
 
@@ -632,17 +1110,32 @@ impl Inquirer for GotoLine {
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::number_completer()
+        user::line_completer()
     }
 
     fn react(&mut self, _: &mut Environment, value: &str, _: &Key) -> Option<String> {
         let value = value.trim();
         if value.len() > 0 {
-            if let Ok(line) = value.trim().parse::<u32>() {
-                let line = if line > 0 { line - 1 } else { 0 };
-                let mut editor = self.editor.borrow_mut();
-                editor.move_line(line, Align::Center);
-                editor.render();
+            if let Some((first, last)) = value.split_once(',') {
+                match (Self::parse_line(first), Self::parse_line(last)) {
+                    (Some(first), Some(last)) => {
+                        self.select(first, last);
+                        None
+                    }
+                    (Some(_), None) if last.trim().is_empty() => None,
+                    _ => Some(" (invalid line range)".to_string()),
+                }
+            } else if let Some((line, col)) = value.split_once(':') {
+                match (Self::parse_line(line), Self::parse_line(col)) {
+                    (Some(line), Some(col)) => {
+                        self.goto(line, Some(col));
+                        None
+                    }
+                    (Some(_), None) if col.trim().is_empty() => None,
+                    _ => Some(" (invalid line and column)".to_string()),
+                }
+            } else if let Some(line) = Self::parse_line(value) {
+                self.goto(line, None);
                 None
             } else {
                 Some(" (invalid line number)".to_string())
@@ -661,24 +1154,144 @@ impl Inquirer for GotoLine {
     }
 }
 
-pub fn insert_char(env: &mut Environment, c: char) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
-    if let Some(editor) = editor.modify() {
-        editor.clear_mark();
-        editor.insert_char(c);
-        editor.render();
-        None
-    } else {
-        Action::echo_readonly()
-    }
+/// Operation: `goto-column`
+fn goto_column(env: &mut Environment) -> Option<Action> {
+    GotoColumn::question(env.get_active_editor().clone())
 }
 
-/// Operation: `insert-line`
+/// An inquirer that orchestrates going to a specific column on the current line of
+/// an editor.
+struct GotoColumn {
+    editor: EditorRef,
+    capture: Capture,
+}
+
+impl GotoColumn {
+    const PROMPT: &str = "goto column:";
+
+    fn question(editor: EditorRef) -> Option<Action> {
+        let capture = editor.borrow().capture();
+        Action::as_question(Box::new(GotoColumn { editor, capture }))
+    }
+
+    fn restore(&mut self) {
+        let mut editor = self.editor.borrow_mut();
+        editor.restore(&self.capture);
+        editor.render();
+    }
+}
+
+impl Inquirer for GotoColumn {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::number_completer()
+    }
+
+    fn react(&mut self, _: &mut Environment, value: &str, _: &Key) -> Option<String> {
+        let value = value.trim();
+        if !value.is_empty() {
+            if let Some(col) = GotoLine::parse_line(value) {
+                self.restore();
+                let mut editor = self.editor.borrow_mut();
+                move_column(&mut editor, col);
+                editor.render();
+                None
+            } else {
+                Some(" (invalid column number)".to_string())
+            }
+        } else {
+            self.restore();
+            None
+        }
+    }
+
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if value.is_none() {
+            self.restore();
+        }
+        None
+    }
+}
+
+/// Moves the cursor of `editor` to the `0`-based `col` on the current line, which is
+/// measured in characters and constrained by the end of the line, unless virtual
+/// editing is enabled, in which case the cursor moves into the empty space beyond.
+fn move_column(editor: &mut Editor, col: u32) {
+    let line = editor.location().row;
+    editor.move_line_col(line, col, Align::Auto);
+    if editor.get_virtual() {
+        let line_pos = editor.buffer().find_line(line);
+        let reached = (editor.pos() - line_pos) as u32;
+        if reached < col {
+            editor.move_forward((col - reached) as usize);
+        }
+    }
+}
+
+pub fn insert_char(env: &mut Environment, c: char) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        // Typing over a block of columns replaces the text on every line of the
+        // block, leaving a cursor on each line for subsequent typing.
+        if let Some(block) = editor.get_block() {
+            editor.remove_block(&block);
+            editor.add_block_cursors(&block);
+        }
+        editor.clear_mark();
+        editor.insert_char(c);
+        editor.render();
+        None
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Returns the name of the operation and its function mapped to `c` in
+/// [`READONLY_MAPPINGS`] if the active editor is _readonly_, otherwise `None`.
+pub fn readonly_op(env: &Environment, c: char) -> Option<(&'static str, OpFn)> {
+    if env.get_active_editor().borrow().is_readonly() {
+        READONLY_MAPPINGS
+            .iter()
+            .find(|(rc, _)| *rc == c)
+            .and_then(|(_, op)| OP_MAPPINGS.iter().find(|(name, _)| name == op))
+            .copied()
+    } else {
+        None
+    }
+}
+
+/// Operation: `insert-line`
 fn insert_line(env: &mut Environment) -> Option<Action> {
+    // Since occurrences editor is readonly, inserting a line is repurposed to visit
+    // the occurrence under the cursor.
+    if is_occur_editor(env.get_active_editor()) {
+        return goto_occur(env);
+    }
+    if is_search_all_editor(env.get_active_editor()) {
+        return goto_search_all(env);
+    }
+
     let mut editor = env.get_active_editor().borrow_mut();
+    let syntax = editor.syntax();
     if let Some(editor) = editor.modify() {
         editor.clear_mark();
-        editor.insert_char('\n');
+
+        // Auto-indentation applies only to the primary cursor, so it is skipped when
+        // secondary cursors exist.
+        if editor.get_indent() && editor.cursors().is_empty() {
+            let pos = editor.pos();
+            let line_pos = editor.buffer().find_start_line(pos);
+            let prefix = editor.copy(line_pos, pos).iter().collect::<String>();
+            let unit = indent_unit(editor);
+            let indent =
+                indent::next_line(&prefix, &unit, syntax.indent_rules(), syntax.comments());
+            editor.insert_str(&format!("\n{indent}"));
+        } else {
+            editor.insert_char('\n');
+        }
         editor.render();
         None
     } else {
@@ -719,9 +1332,10 @@ fn remove_before(env: &mut Environment) -> Option<Action> {
         }
     };
     if let Some(text) = text {
-        env.set_clipboard(text);
+        set_clipboard(env, text, false, false)
+    } else {
+        None
     }
-    None
 }
 
 /// Operation: `remove-after`
@@ -737,6 +1351,48 @@ fn remove_after(env: &mut Environment) -> Option<Action> {
     }
 }
 
+/// Operation: `remove-backward-word`
+fn remove_backward_word(env: &mut Environment) -> Option<Action> {
+    remove_word(env, false)
+}
+
+/// Operation: `remove-forward-word`
+fn remove_forward_word(env: &mut Environment) -> Option<Action> {
+    remove_word(env, true)
+}
+
+/// Removes the word either before or after the cursor depending on `forward`, though
+/// if a selection is active, the selected text is removed and copied to the clipboard
+/// instead.
+fn remove_word(env: &mut Environment, forward: bool) -> Option<Action> {
+    let text = {
+        let mut editor = env.get_active_editor().borrow_mut();
+        if let Some(editor) = editor.modify() {
+            let maybe_mark = editor.clear_mark();
+            let text = if let Some(mark) = maybe_mark {
+                let text = editor.remove_mark(mark);
+                Some(text)
+            } else {
+                if forward {
+                    editor.remove_forward_word();
+                } else {
+                    editor.remove_backward_word();
+                }
+                None
+            };
+            editor.render();
+            text
+        } else {
+            return Action::echo_readonly();
+        }
+    };
+    if let Some(text) = text {
+        set_clipboard(env, text, false, false)
+    } else {
+        None
+    }
+}
+
 /// Operation: `remove-start`
 fn remove_start(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
@@ -763,51 +1419,32 @@ fn remove_end(env: &mut Environment) -> Option<Action> {
     }
 }
 
-/// Operation: `undo`
-fn undo(env: &mut Environment) -> Option<Action> {
+/// Operation: `duplicate-line`
+fn duplicate_line(env: &mut Environment) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
-    if editor.undo() {
+    if let Some(editor) = editor.modify() {
+        editor.duplicate_lines();
         editor.render();
         None
     } else {
-        Action::as_echo("nothing to undo")
+        Action::echo_readonly()
     }
 }
 
-/// Operation: `redo`
-fn redo(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
-    if editor.redo() {
-        editor.render();
-        None
-    } else {
-        Action::as_echo("nothing to redo")
-    }
+/// Operation: `move-line-up`
+fn move_line_up(env: &mut Environment) -> Option<Action> {
+    move_line(env, true)
 }
 
-/// Operation: `copy`
-fn copy(env: &mut Environment) -> Option<Action> {
-    let text = {
-        let mut editor = env.get_active_editor().borrow_mut();
-        let maybe_mark = editor.clear_mark();
-        if let Some(mark) = maybe_mark {
-            editor.copy_mark(mark)
-        } else {
-            editor.copy_line()
-        }
-    };
-    env.get_active_editor().borrow_mut().render();
-    env.set_clipboard(text);
-    None
+/// Operation: `move-line-down`
+fn move_line_down(env: &mut Environment) -> Option<Action> {
+    move_line(env, false)
 }
 
-/// Operation: `paste`
-fn paste(env: &mut Environment) -> Option<Action> {
+fn move_line(env: &mut Environment, up: bool) -> Option<Action> {
     let mut editor = env.get_active_editor().borrow_mut();
     if let Some(editor) = editor.modify() {
-        let maybe_text = env.get_clipboard();
-        if let Some(text) = maybe_text {
-            editor.insert(text);
+        if editor.move_lines(up) {
             editor.render();
         }
         None
@@ -816,842 +1453,3984 @@ fn paste(env: &mut Environment) -> Option<Action> {
     }
 }
 
-/// Operation: `cut`
-fn cut(env: &mut Environment) -> Option<Action> {
-    let text = {
-        let mut editor = env.get_active_editor().borrow_mut();
-        if let Some(editor) = editor.modify() {
-            let text = {
-                let maybe_mark = editor.clear_mark();
-                if let Some(mark) = maybe_mark {
-                    editor.remove_mark(mark)
-                } else {
-                    editor.remove_line()
-                }
-            };
+/// Operation: `join-lines`
+fn join_lines(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        if editor.join_lines() {
             editor.render();
-            Some(text)
-        } else {
-            None
         }
-    };
-    if let Some(text) = text {
-        env.set_clipboard(text);
         None
     } else {
         Action::echo_readonly()
     }
 }
 
-/// Operation: `search`
-fn search(env: &mut Environment) -> Option<Action> {
-    Search::question(env.get_active_editor().clone(), false, false)
+/// Operation: `undo`
+fn undo(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.undo() {
+        editor.render();
+        None
+    } else {
+        Action::as_warn("nothing to undo")
+    }
 }
 
-/// Operation: `search-case`
-fn search_case(env: &mut Environment) -> Option<Action> {
-    Search::question(env.get_active_editor().clone(), false, true)
+/// Operation: `redo`
+fn redo(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.redo() {
+        editor.render();
+        None
+    } else {
+        Action::as_warn("nothing to redo")
+    }
 }
 
-/// Operation: `search-regex`
-fn search_regex(env: &mut Environment) -> Option<Action> {
-    Search::question(env.get_active_editor().clone(), true, false)
+/// Operation: `hard-wrap-region`
+fn hard_wrap_region(env: &mut Environment) -> Option<Action> {
+    let cols = env.get_active_editor().borrow().get_fill() as usize;
+    reflow_region(env, |text| wrap::hard_wrap(text, cols))
 }
 
-/// Operation: `search-regex-case`
-fn search_regex_case(env: &mut Environment) -> Option<Action> {
-    Search::question(env.get_active_editor().clone(), true, true)
+/// Operation: `unwrap-region`
+fn unwrap_region(env: &mut Environment) -> Option<Action> {
+    reflow_region(env, wrap::unwrap)
 }
 
-/// Operation: `search-next`
-fn search_next(env: &mut Environment) -> Option<Action> {
-    let editor = env.get_active_editor().clone();
-    let last_match = editor.borrow_mut().take_last_match();
-    if let Some((pos, pattern)) = last_match {
-        // If position of last match is also current buffer position, then advance
-        // to next position before resuming search.
-        let mut editor = editor.borrow_mut();
-        let cur_pos = editor.pos();
-        let pos = if pos == cur_pos { cur_pos + 1 } else { cur_pos };
+/// Operation: `reflow-paragraph`
+fn reflow_paragraph(env: &mut Environment) -> Option<Action> {
+    let (cols, syntax) = {
+        let editor = env.get_active_editor().borrow();
+        (editor.get_fill() as usize, editor.syntax())
+    };
+    let comments = syntax.comments();
+    reflow_region_with(
+        env,
+        |buffer, pos, mark| match mark {
+            Some(_) => reflow_range(buffer, pos, mark),
+            None => paragraph_range(buffer, pos, comments),
+        },
+        |text| wrap::reflow(text, cols, comments),
+    )
+}
 
-        // Find next match and highlight if found.
-        let found = pattern.find(&editor.buffer(), pos);
-        if let Some((start_pos, end_pos)) = found {
-            editor.move_to(start_pos, Align::Center);
-            editor.clear_mark();
-            editor.set_soft_mark_at(end_pos);
-            editor.render();
-            editor.set_last_match(start_pos, pattern);
-        } else {
-            // Restore match state that was taken earlier.
-            editor.set_last_match(pos, pattern);
-        }
-        None
-    } else {
-        // Since no prior match exists, act as if new term search is started.
-        Search::question(editor, false, false)
-    }
+/// Operation: `toggle-comment`
+fn toggle_comment(env: &mut Environment) -> Option<Action> {
+    let syntax = env.get_active_editor().borrow().syntax();
+    let Some(prefix) = syntax.comments().last() else {
+        return Action::as_warn(&format!("{}: no comment prefix", syntax.name));
+    };
+    reflow_region_with(
+        env,
+        |buffer, pos, mark| match mark {
+            Some(_) => reflow_range(buffer, pos, mark),
+            None => buffer.find_start_line(pos)..buffer.find_next_line(pos).0,
+        },
+        |text| toggle_comments(text, prefix),
+    )
 }
 
-struct Search {
-    editor: EditorRef,
-    capture: Capture,
-    using_regex: bool,
-    case_strict: bool,
-    buf_cache: Option<String>,
-    last_match: Option<(usize, Box<dyn Pattern>)>,
+/// Operation: `sort-lines`
+fn sort_lines(env: &mut Environment) -> Option<Action> {
+    let locale = locale_of(env);
+    reflow_region(env, |text| locale.sort_lines(text))
 }
 
-impl Search {
-    fn question(editor: EditorRef, using_regex: bool, case_strict: bool) -> Option<Action> {
-        let capture = editor.borrow().capture();
-        let buf_cache = if using_regex {
-            let buf = editor.borrow().buffer().iter().collect::<String>();
-            Some(buf)
-        } else {
-            None
-        };
-        Action::as_question(
-            Search {
-                editor,
-                capture,
-                using_regex,
-                case_strict,
-                buf_cache,
-                last_match: None,
-            }
-            .to_box(),
-        )
-    }
+/// Operation: `upcase`
+fn upcase(env: &mut Environment) -> Option<Action> {
+    let locale = locale_of(env);
+    change_case(env, |text| locale.to_upper(text))
+}
 
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
-    }
+/// Operation: `downcase`
+fn downcase(env: &mut Environment) -> Option<Action> {
+    let locale = locale_of(env);
+    change_case(env, |text| locale.to_lower(text))
+}
 
-    fn restore(&mut self) {
-        let mut editor = self.editor.borrow_mut();
-        editor.restore(&self.capture);
-        editor.render();
-    }
+/// Replaces the text of the selection, or the word at the cursor if no selection is
+/// active, with the result of `case_fn`.
+fn change_case<F>(env: &mut Environment, case_fn: F) -> Option<Action>
+where
+    F: Fn(&str) -> String,
+{
+    reflow_region_with(
+        env,
+        |buffer, pos, mark| match mark {
+            Some(Mark(mark_pos, _)) => cmp::min(pos, mark_pos)..cmp::max(pos, mark_pos),
+            None => word_range(buffer, pos),
+        },
+        case_fn,
+    )
 }
 
-impl Inquirer for Search {
-    fn prompt(&self) -> String {
-        format!(
-            "{}search (case-{}sensitive):",
-            if self.using_regex { "regex " } else { "" },
-            if self.case_strict { "" } else { "in" }
-        )
-    }
+/// Returns the locale used for transforming text, which is named by the `locale`
+/// setting or else by the environment.
+fn locale_of(env: &Environment) -> Locale {
+    Locale::new(env.workspace().config().settings.locale.as_deref())
+}
 
-    fn react(&mut self, _: &mut Environment, value: &str, key: &Key) -> Option<String> {
-        if value.len() > 0 {
-            let (pos, pattern) = match self.last_match.take() {
-                Some((pos, pattern)) if *key == TAB => {
-                    // Find next match using existing pattern when TAB is pressed,
-                    // noting that starting position must be incremented so as not to
-                    // match on same term.
-                    (pos + 1, pattern)
+/// Returns `text` with `prefix` removed from the start of every line if all lines
+/// that are not blank begin with `prefix` after their indentation, otherwise
+/// `text` with `prefix` inserted into every such line at the smallest indentation
+/// among them.
+///
+/// A single space separates an inserted `prefix` from the text that follows, and
+/// likewise, a single space following a removed `prefix` is also removed.
+fn toggle_comments(text: &str, prefix: &str) -> String {
+    let lines = text.split_inclusive('\n').collect::<Vec<_>>();
+    let indent_of = |line: &str| line.len() - line.trim_start().len();
+    let is_blank = |line: &&str| line.trim().is_empty();
+
+    let commented = lines
+        .iter()
+        .filter(|line| !is_blank(line))
+        .all(|line| line.trim_start().starts_with(prefix));
+    if commented {
+        lines
+            .iter()
+            .map(|line| {
+                if is_blank(line) {
+                    line.to_string()
+                } else {
+                    let (indent, rest) = line.split_at(indent_of(line));
+                    let rest = &rest[prefix.len()..];
+                    format!("{indent}{}", rest.strip_prefix(' ').unwrap_or(rest))
                 }
-                _ => {
-                    let pattern = if self.using_regex {
-                        // Compile regular expression, which might fail if malformed
-                        // or too large, the latter of which is unlikely in practice.
-                        let regex = RegexBuilder::new(value)
-                            .case_insensitive(!self.case_strict)
-                            .multi_line(true)
-                            .build();
-                        if let Ok(regex) = regex {
-                            search::using_regex(regex)
-                        } else {
-                            return Some(" (no match)".to_string());
-                        }
+            })
+            .collect()
+    } else {
+        let col = lines
+            .iter()
+            .filter(|line| !is_blank(line))
+            .map(|line| indent_of(line))
+            .min()
+            .unwrap_or(0);
+        lines
+            .iter()
+            .map(|line| {
+                if is_blank(line) {
+                    line.to_string()
+                } else {
+                    // Indentation of lines may differ in composition, in which case
+                    // the prefix is inserted after the indentation of this line.
+                    let at = if line.is_char_boundary(col) {
+                        col
                     } else {
-                        search::using_term(value.to_string(), self.case_strict)
+                        indent_of(line)
                     };
-                    (self.capture.pos, pattern)
+                    let (indent, rest) = line.split_at(at);
+                    format!("{indent}{prefix} {rest}")
+                }
+            })
+            .collect()
+    }
+}
+
+/// Operation: `reindent-region`
+fn reindent_region(env: &mut Environment) -> Option<Action> {
+    let (unit, syntax) = {
+        let editor = env.get_active_editor().borrow();
+        (indent_unit(&*editor), editor.syntax())
+    };
+    reflow_region(env, |text| {
+        indent::reindent(text, &unit, syntax.indent_rules(), syntax.comments())
+    })
+}
+
+/// Returns the text representing one level of indentation in `editor`, which depends
+/// on its tab mode.
+fn indent_unit<E: ImmutableEditor + ?Sized>(editor: &E) -> String {
+    if editor.get_tab() {
+        "\t".to_string()
+    } else {
+        " ".repeat(editor.get_tab_size() as usize)
+    }
+}
+
+/// Replaces the text of the region returned by [`reflow_range`] with the result of
+/// applying `reflow_fn`.
+fn reflow_region<F>(env: &mut Environment, reflow_fn: F) -> Option<Action>
+where
+    F: Fn(&str) -> String,
+{
+    reflow_region_with(env, reflow_range, reflow_fn)
+}
+
+/// Replaces the text of the region returned by `range_fn`, given the current buffer
+/// position and mark, with the result of applying `reflow_fn` as a single change.
+fn reflow_region_with<R, F>(env: &mut Environment, range_fn: R, reflow_fn: F) -> Option<Action>
+where
+    R: Fn(&Buffer, usize, Option<Mark>) -> Range<usize>,
+    F: Fn(&str) -> String,
+{
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        let mark = editor.clear_mark();
+        let Range { start, end } = range_fn(&editor.buffer(), editor.pos(), mark);
+        let text = editor.copy(start, end).iter().collect::<String>();
+        let reflowed = reflow_fn(&text);
+        if reflowed != text {
+            editor.move_to(start, Align::Auto);
+            let _ = editor.replace(end, &reflowed.chars().collect::<Vec<_>>());
+            editor.move_to(start, Align::Auto);
+        }
+        editor.render();
+        None
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Returns the range of whole lines spanned by `pos` and `mark` if set, otherwise
+/// the range of the paragraph containing `pos`, where paragraphs are separated by
+/// blank lines.
+fn reflow_range(buffer: &Buffer, pos: usize, mark: Option<Mark>) -> Range<usize> {
+    let is_blank = |start: usize, end: usize| buffer.copy_as_string(start, end).trim().is_empty();
+
+    if let Some(Mark(mark_pos, _)) = mark {
+        let (start, end) = if mark_pos < pos {
+            (mark_pos, pos)
+        } else {
+            (pos, mark_pos)
+        };
+        let start = buffer.find_start_line(start);
+        let end = if end > start && buffer.get_char(end - 1) == Some('\n') {
+            end
+        } else {
+            buffer.find_next_line(end).0
+        };
+        start..end
+    } else {
+        let mut start = buffer.find_start_line(pos);
+        let mut end = buffer.find_next_line(pos).0;
+        if is_blank(start, end) {
+            start..start
+        } else {
+            while start > 0 {
+                let prior = buffer.find_start_line(start - 1);
+                if is_blank(prior, start) {
+                    break;
+                }
+                start = prior;
+            }
+            while end < buffer.size() {
+                let next = buffer.find_next_line(end).0;
+                if is_blank(end, next) {
+                    break;
+                }
+                end = next;
+            }
+            start..end
+        }
+    }
+}
+
+/// Operation: `insert-template-for-type`
+fn insert_template_for_type(env: &mut Environment) -> Option<Action> {
+    let vars = variables_of(env);
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        let source = editor.source().clone();
+        if let Source::File(path, _) = source {
+            match template::load(&path, &vars) {
+                Ok(Some(text)) => {
+                    editor.insert_str(&text);
+                    editor.render();
+                    None
+                }
+                Ok(None) => Action::as_warn(&format!("{path}: no matching template")),
+                Err(e) => Action::as_error(&e),
+            }
+        } else {
+            Action::as_warn("no matching template")
+        }
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Operation: `expand-pair`
+fn expand_pair(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let syntax = editor.syntax();
+    if let Some(editor) = editor.modify() {
+        let pos = editor.pos();
+        let text = {
+            let buffer = editor.buffer();
+            buffer.copy_as_string(buffer.find_start_line(pos), pos)
+        };
+        if let Some(closing) = syntax.closing_of(&text) {
+            editor.clear_mark();
+            editor.insert_str(&closing);
+            editor.move_to(pos, Align::Auto);
+            editor.render();
+            None
+        } else {
+            Action::as_warn("no matching pair")
+        }
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Operation: `copy`
+fn copy(env: &mut Environment) -> Option<Action> {
+    copy_to(env, false)
+}
+
+/// Operation: `copy-append`
+fn copy_append(env: &mut Environment) -> Option<Action> {
+    copy_to(env, true)
+}
+
+/// Copies the selection or line to the clipboard, either replacing or appending to
+/// its contents depending on `append`.
+fn copy_to(env: &mut Environment, append: bool) -> Option<Action> {
+    if let Some(text) = copy_block(env) {
+        return set_clipboard(env, text, true, append);
+    }
+    let text = {
+        let mut editor = env.get_active_editor().borrow_mut();
+        let maybe_mark = editor.clear_mark();
+        if let Some(mark) = maybe_mark {
+            editor.copy_mark(mark)
+        } else {
+            editor.copy_line()
+        }
+    };
+    env.get_active_editor().borrow_mut().render();
+    set_clipboard(env, text, false, append)
+}
+
+/// Sets the clipboard to `text`, or appends `text` if `append` is `true`, where
+/// `block` indicates whether `text` represents a block of columns.
+///
+/// The resulting contents of the clipboard are also sent to the system clipboard
+/// depending on the `clipboard` setting.
+fn set_clipboard(
+    env: &mut Environment,
+    text: Vec<char>,
+    block: bool,
+    append: bool,
+) -> Option<Action> {
+    if append {
+        env.append_clipboard(text, block);
+    } else if block {
+        env.set_block_clipboard(text);
+    } else {
+        env.set_clipboard(text);
+    }
+
+    let mode = env.workspace().config().settings.clipboard;
+    if mode == Clipboard::Internal {
+        return None;
+    }
+    let text = env
+        .get_clipboard()
+        .map(|text| text.iter().collect::<String>())
+        .unwrap_or_default();
+    clip::copy(mode, &text)
+        .err()
+        .and_then(|e| Action::as_error(&e))
+}
+
+/// Replaces the clipboard with the contents of the system clipboard depending on
+/// the `clipboard` setting, but only if the system clipboard was changed by another
+/// application and is not empty.
+fn import_clipboard(env: &mut Environment) -> Result<()> {
+    let mode = env.workspace().config().settings.clipboard;
+    if let Some(text) = clip::paste(mode)? {
+        let changed = env
+            .get_clipboard()
+            .is_none_or(|clipboard| !clipboard.iter().copied().eq(text.chars()));
+        if changed && !text.is_empty() {
+            env.set_clipboard(text.chars().collect());
+        }
+    }
+    Ok(())
+}
+
+/// Operation: `paste`
+fn paste(env: &mut Environment) -> Option<Action> {
+    if let Err(e) = import_clipboard(env) {
+        return Action::as_error(&e);
+    }
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        let maybe_text = env.get_clipboard();
+        if let Some(text) = maybe_text {
+            if env.is_clipboard_block() {
+                editor.insert_block(text);
+            } else {
+                editor.insert(text);
+            }
+            editor.render();
+        }
+        None
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Operation: `paste-block`
+fn paste_block(env: &mut Environment) -> Option<Action> {
+    if let Err(e) = import_clipboard(env) {
+        return Action::as_error(&e);
+    }
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        let maybe_text = env.get_clipboard();
+        if let Some(text) = maybe_text {
+            // A trailing newline, such as from copying whole lines, would otherwise
+            // insert an empty row at the bottom of the block.
+            let text = text.strip_suffix(&['\n']).unwrap_or(text);
+            editor.insert_block(text);
+            editor.render();
+        }
+        None
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Operation: `paste-adjust-indent`
+fn paste_adjust_indent(env: &mut Environment) -> Option<Action> {
+    if let Err(e) = import_clipboard(env) {
+        return Action::as_error(&e);
+    }
+    let mut editor = env.get_active_editor().borrow_mut();
+    if let Some(editor) = editor.modify() {
+        let maybe_text = env.get_clipboard();
+        if let Some(text) = maybe_text {
+            let pos = editor.pos();
+            let line_pos = editor.buffer().find_start_line(pos);
+            let prefix = editor.copy(line_pos, pos).iter().collect::<String>();
+            let text = text.iter().collect::<String>();
+            editor.insert_str(&indent::adjust(&text, &prefix));
+            editor.render();
+        }
+        None
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Copies the block of columns selected in the active editor, if applicable,
+/// clearing the mark.
+fn copy_block(env: &mut Environment) -> Option<Vec<char>> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    editor.get_block().map(|block| {
+        editor.clear_mark();
+        editor.render();
+        editor.copy_block(&block)
+    })
+}
+
+/// Operation: `cut`
+fn cut(env: &mut Environment) -> Option<Action> {
+    cut_to(env, false)
+}
+
+/// Operation: `cut-append`
+fn cut_append(env: &mut Environment) -> Option<Action> {
+    cut_to(env, true)
+}
+
+/// Removes the selection or line and copies it to the clipboard, either replacing or
+/// appending to its contents depending on `append`.
+fn cut_to(env: &mut Environment, append: bool) -> Option<Action> {
+    let block = {
+        let mut editor = env.get_active_editor().borrow_mut();
+        if let Some(editor) = editor.modify() {
+            editor.get_block().map(|block| {
+                editor.clear_mark();
+                let text = editor.remove_block(&block);
+                editor.render();
+                text
+            })
+        } else {
+            return Action::echo_readonly();
+        }
+    };
+    if let Some(text) = block {
+        return set_clipboard(env, text, true, append);
+    }
+
+    let text = {
+        let mut editor = env.get_active_editor().borrow_mut();
+        if let Some(editor) = editor.modify() {
+            let text = {
+                let maybe_mark = editor.clear_mark();
+                if let Some(mark) = maybe_mark {
+                    editor.remove_mark(mark)
+                } else {
+                    editor.remove_line()
                 }
             };
+            editor.render();
+            Some(text)
+        } else {
+            None
+        }
+    };
+    if let Some(text) = text {
+        set_clipboard(env, text, false, append)
+    } else {
+        Action::echo_readonly()
+    }
+}
+
+/// Operation: `add-cursor-up`
+fn add_cursor_up(env: &mut Environment) -> Option<Action> {
+    add_cursor_line(env, false)
+}
+
+/// Operation: `add-cursor-down`
+fn add_cursor_down(env: &mut Environment) -> Option<Action> {
+    add_cursor_line(env, true)
+}
+
+/// Leaves a secondary cursor at the current buffer position and moves the cursor to
+/// the previous or next line depending on `down`.
+fn add_cursor_line(env: &mut Environment, down: bool) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.is_readonly() {
+        return Action::echo_readonly();
+    }
+    editor.clear_mark();
+    let pos = editor.pos();
+    if down {
+        editor.move_down(1, false);
+    } else {
+        editor.move_up(1, false);
+    }
+    if editor.pos() != pos {
+        editor.add_cursor(pos);
+    }
+    editor.render();
+    None
+}
+
+/// Operation: `add-cursor-at-next-match`
+fn add_cursor_at_next_match(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.is_readonly() {
+        return Action::echo_readonly();
+    }
+
+    // Text matched is the selection if set, otherwise the word at the cursor. A
+    // secondary cursor is left at the current position and the cursor moves to the
+    // same offset within the next match, which is also selected if the original text
+    // was selected, so successive uses visit successive matches.
+    let cur_pos = editor.pos();
+    let mark = editor.clear_mark();
+    let range = match mark {
+        Some(Mark(mark_pos, _)) => cmp::min(mark_pos, cur_pos)..cmp::max(mark_pos, cur_pos),
+        None => word_range(&editor.buffer(), cur_pos),
+    };
+    if range.is_empty() {
+        return Action::as_warn("no selection or word at cursor");
+    }
+
+    let term = editor.buffer().copy_as_string(range.start, range.end);
+    let found = search::using_term(term, true).find(&editor.buffer(), range.end);
+    match found {
+        Some((start_pos, _)) if start_pos != range.start => {
+            editor.move_to(start_pos + (cur_pos - range.start), Align::Center);
+            editor.add_cursor(cur_pos);
+            if let Some(Mark(mark_pos, _)) = mark {
+                editor.set_soft_mark_at(start_pos + (mark_pos - range.start));
+            }
+            editor.render();
+            let count = editor.cursors().len() + 1;
+            Action::as_echo(&format!("{count} cursors"))
+        }
+        _ => {
+            // Restore original mark, if any, since nothing has changed.
+            match mark {
+                Some(Mark(mark_pos, true)) => {
+                    editor.set_soft_mark_at(mark_pos);
+                }
+                Some(Mark(mark_pos, false)) => {
+                    editor.move_to(mark_pos, Align::Auto);
+                    editor.set_hard_mark();
+                    editor.move_to(cur_pos, Align::Auto);
+                }
+                None => (),
+            }
+            Action::as_warn("no other match")
+        }
+    }
+}
+
+/// Operation: `clear-cursors`
+fn clear_cursors(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    if editor.clear_cursors() > 0 {
+        editor.render();
+    }
+    None
+}
+
+/// Operation: `sequence-insert`
+fn sequence_insert(env: &mut Environment) -> Option<Action> {
+    if env.get_active_editor().borrow().is_readonly() {
+        Action::echo_readonly()
+    } else {
+        SequenceInsert::question()
+    }
+}
+
+/// An inquirer that inserts an incrementing number at each cursor, or at the start of
+/// each line in the selection if set.
+struct SequenceInsert;
+
+impl SequenceInsert {
+    const PROMPT: &str = "sequence (start [step [width]]):";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(SequenceInsert))
+    }
+
+    /// Parses `value` as whitespace-separated _start_, _step_ and _width_, where
+    /// _step_ defaults to `1` and _width_ defaults to `0`, returning `None` if any
+    /// value is malformed.
+    fn parse(value: &str) -> Option<(i64, i64, usize)> {
+        let mut values = value.split_whitespace();
+        let start = values.next()?.parse::<i64>().ok()?;
+        let step = values.next().map_or(Ok(1), |v| v.parse::<i64>()).ok()?;
+        let width = values.next().map_or(Ok(0), |v| v.parse::<usize>()).ok()?;
+        if values.next().is_none() {
+            Some((start, step, width))
+        } else {
+            None
+        }
+    }
+
+    fn insert(env: &mut Environment, start: i64, step: i64, width: usize) -> Option<Action> {
+        let mut editor = env.get_active_editor().borrow_mut();
+        if let Some(editor) = editor.modify() {
+            // A selection places a temporary cursor at the start of each of its lines,
+            // replacing any existing cursors.
+            let mark = editor.clear_mark();
+            if let Some(mark) = mark {
+                let Range { start, end } = reflow_range(&editor.buffer(), editor.pos(), Some(mark));
+                let mut line_pos = end;
+                editor.clear_cursors();
+                editor.move_to(start, Align::Auto);
+                while line_pos > start {
+                    line_pos = editor.buffer().find_start_line(line_pos - 1);
+                    editor.add_cursor(line_pos);
+                }
+            }
+
+            let count = editor.cursors().len() + 1;
+            let texts = (0..count)
+                .map(|i| {
+                    let n = start.saturating_add(step.saturating_mul(i as i64));
+                    format!("{n:0width$}").chars().collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            editor.insert_each(&texts);
+            if mark.is_some() {
+                editor.clear_cursors();
+            }
+            editor.render();
+            Action::as_echo(&format!("{count} numbers inserted"))
+        } else {
+            Action::echo_readonly()
+        }
+    }
+}
+
+impl Inquirer for SequenceInsert {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn react(&mut self, _: &mut Environment, value: &str, _: &Key) -> Option<String> {
+        if !value.trim().is_empty() && Self::parse(value).is_none() {
+            Some(" (invalid sequence)".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value.map(|value| value.trim()) {
+            Some(value) if !value.is_empty() => {
+                if let Some((start, step, width)) = Self::parse(value) {
+                    Self::insert(env, start, step, width)
+                } else {
+                    Action::as_warn(&format!("{value}: invalid sequence"))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the range of whole lines forming the paragraph containing `pos`, which are
+/// consecutive lines sharing the same indentation and comment prefix, or an empty
+/// range if the line containing `pos` has no text beyond its prefix.
+fn paragraph_range(buffer: &Buffer, pos: usize, comments: &[String]) -> Range<usize> {
+    let key_of = |start: usize, end: usize| {
+        let line = buffer.copy_as_string(start, end);
+        wrap::paragraph_key(&line, comments).map(|key| key.to_string())
+    };
+
+    let mut start = buffer.find_start_line(pos);
+    let mut end = buffer.find_next_line(pos).0;
+    let key = key_of(start, end);
+    if key.is_none() {
+        start..start
+    } else {
+        while start > 0 {
+            let prior = buffer.find_start_line(start - 1);
+            if key_of(prior, start) != key {
+                break;
+            }
+            start = prior;
+        }
+        while end < buffer.size() {
+            let next = buffer.find_next_line(end).0;
+            if key_of(end, next) != key {
+                break;
+            }
+            end = next;
+        }
+        start..end
+    }
+}
+
+/// Returns the range of the word containing or immediately preceding `pos`, where
+/// words are formed of alphanumeric characters and underscores.
+fn word_range(buffer: &Buffer, pos: usize) -> Range<usize> {
+    let is_word = |pos: usize| {
+        buffer
+            .get_char(pos)
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+    };
+    let mut start = pos;
+    while start > 0 && is_word(start - 1) {
+        start -= 1;
+    }
+    let mut end = pos;
+    while is_word(end) {
+        end += 1;
+    }
+    start..end
+}
+
+/// Operation: `search`
+fn search(env: &mut Environment) -> Option<Action> {
+    Search::question(env, false, false)
+}
+
+/// Operation: `search-case`
+fn search_case(env: &mut Environment) -> Option<Action> {
+    Search::question(env, false, true)
+}
+
+/// Operation: `search-regex`
+fn search_regex(env: &mut Environment) -> Option<Action> {
+    Search::question(env, true, false)
+}
+
+/// Operation: `search-regex-case`
+fn search_regex_case(env: &mut Environment) -> Option<Action> {
+    Search::question(env, true, true)
+}
+
+/// Operation: `search-next`
+fn search_next(env: &mut Environment) -> Option<Action> {
+    search_again(env, true)
+}
+
+/// Operation: `search-prev`
+fn search_prev(env: &mut Environment) -> Option<Action> {
+    search_again(env, false)
+}
+
+/// Continues the last search in the active window either forward or backward from
+/// the cursor depending on `forward`, otherwise a new term search is started if no
+/// prior search exists.
+fn search_again(env: &mut Environment, forward: bool) -> Option<Action> {
+    if let Some((pos, pattern)) = env.take_last_match() {
+        let (pos, found) = {
+            let mut editor = env.get_active_editor().borrow_mut();
+            let cur_pos = editor.pos();
+            let found = if forward {
+                // If position of last match is also current buffer position, then
+                // advance to next position before resuming search.
+                let pos = if pos == cur_pos { cur_pos + 1 } else { cur_pos };
+                pattern.find(&editor.buffer(), pos)
+            } else {
+                pattern.rfind(&editor.buffer(), cur_pos)
+            };
+
+            // Highlight match if found.
+            if let Some((start_pos, end_pos)) = found {
+                editor.move_to(start_pos, Align::Center);
+                editor.clear_mark();
+                editor.set_soft_mark_at(end_pos);
+                editor.render();
+                (start_pos, true)
+            } else {
+                (pos, false)
+            }
+        };
+
+        // Indicate direction of search since it may not be obvious, particularly when
+        // search wraps around buffer.
+        let text = format!(
+            "search {}: {}{}",
+            if forward { "forward" } else { "backward" },
+            pattern.pattern(),
+            if found { "" } else { " (no match)" }
+        );
+
+        // Retain match state for subsequent searches in active window.
+        env.set_last_match(pos, pattern);
+        Action::as_echo(&text)
+    } else {
+        // Since no prior match exists, act as if new term search is started.
+        Search::question(env, false, false)
+    }
+}
+
+struct Search {
+    view_id: u32,
+    editor: EditorRef,
+    capture: Capture,
+    using_regex: bool,
+    case_strict: bool,
+    buf_cache: Option<String>,
+    last_match: Option<(usize, Box<dyn Pattern>)>,
+}
+
+impl Search {
+    fn question(env: &Environment, using_regex: bool, case_strict: bool) -> Option<Action> {
+        let view_id = env.get_active_view_id();
+        let editor = env.get_active_editor().clone();
+        let capture = editor.borrow().capture();
+        let buf_cache = if using_regex {
+            let buf = editor.borrow().buffer().iter().collect::<String>();
+            Some(buf)
+        } else {
+            None
+        };
+        Action::as_question(
+            Search {
+                view_id,
+                editor,
+                capture,
+                using_regex,
+                case_strict,
+                buf_cache,
+                last_match: None,
+            }
+            .to_box(),
+        )
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+
+    fn restore(&mut self) {
+        let mut editor = self.editor.borrow_mut();
+        editor.restore(&self.capture);
+        editor.clear_annotations(Lane::Highlight);
+        editor.render();
+    }
+
+    /// Highlights all matches of `pattern` using `color`, though only up to
+    /// `MAX_HIGHLIGHTS` matches.
+    fn highlight(&self, pattern: &dyn Pattern, color: u8) {
+        const MAX_HIGHLIGHTS: usize = 10_000;
+        const PRIORITY: u8 = 0;
+
+        let mut editor = self.editor.borrow_mut();
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        while ranges.len() < MAX_HIGHLIGHTS {
+            let found = match &self.buf_cache {
+                Some(buf) => pattern.find_str(buf, pos),
+                None => pattern.find(&editor.buffer(), pos),
+            };
+            // Search wraps to top of buffer when no further matches are found, which
+            // means all matches have been gathered.
+            match found {
+                Some((start_pos, end_pos)) if start_pos >= pos => {
+                    ranges.push(start_pos..end_pos);
+                    pos = cmp::max(end_pos, start_pos + 1);
+                }
+                _ => break,
+            }
+        }
+        editor.clear_annotations(Lane::Highlight);
+        editor.annotate(Lane::Highlight, ranges, PRIORITY, Style::bg(color));
+    }
+}
+
+impl Inquirer for Search {
+    fn prompt(&self) -> String {
+        format!(
+            "{}search (case-{}sensitive):",
+            if self.using_regex { "regex " } else { "" },
+            if self.case_strict { "" } else { "in" }
+        )
+    }
+
+    fn react(&mut self, env: &mut Environment, value: &str, key: &Key) -> Option<String> {
+        if value.len() > 0 {
+            let (pos, pattern, forward) = match self.last_match.take() {
+                Some((pos, pattern)) if *key == TAB => {
+                    // Find next match using existing pattern when TAB is pressed,
+                    // noting that starting position must be incremented so as not to
+                    // match on same term.
+                    (pos + 1, pattern, true)
+                }
+                Some((pos, pattern)) if *key == Key::ShiftTab => {
+                    // Find previous match using existing pattern when Shift-TAB is
+                    // pressed, which naturally excludes match on same term.
+                    (pos, pattern, false)
+                }
+                _ => {
+                    let pattern = if self.using_regex {
+                        // Compile regular expression, which might fail if malformed
+                        // or too large, the latter of which is unlikely in practice.
+                        let regex = RegexBuilder::new(value)
+                            .case_insensitive(!self.case_strict)
+                            .multi_line(true)
+                            .build();
+                        if let Ok(regex) = regex {
+                            search::using_regex(regex)
+                        } else {
+                            return Some(" (no match)".to_string());
+                        }
+                    } else {
+                        search::using_term(value.to_string(), self.case_strict)
+                    };
+                    (self.capture.pos, pattern, true)
+                }
+            };
+
+            // Find next or previous match and highlight if found.
+            let found = match (&self.buf_cache, forward) {
+                (Some(buf), true) => pattern.find_str(buf, pos),
+                (Some(buf), false) => pattern.rfind_str(buf, pos),
+                (None, true) => pattern.find(&self.editor.borrow().buffer(), pos),
+                (None, false) => pattern.rfind(&self.editor.borrow().buffer(), pos),
+            };
+
+            if let Some((start_pos, end_pos)) = found {
+                self.highlight(
+                    pattern.as_ref(),
+                    env.workspace().config().theme.highlight_bg,
+                );
+                let mut editor = self.editor.borrow_mut();
+                editor.move_to(start_pos, Align::Center);
+                editor.clear_mark();
+                editor.set_soft_mark_at(end_pos);
+                editor.render();
+                self.last_match = Some((start_pos, pattern));
+                if forward {
+                    None
+                } else {
+                    Some(" (backward)".to_string())
+                }
+            } else {
+                let mut editor = self.editor.borrow_mut();
+                editor.clear_annotations(Lane::Highlight);
+                editor.render();
+                Some(" (no match)".to_string())
+            }
+        } else {
+            self.restore();
+            self.last_match = None;
+            None
+        }
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(value) if value.len() > 0 => {
+                {
+                    let mut editor = self.editor.borrow_mut();
+                    editor.clear_annotations(Lane::Highlight);
+                    editor.render();
+                }
+                if let Some((pos, pattern)) = self.last_match.take() {
+                    env.set_last_match_for(self.view_id, pos, pattern);
+                }
+            }
+            _ => self.restore(),
+        }
+        None
+    }
+}
+
+/// Operation: `replace`
+fn replace(env: &mut Environment) -> Option<Action> {
+    Replace::question(env, false, false)
+}
+
+/// Operation: `replace-regex`
+fn replace_regex(env: &mut Environment) -> Option<Action> {
+    Replace::question(env, true, false)
+}
+
+/// Operation: `replace-all`
+fn replace_all(env: &mut Environment) -> Option<Action> {
+    Replace::question(env, false, true)
+}
+
+/// An inquirer that solicits the term or regular expression whose matches are
+/// replaced in the active editor.
+///
+/// Consistent with `search` and `search-regex`, matching is case-insensitive.
+struct Replace {
+    editor: EditorRef,
+    using_regex: bool,
+    all: bool,
+}
+
+impl Replace {
+    fn question(env: &Environment, using_regex: bool, all: bool) -> Option<Action> {
+        let editor = env.get_active_editor().clone();
+        if editor.borrow().is_readonly() {
+            Action::echo_readonly()
+        } else {
+            Action::as_question(Box::new(Replace {
+                editor,
+                using_regex,
+                all,
+            }))
+        }
+    }
+}
+
+impl Inquirer for Replace {
+    fn prompt(&self) -> String {
+        format!(
+            "{}replace{}:",
+            if self.using_regex { "regex " } else { "" },
+            if self.all { " all" } else { "" }
+        )
+    }
+
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(value) if !value.is_empty() => {
+                let (pattern, regex) = if self.using_regex {
+                    let regex = RegexBuilder::new(value)
+                        .case_insensitive(true)
+                        .multi_line(true)
+                        .build();
+                    match regex {
+                        Ok(regex) => (search::using_regex(regex.clone()), Some(regex)),
+                        Err(_) => {
+                            return Action::as_warn(&format!(
+                                "{value}: invalid regular expression"
+                            ));
+                        }
+                    }
+                } else {
+                    (search::using_term(value.to_string(), false), None)
+                };
+                ReplaceWith::question(self.editor.clone(), pattern, regex, self.all)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An inquirer spawned from [`Replace`] that solicits the replacement text, which
+/// may be empty.
+///
+/// When replacing matches of a regular expression, references to capture groups in
+/// the replacement text, such as `$1` or `${name}`, are expanded.
+struct ReplaceWith {
+    editor: EditorRef,
+    pattern: Option<Box<dyn Pattern>>,
+    regex: Option<Regex>,
+    all: bool,
+}
+
+impl ReplaceWith {
+    fn question(
+        editor: EditorRef,
+        pattern: Box<dyn Pattern>,
+        regex: Option<Regex>,
+        all: bool,
+    ) -> Option<Action> {
+        Action::as_question(Box::new(ReplaceWith {
+            editor,
+            pattern: Some(pattern),
+            regex,
+            all,
+        }))
+    }
+}
+
+impl Inquirer for ReplaceWith {
+    fn prompt(&self) -> String {
+        let pattern = self.pattern.as_ref().map(|p| p.pattern()).unwrap_or("");
+        format!("replace {pattern} with:")
+    }
+
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match (value, self.pattern.take()) {
+            (Some(value), Some(pattern)) => {
+                let replace = ReplaceMatch {
+                    editor: self.editor.clone(),
+                    pattern: Rc::from(pattern),
+                    regex: self.regex.take(),
+                    with: value.to_string(),
+                    pos: 0,
+                    found: 0..0,
+                    count: 0,
+                };
+                if self.all {
+                    replace.replace_all()
+                } else {
+                    let pos = self.editor.borrow().pos();
+                    replace.start(pos)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An inquirer spawned from [`ReplaceWith`] that visits matches from the cursor to
+/// the end of the buffer, asking whether each should be replaced, where `a` replaces
+/// the current and all remaining matches without further confirmation.
+///
+/// Each replacement is a removal followed by an insertion in the editor, both of
+/// which are recorded in the _undo_ stack.
+#[derive(Clone)]
+struct ReplaceMatch {
+    editor: EditorRef,
+    pattern: Rc<dyn Pattern>,
+    regex: Option<Regex>,
+    with: String,
+
+    /// Position from which the next match is sought, noting that matches starting
+    /// before this position are never visited.
+    pos: usize,
+
+    /// Range of the current match.
+    found: Range<usize>,
+
+    /// Number of replacements made so far.
+    count: usize,
+}
+
+impl ReplaceMatch {
+    /// Moves to the first match at or after `pos` and asks whether it should be
+    /// replaced, otherwise the flow concludes if no such match exists.
+    fn start(mut self, pos: usize) -> Option<Action> {
+        self.pos = pos;
+        let found = {
+            let editor = self.editor.borrow();
+            let found = self.pattern.find(&editor.buffer(), pos);
+            found
+        };
+        match found {
+            // Search wraps to top of buffer when no further matches are found, which
+            // concludes the flow.
+            Some((start_pos, end_pos)) if start_pos >= pos => {
+                {
+                    let mut editor = self.editor.borrow_mut();
+                    editor.move_to(start_pos, Align::Center);
+                    editor.clear_mark();
+                    editor.set_soft_mark_at(end_pos);
+                    editor.render();
+                }
+                self.found = start_pos..end_pos;
+                Action::as_question(Box::new(self))
+            }
+            _ => self.finish(),
+        }
+    }
+
+    /// Replaces the current match, returning the position following the replacement
+    /// text from which the next match is sought.
+    fn replace(&mut self) -> usize {
+        let Range { start, end } = self.found.clone();
+        let mut editor = self.editor.borrow_mut();
+        let text = editor.buffer().copy_as_string(start, end);
+        let with = self.expand(&text);
+        if let Some(editor) = editor.modify() {
+            editor.clear_mark();
+            editor.move_to(start, Align::Auto);
+            let _ = editor.remove(end);
+            editor.insert_str(&with);
+        }
+        self.count += 1;
+
+        // An empty match must advance at least one position to avoid matching the
+        // same position again.
+        let pos = start + with.chars().count();
+        if start == end {
+            pos + 1
+        } else {
+            pos
+        }
+    }
+
+    /// Replaces all matches in the buffer without asking, starting from the bottom so
+    /// that positions of prior matches remain valid.
+    fn replace_all(mut self) -> Option<Action> {
+        let matches = {
+            let editor = self.editor.borrow();
+            let buf = editor.buffer().iter().collect::<String>();
+            let mut matches = Vec::new();
+            let mut pos = 0;
+            while let Some((start_pos, end_pos)) = self.pattern.find_str(&buf, pos) {
+                if start_pos < pos {
+                    break;
+                }
+                matches.push(start_pos..end_pos);
+                pos = cmp::max(end_pos, start_pos + 1);
+            }
+            matches
+        };
+        for found in matches.into_iter().rev() {
+            self.found = found;
+            self.replace();
+        }
+        self.finish()
+    }
+
+    /// Replaces the current match and all remaining matches without asking.
+    fn replace_rest(mut self) -> Option<Action> {
+        loop {
+            let pos = self.replace();
+            let found = {
+                let editor = self.editor.borrow();
+                let found = self.pattern.find(&editor.buffer(), pos);
+                found
+            };
+            match found {
+                Some((start_pos, end_pos)) if start_pos >= pos => {
+                    self.found = start_pos..end_pos;
+                }
+                _ => break self.finish(),
+            }
+        }
+    }
+
+    /// Returns the replacement text for the matching `text`, expanding references to
+    /// capture groups if matching a regular expression.
+    fn expand(&self, text: &str) -> String {
+        match self.regex.as_ref().and_then(|regex| regex.captures(text)) {
+            Some(caps) => {
+                let mut with = String::new();
+                caps.expand(&self.with, &mut with);
+                with
+            }
+            None => self.with.clone(),
+        }
+    }
+
+    fn finish(self) -> Option<Action> {
+        {
+            let mut editor = self.editor.borrow_mut();
+            editor.clear_mark();
+            editor.render();
+        }
+        Action::as_echo(&format!(
+            "replaced {} occurrence{}",
+            self.count,
+            if self.count == 1 { "" } else { "s" }
+        ))
+    }
+}
+
+impl Inquirer for ReplaceMatch {
+    fn prompt(&self) -> String {
+        "replace?".to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_all_completer()
+    }
+
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some("y") => {
+                let pos = self.replace();
+                self.clone().start(pos)
+            }
+            Some("n") => {
+                let pos = cmp::max(self.found.end, self.found.start + 1);
+                self.clone().start(pos)
+            }
+            Some("a") => self.clone().replace_rest(),
+            Some(_) => {
+                let pos = self.pos;
+                self.clone().start(pos)
+            }
+            None => self.clone().finish(),
+        }
+    }
+}
+
+/// Operation: `next-marker`
+fn next_marker(env: &mut Environment) -> Option<Action> {
+    goto_marker(env, true)
+}
+
+/// Operation: `prev-marker`
+fn prev_marker(env: &mut Environment) -> Option<Action> {
+    goto_marker(env, false)
+}
+
+/// Moves the cursor in the active editor to the next or previous marker depending on
+/// `forward`, where markers are defined by the syntax of the editor or otherwise by
+/// the `markers` setting.
+fn goto_marker(env: &mut Environment, forward: bool) -> Option<Action> {
+    const PRIORITY: u8 = 0;
+
+    let config = env.workspace().config().clone();
+    let mut editor = env.get_active_editor().borrow_mut();
+    let syntax = editor.syntax();
+    let regex = match syntax.markers() {
+        Some(regex) => regex.clone(),
+        None => match Syntax::marker_regex(&config.settings.markers) {
+            Ok(Some(regex)) => regex,
+            Ok(None) => return Action::as_warn("no markers defined"),
+            Err(e) => return Action::as_error(&e),
+        },
+    };
+    let pattern = search::using_regex(regex);
+    let cur_pos = editor.pos();
+    let found = if forward {
+        pattern.find(&editor.buffer(), cur_pos + 1)
+    } else {
+        pattern.rfind(&editor.buffer(), cur_pos)
+    };
+
+    if config.settings.highlight_markers {
+        let mut ranges = Vec::new();
+        let mut pos = 0;
+        while let Some((start_pos, end_pos)) = pattern.find(&editor.buffer(), pos) {
+            if start_pos < pos {
+                break;
+            }
+            ranges.push(start_pos..end_pos);
+            pos = cmp::max(end_pos, start_pos + 1);
+        }
+        let style = Style {
+            fg: Some(config.theme.warn_fg),
+            bg: None,
+        };
+        editor.clear_annotations(Lane::Marker);
+        editor.annotate(Lane::Marker, ranges, PRIORITY, style);
+    }
+
+    if let Some((start_pos, end_pos)) = found {
+        editor.move_to(start_pos, Align::Center);
+        editor.render();
+        let text = editor.buffer().copy_as_string(start_pos, end_pos);
+        Action::as_echo(&format!(
+            "marker {}: {}",
+            if forward { "forward" } else { "backward" },
+            text.trim()
+        ))
+    } else {
+        editor.render();
+        Action::as_echo("no markers found")
+    }
+}
+
+/// Operation: `occur`
+fn occur(env: &mut Environment) -> Option<Action> {
+    if is_occur_editor(env.get_active_editor()) {
+        // Gather occurrences again using term of prior search, which brings list in
+        // sync with any changes to originating editor.
+        if let Some((editor_id, term)) = env.get_occur().cloned() {
+            show_occur(env, editor_id, &term)
+        } else {
+            None
+        }
+    } else {
+        Occur::question(env.get_active_editor_id())
+    }
+}
+
+struct Occur {
+    editor_id: u32,
+}
+
+impl Occur {
+    const PROMPT: &str = "occur (case-insensitive):";
+
+    fn question(editor_id: u32) -> Option<Action> {
+        Action::as_question(Box::new(Occur { editor_id }))
+    }
+}
+
+impl Inquirer for Occur {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::null_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(term) if !term.is_empty() => show_occur(env, self.editor_id, term),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the editor listing occurrences of a search term.
+const OCCUR_EDITOR_NAME: &str = "occur";
+
+/// Gathers all lines in the editor of `editor_id` matching `term` and shows them in
+/// a readonly editor, which replaces the prior occurrences editor if one exists.
+///
+/// Each line in the resulting editor is prefixed with its line number, which allows
+/// the originating editor to be positioned at the corresponding line.
+fn show_occur(env: &mut Environment, editor_id: u32, term: &str) -> Option<Action> {
+    let editor = if let Some(editor) = env.editor_map().get(&editor_id) {
+        editor.clone()
+    } else {
+        return Action::as_warn("originating editor no longer exists");
+    };
+    env.set_occur(editor_id, term);
+
+    let lines = find_occurrences(&editor.borrow().buffer(), term);
+    let mut buffer = Buffer::new();
+    buffer.insert_str(&format!(
+        "{}: {} lines matching \"{term}\"\n",
+        source_of(&editor),
+        lines.len()
+    ));
+    for (line, text) in lines.iter() {
+        buffer.insert_str(&format!("{:>6}: {text}\n", line + 1));
+    }
+    buffer.set_pos(0);
+
+    let config = env.workspace().config().clone();
+    let source = Source::as_ephemeral(OCCUR_EDITOR_NAME);
+    let occur_editor = Editor::readonly(config, source, buffer).to_ref();
+    if let Some((view_id, _)) = replace_editor(env, occur_editor, Align::Top) {
+        env.set_active(Focus::To(view_id));
+        Action::as_echo(&format!("{} matching lines", lines.len()))
+    } else {
+        Action::echo_no_window()
+    }
+}
+
+/// Returns a collection of `0`-based line numbers and corresponding text for each line
+/// in `buffer` matching `term`.
+fn find_occurrences(buffer: &Buffer, term: &str) -> Vec<(u32, String)> {
+    let pattern = search::using_term(term.to_string(), false);
+    search::find_lines(pattern.as_ref(), buffer)
+}
+
+/// Positions the originating editor at the line corresponding to the occurrence under
+/// the cursor of the occurrences editor, which must be active.
+fn goto_occur(env: &mut Environment) -> Option<Action> {
+    let line = {
+        let editor = env.get_active_editor().borrow();
+        let buffer = editor.buffer();
+        let start_line = buffer.find_start_line(editor.pos());
+        buffer
+            .forward(start_line)
+            .take_while(|c| *c != ':' && *c != '\n')
+            .collect::<String>()
+            .trim()
+            .parse::<u32>()
+    };
+
+    let (editor_id, _) = env.get_occur().cloned()?;
+    if let Ok(line) = line {
+        if env.editor_map().contains_key(&editor_id) {
+            let view_id = env.get_active_view_id();
+            if let Some(view_id) =
+                env.open_window(editor_id, Placement::Above(view_id), Align::Auto)
+            {
+                env.set_active(Focus::To(view_id));
+                let mut editor = env.get_active_editor().borrow_mut();
+                editor.clear_soft_mark();
+                editor.move_line(line.saturating_sub(1), Align::Center);
+                editor.render();
+                None
+            } else {
+                Action::echo_no_window()
+            }
+        } else {
+            Action::as_warn("originating editor no longer exists")
+        }
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if `editor` is the occurrences editor.
+fn is_occur_editor(editor: &EditorRef) -> bool {
+    source_of(editor) == Source::as_ephemeral(OCCUR_EDITOR_NAME).to_string()
+}
+
+/// Operation: `search-all`
+fn search_all(env: &mut Environment) -> Option<Action> {
+    if is_search_all_editor(env.get_active_editor()) {
+        // Gather matches again using pattern of prior search, which brings list in
+        // sync with any changes to editors.
+        let pattern = env
+            .get_search_all()
+            .map(|matches| matches.pattern.clone_box())?;
+        show_search_all(env, pattern)
+    } else if let Some((pos, pattern)) = env.take_last_match() {
+        // Use pattern of last search in active window, which must be retained so that
+        // subsequent searches in that window continue to work.
+        let search_pattern = pattern.clone_box();
+        env.set_last_match(pos, pattern);
+        show_search_all(env, search_pattern)
+    } else {
+        SearchAll::question()
+    }
+}
+
+struct SearchAll;
+
+impl SearchAll {
+    const PROMPT: &str = "search all editors (case-insensitive):";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(SearchAll))
+    }
+}
+
+impl Inquirer for SearchAll {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::null_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(term) if !term.is_empty() => {
+                show_search_all(env, search::using_term(term.to_string(), false))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Name of the editor listing matches across all editors.
+const SEARCH_ALL_EDITOR_NAME: &str = "matches";
+
+/// Gathers all lines matching `pattern` in every editor and shows them in a readonly
+/// editor, which replaces the prior matches editor if one exists.
+///
+/// The location of each match is retained in the environment, which allows the
+/// corresponding editor to be positioned at the line of the match.
+fn show_search_all(env: &mut Environment, pattern: Box<dyn Pattern>) -> Option<Action> {
+    let mut results = Results::new();
+    for (editor_id, editor) in env.editor_map() {
+        if !is_search_all_editor(editor) {
+            let editor = editor.borrow();
+            let lines = search::find_lines(pattern.as_ref(), &editor.buffer());
+            results.add(*editor_id, &editor.source().to_string(), lines);
+        }
+    }
+    let (buffer, locations) = results.build(pattern.pattern());
+    let count = locations.len();
+    env.set_search_all(pattern, locations);
+
+    let config = env.workspace().config().clone();
+    let source = Source::as_ephemeral(SEARCH_ALL_EDITOR_NAME);
+    let search_editor = Editor::readonly(config, source, buffer).to_ref();
+    if let Some((view_id, _)) = replace_editor(env, search_editor, Align::Top) {
+        env.set_active(Focus::To(view_id));
+        Action::as_echo(&format!("{count} matching lines"))
+    } else {
+        Action::echo_no_window()
+    }
+}
+
+/// Positions the editor containing the match under the cursor of the matches editor,
+/// which must be active, at the line of that match.
+fn goto_search_all(env: &mut Environment) -> Option<Action> {
+    // First line of matches editor is a summary, so locations start on second line.
+    let index = env
+        .get_active_editor()
+        .borrow()
+        .location()
+        .row
+        .checked_sub(1)?;
+    let (editor_id, line) = env
+        .get_search_all()
+        .and_then(|matches| matches.locations.get(index as usize).cloned())?;
+
+    if env.editor_map().contains_key(&editor_id) {
+        let view_id = env.get_active_view_id();
+        if let Some(view_id) = env.open_window(editor_id, Placement::Above(view_id), Align::Auto) {
+            env.set_active(Focus::To(view_id));
+            let mut editor = env.get_active_editor().borrow_mut();
+            editor.clear_soft_mark();
+            editor.move_line(line, Align::Center);
+            editor.render();
+            None
+        } else {
+            Action::echo_no_window()
+        }
+    } else {
+        Action::as_warn("editor no longer exists")
+    }
+}
+
+/// Returns `true` if `editor` is the matches editor.
+fn is_search_all_editor(editor: &EditorRef) -> bool {
+    source_of(editor) == Source::as_ephemeral(SEARCH_ALL_EDITOR_NAME).to_string()
+}
+
+/// Operation: `open-file`
+fn open_file(env: &mut Environment) -> Option<Action> {
+    Open::question(env, None)
+}
+
+/// Operation: `open-file-top`
+fn open_file_top(env: &mut Environment) -> Option<Action> {
+    Open::question(env, Some(Placement::Top))
+}
+
+/// Operation: `open-file-bottom`
+fn open_file_bottom(env: &mut Environment) -> Option<Action> {
+    Open::question(env, Some(Placement::Bottom))
+}
+
+/// Operation: `open-file-above`
+fn open_file_above(env: &mut Environment) -> Option<Action> {
+    let view_id = env.get_active_view_id();
+    Open::question(env, Some(Placement::Above(view_id)))
+}
+
+/// Operation: `open-file-below`
+fn open_file_below(env: &mut Environment) -> Option<Action> {
+    let view_id = env.get_active_view_id();
+    Open::question(env, Some(Placement::Below(view_id)))
+}
+
+/// Operation: `open-file-left`
+fn open_file_left(env: &mut Environment) -> Option<Action> {
+    let view_id = env.get_active_view_id();
+    Open::question(env, Some(Placement::Left(view_id)))
+}
+
+/// Operation: `open-file-right`
+fn open_file_right(env: &mut Environment) -> Option<Action> {
+    let view_id = env.get_active_view_id();
+    Open::question(env, Some(Placement::Right(view_id)))
+}
+
+/// An inquirer that orchestrates the process of opening a file.
+struct Open {
+    /// Base directory used for joining paths entered by the user, which is typically
+    /// derived from the path of the active editor.
+    dir: PathBuf,
+
+    /// Where to open the new window if specified, otherwise is replaces the editor in
+    /// the current window.
+    place: Option<Placement>,
+
+    /// Path of the file being previewed in the current window, if any.
+    preview: Option<String>,
+
+    /// Patterns of files excluded from completion.
+    ignore: Vec<String>,
+}
+
+impl Open {
+    fn question(env: &mut Environment, place: Option<Placement>) -> Option<Action> {
+        Action::as_question(
+            Open {
+                dir: derive_dir(env),
+                place,
+                preview: None,
+                ignore: env.workspace().config().settings.ignore.clone(),
+            }
+            .to_box(),
+        )
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+
+    fn open(&mut self, env: &mut Environment, path: &str) -> Option<Action> {
+        let path = sys::canonicalize(self.dir.join(sys::expand_path(path))).as_string();
+        let config = env.workspace().config().clone();
+        if crypt::is_encrypted(&path, &config.settings) {
+            Decrypt::question(vec![path], self.place)
+        } else {
+            match open_editor(config, &path) {
+                Ok(editor) => place_editor(env, editor, self.place),
+                Err(e) => Action::as_error(&e),
+            }
+        }
+    }
+
+    /// Previews the first screenful of the file at `path` in the current window, or
+    /// restores the window to its own editor if `path` is not a readable file.
+    fn preview(&mut self, env: &mut Environment, path: &str) {
+        let path = sys::canonicalize(self.dir.join(sys::expand_path(path))).as_string();
+        if self.preview.as_ref() != Some(&path) {
+            let config = env.workspace().config().clone();
+            let editor = if sys::is_file(&path) && !crypt::is_encrypted(&path, &config.settings) {
+                let rows = env
+                    .workspace()
+                    .get_view(env.get_active_view_id())
+                    .window
+                    .borrow()
+                    .canvas()
+                    .borrow()
+                    .size()
+                    .rows;
+                io::read_head(&path, rows as usize).ok().map(|text| {
+                    let mut buffer = Buffer::new();
+                    buffer.insert_str(&text);
+                    buffer.set_pos(0);
+                    Editor::readonly(config, Source::as_file(&path, None), buffer).to_ref()
+                })
+            } else {
+                None
+            };
+            self.preview = editor.as_ref().map(|_| path);
+            env.set_preview(editor);
+        }
+    }
+}
+
+impl Inquirer for Open {
+    fn prompt(&self) -> String {
+        let path = sys::pretty_path(&self.dir);
+        format!("open file [{path}]:")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::file_completer(self.dir.clone(), self.ignore.clone())
+    }
+
+    fn react(&mut self, env: &mut Environment, value: &str, _: &Key) -> Option<String> {
+        if env.workspace().config().settings.open_preview {
+            self.preview(env, value);
+        }
+        None
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        env.set_preview(None);
+        if let Some(path) = value {
+            self.open(env, path)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns an inquirer that solicits the passphrase for each of the encrypted files
+/// in `paths`, opening each in a window at the bottom of the workspace.
+pub fn decrypt_question(paths: Vec<String>) -> Box<dyn Inquirer> {
+    Box::new(Decrypt {
+        paths,
+        place: Some(Placement::Bottom),
+    })
+}
+
+/// An inquirer that orchestrates the process of opening encrypted files, which are
+/// decrypted using the passphrase solicited for each file in turn.
+struct Decrypt {
+    /// Paths of encrypted files, where the first entry is pertinent to this flow.
+    paths: Vec<String>,
+
+    /// Where to open the new window if specified, otherwise it replaces the editor in
+    /// the current window.
+    place: Option<Placement>,
+}
+
+impl Decrypt {
+    fn question(paths: Vec<String>, place: Option<Placement>) -> Option<Action> {
+        Action::as_question(Box::new(Decrypt { paths, place }))
+    }
+
+    /// Continues the process of opening encrypted files if more remain, each of which
+    /// is opened in a window at the bottom of the workspace.
+    fn next(&self) -> Option<Action> {
+        if self.paths.len() > 1 {
+            Self::question(self.paths[1..].to_vec(), Some(Placement::Bottom))
+        } else {
+            None
+        }
+    }
+}
+
+impl Inquirer for Decrypt {
+    fn prompt(&self) -> String {
+        format!("{}: passphrase:", sys::pretty_path(&self.paths[0]))
+    }
+
+    fn masked(&self) -> bool {
+        true
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if let Some(passphrase) = value {
+            let config = env.workspace().config().clone();
+            match open_crypt_editor(config, &self.paths[0], passphrase) {
+                Ok(editor) => place_editor(env, editor, self.place).or_else(|| self.next()),
+                Err(e) => Action::as_error(&e),
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// Operation: `save-file`
+fn save_file(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor();
+    if is_file(editor) {
+        match stale_editor(editor) {
+            Ok(true) => SaveOverride::question(editor.clone()),
+            Ok(false) => Save::save(editor),
+            Err(e) => Action::as_error(&e),
+        }
+    } else {
+        let editor = editor.clone();
+        Save::question(env, editor)
+    }
+}
+
+/// Operation: `save-file-as`
+fn save_file_as(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor().clone();
+    Save::question(env, editor)
+}
+
+/// An inquirer that orchestrates the process of saving a file.
+struct Save {
+    editor: EditorRef,
+    ignore: Vec<String>,
+}
+
+impl Save {
+    fn question(env: &Environment, editor: EditorRef) -> Option<Action> {
+        let ignore = env.workspace().config().settings.ignore.clone();
+        Action::as_question(Save { editor, ignore }.to_box())
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+
+    fn save_as(editor: &EditorRef, env: &mut Environment, path: &str) -> Option<Action> {
+        let action = if is_file(editor) {
+            Self::save_file(editor, path)
+        } else {
+            Self::save_ephemeral(editor, env, path)
+        };
+        env.refresh_labels();
+        action
+    }
+
+    fn save_file(editor: &EditorRef, path: &str) -> Option<Action> {
+        if let Err(e) = save_editor_as(editor, Some(path)) {
+            save_failed(e, editor, path, AfterSave::Echo)
+        } else {
+            Action::as_echo(&Self::echo_saved(&path))
+        }
+    }
+
+    fn save_ephemeral(editor: &EditorRef, env: &mut Environment, path: &str) -> Option<Action> {
+        let stamp = write_editor(editor, path);
+        match stamp {
+            Ok(stamp) => {
+                let cloned_editor = editor.borrow().clone_as(Source::as_file(path, Some(stamp)));
+                let row = cloned_editor.cursor().row;
+                env.set_editor(cloned_editor.to_ref(), Align::Row(row));
+                Action::as_echo(&Self::echo_saved(path))
+            }
+            Err(e) => save_failed(e, editor, path, AfterSave::Echo),
+        }
+    }
+
+    fn save(editor: &EditorRef) -> Option<Action> {
+        let path = path_of(editor).as_string();
+        if let Err(e) = save_editor(editor) {
+            save_failed(e, editor, &path, AfterSave::Echo)
+        } else {
+            Action::as_echo(&Self::echo_saved(&path))
+        }
+    }
+
+    fn echo_saved(path: &str) -> String {
+        format!("{path}: saved")
+    }
+}
+
+impl Inquirer for Save {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        format!("save {source} as:")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::file_completer(sys::working_dir(), self.ignore.clone())
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if let Some(path) = value {
+            let path = &sys::expand_path(path);
+            if Path::new(path).exists() {
+                SaveExists::question(self.editor.clone(), path.to_string())
+            } else {
+                Self::save_as(&self.editor, env, path)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// An inquirer spawned from [`Save`] that orchestrates the saving of an editor whose
+/// path provided by the user conflicts with an existing file in storage.
+#[derive(Clone)]
+struct SaveExists {
+    editor: EditorRef,
+    path: String,
+}
+
+impl SaveExists {
+    fn question(editor: EditorRef, path: String) -> Option<Action> {
+        Action::as_question(SaveExists { editor, path }.to_box())
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(self.clone().to_box())
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+}
+
+impl Inquirer for SaveExists {
+    fn prompt(&self) -> String {
+        let path = sys::pretty_path(&self.path);
+        format!("{path}: file already exists, overwrite?")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(yes_no) if yes_no == "y" => Save::save_as(&self.editor, env, &self.path),
+            Some(yes_no) if yes_no == "n" => None,
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
+
+/// An inquirer spawned from [`Save`] that orchestrates the saving of an editor whose
+/// corresponding file in storage was changed since it was last read or written.
+#[derive(Clone)]
+struct SaveOverride {
+    editor: EditorRef,
+}
+
+impl SaveOverride {
+    fn question(editor: EditorRef) -> Option<Action> {
+        Action::as_question(SaveOverride { editor }.to_box())
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(self.clone().to_box())
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+}
+
+impl Inquirer for SaveOverride {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        format!("{source}: file in storage has changed, save anyway?")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(yes_no) if yes_no == "y" => Save::save(&self.editor),
+            Some(yes_no) if yes_no == "n" => None,
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
+
+/// The flow that continues once an editor is saved after retrying.
+#[derive(Clone)]
+enum AfterSave {
+    /// Echoes that the editor was saved.
+    Echo,
+
+    /// Continues [`Quit`] with the list of dirty editors, where the first entry is
+    /// the editor that was saved.
+    Quit(Vec<EditorRef>),
+
+    /// Continues [`Kill`] by closing the editor or killing its window.
+    Kill(Option<(u32, u32)>),
+}
+
+/// Returns a question asking whether to try saving `editor` to `path` again if `e`
+/// is transient, such as an error reported by a network filesystem, otherwise `e`
+/// is returned as an error.
+fn save_failed(e: Error, editor: &EditorRef, path: &str, after: AfterSave) -> Option<Action> {
+    if e.is_transient() {
+        SaveRetry::question(editor.clone(), path.to_string(), e.to_string(), after)
+    } else {
+        Action::as_error(&e)
+    }
+}
+
+/// An inquirer that orchestrates saving an editor again after failing with an error
+/// that is likely transient, which preserves the flow from which saving was
+/// initiated.
+#[derive(Clone)]
+struct SaveRetry {
+    editor: EditorRef,
+    path: String,
+    error: String,
+    after: AfterSave,
+}
+
+impl SaveRetry {
+    fn question(
+        editor: EditorRef,
+        path: String,
+        error: String,
+        after: AfterSave,
+    ) -> Option<Action> {
+        Action::as_question(Box::new(SaveRetry {
+            editor,
+            path,
+            error,
+            after,
+        }))
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(Box::new(self.clone()))
+    }
+
+    fn retry(&self, env: &mut Environment) -> Option<Action> {
+        match &self.after {
+            AfterSave::Echo => Save::save_as(&self.editor, env, &self.path),
+            AfterSave::Quit(dirty) => {
+                if let Err(e) = save_editor_as(&self.editor, Some(&self.path)) {
+                    save_failed(e, &self.editor, &self.path, self.after.clone())
+                } else {
+                    Quit::next(env, dirty)
+                }
+            }
+            AfterSave::Kill(close_and_switch) => {
+                Kill::save_and_kill(env, &self.editor, *close_and_switch)
+            }
+        }
+    }
+}
+
+impl Inquirer for SaveRetry {
+    fn prompt(&self) -> String {
+        format!("{}, try again?", self.error)
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some("y") => self.retry(env),
+            Some("n") => None,
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
+
+/// Operation: `delete-file`
+fn delete_file(env: &mut Environment) -> Option<Action> {
+    if env.get_edit_lock().is_some() {
+        return Action::as_warn("editing is locked");
+    }
+    let editor = env.get_active_editor();
+    if is_file(editor) && path_of(editor).exists() {
+        Delete::question(editor.clone())
+    } else {
+        Action::as_warn("no file to delete")
+    }
+}
+
+/// An inquirer that orchestrates the process of moving the file of an editor to the
+/// trash, which also closes the editor.
+struct Delete {
+    editor: EditorRef,
+}
+
+impl Delete {
+    fn question(editor: EditorRef) -> Option<Action> {
+        Action::as_question(Box::new(Delete { editor }))
+    }
+
+    fn delete(&self, env: &mut Environment) -> Option<Action> {
+        let path = path_of(&self.editor);
+        let trash_dir = env
+            .workspace()
+            .config()
+            .settings
+            .trash_dir
+            .as_ref()
+            .map(|dir| PathBuf::from(sys::expand_path(dir)))
+            .unwrap_or_else(sys::trash_dir);
+        match io::trash_file(&path, &trash_dir) {
+            Ok(trash_path) => {
+                env.push_trash(path.clone(), trash_path);
+                if let Some(editor_id) = env.find_editor_id(&source_of(&self.editor)) {
+                    close_editor(env, editor_id);
+                }
+                let path = sys::pretty_path(&path);
+                Action::as_echo(&format!("{path}: moved to trash"))
+            }
+            Err(e) => Action::as_error(&e),
+        }
+    }
+}
+
+impl Inquirer for Delete {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        if is_dirty_file(&self.editor) {
+            format!("{source}: discard unsaved changes and move to trash?")
+        } else {
+            format!("{source}: move to trash?")
+        }
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some("y") => self.delete(env),
+            _ => None,
+        }
+    }
+}
+
+/// Operation: `undelete-file`
+fn undelete_file(env: &mut Environment) -> Option<Action> {
+    if env.get_edit_lock().is_some() {
+        return Action::as_warn("editing is locked");
+    }
+    if let Some((path, trash_path)) = env.pop_trash() {
+        match io::restore_file(&trash_path, &path) {
+            Ok(()) => {
+                let config = env.workspace().config().clone();
+                if crypt::is_encrypted(&path, &config.settings) {
+                    Decrypt::question(vec![path.as_string()], None)
+                } else {
+                    match open_editor(config, &path.as_string()) {
+                        Ok(editor) => {
+                            env.set_editor(editor, Align::Auto);
+                            let path = sys::pretty_path(&path);
+                            Action::as_echo(&format!("{path}: restored from trash"))
+                        }
+                        Err(e) => Action::as_error(&e),
+                    }
+                }
+            }
+            Err(e) => {
+                env.push_trash(path, trash_path);
+                Action::as_error(&e)
+            }
+        }
+    } else {
+        Action::as_warn("nothing to undelete")
+    }
+}
+
+/// Operation: `rename-file`
+fn rename_file(env: &mut Environment) -> Option<Action> {
+    if env.get_edit_lock().is_some() {
+        return Action::as_warn("editing is locked");
+    }
+    let editor = env.get_active_editor();
+    if is_file(editor) && path_of(editor).exists() {
+        let editor = editor.clone();
+        Rename::question(env, editor)
+    } else {
+        Action::as_warn("no file to rename")
+    }
+}
+
+/// An inquirer that orchestrates the process of renaming the file of an editor in
+/// storage, which also changes the source of the editor to the new path.
+struct Rename {
+    editor: EditorRef,
+    ignore: Vec<String>,
+}
+
+impl Rename {
+    fn question(env: &Environment, editor: EditorRef) -> Option<Action> {
+        let ignore = env.workspace().config().settings.ignore.clone();
+        Action::as_question(Box::new(Rename { editor, ignore }))
+    }
+
+    fn rename(editor: &EditorRef, env: &mut Environment, path: &str) -> Option<Action> {
+        let from = path_of(editor);
+        match io::move_file(&from, path) {
+            Ok(()) => {
+                // Contents of file are unchanged by moving, so only the modification
+                // time needs to be refreshed.
+                let stamp = match editor.borrow().source() {
+                    Source::File(_, Some(stamp)) => {
+                        io::get_time(path).ok().map(|time| Stamp { time, ..*stamp })
+                    }
+                    _ => None,
+                };
+                editor.borrow_mut().assume(Source::as_file(path, stamp));
+                env.refresh_labels();
+                let from = sys::pretty_path(&from);
+                let path = sys::pretty_path(path);
+                Action::as_echo(&format!("{from}: renamed to {path}"))
+            }
+            Err(e) => Action::as_error(&e),
+        }
+    }
+}
+
+impl Inquirer for Rename {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        format!("rename {source} to:")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::file_completer(sys::working_dir(), self.ignore.clone())
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if let Some(path) = value {
+            let path = &sys::expand_path(path);
+            if sys::canonicalize(path) == sys::canonicalize(path_of(&self.editor)) {
+                None
+            } else if Path::new(path).exists() {
+                RenameExists::question(self.editor.clone(), path.to_string())
+            } else {
+                Self::rename(&self.editor, env, path)
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// An inquirer spawned from [`Rename`] that orchestrates the renaming of a file whose
+/// path provided by the user conflicts with an existing file in storage.
+#[derive(Clone)]
+struct RenameExists {
+    editor: EditorRef,
+    path: String,
+}
+
+impl RenameExists {
+    fn question(editor: EditorRef, path: String) -> Option<Action> {
+        Action::as_question(Box::new(RenameExists { editor, path }))
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(Box::new(self.clone()))
+    }
+}
+
+impl Inquirer for RenameExists {
+    fn prompt(&self) -> String {
+        let path = sys::pretty_path(&self.path);
+        format!("{path}: file already exists, overwrite?")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some("y") => Rename::rename(&self.editor, env, &self.path),
+            Some("n") => None,
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
+
+/// Operation: `kill-window`
+fn kill_window(env: &mut Environment) -> Option<Action> {
+    if env.view_map().len() > 1 {
+        let editor = env.get_active_editor();
+        if is_dirty_file(editor) {
+            Kill::question(editor.clone(), None)
+        } else {
+            env.kill_window();
+            None
+        }
+    } else {
+        if let Some((switch_id, _)) = next_unattached_editor(env) {
+            let editor_id = env.get_active_editor_id();
+            let editor = env.get_active_editor();
+            if is_dirty_file(editor) {
+                Kill::question(editor.clone(), Some((editor_id, switch_id)))
+            } else {
+                env.switch_editor(switch_id, Align::Auto);
+                env.close_editor(editor_id);
+                None
+            }
+        } else {
+            Action::echo_cannot_close()
+        }
+    }
+}
+
+/// An inquirer that orchestrates the process of killing a window with a dirty editor
+/// attached.
+#[derive(Clone)]
+struct Kill {
+    editor: EditorRef,
+    close_and_switch: Option<(u32, u32)>,
+}
+
+impl Kill {
+    fn question(editor: EditorRef, close_and_switch: Option<(u32, u32)>) -> Option<Action> {
+        Action::as_question(
+            Kill {
+                editor,
+                close_and_switch,
+            }
+            .to_box(),
+        )
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(self.clone().to_box())
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+
+    fn kill(&mut self, env: &mut Environment) -> Option<Action> {
+        Self::save_and_kill(env, &self.editor, self.close_and_switch)
+    }
+
+    fn kill_only(&mut self, env: &mut Environment) -> Option<Action> {
+        Self::close(env, self.close_and_switch)
+    }
+
+    /// Saves `editor` and then closes it or kills its window, though neither happens
+    /// if saving fails.
+    fn save_and_kill(
+        env: &mut Environment,
+        editor: &EditorRef,
+        close_and_switch: Option<(u32, u32)>,
+    ) -> Option<Action> {
+        let path = path_of(editor).as_string();
+        if let Err(e) = save_editor(editor) {
+            save_failed(e, editor, &path, AfterSave::Kill(close_and_switch))
+        } else {
+            Self::close(env, close_and_switch);
+            Action::as_echo(&Save::echo_saved(&path))
+        }
+    }
+
+    fn close(env: &mut Environment, close_and_switch: Option<(u32, u32)>) -> Option<Action> {
+        if let Some((editor_id, switch_id)) = close_and_switch {
+            env.switch_editor(switch_id, Align::Auto);
+            env.close_editor(editor_id);
+        } else {
+            env.kill_window();
+        }
+        None
+    }
+}
+
+impl Inquirer for Kill {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        format!("{source}: save?")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(yes_no) if yes_no == "y" => match stale_editor(&self.editor) {
+                Ok(true) => KillOverride::question(self.editor.clone(), self.close_and_switch),
+                Ok(false) => self.kill(env),
+                Err(e) => Action::as_error(&e),
+            },
+            Some(yes_no) if yes_no == "n" => self.kill_only(env),
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
+
+/// An inquirer spawned from [`Kill`] that orchestrates the saving of an editor whose
+/// corresponding file in storage was changed since it was last read or written.
+#[derive(Clone)]
+struct KillOverride {
+    editor: EditorRef,
+    close_and_switch: Option<(u32, u32)>,
+}
+
+impl KillOverride {
+    fn question(editor: EditorRef, close_and_switch: Option<(u32, u32)>) -> Option<Action> {
+        Action::as_question(
+            KillOverride {
+                editor,
+                close_and_switch,
+            }
+            .to_box(),
+        )
+    }
+
+    fn again(&self) -> Option<Action> {
+        Action::as_question(self.clone().to_box())
+    }
+
+    fn to_box(self) -> Box<dyn Inquirer> {
+        Box::new(self)
+    }
+
+    fn kill(&mut self, env: &mut Environment) -> Option<Action> {
+        Kill::save_and_kill(env, &self.editor, self.close_and_switch)
+    }
+}
+
+impl Inquirer for KillOverride {
+    fn prompt(&self) -> String {
+        let source = source_of(&self.editor);
+        format!("{source}: file in storage has changed, save anyway?")
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::yes_no_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(yes_no) if yes_no == "y" => self.kill(env),
+            Some(yes_no) if yes_no == "n" => None,
+            Some(_) => self.again(),
+            None => None,
+        }
+    }
+}
 
-            // Find next match and highlight if found.
-            let found = if let Some(buf) = &self.buf_cache {
-                pattern.find_str(buf, pos)
-            } else {
-                pattern.find(&self.editor.borrow().buffer(), pos)
-            };
+/// Operation: `close-window`
+fn close_window(env: &mut Environment) -> Option<Action> {
+    if let Some(_) = env.close_window() {
+        None
+    } else {
+        Action::echo_cannot_close()
+    }
+}
 
-            if let Some((start_pos, end_pos)) = found {
-                let mut editor = self.editor.borrow_mut();
-                editor.move_to(start_pos, Align::Center);
-                editor.clear_mark();
-                editor.set_soft_mark_at(end_pos);
-                editor.render();
-                self.last_match = Some((start_pos, pattern));
+/// Operation: `close-other-windows`
+fn close_other_windows(env: &mut Environment) -> Option<Action> {
+    let active_id = env.get_active_view_id();
+    let other_ids = env
+        .view_map()
+        .keys()
+        .cloned()
+        .filter(|id| *id != active_id)
+        .collect::<Vec<_>>();
+    for id in other_ids {
+        env.close_window_for(id);
+    }
+    None
+}
+
+/// Operation: `top-window`
+fn top_window(env: &mut Environment) -> Option<Action> {
+    env.set_active(Focus::Top);
+    None
+}
+
+/// Operation: `bottom-window`
+fn bottom_window(env: &mut Environment) -> Option<Action> {
+    env.set_active(Focus::Bottom);
+    None
+}
+
+/// Operation: `prev-window`
+fn prev_window(env: &mut Environment) -> Option<Action> {
+    env.set_active(Focus::Above);
+    None
+}
+
+/// Operation: `next-window`
+fn next_window(env: &mut Environment) -> Option<Action> {
+    env.set_active(Focus::Below);
+    None
+}
+
+/// Operation: `toggle-ruler`
+fn toggle_ruler(env: &mut Environment) -> Option<Action> {
+    if env.toggle_ruler() {
+        Action::as_echo("ruler enabled")
+    } else {
+        Action::as_echo("ruler disabled")
+    }
+}
+
+/// Operation: `grow-window`
+fn grow_window(env: &mut Environment) -> Option<Action> {
+    if env.grow_window(1) {
+        None
+    } else {
+        Action::as_warn("cannot grow window")
+    }
+}
+
+/// Operation: `shrink-window`
+fn shrink_window(env: &mut Environment) -> Option<Action> {
+    if env.shrink_window(1) {
+        None
+    } else {
+        Action::as_warn("cannot shrink window")
+    }
+}
+
+/// Operation: `balance-windows`
+fn balance_windows(env: &mut Environment) -> Option<Action> {
+    env.balance_windows();
+    None
+}
+
+/// Operation: `scroll-lock`
+fn scroll_lock(env: &mut Environment) -> Option<Action> {
+    if env.get_scroll_lock_for(env.get_active_view_id()).is_some() {
+        env.clear_scroll_lock();
+        Action::as_echo("scroll lock disabled")
+    } else if env.set_scroll_lock().is_some() {
+        Action::as_echo("scroll lock enabled")
+    } else {
+        Action::as_warn("scroll lock requires another window")
+    }
+}
+
+/// Operation: `save-layout`
+fn save_layout(_: &mut Environment) -> Option<Action> {
+    SaveLayout::question()
+}
+
+/// Operation: `restore-layout`
+fn restore_layout(env: &mut Environment) -> Option<Action> {
+    let names = env.layout_names();
+    if !names.is_empty() {
+        RestoreLayout::question(names)
+    } else {
+        Action::as_warn("no saved layouts")
+    }
+}
+
+/// An inquirer that saves the current window arrangement under a name.
+struct SaveLayout;
+
+impl SaveLayout {
+    const PROMPT: &str = "save layout as:";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(SaveLayout))
+    }
+}
+
+impl Inquirer for SaveLayout {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::null_completer()
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(name) if !name.trim().is_empty() => {
+                let name = name.trim();
+                env.save_layout(name);
+                Action::as_echo(&format!("{name}: layout saved"))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// An inquirer that restores a window arrangement previously saved under a name.
+struct RestoreLayout {
+    names: Vec<String>,
+}
+
+impl RestoreLayout {
+    const PROMPT: &str = "restore layout:";
+
+    fn question(names: Vec<String>) -> Option<Action> {
+        Action::as_question(Box::new(RestoreLayout { names }))
+    }
+}
+
+impl Inquirer for RestoreLayout {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        user::list_completer(self.names.clone())
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if let Some(name) = value {
+            if env.restore_layout(name) {
                 None
             } else {
-                Some(" (no match)".to_string())
+                Action::as_warn(&format!("{name}: layout not restored"))
             }
         } else {
-            self.restore();
-            self.last_match = None;
             None
         }
     }
+}
 
-    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
-        match value {
-            Some(value) if value.len() > 0 => {
-                if let Some((pos, pattern)) = self.last_match.take() {
-                    self.editor.borrow_mut().set_last_match(pos, pattern);
+/// Operation: `session-save`
+fn session_save(env: &mut Environment) -> Option<Action> {
+    match capture_session(env).save() {
+        Ok(()) => Action::as_echo(&format!(
+            "session saved: {}",
+            sys::pretty_path(Session::path())
+        )),
+        Err(e) => Action::as_error(&e),
+    }
+}
+
+/// Operation: `session-load`
+fn session_load(env: &mut Environment) -> Option<Action> {
+    match Session::load() {
+        Ok(Some(session)) => match restore_session(env, &session) {
+            Ok(true) => None,
+            Ok(false) => Action::as_warn("session not restored"),
+            Err(e) => Action::as_error(&e),
+        },
+        Ok(None) => Action::as_warn("no saved session"),
+        Err(e) => Action::as_error(&e),
+    }
+}
+
+/// Operation: `select-editor`
+fn select_editor(env: &mut Environment) -> Option<Action> {
+    let editors = unattached_editors(env, true);
+    if editors.len() > 0 {
+        SelectEditor::question(editors, None)
+    } else {
+        Action::echo_no_editors()
+    }
+}
+
+/// Operation: `select-editor-top`
+fn select_editor_top(env: &mut Environment) -> Option<Action> {
+    let editors = unattached_editors(env, true);
+    if editors.len() > 0 {
+        SelectEditor::question(editors, Some(Placement::Top))
+    } else {
+        Action::echo_no_editors()
+    }
+}
+
+/// Operation: `select-editor-bottom`
+fn select_editor_bottom(env: &mut Environment) -> Option<Action> {
+    let editors = unattached_editors(env, true);
+    if editors.len() > 0 {
+        SelectEditor::question(editors, Some(Placement::Bottom))
+    } else {
+        Action::echo_no_editors()
+    }
+}
+
+/// Operation: `select-editor-above`
+fn select_editor_above(env: &mut Environment) -> Option<Action> {
+    let editors = unattached_editors(env, true);
+    if editors.len() > 0 {
+        SelectEditor::question(editors, Some(Placement::Above(env.get_active_view_id())))
+    } else {
+        Action::echo_no_editors()
+    }
+}
+
+/// Operation: `select-editor-below`
+fn select_editor_below(env: &mut Environment) -> Option<Action> {
+    let editors = unattached_editors(env, true);
+    if editors.len() > 0 {
+        SelectEditor::question(editors, Some(Placement::Below(env.get_active_view_id())))
+    } else {
+        Action::echo_no_editors()
+    }
+}
+
+/// Operation: `command-palette`
+fn command_palette(env: &mut Environment) -> Option<Action> {
+    CommandPalette::question(env)
+}
+
+/// An inquirer that gathers an editor or an editing operation from a single list
+/// ranked by recency and frequency of use, switching to the editor or running the
+/// operation upon selection.
+struct CommandPalette {
+    /// Labels of editors and names of operations paired with their usage.
+    entries: Vec<(String, Usage)>,
+}
+
+impl CommandPalette {
+    const PROMPT: &str = "palette:";
+
+    fn question(env: &Environment) -> Option<Action> {
+        let mut entries = env
+            .editor_map()
+            .iter()
+            .map(|(id, e)| (label_of(e), Usage::Editor(*id)))
+            .chain(
+                OP_MAPPINGS
+                    .iter()
+                    .filter(|(op, _)| *op != "command-palette")
+                    .map(|(op, _)| (op.to_string(), Usage::Op(op.to_string()))),
+            )
+            .collect::<Vec<_>>();
+        env.recent().rank(&mut entries, |(_, usage)| usage.clone());
+        Action::as_question(Box::new(CommandPalette { entries }))
+    }
+}
+
+impl Inquirer for CommandPalette {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        let accepted = self
+            .entries
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect();
+        user::fuzzy_completer(accepted)
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        let (_, usage) = self
+            .entries
+            .iter()
+            .find(|(label, _)| Some(label.as_str()) == value)?;
+        match usage {
+            Usage::Editor(editor_id) => {
+                if !env.editor_map().contains_key(editor_id) {
+                    Action::as_warn("editor no longer exists")
+                } else if let Some(view_id) = env
+                    .view_map()
+                    .iter()
+                    .find(|(_, id)| *id == editor_id)
+                    .map(|(view_id, _)| *view_id)
+                {
+                    env.set_active(Focus::To(view_id));
+                    None
+                } else {
+                    env.switch_editor(*editor_id, Align::Auto);
+                    None
                 }
             }
-            _ => self.restore(),
+            Usage::Op(op) => {
+                let op_fn = OP_MAPPINGS
+                    .iter()
+                    .find(|(name, _)| name == op)
+                    .map(|(_, op_fn)| *op_fn)?;
+                env.touch(usage.clone());
+                op_fn(env)
+            }
         }
-        None
     }
 }
 
-/// Operation: `open-file`
-fn open_file(env: &mut Environment) -> Option<Action> {
-    Open::question(derive_dir(env), None)
-}
-
-/// Operation: `open-file-top`
-fn open_file_top(env: &mut Environment) -> Option<Action> {
-    Open::question(derive_dir(env), Some(Placement::Top))
+/// Operation: `prev-editor`
+fn prev_editor(env: &mut Environment) -> Option<Action> {
+    if let Some((prev_id, _)) = prev_unattached_editor(env) {
+        env.restore_editor(prev_id);
+    }
+    None
 }
 
-/// Operation: `open-file-bottom`
-fn open_file_bottom(env: &mut Environment) -> Option<Action> {
-    Open::question(derive_dir(env), Some(Placement::Bottom))
+/// Operation: `next-editor`
+fn next_editor(env: &mut Environment) -> Option<Action> {
+    if let Some((next_id, _)) = next_unattached_editor(env) {
+        env.restore_editor(next_id);
+    }
+    None
 }
 
-/// Operation: `open-file-above`
-fn open_file_above(env: &mut Environment) -> Option<Action> {
-    Open::question(
-        derive_dir(env),
-        Some(Placement::Above(env.get_active_view_id())),
-    )
+/// Operation: `next-dirty-editor`
+fn next_dirty_editor(env: &mut Environment) -> Option<Action> {
+    let editor_id = env.get_active_editor_id();
+    let dirty_ids = env
+        .editor_map()
+        .iter()
+        .filter(|(_, e)| is_dirty_file(e))
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    let next_id = dirty_ids
+        .iter()
+        .find(|id| **id > editor_id)
+        .or(dirty_ids.first())
+        .copied();
+    match next_id {
+        Some(next_id) if next_id != editor_id => {
+            // Editors already attached to another window are visited by moving to that
+            // window rather than detaching them.
+            if let Some(view_id) = env.find_editor_view_id(next_id) {
+                env.set_active(Focus::To(view_id));
+            } else {
+                env.restore_editor(next_id);
+            }
+            match dirty_ids.len() {
+                1 => Action::as_echo("1 unsaved editor"),
+                n => Action::as_echo(&format!("{n} unsaved editors")),
+            }
+        }
+        Some(_) => Action::as_echo("no other unsaved editors"),
+        None => Action::as_echo("no unsaved editors"),
+    }
 }
 
-/// Operation: `open-file-below`
-fn open_file_below(env: &mut Environment) -> Option<Action> {
-    Open::question(
-        derive_dir(env),
-        Some(Placement::Below(env.get_active_view_id())),
-    )
+/// Operation: `switch-last-editor`
+fn switch_last_editor(env: &mut Environment) -> Option<Action> {
+    if let Some(last_id) = env.get_last_editor_id() {
+        if env.restore_editor(last_id) == last_id {
+            None
+        } else {
+            Action::as_warn("last editor already attached to another window")
+        }
+    } else {
+        Action::as_warn("no last editor")
+    }
 }
 
-/// An inquirer that orchestrates the process of opening a file.
-struct Open {
-    /// Base directory used for joining paths entered by the user, which is typically
-    /// derived from the path of the active editor.
-    dir: PathBuf,
+/// An iquirer that orchetrates the selection of an editor by name, replacing the editor
+/// in the active window.
+struct SelectEditor {
+    /// Unattached editors available for selection.
+    editors: Vec<(u32, EditorRef)>,
 
     /// Where to open the new window if specified, otherwise is replaces the editor in
     /// the current window.
     place: Option<Placement>,
 }
 
-impl Open {
-    fn question(dir: PathBuf, place: Option<Placement>) -> Option<Action> {
-        Action::as_question(Open { dir, place }.to_box())
+impl SelectEditor {
+    const PROMPT: &str = "select editor:";
+
+    fn question(editors: Vec<(u32, EditorRef)>, place: Option<Placement>) -> Option<Action> {
+        Action::as_question(SelectEditor { editors, place }.to_box())
     }
 
     fn to_box(self) -> Box<dyn Inquirer> {
         Box::new(self)
     }
+}
 
-    fn open(&mut self, env: &mut Environment, path: &str) -> Option<Action> {
-        let path = sys::canonicalize(&self.dir.join(path)).as_string();
-        let config = env.workspace().config().clone();
-        match open_editor(config, &path) {
-            Ok(editor) => {
+impl Inquirer for SelectEditor {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
+    }
+
+    fn completer(&self) -> Box<dyn Completer> {
+        let accepted = self.editors.iter().map(|(_, e)| label_of(e)).collect();
+        user::list_completer(accepted)
+    }
+
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        if let Some(value) = value {
+            let editor = self
+                .editors
+                .iter()
+                .find(|(_, e)| label_of(e) == value)
+                .map(|(id, _)| *id);
+            if let Some(editor_id) = editor {
                 if let Some(place) = self.place {
-                    if let Some((view_id, _)) = env.open_editor(editor, place, Align::Auto) {
+                    if let Some(view_id) = env.open_window(editor_id, place, Align::Auto) {
                         env.set_active(Focus::To(view_id));
                         None
                     } else {
                         Action::echo_no_window()
                     }
                 } else {
-                    env.set_editor(editor, Align::Auto);
+                    env.switch_editor(editor_id, Align::Auto);
                     None
                 }
+            } else {
+                Action::as_warn("{value}: editor not found")
             }
-            Err(e) => Action::as_echo(&e),
+        } else {
+            None
         }
     }
 }
 
-impl Inquirer for Open {
-    fn prompt(&self) -> String {
-        let path = sys::pretty_path(&self.dir);
-        format!("open file [{path}]:")
-    }
+/// Operation: `describe-editor`
+fn describe_editor(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor().borrow();
+    let buffer = editor.buffer();
+    let (c_char, c_code) = if let Some(c) = buffer.get_char(editor.pos()) {
+        let c_char = if c.is_control() {
+            "".to_string()
+        } else {
+            format!("'{c}' ")
+        };
+        (c_char, format!("\\u{:04x}", c as u32))
+    } else {
+        ("EOF".to_string(), "".to_string())
+    };
+    let (undo_len, undo_chars) = editor.usage().undo;
+    let text = format!(
+        "characters: {} | lines: {} | cursor: {}{} | undo: {} ({} KiB of {} MiB) | {}",
+        buffer.size(),
+        buffer.line_of(usize::MAX) + 1,
+        c_char,
+        c_code,
+        undo_len,
+        (undo_chars * mem::size_of::<char>()).div_ceil(1024),
+        env.workspace().config().settings.undo_memory,
+        SetLocal::NAMES
+            .iter()
+            .map(|name| format!("{name}: {}", SetLocal::value_of(&editor, name)))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    );
+    Action::as_echo(&text)
+}
 
-    fn completer(&self) -> Box<dyn Completer> {
-        user::file_completer(self.dir.clone())
-    }
+/// Operation: `describe-char`
+fn describe_char(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor().borrow();
+    let text = if let Some(c) = editor.buffer().get_char(editor.pos()) {
+        format!(
+            "{} | code point: \\u{:04x} | decimal: {} | utf-8: {}",
+            char_label(c),
+            c as u32,
+            c as u32,
+            utf8_of(c)
+        )
+    } else {
+        "EOF".to_string()
+    };
+    Action::as_echo(&text)
+}
 
-    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
-        if let Some(path) = value {
-            self.open(env, path)
-        } else {
-            None
+/// Operation: `inspect-char`
+fn inspect_char(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor().borrow();
+    let buffer = editor.buffer();
+    let text = if let Some(c) = buffer.get_char(editor.pos()) {
+        let (abbrev, desc) = unicode::category(c);
+        let name = unicode::name(c).unwrap_or_else(|| format!("<{desc}>"));
+        let mut text = format!(
+            "{} U+{:04X} {name}\ncategory: {abbrev} ({desc}) | decimal: {} | utf-8: {}",
+            char_label(c),
+            c as u32,
+            c as u32,
+            utf8_of(c)
+        );
+
+        // Grapheme cluster is only shown when the character combines with others,
+        // since it would otherwise be redundant.
+        let grapheme = unicode::grapheme_at(&buffer, editor.pos());
+        if grapheme.len() > 1 {
+            let cs = buffer.copy(grapheme.start, grapheme.end);
+            let s = cs.iter().collect::<String>();
+            let code_points = cs
+                .iter()
+                .map(|c| format!("U+{:04X}", *c as u32))
+                .collect::<Vec<_>>()
+                .join(" ");
+            text.push_str(&format!(
+                "\ngrapheme: '{s}' = {code_points} ({} chars, {} bytes)",
+                cs.len(),
+                s.len()
+            ));
         }
-    }
+        text
+    } else {
+        "EOF".to_string()
+    };
+    Action::as_echo(&text)
 }
 
-/// Operation: `save-file`
-fn save_file(env: &mut Environment) -> Option<Action> {
-    let editor = env.get_active_editor();
-    if is_file(editor) {
-        match stale_editor(editor) {
-            Ok(true) => SaveOverride::question(editor.clone()),
-            Ok(false) => Save::save(editor),
-            Err(e) => Action::as_echo(&e),
-        }
+/// Returns a printable representation of `c`, where control characters are shown in
+/// caret notation along with their abbreviated names.
+fn char_label(c: char) -> String {
+    if c.is_ascii_control() {
+        let caret = char::from(c as u8 ^ 0x40);
+        format!(
+            "^{caret} ({})",
+            CONTROL_NAMES.get(c as usize).unwrap_or(&"DEL")
+        )
+    } else if c.is_control() {
+        "control".to_string()
     } else {
-        Save::question(editor.clone())
+        format!("'{c}'")
     }
 }
 
-/// Operation: `save-file-as`
-fn save_file_as(env: &mut Environment) -> Option<Action> {
-    Save::question(env.get_active_editor().clone())
+/// Returns the bytes of the UTF-8 encoding of `c` in hexadecimal.
+fn utf8_of(c: char) -> String {
+    c.encode_utf8(&mut [0; 4])
+        .bytes()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
-/// An inquirer that orchestrates the process of saving a file.
-struct Save {
-    editor: EditorRef,
-}
+/// Abbreviated names of ASCII control characters in the range `0x00..0x20`.
+const CONTROL_NAMES: [&str; 32] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FS", "GS", "RS", "US",
+];
 
-impl Save {
-    fn question(editor: EditorRef) -> Option<Action> {
-        Action::as_question(Save { editor }.to_box())
+/// Operation: `tab-mode`
+fn tab_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let hard = editor.get_tab();
+    editor.set_tab(!hard);
+    if hard {
+        Action::as_echo("soft tabs enabled")
+    } else {
+        Action::as_echo("hard tabs enabled")
     }
+}
 
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+/// Operation: `toggle-readonly`
+fn toggle_readonly(env: &mut Environment) -> Option<Action> {
+    if env.get_edit_lock().is_some() {
+        return Action::as_warn("editing is locked");
     }
-
-    fn save_as(editor: &EditorRef, env: &mut Environment, path: &str) -> Option<Action> {
-        if is_file(editor) {
-            Self::save_file(editor, path)
-        } else {
-            Self::save_ephemeral(editor, env, path)
-        }
+    let mut editor = env.get_active_editor().borrow_mut();
+    let readonly = editor.is_readonly();
+    if readonly && !editor.source().is_file() {
+        return Action::as_warn("readonly mode required for this editor");
     }
-
-    fn save_file(editor: &EditorRef, path: &str) -> Option<Action> {
-        if let Err(e) = save_editor_as(editor, Some(path)) {
-            Action::as_echo(&e)
-        } else {
-            Action::as_echo(&Self::echo_saved(&path))
-        }
+    editor.set_readonly(!readonly);
+    if readonly {
+        Action::as_echo("readonly mode disabled")
+    } else {
+        Action::as_echo("readonly mode enabled")
     }
+}
 
-    fn save_ephemeral(editor: &EditorRef, env: &mut Environment, path: &str) -> Option<Action> {
-        let timestamp = write_editor(editor, path);
-        match timestamp {
-            Ok(timestamp) => {
-                let cloned_editor = editor
-                    .borrow()
-                    .clone_as(Source::as_file(path, Some(timestamp)));
-                let row = cloned_editor.cursor().row;
-                env.set_editor(cloned_editor.to_ref(), Align::Row(row));
-                Action::as_echo(&Self::echo_saved(path))
-            }
-            Err(e) => Action::as_echo(&e),
-        }
+/// Operation: `lock-editing`
+fn lock_editing(env: &mut Environment) -> Option<Action> {
+    match env.get_edit_lock() {
+        Some(keyword) => UnlockEditing::question(!keyword.is_empty()),
+        None => LockEditing::question(false),
     }
+}
 
-    fn save(editor: &EditorRef) -> Option<Action> {
-        if let Err(e) = save_editor(editor) {
-            Action::as_echo(&e)
-        } else {
-            let path = path_of(editor);
-            Action::as_echo(&Self::echo_saved(&path.as_string()))
-        }
-    }
+/// An inquirer that confirms locking of all editors and then solicits a keyword
+/// required to unlock them, which may be empty.
+struct LockEditing {
+    /// Indicates that locking was confirmed and the keyword is being solicited.
+    confirmed: bool,
+}
 
-    fn echo_saved(path: &str) -> String {
-        format!("{path}: saved")
+impl LockEditing {
+    fn question(confirmed: bool) -> Option<Action> {
+        Action::as_question(Box::new(LockEditing { confirmed }))
     }
 }
 
-impl Inquirer for Save {
+impl Inquirer for LockEditing {
     fn prompt(&self) -> String {
-        let source = source_of(&self.editor);
-        format!("save {source} as:")
+        if self.confirmed {
+            "keyword to unlock (optional):".to_string()
+        } else {
+            "lock editing of all editors?".to_string()
+        }
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::file_completer(sys::working_dir())
+        if self.confirmed {
+            user::null_completer()
+        } else {
+            user::yes_no_completer()
+        }
+    }
+
+    fn masked(&self) -> bool {
+        self.confirmed
     }
 
     fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
-        if let Some(path) = value {
-            if Path::new(path).exists() {
-                SaveExists::question(self.editor.clone(), path.to_string())
-            } else {
-                Self::save_as(&self.editor, env, path)
+        match value {
+            Some(keyword) if self.confirmed => {
+                env.lock_editing(keyword);
+                Action::as_echo("editing locked")
             }
-        } else {
-            None
+            Some("y") => Self::question(true),
+            Some("n") => None,
+            Some(_) => Self::question(false),
+            None => None,
         }
     }
 }
 
-/// An inquirer spawned from [`Save`] that orchestrates the saving of an editor whose
-/// path provided by the user conflicts with an existing file in storage.
-#[derive(Clone)]
-struct SaveExists {
-    editor: EditorRef,
-    path: String,
+/// An inquirer that unlocks all editors, either by confirmation or by soliciting the
+/// keyword given when editing was locked.
+struct UnlockEditing {
+    /// Indicates that a keyword is required to unlock.
+    keyword: bool,
 }
 
-impl SaveExists {
-    fn question(editor: EditorRef, path: String) -> Option<Action> {
-        Action::as_question(SaveExists { editor, path }.to_box())
-    }
-
-    fn again(&self) -> Option<Action> {
-        Action::as_question(self.clone().to_box())
-    }
-
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+impl UnlockEditing {
+    fn question(keyword: bool) -> Option<Action> {
+        Action::as_question(Box::new(UnlockEditing { keyword }))
     }
 }
 
-impl Inquirer for SaveExists {
+impl Inquirer for UnlockEditing {
     fn prompt(&self) -> String {
-        let path = sys::pretty_path(&self.path);
-        format!("{path}: file already exists, overwrite?")
+        if self.keyword {
+            "keyword to unlock editing:".to_string()
+        } else {
+            "unlock editing of all editors?".to_string()
+        }
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::yes_no_completer()
+        if self.keyword {
+            user::null_completer()
+        } else {
+            user::yes_no_completer()
+        }
+    }
+
+    fn masked(&self) -> bool {
+        self.keyword
     }
 
     fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
         match value {
-            Some(yes_no) if yes_no == "y" => Save::save_as(&self.editor, env, &self.path),
-            Some(yes_no) if yes_no == "n" => None,
-            Some(_) => self.again(),
+            Some(keyword) if self.keyword => {
+                if env.get_edit_lock() == Some(keyword) {
+                    env.unlock_editing();
+                    Action::as_echo("editing unlocked")
+                } else {
+                    Action::as_warn("incorrect keyword")
+                }
+            }
+            Some("y") => {
+                env.unlock_editing();
+                Action::as_echo("editing unlocked")
+            }
+            Some("n") => None,
+            Some(_) => Self::question(false),
             None => None,
         }
     }
 }
 
-/// An inquirer spawned from [`Save`] that orchestrates the saving of an editor whose
-/// corresponding file in storage is newer than its timestamp.
-#[derive(Clone)]
-struct SaveOverride {
-    editor: EditorRef,
-}
-
-impl SaveOverride {
-    fn question(editor: EditorRef) -> Option<Action> {
-        Action::as_question(SaveOverride { editor }.to_box())
-    }
-
-    fn again(&self) -> Option<Action> {
-        Action::as_question(self.clone().to_box())
-    }
-
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+/// Operation: `redact-mode`
+fn redact_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let redact = editor.get_redact();
+    editor.set_redact(!redact);
+    if redact {
+        Action::as_echo("redact mode disabled")
+    } else {
+        Action::as_echo("redact mode enabled")
     }
 }
 
-impl Inquirer for SaveOverride {
-    fn prompt(&self) -> String {
-        let source = source_of(&self.editor);
-        format!("{source}: file in storage is newer, save anyway?")
+/// Operation: `wrap-mode`
+fn wrap_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let wrap = editor.get_wrap();
+    editor.set_wrap(!wrap);
+    if wrap {
+        Action::as_echo("line wrap disabled")
+    } else {
+        Action::as_echo("line wrap enabled")
     }
+}
 
-    fn completer(&self) -> Box<dyn Completer> {
-        user::yes_no_completer()
+/// Operation: `virtual-mode`
+fn virtual_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let virtual_edit = editor.get_virtual();
+    editor.set_virtual(!virtual_edit);
+    if virtual_edit {
+        Action::as_echo("virtual edit disabled")
+    } else {
+        Action::as_echo("virtual edit enabled")
     }
+}
 
-    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
-        match value {
-            Some(yes_no) if yes_no == "y" => Save::save(&self.editor),
-            Some(yes_no) if yes_no == "n" => None,
-            Some(_) => self.again(),
-            None => None,
-        }
+/// Operation: `indent-mode`
+fn indent_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let auto = editor.get_indent();
+    editor.set_indent(!auto);
+    if auto {
+        Action::as_echo("auto-indent disabled")
+    } else {
+        Action::as_echo("auto-indent enabled")
     }
 }
 
-/// Operation: `kill-window`
-fn kill_window(env: &mut Environment) -> Option<Action> {
-    if env.view_map().len() > 1 {
-        let editor = env.get_active_editor();
-        if is_dirty_file(editor) {
-            Kill::question(editor.clone(), None)
-        } else {
-            env.kill_window();
-            None
-        }
+/// Operation: `bom-mode`
+fn bom_mode(env: &mut Environment) -> Option<Action> {
+    let mut editor = env.get_active_editor().borrow_mut();
+    let bom = editor.get_bom();
+    editor.set_bom(!bom);
+    if bom {
+        Action::as_echo("byte order mark removed on save")
     } else {
-        if let Some((switch_id, _)) = next_unattached_editor(env) {
-            let editor_id = env.get_active_editor_id();
-            let editor = env.get_active_editor();
-            if is_dirty_file(editor) {
-                Kill::question(editor.clone(), Some((editor_id, switch_id)))
-            } else {
-                env.switch_editor(switch_id, Align::Auto);
-                env.close_editor(editor_id);
-                None
-            }
-        } else {
-            Action::echo_cannot_close()
-        }
+        Action::as_echo("byte order mark added on save")
     }
 }
 
-/// An inquirer that orchestrates the process of killing a window with a dirty editor
-/// attached.
-#[derive(Clone)]
-struct Kill {
+/// Operation: `set-local`
+fn set_local(env: &mut Environment) -> Option<Action> {
+    SetLocal::question(env.get_active_editor().clone())
+}
+
+/// An inquirer that gathers the name of a setting local to an editor, and upon
+/// selection, gathers its new value.
+struct SetLocal {
     editor: EditorRef,
-    close_and_switch: Option<(u32, u32)>,
 }
 
-impl Kill {
-    fn question(editor: EditorRef, close_and_switch: Option<(u32, u32)>) -> Option<Action> {
-        Action::as_question(
-            Kill {
-                editor,
-                close_and_switch,
-            }
-            .to_box(),
-        )
+impl SetLocal {
+    const PROMPT: &str = "set local:";
+
+    /// Names of settings that are local to an editor.
+    const NAMES: [&str; 10] = [
+        "auto-indent",
+        "bom",
+        "fill-column",
+        "readonly",
+        "redact",
+        "syntax",
+        "tab-hard",
+        "tab-size",
+        "virtual-edit",
+        "wrap",
+    ];
+
+    fn question(editor: EditorRef) -> Option<Action> {
+        Action::as_question(Box::new(SetLocal { editor }))
     }
 
-    fn again(&self) -> Option<Action> {
-        Action::as_question(self.clone().to_box())
+    /// Returns the value of the local setting `name` in `editor`.
+    fn value_of(editor: &Editor, name: &str) -> String {
+        match name {
+            "auto-indent" => editor.get_indent().to_string(),
+            "bom" => editor.get_bom().to_string(),
+            "fill-column" => editor.get_fill().to_string(),
+            "readonly" => editor.is_readonly().to_string(),
+            "redact" => editor.get_redact().to_string(),
+            "syntax" => editor.syntax().name,
+            "tab-hard" => editor.get_tab().to_string(),
+            "tab-size" => editor.get_tab_size().to_string(),
+            "virtual-edit" => editor.get_virtual().to_string(),
+            "wrap" => editor.get_wrap().to_string(),
+            _ => String::new(),
+        }
     }
+}
 
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+impl Inquirer for SetLocal {
+    fn prompt(&self) -> String {
+        Self::PROMPT.to_string()
     }
 
-    fn kill(&mut self, env: &mut Environment) -> Option<Action> {
-        Save::save(&self.editor).and_then(|action| {
-            self.kill_only(env);
-            Some(action)
-        })
+    fn completer(&self) -> Box<dyn Completer> {
+        user::list_completer(Self::NAMES.iter().map(|name| name.to_string()).collect())
     }
 
-    fn kill_only(&mut self, env: &mut Environment) -> Option<Action> {
-        if let Some((editor_id, switch_id)) = self.close_and_switch {
-            env.switch_editor(switch_id, Align::Auto);
-            env.close_editor(editor_id);
-        } else {
-            env.kill_window();
+    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some(name) => {
+                let name = Self::NAMES.iter().find(|n| **n == name)?;
+                let syntax_names = {
+                    let mut names = env.workspace().config().registry.names();
+                    names.push(Syntax::DEFAULT_NAME.to_string());
+                    names.sort();
+                    names
+                };
+                SetLocalValue::question(self.editor.clone(), name, syntax_names)
+            }
+            None => None,
         }
-        None
     }
 }
 
-impl Inquirer for Kill {
+/// An inquirer that gathers the value of a setting local to an editor.
+struct SetLocalValue {
+    editor: EditorRef,
+    name: &'static str,
+    syntax_names: Vec<String>,
+}
+
+impl SetLocalValue {
+    fn question(
+        editor: EditorRef,
+        name: &'static str,
+        syntax_names: Vec<String>,
+    ) -> Option<Action> {
+        Action::as_question(Box::new(SetLocalValue {
+            editor,
+            name,
+            syntax_names,
+        }))
+    }
+
+    /// Sets the local setting to `value`, returning `false` if `value` is invalid.
+    fn set(&self, env: &mut Environment, value: &str) -> bool {
+        let mut editor = self.editor.borrow_mut();
+        match (self.name, value) {
+            (
+                "auto-indent" | "bom" | "readonly" | "redact" | "tab-hard" | "virtual-edit"
+                | "wrap",
+                "true" | "false",
+            ) => {
+                let value = value == "true";
+                match self.name {
+                    "auto-indent" => editor.set_indent(value),
+                    "bom" => editor.set_bom(value),
+                    "readonly" => editor.set_readonly(value),
+                    "redact" => editor.set_redact(value),
+                    "virtual-edit" => editor.set_virtual(value),
+                    "wrap" => editor.set_wrap(value),
+                    _ => editor.set_tab(value),
+                }
+                true
+            }
+            ("fill-column" | "tab-size", _) => match value.parse::<u32>() {
+                Ok(cols) if cols > 0 => {
+                    if self.name == "fill-column" {
+                        editor.set_fill(cols);
+                    } else {
+                        editor.set_tab_size(cols);
+                    }
+                    true
+                }
+                _ => false,
+            },
+            ("syntax", _) => {
+                let syntax = if value == Syntax::DEFAULT_NAME {
+                    Some(Syntax::default())
+                } else {
+                    env.workspace().config().registry.get(value).cloned()
+                };
+                if let Some(syntax) = syntax {
+                    editor.set_syntax(syntax);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Inquirer for SetLocalValue {
     fn prompt(&self) -> String {
-        let source = source_of(&self.editor);
-        format!("{source}: save?")
+        let value = SetLocal::value_of(&self.editor.borrow(), self.name);
+        format!("{} [{value}]:", self.name)
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::yes_no_completer()
+        match self.name {
+            "auto-indent" | "bom" | "readonly" | "redact" | "tab-hard" | "virtual-edit"
+            | "wrap" => user::list_completer(vec!["false".to_string(), "true".to_string()]),
+            "syntax" => user::list_completer(self.syntax_names.clone()),
+            _ => user::number_completer(),
+        }
     }
 
     fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
         match value {
-            Some(yes_no) if yes_no == "y" => match stale_editor(&self.editor) {
-                Ok(true) => KillOverride::question(self.editor.clone(), self.close_and_switch),
-                Ok(false) => self.kill(env),
-                Err(e) => Action::as_echo(&e),
-            },
-            Some(yes_no) if yes_no == "n" => self.kill_only(env),
-            Some(_) => self.again(),
-            None => None,
+            Some(value) if !value.is_empty() => {
+                let fixed = {
+                    let editor = self.editor.borrow();
+                    editor.is_readonly() && !editor.source().is_file()
+                };
+                if self.name == "readonly" && env.get_edit_lock().is_some() {
+                    Action::as_warn("editing is locked")
+                } else if self.name == "readonly" && value == "false" && fixed {
+                    // Only editors attached to files may be made writable, which excludes
+                    // help, output, and other ephemeral buffers.
+                    Action::as_warn("readonly mode required for this editor")
+                } else if self.set(env, value) {
+                    let value = SetLocal::value_of(&self.editor.borrow(), self.name);
+                    Action::as_echo(&format!("{}: {value}", self.name))
+                } else {
+                    Action::as_warn(&format!("{value}: invalid value for {}", self.name))
+                }
+            }
+            _ => None,
         }
     }
 }
 
-/// An inquirer spawned from [`Kill`] that orchestrates the saving of an editor whose
-/// corresponding file in storage is newer than its timestamp.
-#[derive(Clone)]
-struct KillOverride {
-    editor: EditorRef,
-    close_and_switch: Option<(u32, u32)>,
+/// Operation: `run-command`
+fn run_command(_: &mut Environment) -> Option<Action> {
+    RunCommand::question()
 }
 
-impl KillOverride {
-    fn question(editor: EditorRef, close_and_switch: Option<(u32, u32)>) -> Option<Action> {
-        Action::as_question(
-            KillOverride {
-                editor,
-                close_and_switch,
-            }
-            .to_box(),
-        )
+/// Operation: `rerun-last-command`
+fn rerun_last_command(env: &mut Environment) -> Option<Action> {
+    if let Some(command) = env.get_last_command().cloned() {
+        start_command(env, &command)
+    } else {
+        Action::as_warn("no prior command")
     }
+}
 
-    fn again(&self) -> Option<Action> {
-        Action::as_question(self.clone().to_box())
+/// Operation: `print-buffer`
+fn print_buffer(env: &mut Environment) -> Option<Action> {
+    if env.is_printing() {
+        return Action::as_warn("print already in progress");
+    }
+    let config = env.workspace().config().clone();
+    let editor = env.get_active_editor().clone();
+    let (text, lines) = print_text(&editor, &config.settings);
+    let command = &config.settings.print_command;
+    let source = source_of(&editor);
+    env.set_print(
+        &format!("{source}: {lines} lines sent to {command}"),
+        Pipe::spawn(command, text),
+    );
+    Action::as_echo(&format!("{source}: printing {lines} lines"))
+}
+
+/// Returns the outcome of the running print command once it terminates, which is
+/// the message to echo if successful, otherwise `None` if the command is still
+/// running or none exists.
+pub fn poll_print(env: &mut Environment) -> Option<Result<String>> {
+    let (message, pipe) = env.take_print()?;
+    match pipe.poll() {
+        Some(result) => Some(result.map(|_| message)),
+        None => {
+            env.set_print(&message, pipe);
+            None
+        }
+    }
+}
+
+/// Returns a tuple containing the text of `editor` formatted for printing according
+/// to `settings` and the number of lines.
+fn print_text(editor: &EditorRef, settings: &Settings) -> (String, usize) {
+    let editor = editor.borrow();
+    let content = editor.buffer().iter().collect::<String>();
+    let mut text = String::new();
+    if settings.print_header {
+        text.push_str(&format!("{}\n\n", editor.source()));
+    }
+    let mut lines = 0;
+    for (i, line) in content.lines().enumerate() {
+        if settings.print_lines {
+            text.push_str(&format!("{:>6} {line}\n", i + 1));
+        } else {
+            text.push_str(&format!("{line}\n"));
+        }
+        lines += 1;
     }
+    (text, lines)
+}
 
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+/// Operation: `difftool`
+fn difftool(env: &mut Environment) -> Option<Action> {
+    let editor = env.get_active_editor().clone();
+    let path = path_of(&editor);
+    if !is_file(&editor) || !path.exists() {
+        return Action::as_warn("no file to compare");
+    }
+    if editor.borrow().is_loading() {
+        return Action::as_error(&Error::loading(&path.as_string()));
+    }
+    if editor.borrow().crypt().is_some() {
+        // Decrypted buffer must never be written to storage, and comparing with the
+        // encrypted file would be meaningless anyway.
+        return Action::as_warn("cannot compare encrypted file");
     }
 
-    fn kill(&mut self, env: &mut Environment) -> Option<Action> {
-        Save::save(&self.editor).and_then(|action| {
-            if let Some((editor_id, switch_id)) = self.close_and_switch {
-                env.switch_editor(switch_id, Align::Auto);
-                env.close_editor(editor_id);
+    // Buffer is written to a temporary file that retains the file name of the source,
+    // which allows diff tools to recognize its syntax.
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_path = match io::create_temp_file(std::env::temp_dir(), &name, 0o600) {
+        Ok((temp_path, file)) => {
+            if let Err(e) = io::write_to(file, &temp_path, &editor.borrow().buffer(), false) {
+                let _ = fs::remove_file(&temp_path);
+                return Action::as_error(&e);
+            }
+            temp_path
+        }
+        Err(e) => return Action::as_error(&e),
+    };
+
+    // Terminal is relinquished to diff tool and resumed afterwards, even if the diff
+    // tool could not be run, after which all windows are redrawn since the screen
+    // was cleared.
+    let command = env.workspace().config().settings.diff_tool.clone();
+    let result = term::suspend().and_then(|_| {
+        let status = job::attach(&command, &[&path.as_string(), &temp_path.as_string()]);
+        let resumed = term::resume();
+        status.and_then(|status| resumed.map(|_| status))
+    });
+    env.redraw();
+
+    // Changes made to the temporary file are loaded back into the buffer.
+    let result = result.and_then(|_| changes_of(&editor, &temp_path));
+    let _ = fs::remove_file(&temp_path);
+    match result {
+        Ok(Some(text)) => {
+            let mut editor = editor.borrow_mut();
+            if let Some(editor) = editor.modify() {
+                // Replacement is recorded in the undo stack as a single change, and
+                // the cursor remains at the same buffer position if possible.
+                let cur_pos = editor.pos();
+                let end_pos = editor.buffer().size();
+                editor.move_to(0, Align::Auto);
+                let _ = editor.replace(end_pos, &text);
+                editor.move_to(cmp::min(cur_pos, text.len()), Align::Center);
+                editor.render();
+                Action::as_echo(&format!("changes from {command} applied"))
             } else {
-                env.kill_window();
+                Action::as_warn(&format!(
+                    "changes from {command} not applied: editor is readonly"
+                ))
             }
-            Some(action)
-        })
+        }
+        Ok(None) => None,
+        Err(e) => Action::as_error(&e),
     }
 }
 
-impl Inquirer for KillOverride {
+/// Returns the contents of the file at `path` if they differ from the contents of
+/// `editor`, otherwise `None`.
+fn changes_of(editor: &EditorRef, path: &Path) -> Result<Option<Vec<char>>> {
+    let mut buffer = Buffer::new();
+    let _ = io::read_file(path, &mut buffer)?;
+    if buffer.iter().eq(editor.borrow().buffer().iter()) {
+        Ok(None)
+    } else {
+        Ok(Some(buffer.iter().collect()))
+    }
+}
+
+struct RunCommand;
+
+impl RunCommand {
+    const PROMPT: &str = "run command:";
+
+    fn question() -> Option<Action> {
+        Action::as_question(Box::new(RunCommand))
+    }
+}
+
+impl Inquirer for RunCommand {
     fn prompt(&self) -> String {
-        let source = source_of(&self.editor);
-        format!("{source}: file in storage is newer, save anyway?")
+        Self::PROMPT.to_string()
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        user::yes_no_completer()
+        user::null_completer()
     }
 
     fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
         match value {
-            Some(yes_no) if yes_no == "y" => self.kill(env),
-            Some(yes_no) if yes_no == "n" => None,
-            Some(_) => self.again(),
-            None => None,
+            Some(command) if !command.trim().is_empty() => start_command(env, command.trim()),
+            _ => None,
         }
     }
 }
 
-/// Operation: `close-window`
-fn close_window(env: &mut Environment) -> Option<Action> {
-    if let Some(_) = env.close_window() {
+/// Name of the editor receiving output from commands.
+const OUTPUT_EDITOR_NAME: &str = "output";
+
+/// Runs `command` and directs its output to a new readonly editor, which replaces the
+/// prior output editor if one exists.
+///
+/// Editor variables in `command` are expanded prior to running, though `command` is
+/// retained in its unexpanded form as the last command so that variables are expanded
+/// again when rerun.
+///
+/// The output editor is placed in a window at the bottom of the workspace, though the
+/// active window does not change.
+fn start_command(env: &mut Environment, command: &str) -> Option<Action> {
+    let expanded = variables_of(env).expand(command);
+    let job = match Job::spawn(&expanded) {
+        Ok(job) => job,
+        Err(e) => return Action::as_error(&e),
+    };
+    env.set_last_command(command);
+
+    let mut buffer = Buffer::new();
+    buffer.insert_str(&format!("$ {expanded}\n"));
+    let config = env.workspace().config().clone();
+    let source = Source::as_ephemeral(OUTPUT_EDITOR_NAME);
+    let editor = Editor::readonly(config, source, buffer).to_ref();
+
+    if let Some((_, editor_id)) = replace_editor(env, editor, Align::Bottom) {
+        env.set_job(editor_id, job);
         None
     } else {
-        Action::echo_cannot_close()
+        Action::echo_no_window()
     }
 }
 
-/// Operation: `close-other-windows`
-fn close_other_windows(env: &mut Environment) -> Option<Action> {
-    let active_id = env.get_active_view_id();
-    let other_ids = env
-        .view_map()
-        .keys()
-        .cloned()
-        .filter(|id| *id != active_id)
-        .collect::<Vec<_>>();
-    for id in other_ids {
-        env.close_window_for(id);
-    }
-    None
-}
+/// Appends output collected from the running job, if any, to its editor and returns
+/// `true` if the editor was rendered.
+///
+/// Once the job terminates, its exit status is appended and the job is discarded.
+/// The job is also killed if its editor was closed in the meantime.
+pub fn poll_job(env: &mut Environment) -> bool {
+    let (editor_id, mut job) = match env.take_job() {
+        Some(job) => job,
+        None => return false,
+    };
 
-/// Operation: `top-window`
-fn top_window(env: &mut Environment) -> Option<Action> {
-    env.set_active(Focus::Top);
-    None
-}
+    let editor = match env.editor_map().get(&editor_id) {
+        Some(editor) => editor.clone(),
+        None => {
+            // Dropping the job kills the underlying process.
+            return false;
+        }
+    };
 
-/// Operation: `bottom-window`
-fn bottom_window(env: &mut Environment) -> Option<Action> {
-    env.set_active(Focus::Bottom);
-    None
-}
+    let (mut text, status) = job.poll();
+    let done = status.is_some();
+    if let Some(status) = status {
+        // Ensure exit status appears on its own line.
+        let last_char = text.chars().last().or_else(|| {
+            let editor = editor.borrow();
+            let buffer = editor.buffer();
+            buffer
+                .size()
+                .checked_sub(1)
+                .and_then(|pos| buffer.get_char(pos))
+        });
+        if last_char.is_some_and(|c| c != '\n') {
+            text.push('\n');
+        }
+        text.push_str(&format!("[{status}]\n"));
+    } else {
+        env.set_job(editor_id, job);
+    }
 
-/// Operation: `prev-window`
-fn prev_window(env: &mut Environment) -> Option<Action> {
-    env.set_active(Focus::Above);
-    None
+    let mut rendered = false;
+    if !text.is_empty() {
+        let mut editor = editor.borrow_mut();
+        editor.append(&text);
+        if env.find_editor_view_id(editor_id).is_some() {
+            editor.render();
+            rendered = true;
+        }
+    }
+    if done {
+        rendered |= show_diagnostics(env, &editor);
+    }
+    rendered
 }
 
-/// Operation: `next-window`
-fn next_window(env: &mut Environment) -> Option<Action> {
-    env.set_active(Focus::Below);
-    None
-}
+/// Shows the diagnostics found in the output of a finished command as hints at the end
+/// of the corresponding lines in editors of files, replacing the hints of any prior
+/// command, and returns `true` if an editor attached to a window was rendered.
+///
+/// A diagnostic is a line of output of the form `path:line:col: message`, where `col`
+/// is optional, which is the format produced by most compilers and by `grep -n`.
+/// Relative paths are resolved against the working directory.
+fn show_diagnostics(env: &Environment, output: &EditorRef) -> bool {
+    let text = {
+        let editor = output.borrow();
+        let buffer = editor.buffer();
+        buffer.copy_as_string(0, buffer.size())
+    };
+    let diagnostics = text
+        .lines()
+        .filter_map(diagnostic_of)
+        .map(|(path, line, col, message)| (sys::canonicalize(path), line, col, message))
+        .collect::<Vec<_>>();
+    let style = Style {
+        fg: Some(env.workspace().config().theme.warn_fg),
+        bg: None,
+    };
 
-/// Operation: `select-editor`
-fn select_editor(env: &mut Environment) -> Option<Action> {
-    let editors = unattached_editors(env, true);
-    if editors.len() > 0 {
-        SelectEditor::question(editors, None)
-    } else {
-        Action::echo_no_editors()
+    let mut rendered = false;
+    for (editor_id, editor) in env.editor_map().iter() {
+        if !is_file(editor) {
+            continue;
+        }
+        let path = sys::canonicalize(path_of(editor));
+        let mut editor = editor.borrow_mut();
+        editor.clear_annotations(Lane::Diagnostic);
+        let hints = {
+            let buffer = editor.buffer();
+            let mut lines = None;
+            diagnostics
+                .iter()
+                .filter(|(p, ..)| *p == path)
+                .filter_map(|(_, line, col, message)| {
+                    let lines = *lines.get_or_insert_with(|| buffer.line_of(buffer.size()) + 1);
+                    diagnostic_pos(&buffer, lines, *line, *col).map(|pos| (pos, *message))
+                })
+                .collect::<Vec<_>>()
+        };
+        for (pos, message) in hints {
+            editor.add_hint(Lane::Diagnostic, pos, message, style);
+        }
+        if env.find_editor_view_id(*editor_id).is_some() {
+            editor.render();
+            rendered = true;
+        }
     }
+    rendered
 }
 
-/// Operation: `select-editor-top`
-fn select_editor_top(env: &mut Environment) -> Option<Action> {
-    let editors = unattached_editors(env, true);
-    if editors.len() > 0 {
-        SelectEditor::question(editors, Some(Placement::Top))
+/// Returns the path, `1`-based line and column, and message of the diagnostic in
+/// `line` of command output, or `None` if `line` is not a diagnostic.
+///
+/// The column defaults to `1` when absent.
+fn diagnostic_of(line: &str) -> Option<(&str, u32, u32, &str)> {
+    let (path, rest) = line.split_once(':')?;
+    let (line, rest) = rest.split_once(':')?;
+    let line = line.parse::<u32>().ok().filter(|line| *line > 0)?;
+    let (col, message) = match rest.split_once(':') {
+        Some((col, message)) => match col.parse::<u32>() {
+            Ok(col) => (col, message),
+            Err(_) => (1, rest),
+        },
+        None => (1, rest),
+    };
+    let message = message.trim();
+    if path.is_empty() || path.starts_with(char::is_whitespace) || message.is_empty() {
+        None
     } else {
-        Action::echo_no_editors()
+        Some((path, line, col, message))
     }
 }
 
-/// Operation: `select-editor-bottom`
-fn select_editor_bottom(env: &mut Environment) -> Option<Action> {
-    let editors = unattached_editors(env, true);
-    if editors.len() > 0 {
-        SelectEditor::question(editors, Some(Placement::Bottom))
+/// Returns the buffer position of the `1`-based `line` and `col`, where `col` is
+/// clamped to the end of the line, or `None` if `line` exceeds the number of `lines`
+/// in `buffer`.
+fn diagnostic_pos(buffer: &Buffer, lines: u32, line: u32, col: u32) -> Option<usize> {
+    if line > lines {
+        None
     } else {
-        Action::echo_no_editors()
+        let line_pos = buffer.find_line(line - 1);
+        let (next_pos, eob) = buffer.find_next_line(line_pos);
+        let end_pos = if eob { next_pos } else { next_pos - 1 };
+        Some(cmp::min(line_pos + col.saturating_sub(1) as usize, end_pos))
     }
 }
 
-/// Operation: `select-editor-above`
-fn select_editor_above(env: &mut Environment) -> Option<Action> {
-    let editors = unattached_editors(env, true);
-    if editors.len() > 0 {
-        SelectEditor::question(editors, Some(Placement::Above(env.get_active_view_id())))
-    } else {
-        Action::echo_no_editors()
+/// Loads the next portion of a large file for the first editor still loading,
+/// returning `true` if content was appended to an editor attached to a window.
+///
+/// An error is returned if loading fails, in which case the editor retains only the
+/// content loaded up to that point.
+pub fn poll_load(env: &mut Environment) -> Result<bool> {
+    // Upper bound on time spent loading so that keys remain responsive.
+    const LOAD_BUDGET: Duration = Duration::from_millis(50);
+
+    let loading = env
+        .editor_map()
+        .iter()
+        .find(|(_, e)| e.borrow().is_loading())
+        .map(|(id, e)| (*id, e.clone()));
+    if let Some((editor_id, editor)) = loading {
+        let mut editor = editor.borrow_mut();
+        let loaded = editor.load(LOAD_BUDGET)?;
+        if loaded && env.find_editor_view_id(editor_id).is_some() {
+            editor.render();
+            return Ok(true);
+        }
     }
+    Ok(false)
 }
 
-/// Operation: `select-editor-below`
-fn select_editor_below(env: &mut Environment) -> Option<Action> {
-    let editors = unattached_editors(env, true);
-    if editors.len() > 0 {
-        SelectEditor::question(editors, Some(Placement::Below(env.get_active_view_id())))
-    } else {
-        Action::echo_no_editors()
+/// Checks whether the files of editors were changed in storage by other programs,
+/// marking such editors as _stale_, and returns a question asking whether to reload
+/// the editor in the active window if it became stale since the last check, or if
+/// `focused` is `true` and it is already stale.
+///
+/// Modification times are compared first, and only if they differ are the contents
+/// hashed to confirm the change, so a file that was merely touched is not stale.
+pub fn poll_stale(env: &mut Environment, focused: bool) -> Option<Action> {
+    let active_id = env.get_active_editor_id();
+    let mut changed = false;
+    for (editor_id, editor) in env.editor_map().iter() {
+        if check_stale(editor) && *editor_id == active_id {
+            changed = true;
+        }
     }
-}
 
-/// Operation: `prev-editor`
-fn prev_editor(env: &mut Environment) -> Option<Action> {
-    if let Some((prev_id, _)) = prev_unattached_editor(env) {
-        env.switch_editor(prev_id, Align::Auto);
+    let editor = env.get_active_editor().clone();
+    let stale = editor.borrow().stale().is_some();
+    if changed || (focused && stale) {
+        ReloadFile::question(editor)
+    } else {
+        None
     }
-    None
 }
 
-/// Operation: `next-editor`
-fn next_editor(env: &mut Environment) -> Option<Action> {
-    if let Some((next_id, _)) = next_unattached_editor(env) {
-        env.switch_editor(next_id, Align::Auto);
+/// Returns `true` if the file of `editor` changed in storage since it was last
+/// checked, in which case the editor is marked as _stale_.
+///
+/// If the modification time of the file changed but its contents did not, the new
+/// time is remembered so the contents are not hashed again, and any prior mark is
+/// cleared since the file was restored to what the editor last read or wrote.
+fn check_stale(editor: &EditorRef) -> bool {
+    let (path, stamp) = match editor.borrow().source() {
+        Source::File(path, Some(stamp)) => (path.clone(), *stamp),
+        _ => return false,
+    };
+    if editor.borrow().is_loading() {
+        return false;
+    }
+    let time = match io::get_time(&path) {
+        Ok(time) if time != stamp.time && editor.borrow().stale() != Some(time) => time,
+        _ => return false,
+    };
+    match stale_editor(editor) {
+        Ok(true) => {
+            editor.borrow_mut().set_stale(Some(time));
+            true
+        }
+        Ok(false) => {
+            let mut editor = editor.borrow_mut();
+            editor.assume(Source::as_file(&path, Some(Stamp { time, ..stamp })));
+            editor.set_stale(None);
+            false
+        }
+        Err(_) => false,
     }
-    None
 }
 
-/// An iquirer that orchetrates the selection of an editor by name, replacing the editor
-/// in the active window.
-struct SelectEditor {
-    /// Unattached editors available for selection.
-    editors: Vec<(u32, EditorRef)>,
-
-    /// Where to open the new window if specified, otherwise is replaces the editor in
-    /// the current window.
-    place: Option<Placement>,
+/// An inquirer that orchestrates reloading an editor whose corresponding file in
+/// storage was changed by another program.
+#[derive(Clone)]
+struct ReloadFile {
+    editor: EditorRef,
 }
 
-impl SelectEditor {
-    const PROMPT: &str = "select editor:";
-
-    fn question(editors: Vec<(u32, EditorRef)>, place: Option<Placement>) -> Option<Action> {
-        Action::as_question(SelectEditor { editors, place }.to_box())
+impl ReloadFile {
+    fn question(editor: EditorRef) -> Option<Action> {
+        Action::as_question(Box::new(ReloadFile { editor }))
     }
 
-    fn to_box(self) -> Box<dyn Inquirer> {
-        Box::new(self)
+    fn again(&self) -> Option<Action> {
+        Action::as_question(Box::new(self.clone()))
     }
 }
 
-impl Inquirer for SelectEditor {
+impl Inquirer for ReloadFile {
     fn prompt(&self) -> String {
-        Self::PROMPT.to_string()
+        let source = source_of(&self.editor);
+        if self.editor.borrow().is_dirty() {
+            format!("{source}: file in storage has changed, discard changes and reload?")
+        } else {
+            format!("{source}: file in storage has changed, reload?")
+        }
     }
 
     fn completer(&self) -> Box<dyn Completer> {
-        let accepted = self.editors.iter().map(|(_, e)| source_of(e)).collect();
-        user::list_completer(accepted)
+        user::yes_no_completer()
     }
 
-    fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action> {
-        if let Some(value) = value {
-            let editor = self
-                .editors
-                .iter()
-                .find(|(_, e)| source_of(e) == value)
-                .map(|(id, _)| *id);
-            if let Some(editor_id) = editor {
-                if let Some(place) = self.place {
-                    if let Some(view_id) = env.open_window(editor_id, place, Align::Auto) {
-                        env.set_active(Focus::To(view_id));
-                        None
-                    } else {
-                        Action::echo_no_window()
-                    }
-                } else {
-                    env.switch_editor(editor_id, Align::Auto);
-                    None
-                }
-            } else {
-                Action::as_echo("{value}: editor not found")
-            }
-        } else {
-            None
+    fn respond(&mut self, _: &mut Environment, value: Option<&str>) -> Option<Action> {
+        match value {
+            Some("y") => match reload_editor(&self.editor) {
+                Ok(()) => Action::as_echo(&format!("{}: reloaded", source_of(&self.editor))),
+                Err(e) => Action::as_error(&e),
+            },
+            Some("n") => None,
+            Some(_) => self.again(),
+            None => None,
         }
     }
 }
 
-/// Operation: `describe-editor`
-fn describe_editor(env: &mut Environment) -> Option<Action> {
-    let editor = env.get_active_editor().borrow();
-    let buffer = editor.buffer();
-    let (c_char, c_code) = if let Some(c) = buffer.get_char(editor.pos()) {
-        let c_char = if c.is_control() {
-            "".to_string()
-        } else {
-            format!("'{c}' ")
-        };
-        (c_char, format!("\\u{:04x}", c as u32))
-    } else {
-        ("EOF".to_string(), "".to_string())
-    };
-    let text = format!(
-        "characters: {} | lines: {} | cursor: {}{}",
-        buffer.size(),
-        buffer.line_of(usize::MAX) + 1,
-        c_char,
-        c_code,
-    );
-    Action::as_echo(&text)
-}
-
-/// Operation: `tab-mode`
-fn tab_mode(env: &mut Environment) -> Option<Action> {
-    let mut editor = env.get_active_editor().borrow_mut();
-    let hard = editor.get_tab();
-    editor.set_tab(!hard);
-    if hard {
-        Action::as_echo("soft tabs enabled")
-    } else {
-        Action::as_echo("hard tabs enabled")
+/// Reads the file of `editor` again, replacing its buffer as a single change that
+/// can be undone, and clears the dirty flag.
+fn reload_editor(editor: &EditorRef) -> Result<()> {
+    let path = path_of(editor).as_string();
+    let config = editor.borrow().config().clone();
+    let crypt = editor.borrow().crypt().cloned();
+    let mut buffer = Buffer::new();
+    let stamp = Retry::new(&config.settings).run(|| {
+        buffer = Buffer::new();
+        match &crypt {
+            Some(crypt) => crypt.read_file(&path, &mut buffer),
+            None => io::read_file(&path, &mut buffer),
+        }
+    })?;
+    let bom = bom_of(&config.settings, io::strip_bom(&mut buffer));
+    {
+        let mut editor = editor.borrow_mut();
+        editor.reload(&buffer.iter().collect::<Vec<_>>());
+        editor.set_bom(bom);
+        editor.render();
     }
+    update_editor(editor, &path, stamp);
+    Ok(())
 }
 
 /// Scrolls the display down for the editor associated with `p`, which represents a
@@ -1659,26 +5438,29 @@ fn tab_mode(env: &mut Environment) -> Option<Action> {
 pub fn track_up(env: &mut Environment, p: Point, select: bool) {
     let view = env.workspace().locate_view(p);
     if let Some((view_id, _)) = view {
-        let mut editor = env.get_view_editor(view_id).borrow_mut();
+        {
+            let mut editor = env.get_view_editor(view_id).borrow_mut();
 
-        // Update soft mark if selection is active, otherwise capture current buffer
-        // position before scrolling in case soft mark needs to be cleared.
-        let prior_pos = if select {
-            editor.set_soft_mark();
-            None
-        } else {
-            Some(editor.pos())
-        };
-        editor.scroll_down(1);
+            // Update soft mark if selection is active, otherwise capture current
+            // buffer position before scrolling in case soft mark needs to be cleared.
+            let prior_pos = if select {
+                editor.set_soft_mark();
+                None
+            } else {
+                Some(editor.pos())
+            };
+            editor.scroll_down(1);
 
-        // If selection is inactive and buffer position moved as a result of scrolling,
-        // then soft mark must be cleared.
-        if let Some(prior_pos) = prior_pos {
-            if editor.pos() != prior_pos {
-                editor.clear_soft_mark();
+            // If selection is inactive and buffer position moved as a result of
+            // scrolling, then soft mark must be cleared.
+            if let Some(prior_pos) = prior_pos {
+                if editor.pos() != prior_pos {
+                    editor.clear_soft_mark();
+                }
             }
+            editor.render();
         }
-        editor.render();
+        scroll_locked(env, view_id, |editor| editor.scroll_down(1));
     }
 }
 
@@ -1687,26 +5469,29 @@ pub fn track_up(env: &mut Environment, p: Point, select: bool) {
 pub fn track_down(env: &mut Environment, p: Point, select: bool) {
     let view = env.workspace().locate_view(p);
     if let Some((view_id, _)) = view {
-        let mut editor = env.get_view_editor(view_id).borrow_mut();
+        {
+            let mut editor = env.get_view_editor(view_id).borrow_mut();
 
-        // Update soft mark if selection is active, otherwise capture current buffer
-        // position before scrolling in case soft mark needs to be cleared.
-        let prior_pos = if select {
-            editor.set_soft_mark();
-            None
-        } else {
-            Some(editor.pos())
-        };
-        editor.scroll_up(1);
+            // Update soft mark if selection is active, otherwise capture current
+            // buffer position before scrolling in case soft mark needs to be cleared.
+            let prior_pos = if select {
+                editor.set_soft_mark();
+                None
+            } else {
+                Some(editor.pos())
+            };
+            editor.scroll_up(1);
 
-        // If selection is inactive and buffer position moved as a result of scrolling,
-        // then soft mark must be cleared.
-        if let Some(prior_pos) = prior_pos {
-            if editor.pos() != prior_pos {
-                editor.clear_soft_mark();
+            // If selection is inactive and buffer position moved as a result of
+            // scrolling, then soft mark must be cleared.
+            if let Some(prior_pos) = prior_pos {
+                if editor.pos() != prior_pos {
+                    editor.clear_soft_mark();
+                }
             }
+            editor.render();
         }
-        editor.render();
+        scroll_locked(env, view_id, |editor| editor.scroll_up(1));
     }
 }
 
@@ -1755,19 +5540,210 @@ pub fn set_focus(env: &mut Environment, p: Point) {
     }
 }
 
+/// Pastes the contents of the clipboard into the editor associated with `p`, which
+/// represents a point whose origin is the top-left position of the terminal display,
+/// at the corresponding cursor position.
+pub fn paste_at(env: &mut Environment, p: Point) -> Option<Action> {
+    let view = env.workspace().locate_view(p);
+    if view.is_some() {
+        set_focus(env, p);
+        paste(env)
+    } else {
+        None
+    }
+}
+
+/// Returns `true` if any editor whose source is a file contains unsaved changes.
+pub fn has_dirty_editors(env: &Environment) -> bool {
+    dirty_count(env) > 0
+}
+
+/// Returns the number of editors whose source is a file that contain unsaved
+/// changes.
+pub fn dirty_count(env: &Environment) -> usize {
+    env.editor_map()
+        .values()
+        .filter(|e| is_dirty_file(e))
+        .count()
+}
+
+/// Captures the editors whose source is a file, along with the arrangement of windows,
+/// as a session.
+///
+/// Windows attached to editors that are not files are omitted from the session, and
+/// if the _active_ window is among them, the first window becomes the _active_ window.
+pub fn capture_session(env: &Environment) -> Session {
+    let mut editors = Vec::new();
+    let mut index_map = HashMap::new();
+    for (editor_id, editor) in env.editor_map() {
+        let editor = editor.borrow();
+        if let Source::File(path, _) = editor.source() {
+            let loc = editor.location();
+            index_map.insert(*editor_id, editors.len());
+            editors.push(SessionEditor {
+                path: path.clone(),
+                line: loc.row,
+                col: loc.col,
+                row: editor.cursor().row,
+            });
+        }
+    }
+    let (views, active) = env.get_arrangement();
+    let active_id = views.get(active).map(|pane| pane.editor_id);
+    let windows = Pane::retain(views, |pane| index_map.contains_key(&pane.editor_id))
+        .into_iter()
+        .filter_map(|pane| {
+            index_map.get(&pane.editor_id).map(|index| SessionWindow {
+                editor: *index,
+                ruler: pane.ruler,
+                beside: pane.beside,
+                rows: pane.rows,
+            })
+        })
+        .collect::<Vec<_>>();
+    let active = active_id
+        .and_then(|id| index_map.get(&id))
+        .and_then(|index| windows.iter().position(|w| w.editor == *index))
+        .unwrap_or(0);
+    Session {
+        active,
+        editors,
+        windows,
+    }
+}
+
+/// Restores `session` by opening editors for its files, moving the cursor of each to
+/// its saved location, and arranging windows accordingly, returning `false` if none
+/// of its windows could be restored.
+///
+/// Editors already open with the same file are reused rather than opened again, and
+/// files that no longer exist or are encrypted are skipped.
+pub fn restore_session(env: &mut Environment, session: &Session) -> Result<bool> {
+    let config = env.workspace().config().clone();
+    let mut editor_ids = Vec::new();
+    for saved in &session.editors {
+        let source = Source::as_file(&saved.path, None).to_string();
+        let editor_id = if let Some(editor_id) = env.find_editor_id(&source) {
+            Some(editor_id)
+        } else if sys::is_file(&saved.path) && !crypt::is_encrypted(&saved.path, &config.settings) {
+            let editor = open_editor(config.clone(), &saved.path)?;
+            Some(env.add_editor(editor))
+        } else {
+            None
+        };
+
+        // Since editors can only align the cursor while attached to a window, those
+        // not already attached are temporarily attached to the active window.
+        if let Some(editor_id) = editor_id {
+            if env.find_editor_view_id(editor_id).is_none() {
+                env.switch_editor(editor_id, Align::Auto);
+            }
+            if let Some(editor) = env.editor_map().get(&editor_id) {
+                editor
+                    .borrow_mut()
+                    .move_line_col(saved.line, saved.col, Align::Row(saved.row));
+            }
+        }
+        editor_ids.push(editor_id);
+    }
+
+    // Windows whose editors were not restored are omitted, though this is determined
+    // while the ids of panes still refer to indexes of editors in the session.
+    let editor_of = |index: u32| editor_ids.get(index as usize).cloned().flatten();
+    let active_id = session
+        .windows
+        .get(session.active)
+        .and_then(|w| editor_of(w.editor as u32));
+    let panes = session
+        .windows
+        .iter()
+        .map(|w| Pane {
+            editor_id: w.editor as u32,
+            ruler: w.ruler,
+            beside: w.beside,
+            rows: w.rows,
+        })
+        .collect::<Vec<_>>();
+    let views = Pane::retain(panes, |pane| editor_of(pane.editor_id).is_some())
+        .into_iter()
+        .filter_map(|pane| editor_of(pane.editor_id).map(|editor_id| Pane { editor_id, ..pane }))
+        .collect::<Vec<_>>();
+    let active = views
+        .iter()
+        .position(|pane| Some(pane.editor_id) == active_id)
+        .unwrap_or(0);
+    Ok(env.set_arrangement(views, active))
+}
+
+/// Reads the encrypted file at `path`, decrypting its contents using `passphrase`,
+/// and returns a new editor that encrypts its buffer using the same `passphrase` when
+/// saved.
+///
+/// If the file does not exist, the editor is empty.
+fn open_crypt_editor(config: ConfigurationRef, path: &str, passphrase: &str) -> Result<EditorRef> {
+    let crypt = Crypt::new(&config.settings, passphrase);
+    let mut buffer = Buffer::new();
+    let stamp = if Path::new(path).exists() {
+        let stamp = Retry::new(&config.settings).run(|| {
+            buffer = Buffer::new();
+            crypt.read_file(path, &mut buffer)
+        })?;
+        Some(stamp)
+    } else {
+        None
+    };
+    let bom = bom_of(&config.settings, io::strip_bom(&mut buffer));
+    buffer.set_pos(0);
+    let mut editor = Editor::mutable(config, Source::as_file(path, stamp), Some(buffer));
+    editor.set_crypt(Some(crypt));
+    editor.set_bom(bom);
+    if !sys::is_writable(path) {
+        editor.set_protected();
+    }
+    Ok(editor.to_ref())
+}
+
+/// Attaches `editor` to a new window at `place` if specified, otherwise it replaces
+/// the editor in the active window.
+fn place_editor(
+    env: &mut Environment,
+    editor: EditorRef,
+    place: Option<Placement>,
+) -> Option<Action> {
+    if let Some(place) = place {
+        if let Some((view_id, _)) = env.open_editor(editor, place, Align::Auto) {
+            env.set_active(Focus::To(view_id));
+            None
+        } else {
+            Action::echo_no_window()
+        }
+    } else {
+        env.set_editor(editor, Align::Auto);
+        None
+    }
+}
+
 /// Reads the file at `path` and returns a new editor.
 pub fn open_editor(config: ConfigurationRef, path: &str) -> Result<EditorRef> {
-    // Try reading file contents into buffer.
+    // Try reading file contents into buffer, though only the first chunk of a large
+    // file is read so the remainder can be loaded incrementally. Reading is retried
+    // from scratch if it fails with a transient error.
     let mut buffer = Buffer::new();
-    let time = match io::read_file(path, &mut buffer) {
-        Ok(_) => {
-            // Contents read successfully, so fetch time of last modification for use
-            // in checking before subsequent write operation.
-            io::get_time(path).ok()
+    let mut loader = None;
+    let result = Retry::new(&config.settings).run(|| {
+        buffer = Buffer::new();
+        read_first(path, &mut buffer, &mut loader)
+    });
+    let stamp = match result {
+        Ok(stamp) => {
+            // Contents read successfully, so keep stamp for use in checking before
+            // subsequent write operation, noting that digest of a large file is only
+            // known once loading finishes.
+            Some(stamp)
         }
         Err(Error::Io { path: _, cause }) if cause.kind() == ErrorKind::NotFound => {
             // File was not found, but still treat this error condition as successful,
-            // though note that last modification time is absent to indicate new file.
+            // though note that stamp is absent to indicate new file.
             None
         }
         Err(e) => {
@@ -1776,12 +5752,90 @@ pub fn open_editor(config: ConfigurationRef, path: &str) -> Result<EditorRef> {
         }
     };
 
-    // Create file buffer with position set at top.
+    // Pre-fill buffer of new file with matching template if enabled, though note that
+    // failure to read the template is quietly ignored since the file itself is absent.
+    if stamp.is_none() && buffer.size() == 0 && config.settings.templates {
+        let vars = Variables::new(Some(Path::new(path)), None);
+        if let Ok(Some(text)) = template::load(path, &vars) {
+            buffer.insert_str(&text);
+        }
+    }
+
+    // Byte order mark is removed from buffer but remembered so it can be written
+    // when saved, depending on configuration.
+    let bom = bom_of(&config.settings, io::strip_bom(&mut buffer));
+
+    // Create file buffer with position set at top, though files that cannot be
+    // written are opened as readonly.
     buffer.set_pos(0);
-    let editor = Editor::mutable(config, Source::as_file(path, time), Some(buffer));
+    let mut editor = Editor::mutable(config, Source::as_file(path, stamp), Some(buffer));
+    editor.set_bom(bom);
+    if !sys::is_writable(path) {
+        editor.set_protected();
+    }
+    if let Some(loader) = loader {
+        editor.set_loader(loader);
+    }
     Ok(editor.to_ref())
 }
 
+/// Reads the file at `path` into `buf`, unless the file is large enough to warrant
+/// incremental loading, in which case only the first chunk is read and `loader` is
+/// set to read the remainder, returning the [`Stamp`] of the file.
+///
+/// The digest of the stamp is absent if `loader` is set, since it is only known once
+/// the remainder is read.
+fn read_first(path: &str, buf: &mut Buffer, loader: &mut Option<Loader>) -> Result<Stamp> {
+    if let Some(mut l) = Loader::open(path)? {
+        // Reserving capacity for the entire file avoids repeatedly growing the buffer
+        // as chunks are appended, since characters never outnumber bytes.
+        *buf = Buffer::with_capacity(l.size() as usize);
+        if let Some(text) = l.read_chunk()? {
+            buf.insert_str(&text);
+        }
+        let stamp = Stamp {
+            time: l.time(),
+            digest: None,
+        };
+        *loader = Some(l);
+        Ok(stamp)
+    } else {
+        io::read_file(path, buf)
+    }
+}
+
+/// Returns `true` if an editor should write a byte order mark when saved according
+/// to the `bom` setting, where `present` indicates whether the file contained one.
+fn bom_of(settings: &Settings, present: bool) -> bool {
+    match settings.bom {
+        ByteOrderMark::Preserve => present,
+        ByteOrderMark::Strip => false,
+        ByteOrderMark::Add => true,
+    }
+}
+
+/// Attaches `editor` to the window of an existing editor with the same source, which
+/// is then closed, otherwise `editor` is attached to a new window at the bottom of the
+/// workspace.
+///
+/// Returns a tuple containing the view id and editor id, or `None` if a new window
+/// could not be created.
+fn replace_editor(env: &mut Environment, editor: EditorRef, align: Align) -> Option<(u32, u32)> {
+    let source = source_of(&editor);
+    if let Some(prior_id) = env.find_editor_id(&source) {
+        if let Some(view_id) = env.find_editor_view_id(prior_id) {
+            let editor_id = env.set_editor_for(view_id, editor, align);
+            env.close_editor(prior_id);
+            Some((view_id, editor_id))
+        } else {
+            env.close_editor(prior_id);
+            env.open_editor(editor, Placement::Bottom, align)
+        }
+    } else {
+        env.open_editor(editor, Placement::Bottom, align)
+    }
+}
+
 /// Combines [`write_editor`] and [`update_editor`] into a single operation.
 fn save_editor(editor: &EditorRef) -> Result<()> {
     save_editor_as(editor, None)
@@ -1796,30 +5850,68 @@ fn save_editor_as(editor: &EditorRef, path: Option<&str>) -> Result<()> {
     write_editor(editor, &path).map(|time| update_editor(editor, &path, time))
 }
 
-/// Writes the buffer of `editor` to `path` and returns the resulting file modification
-/// time.
-fn write_editor(editor: &EditorRef, path: &str) -> Result<SystemTime> {
-    let _ = io::write_file(path, &editor.borrow().buffer())?;
-    io::get_time(path)
+/// Writes the buffer of `editor` to `path` and returns the resulting stamp of the
+/// file.
+///
+/// If `editor` is attached to an encrypted file, the buffer is encrypted before being
+/// written.
+fn write_editor(editor: &EditorRef, path: &str) -> Result<Stamp> {
+    let (bytes, retry) = {
+        let editor = editor.borrow();
+        if editor.is_loading() {
+            return Err(Error::loading(path));
+        }
+        let bytes = if let Some(crypt) = editor.crypt() {
+            crypt.encrypt(&editor.buffer(), editor.get_bom())?
+        } else {
+            io::encode(&editor.buffer(), editor.get_bom())
+        };
+        (Arc::new(bytes), Retry::new(&editor.config().settings))
+    };
+
+    // Writing is retried if it fails with a transient error, showing progress in the
+    // banner should writing be slow. A write that times out is not retried, since the
+    // abandoned write may still be in progress.
+    let result = retry.run(|| {
+        io::write_bytes(path, bytes.clone(), retry.timeout, |progress| {
+            editor.borrow_mut().show_saving(Some(progress))
+        })
+    });
+    editor.borrow_mut().show_saving(None);
+    let _ = result?;
+
+    // Digest is computed from the bytes just written rather than reading the file
+    // again.
+    let time = retry.run(|| io::get_time(path))?;
+    Ok(Stamp {
+        time,
+        digest: Some(io::hash_bytes(&bytes)),
+    })
 }
 
-/// Clears the dirty flag on `editor` and sets its source as _file_ using `path` and
-/// its modification `timestamp`.
-fn update_editor(editor: &EditorRef, path: &str, timestamp: SystemTime) {
+/// Clears the dirty and stale flags on `editor` and sets its source as _file_ using
+/// `path` and its `stamp`.
+fn update_editor(editor: &EditorRef, path: &str, stamp: Stamp) {
     let mut editor = editor.borrow_mut();
-    editor.assume(Source::as_file(path, Some(timestamp)));
+    editor.assume(Source::as_file(path, Some(stamp)));
+    editor.set_stale(None);
     editor.clear_dirty();
 }
 
-/// Returns `true` if `editor` has a modification time older than the modification time
-/// of the file in storage.
+/// Returns `true` if the file in storage was changed since `editor` last read or
+/// wrote it.
+///
+/// Changes are detected by comparing digests of the file contents, which means that
+/// merely touching the file is not considered a change, and also that changes are
+/// detected even if the modification time is unreliable. The modification time is
+/// only compared if the digest is not yet known.
 fn stale_editor(editor: &EditorRef) -> Result<bool> {
     let editor = editor.borrow();
-    let stale = if let Source::File(path, timestamp) = editor.source() {
-        if let Some(timestamp) = timestamp {
-            io::get_time(path)? > *timestamp
+    let stale = if let Source::File(path, Some(stamp)) = editor.source() {
+        if let Some(digest) = stamp.digest {
+            io::hash_file(path)? != digest
         } else {
-            false
+            io::get_time(path)? > stamp.time
         }
     } else {
         false
@@ -1836,6 +5928,15 @@ fn dirty_editors(env: &Environment) -> Vec<EditorRef> {
         .collect()
 }
 
+/// Returns an ordered collection of mutable _ephemeral_ editors that contain text.
+fn ephemeral_editors(env: &Environment) -> Vec<EditorRef> {
+    env.editor_map()
+        .iter()
+        .filter(|(_, e)| is_ephemeral(e) && is_mutable_with_text(e))
+        .map(|(_, e)| e.clone())
+        .collect()
+}
+
 /// Returns an ordered collection of editor ids and editors for those editors that are
 /// not attached to a window.
 ///
@@ -1883,6 +5984,21 @@ fn next_unattached_editor(env: &Environment) -> Option<(u32, EditorRef)> {
     }
 }
 
+/// Closes the editor of `editor_id`, which also kills its window if attached to one
+/// of several windows, or switches to another editor if attached to the only window.
+fn close_editor(env: &mut Environment, editor_id: u32) {
+    if let Some(view_id) = env.find_editor_view_id(editor_id) {
+        if env.view_map().len() > 1 {
+            env.kill_window_for(view_id);
+        } else if let Some((switch_id, _)) = next_unattached_editor(env) {
+            env.switch_editor_for(view_id, switch_id, Align::Auto);
+            env.close_editor(editor_id);
+        }
+    } else {
+        env.close_editor(editor_id);
+    }
+}
+
 /// Returns the path associated with `editor`.
 fn path_of(editor: &EditorRef) -> PathBuf {
     if let Source::File(path, _) = editor.borrow().source() {
@@ -1897,6 +6013,11 @@ fn source_of(editor: &EditorRef) -> String {
     editor.borrow().source().to_string()
 }
 
+/// Returns the label associated with `editor`.
+fn label_of(editor: &EditorRef) -> String {
+    editor.borrow().label()
+}
+
 /// Returns `true` if source of `editor` is a _file_.
 fn is_file(editor: &EditorRef) -> bool {
     editor.borrow().source().is_file()
@@ -1907,12 +6028,37 @@ fn is_ephemeral(editor: &EditorRef) -> bool {
     editor.borrow().source().is_ephemeral()
 }
 
+/// Returns `true` if `editor` is mutable and contains text.
+fn is_mutable_with_text(editor: &EditorRef) -> bool {
+    let editor = editor.borrow();
+    (!editor.is_readonly() || editor.is_locked()) && editor.buffer().size() > 0
+}
+
 /// Returns `true` if source of `editor` is a _file_ and is dirty.
 fn is_dirty_file(editor: &EditorRef) -> bool {
     let editor = editor.borrow();
     editor.source().is_file() && editor.is_dirty()
 }
 
+/// Returns the editor variables derived from the active editor.
+fn variables_of(env: &Environment) -> Variables {
+    let editor = env.get_active_editor();
+    let file = if is_file(editor) {
+        Some(path_of(editor))
+    } else {
+        None
+    };
+    Variables::new(file.as_deref(), selection_of(&editor.borrow()))
+}
+
+/// Returns the text of the selection in `editor`, or `None` if no selection is active.
+fn selection_of(editor: &Editor) -> Option<String> {
+    editor
+        .capture()
+        .mark
+        .map(|mark| editor.copy_mark(mark).iter().collect())
+}
+
 /// Returns the base directory of the active editor.
 fn derive_dir(env: &mut Environment) -> PathBuf {
     derive_dir_from(env.get_active_editor())
@@ -1941,7 +6087,7 @@ fn base_dir(editor: &EditorRef) -> PathBuf {
 }
 
 /// Predefined mapping of editing operations to editing functions.
-pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
+pub const OP_MAPPINGS: [(&str, OpFn); 154] = [
     // --- exit and cancellation ---
     ("quit", quit),
     // --- help ---
@@ -1949,7 +6095,16 @@ pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
     ("help-keys", help_keys),
     ("help-ops", help_ops),
     ("help-bindings", help_bindings),
+    ("audit-bindings", audit_bindings),
+    ("export-bindings", export_bindings),
     ("help-colors", help_colors),
+    ("preview-theme", preview_theme),
+    ("debug-syntax", debug_syntax),
+    ("memory-report", memory_report),
+    ("perf-stats", perf_stats),
+    ("show-audit", show_audit),
+    ("describe-key", describe_key),
+    ("search-help", search_help),
     // --- navigation and selection ---
     ("move-backward", move_backward),
     ("move-backward-word", move_backward_word),
@@ -1967,6 +6122,8 @@ pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
     ("move-up-page-select", move_up_page_select),
     ("move-down-page", move_down_page),
     ("move-down-page-select", move_down_page_select),
+    ("move-up-half-page", move_up_half_page),
+    ("move-down-half-page", move_down_half_page),
     ("move-start", move_start),
     ("move-start-select", move_start_select),
     ("move-end", move_end),
@@ -1981,34 +6138,76 @@ pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
     ("scroll-down-select", scroll_down_select),
     ("scroll-center", scroll_center),
     ("set-mark", set_mark),
+    ("set-mark-column", set_mark_column),
+    ("match-bracket", match_bracket),
     ("goto-line", goto_line),
+    ("goto-column", goto_column),
     // --- insertion and removal ---
     ("insert-line", insert_line),
     ("insert-tab", insert_tab),
     ("remove-before", remove_before),
     ("remove-after", remove_after),
+    ("remove-backward-word", remove_backward_word),
+    ("remove-forward-word", remove_forward_word),
     ("remove-start", remove_start),
     ("remove-end", remove_end),
+    ("duplicate-line", duplicate_line),
+    ("move-line-up", move_line_up),
+    ("move-line-down", move_line_down),
+    ("join-lines", join_lines),
     ("undo", undo),
     ("redo", redo),
+    ("hard-wrap-region", hard_wrap_region),
+    ("unwrap-region", unwrap_region),
+    ("reflow-paragraph", reflow_paragraph),
+    ("reindent-region", reindent_region),
+    ("toggle-comment", toggle_comment),
+    ("sort-lines", sort_lines),
+    ("upcase", upcase),
+    ("downcase", downcase),
+    ("insert-template-for-type", insert_template_for_type),
+    ("expand-pair", expand_pair),
     // --- selection actions ---
     ("copy", copy),
     ("paste", paste),
+    ("paste-adjust-indent", paste_adjust_indent),
+    ("paste-block", paste_block),
     ("cut", cut),
+    ("copy-append", copy_append),
+    ("cut-append", cut_append),
+    // --- multiple cursors ---
+    ("add-cursor-up", add_cursor_up),
+    ("add-cursor-down", add_cursor_down),
+    ("add-cursor-at-next-match", add_cursor_at_next_match),
+    ("clear-cursors", clear_cursors),
+    ("sequence-insert", sequence_insert),
     // --- search ---
     ("search", search),
     ("search-case", search_case),
     ("search-regex", search_regex),
     ("search-regex-case", search_regex_case),
     ("search-next", search_next),
+    ("search-prev", search_prev),
+    ("replace", replace),
+    ("replace-regex", replace_regex),
+    ("replace-all", replace_all),
+    ("occur", occur),
+    ("search-all", search_all),
+    ("next-marker", next_marker),
+    ("prev-marker", prev_marker),
     // --- file handling ---
     ("open-file", open_file),
     ("open-file-top", open_file_top),
     ("open-file-bottom", open_file_bottom),
     ("open-file-above", open_file_above),
     ("open-file-below", open_file_below),
+    ("open-file-left", open_file_left),
+    ("open-file-right", open_file_right),
     ("save-file", save_file),
     ("save-file-as", save_file_as),
+    ("delete-file", delete_file),
+    ("undelete-file", undelete_file),
+    ("rename-file", rename_file),
     // --- editor handling ---
     ("select-editor", select_editor),
     ("select-editor-top", select_editor_top),
@@ -2017,6 +6216,9 @@ pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
     ("select-editor-below", select_editor_below),
     ("prev-editor", prev_editor),
     ("next-editor", next_editor),
+    ("next-dirty-editor", next_dirty_editor),
+    ("switch-last-editor", switch_last_editor),
+    ("command-palette", command_palette),
     // --- window handling ---
     ("kill-window", kill_window),
     ("close-window", close_window),
@@ -2025,11 +6227,76 @@ pub const OP_MAPPINGS: [(&'static str, OpFn); 76] = [
     ("bottom-window", bottom_window),
     ("prev-window", prev_window),
     ("next-window", next_window),
+    ("toggle-ruler", toggle_ruler),
+    ("grow-window", grow_window),
+    ("shrink-window", shrink_window),
+    ("balance-windows", balance_windows),
+    ("scroll-lock", scroll_lock),
+    ("save-layout", save_layout),
+    ("restore-layout", restore_layout),
+    ("session-save", session_save),
+    ("session-load", session_load),
     // --- behaviors ---
     ("describe-editor", describe_editor),
     ("tab-mode", tab_mode),
+    ("set-local", set_local),
+    ("toggle-readonly", toggle_readonly),
+    ("lock-editing", lock_editing),
+    ("redact-mode", redact_mode),
+    ("wrap-mode", wrap_mode),
+    ("virtual-mode", virtual_mode),
+    ("indent-mode", indent_mode),
+    ("bom-mode", bom_mode),
+    ("describe-char", describe_char),
+    ("inspect-char", inspect_char),
+    // --- external commands ---
+    ("run-command", run_command),
+    ("rerun-last-command", rerun_last_command),
+    ("print-buffer", print_buffer),
+    ("difftool", difftool),
 ];
 
+/// Predefined mapping of characters to editing operations when typed in a _readonly_
+/// editor, which offers quick access to searching since characters cannot otherwise
+/// be inserted.
+pub const READONLY_MAPPINGS: [(char, &str); 3] =
+    [('/', "search"), ('n', "search-next"), ('N', "search-prev")];
+
+/// An operation in a script given on the command line, paired with an optional
+/// answer to the question it poses.
+pub struct ScriptOp {
+    pub op: &'static str,
+    pub op_fn: OpFn,
+    pub answer: Option<String>,
+}
+
+/// Parses `script` into a sequence of operations, where each is separated by `;`
+/// and optionally followed by whitespace and an answer, such as `goto-line 100`.
+///
+/// An error is returned if any operation is not recognized.
+pub fn parse_script(script: &str) -> Result<Vec<ScriptOp>> {
+    script
+        .split(';')
+        .map(|step| step.trim())
+        .filter(|step| !step.is_empty())
+        .map(|step| {
+            let (op, answer) = match step.split_once(char::is_whitespace) {
+                Some((op, answer)) => (op, Some(answer.trim().to_string())),
+                None => (step, None),
+            };
+            OP_MAPPINGS
+                .iter()
+                .find(|(name, _)| *name == op)
+                .map(|(op, op_fn)| ScriptOp {
+                    op,
+                    op_fn: *op_fn,
+                    answer,
+                })
+                .ok_or_else(|| Error::invalid_op(op))
+        })
+        .collect()
+}
+
 pub fn init_op_map() -> OpMap {
     let mut op_map = OpMap::new();
     for (op, op_fn) in OP_MAPPINGS {
@@ -1,6 +1,7 @@
 //! A simple parser for CLI options.
 
 use crate::error::{Error, Result};
+use crate::io::{self, FileSpec};
 use std::str::FromStr;
 
 /// Represents all potential CLI options.
@@ -13,6 +14,7 @@ pub struct Options {
     pub keys: bool,
     pub ops: bool,
     pub bindings: bool,
+    pub export_bindings: bool,
     pub colors: bool,
     pub theme: bool,
     pub config_path: Option<String>,
@@ -22,7 +24,12 @@ pub struct Options {
     pub help: bool,
     pub version: bool,
     pub source: bool,
-    pub files: Vec<String>,
+    pub dirty_fail: bool,
+    pub readonly_check: bool,
+    pub restore: bool,
+    pub exec: Vec<String>,
+    pub files: Vec<FileSpec>,
+    pub files_from: Vec<String>,
 }
 
 impl Default for Options {
@@ -36,6 +43,7 @@ impl Default for Options {
             keys: false,
             ops: false,
             bindings: false,
+            export_bindings: false,
             colors: false,
             theme: false,
             config_path: None,
@@ -45,7 +53,12 @@ impl Default for Options {
             help: false,
             version: false,
             source: false,
+            dirty_fail: false,
+            readonly_check: false,
+            restore: false,
+            exec: vec![],
             files: vec![],
+            files_from: vec![],
         }
     }
 }
@@ -57,6 +70,10 @@ impl Options {
     {
         let mut opts = Options::default();
         let mut it = args.into_iter();
+
+        // Line number given as `+line`, which applies to the file that follows.
+        let mut next_line = None;
+
         while let Some(arg) = it.next() {
             match arg.as_str() {
                 "--spotlight" => opts.spotlight = Some(true),
@@ -71,6 +88,7 @@ impl Options {
                 "--keys" => opts.keys = true,
                 "--ops" => opts.ops = true,
                 "--bindings" => opts.bindings = true,
+                "--export-bindings" => opts.export_bindings = true,
                 "--colors" => opts.colors = true,
                 "--theme" => opts.theme = true,
                 "--config" | "-C" => opts.config_path = Some(expect_value(&arg, it.next())?),
@@ -80,17 +98,31 @@ impl Options {
                 "--help" | "-h" | "-?" => opts.help = true,
                 "--version" | "-v" => opts.version = true,
                 "--source" => opts.source = true,
+                "--dirty-fail" => opts.dirty_fail = true,
+                "--readonly-check" => opts.readonly_check = true,
+                "--restore" | "-r" => opts.restore = true,
+                "--files-from" => opts.files_from.push(expect_value(&arg, it.next())?),
+                "--exec" | "-e" => opts.exec.push(expect_value(&arg, it.next())?),
                 "--" => {
-                    // All arguments following `--` are interpreted as files.
-                    opts.files.extend(it);
+                    // All arguments following `--` are interpreted literally as files.
+                    opts.files.extend(it.map(|file| (file, None, None)));
                     break;
                 }
                 arg if arg.starts_with("--") || arg.starts_with("-") => {
                     return Err(Error::unexpected_arg(arg))
                 }
+                arg if arg.starts_with("+") && arg[1..].parse::<u32>().is_ok() => {
+                    next_line = arg[1..].parse::<u32>().ok();
+                }
+                arg if arg.starts_with("@") && arg.len() > 1 => {
+                    // Any file prefixed with `@` contains a list of files.
+                    opts.files_from.push(arg[1..].to_string())
+                }
                 _ => {
-                    // Any other match is presumed to be a file.
-                    opts.files.push(arg)
+                    // Any other match is presumed to be a file, possibly suffixed with
+                    // a line and column.
+                    let (file, line, col) = io::parse_file_spec(&arg);
+                    opts.files.push((file, next_line.take().or(line), col))
                 }
             }
         }
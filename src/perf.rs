@@ -0,0 +1,193 @@
+//! Tracks execution times of operations and frames for diagnosing performance.
+//!
+//! Each duration is recorded with [`Perf::record_op`] or [`Perf::record_frame`] in a
+//! [`Timing`], which retains a rolling window of the most recent samples along with
+//! a histogram of those samples in buckets whose bounds are powers of two measured in
+//! microseconds. A _frame_ spans the entire processing of a key, including the
+//! operation it runs and the subsequent rendering of the display.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// A record of execution times of operations by name and of frames.
+pub struct Perf {
+    ops: HashMap<String, Timing>,
+    frames: Timing,
+}
+
+/// A rolling window of durations, measured in microseconds, and a histogram of
+/// those durations.
+pub struct Timing {
+    samples: VecDeque<u64>,
+    buckets: [u32; Timing::BUCKETS],
+    total: u64,
+    count: u64,
+}
+
+impl Perf {
+    pub fn new() -> Perf {
+        Perf {
+            ops: HashMap::new(),
+            frames: Timing::new(),
+        }
+    }
+
+    /// Records an execution of the operation `op` that took `time`.
+    pub fn record_op(&mut self, op: &str, time: Duration) {
+        if let Some(timing) = self.ops.get_mut(op) {
+            timing.record(time);
+        } else {
+            let mut timing = Timing::new();
+            timing.record(time);
+            self.ops.insert(op.to_string(), timing);
+        }
+    }
+
+    /// Records a frame that took `time`.
+    pub fn record_frame(&mut self, time: Duration) {
+        self.frames.record(time);
+    }
+
+    /// Returns the timing of frames.
+    pub fn frames(&self) -> &Timing {
+        &self.frames
+    }
+
+    /// Returns at most `n` operations and their timings ordered by the slowest
+    /// recent execution first.
+    pub fn slowest(&self, n: usize) -> Vec<(&str, &Timing)> {
+        let mut ops = self
+            .ops
+            .iter()
+            .map(|(op, timing)| (op.as_str(), timing))
+            .collect::<Vec<_>>();
+        ops.sort_by(|(a_op, a), (b_op, b)| b.max().cmp(&a.max()).then(a_op.cmp(b_op)));
+        ops.truncate(n);
+        ops
+    }
+}
+
+impl Timing {
+    /// Maximum number of recent samples retained.
+    const WINDOW: usize = 256;
+
+    /// Number of histogram buckets, where the last bucket contains all samples of
+    /// at least `2^(BUCKETS - 2)` microseconds, or roughly 4 seconds.
+    pub const BUCKETS: usize = 24;
+
+    fn new() -> Timing {
+        Timing {
+            samples: VecDeque::with_capacity(Self::WINDOW),
+            buckets: [0; Self::BUCKETS],
+            total: 0,
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, time: Duration) {
+        if self.samples.len() == Self::WINDOW {
+            if let Some(micros) = self.samples.pop_front() {
+                self.buckets[Self::bucket_of(micros)] -= 1;
+                self.total -= micros;
+            }
+        }
+        let micros = time.as_micros().min(u64::MAX as u128) as u64;
+        self.samples.push_back(micros);
+        self.buckets[Self::bucket_of(micros)] += 1;
+        self.total += micros;
+        self.count += 1;
+    }
+
+    /// Returns the total number of samples recorded, including those no longer
+    /// retained in the rolling window.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the average of recent samples.
+    pub fn average(&self) -> Duration {
+        if self.samples.is_empty() {
+            Duration::ZERO
+        } else {
+            Duration::from_micros(self.total / self.samples.len() as u64)
+        }
+    }
+
+    /// Returns the maximum of recent samples.
+    pub fn max(&self) -> Duration {
+        Duration::from_micros(self.samples.iter().copied().max().unwrap_or(0))
+    }
+
+    /// Returns an estimate of the `p`th percentile of recent samples, where `p` is in
+    /// the range `0.0..=1.0`, which is the upper bound of the histogram bucket
+    /// containing that percentile, though never more than the maximum.
+    pub fn percentile(&self, p: f64) -> Duration {
+        let n = self.samples.len() as f64 * p.clamp(0.0, 1.0);
+        let mut seen = 0;
+        for (i, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen > 0 && seen as f64 >= n {
+                return Duration::from_micros(Self::bound_of(i)).min(self.max());
+            }
+        }
+        Duration::ZERO
+    }
+
+    /// Returns the histogram of recent samples, where the bucket at index `i`
+    /// contains samples less than [`bound_of(i)`](Self::bound_of) microseconds.
+    pub fn histogram(&self) -> &[u32; Self::BUCKETS] {
+        &self.buckets
+    }
+
+    /// Returns the exclusive upper bound in microseconds of the bucket at `index`.
+    pub fn bound_of(index: usize) -> u64 {
+        1 << index
+    }
+
+    fn bucket_of(micros: u64) -> usize {
+        let bits = (u64::BITS - micros.leading_zeros()) as usize;
+        bits.min(Self::BUCKETS - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_window() {
+        let mut timing = Timing::new();
+        for _ in 0..Timing::WINDOW {
+            timing.record(Duration::from_millis(10));
+        }
+        assert_eq!(timing.max(), Duration::from_millis(10));
+        for _ in 0..Timing::WINDOW {
+            timing.record(Duration::from_micros(100));
+        }
+        assert_eq!(timing.count(), 2 * Timing::WINDOW as u64);
+        assert_eq!(timing.average(), Duration::from_micros(100));
+        assert_eq!(timing.max(), Duration::from_micros(100));
+        assert_eq!(
+            timing.histogram().iter().sum::<u32>(),
+            Timing::WINDOW as u32
+        );
+        assert_eq!(timing.percentile(0.95), Duration::from_micros(100));
+        timing.record(Duration::from_micros(200));
+        assert_eq!(timing.percentile(0.5), Duration::from_micros(128));
+    }
+
+    #[test]
+    fn slowest_ops() {
+        let mut perf = Perf::new();
+        perf.record_op("a", Duration::from_micros(5));
+        perf.record_op("b", Duration::from_millis(5));
+        perf.record_op("c", Duration::from_micros(50));
+        perf.record_op("a", Duration::from_micros(7));
+        let ops = perf
+            .slowest(2)
+            .into_iter()
+            .map(|(op, _)| op)
+            .collect::<Vec<_>>();
+        assert_eq!(ops, ["b", "c"]);
+    }
+}
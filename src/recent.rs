@@ -0,0 +1,123 @@
+//! Tracks recently used items for ranking by recency and frequency.
+//!
+//! Each use of an item is recorded with [`Recent::touch`], which increments its
+//! count and stamps it with a logical clock that advances on every use. Items are
+//! ranked by a score that favors frequent use but decays as other items are used, so
+//! that an item used many times long ago eventually yields to one used recently.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A record of uses of items of type `T`.
+pub struct Recent<T> {
+    uses: HashMap<T, Use>,
+    clock: u64,
+}
+
+/// The number of uses of an item and the clock value of its last use.
+struct Use {
+    count: u32,
+    last: u64,
+}
+
+impl<T: Eq + Hash> Recent<T> {
+    /// Number of subsequent uses of other items after which the score of an item is
+    /// effectively halved.
+    const HALF_LIFE: f64 = 16.0;
+
+    /// Upper bound on the number of items retained, beyond which items with the
+    /// lowest scores are forgotten.
+    const MAX_ITEMS: usize = 512;
+
+    pub fn new() -> Recent<T> {
+        Recent {
+            uses: HashMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Records a use of `item`.
+    pub fn touch(&mut self, item: T) {
+        self.clock += 1;
+        let u = self.uses.entry(item).or_insert(Use { count: 0, last: 0 });
+        u.count += 1;
+        u.last = self.clock;
+        if self.uses.len() > Self::MAX_ITEMS {
+            let clock = self.clock;
+            let oldest = self
+                .uses
+                .iter()
+                .min_by(|(_, a), (_, b)| Self::compare(a, b, clock))
+                .map(|(_, u)| u.last);
+            if let Some(last) = oldest {
+                self.uses.retain(|_, u| u.last != last);
+            }
+        }
+    }
+
+    /// Removes `item`, typically because it no longer exists.
+    pub fn forget(&mut self, item: &T) {
+        self.uses.remove(item);
+    }
+
+    /// Sorts `items` in place such that items with higher scores come first, where
+    /// items never used retain their relative order after all items that were used.
+    pub fn rank<U, F>(&self, items: &mut [U], key: F)
+    where
+        F: Fn(&U) -> T,
+    {
+        items.sort_by(
+            |a, b| match (self.uses.get(&key(a)), self.uses.get(&key(b))) {
+                (Some(a), Some(b)) => Self::compare(b, a, self.clock),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        );
+    }
+
+    fn compare(a: &Use, b: &Use, clock: u64) -> Ordering {
+        Self::score(a, clock)
+            .total_cmp(&Self::score(b, clock))
+            .then(a.last.cmp(&b.last))
+    }
+
+    fn score(u: &Use, clock: u64) -> f64 {
+        let age = (clock - u.last) as f64;
+        u.count as f64 * (0.5_f64).powf(age / Self::HALF_LIFE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_by_frequency() {
+        let mut recent = Recent::new();
+        recent.touch("a");
+        recent.touch("b");
+        recent.touch("b");
+        recent.touch("c");
+        let mut items = ["x", "a", "c", "b", "y"];
+        recent.rank(&mut items, |s| *s);
+        assert_eq!(items, ["b", "c", "a", "x", "y"]);
+    }
+
+    #[test]
+    fn rank_by_recency() {
+        let mut recent = Recent::new();
+        for _ in 0..4 {
+            recent.touch("a");
+        }
+        for _ in 0..64 {
+            recent.touch("b");
+        }
+        recent.touch("c");
+        recent.forget(&"b");
+        let mut items = ["a", "b", "c"];
+        recent.rank(&mut items, |s| *s);
+        assert_eq!(items, ["c", "a", "b"]);
+    }
+}
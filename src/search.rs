@@ -3,13 +3,13 @@
 use crate::buffer::Buffer;
 use crate::etc;
 use regex_lite::Regex;
+use std::cmp;
 use std::collections::HashMap;
 use std::ops::Range;
 
 /// Defines an interface for a pattern-matching algorithm.
 pub trait Pattern {
     /// Returns the pattern.
-    #[allow(dead_code)]
     fn pattern(&self) -> &str;
 
     /// Searches `buffer` starting at `pos` for the first pattern match, returning a
@@ -26,6 +26,26 @@ pub trait Pattern {
     /// Equivalent to [`find`](Self::find) with the exception that `buffer` is an
     /// `&str` type.
     fn find_str(&self, buffer: &str, pos: usize) -> Option<(usize, usize)>;
+
+    /// Searches `buffer` backward from `pos` for the first pattern match that starts
+    /// before `pos`, returning a tuple comprised of the starting and ending positions
+    /// of the match in `buffer`.
+    ///
+    /// Similar to [`find`](Self::find), implementations are required to perform a full
+    /// search over `buffer`, which implies that the search will effectively wrap, if
+    /// necessary, when `pos` < _size of buffer_.
+    ///
+    /// A return value of `None` indicates that `buffer` does not contain a match for
+    /// the pattern.
+    fn rfind(&self, buffer: &Buffer, pos: usize) -> Option<(usize, usize)>;
+
+    /// Equivalent to [`rfind`](Self::rfind) with the exception that `buffer` is an
+    /// `&str` type.
+    fn rfind_str(&self, buffer: &str, pos: usize) -> Option<(usize, usize)>;
+
+    /// Returns a copy of this pattern, which allows the same search to be applied
+    /// in other contexts.
+    fn clone_box(&self) -> Box<dyn Pattern>;
 }
 
 /// Returns a pattern-matching algorithm using `term` as the search string, and
@@ -39,6 +59,92 @@ pub fn using_regex(regex: Regex) -> Box<dyn Pattern> {
     Box::new(RegexPattern::new(regex))
 }
 
+/// Returns a collection of `0`-based line numbers and corresponding text for each line
+/// in `buffer` matching `pattern`.
+pub fn find_lines(pattern: &dyn Pattern, buffer: &Buffer) -> Vec<(u32, String)> {
+    let mut lines = Vec::new();
+    let mut line = 0;
+    let mut line_pos = 0;
+    let mut pos = 0;
+    while let Some((start_pos, _)) = pattern.find(buffer, pos) {
+        // Search wraps around buffer, so a match prior to starting position indicates
+        // that all matches were found.
+        if start_pos < pos {
+            break;
+        }
+
+        // Line number is calculated incrementally from line of prior match.
+        line += buffer
+            .forward(line_pos)
+            .take(start_pos - line_pos)
+            .filter(|c| *c == '\n')
+            .count() as u32;
+        line_pos = start_pos;
+
+        // Only first match on line is relevant, so search resumes on next line.
+        let start_line = buffer.find_start_line(start_pos);
+        let (next_line, end) = buffer.find_next_line(start_pos);
+        let text = buffer.copy_as_string(start_line, next_line);
+        lines.push((line, text.trim_end_matches('\n').to_string()));
+        if end {
+            break;
+        }
+        pos = next_line;
+    }
+    lines
+}
+
+/// A builder of the buffer listing lines that match a pattern across multiple
+/// editors, where each line is formatted as `source:line:text`.
+///
+/// The first line of the buffer is a summary of the matches, and each subsequent
+/// line corresponds to a location in the order given by [`build`](Self::build).
+pub struct Results {
+    lines: Vec<String>,
+    locations: Vec<(u32, u32)>,
+    editors: usize,
+}
+
+impl Results {
+    pub fn new() -> Results {
+        Results {
+            lines: Vec::new(),
+            locations: Vec::new(),
+            editors: 0,
+        }
+    }
+
+    /// Adds the matching `lines` found in the editor of `editor_id` whose source is
+    /// described by `source`, where `lines` is the result of [`find_lines`].
+    pub fn add(&mut self, editor_id: u32, source: &str, lines: Vec<(u32, String)>) {
+        if !lines.is_empty() {
+            for (line, text) in lines {
+                self.lines.push(format!("{source}:{}:{text}", line + 1));
+                self.locations.push((editor_id, line));
+            }
+            self.editors += 1;
+        }
+    }
+
+    /// Returns a buffer with the summary and matching lines, along with the editor id
+    /// and `0`-based line number of each match, where the `n`th location corresponds
+    /// to line `n + 1` of the buffer.
+    pub fn build(self, pattern: &str) -> (Buffer, Vec<(u32, u32)>) {
+        let mut buffer = Buffer::new();
+        buffer.insert_str(&format!(
+            "{} lines matching \"{pattern}\" in {} editors\n",
+            self.lines.len(),
+            self.editors
+        ));
+        for line in self.lines {
+            buffer.insert_str(&line);
+            buffer.insert_char('\n');
+        }
+        buffer.set_pos(0);
+        (buffer, self.locations)
+    }
+}
+
 /// A term-oriented pattern-matching algorithm implemented using the Boyer-Moore
 /// algorithm.
 ///
@@ -46,6 +152,7 @@ pub fn using_regex(regex: Regex) -> Box<dyn Pattern> {
 /// algorithm is able to work directly with [`Buffer`]s. Using
 /// [`find_str()`](Pattern::find_str) requires an intermediate conversion from
 /// `&str` to [`Buffer`].
+#[derive(Clone)]
 struct TermPattern {
     /// The term provided during construction.
     term: String,
@@ -127,6 +234,29 @@ impl TermPattern {
         }
     }
 
+    fn rsearch(&self, buffer: &Buffer, pos: usize) -> Option<(usize, usize)> {
+        let pat_len = self.pattern.len();
+        if pat_len > 0 && pat_len <= buffer.size() && pos > 0 {
+            // Candidate starting positions are visited in reverse order using the
+            // backward iterator, comparing pattern left-to-right at each position.
+            let start_pos = cmp::min(pos, buffer.size() - pat_len + 1);
+            buffer
+                .backward(start_pos)
+                .index()
+                .map(|(pos, _)| pos)
+                .find(|pos| {
+                    self.pattern
+                        .iter()
+                        .enumerate()
+                        .all(|(i, c)| *c == self.buf_at(buffer, pos + i))
+                })
+                .map(|pos| (pos, pos + pat_len))
+        } else {
+            // Short-circuit when obvious that pattern will not match.
+            None
+        }
+    }
+
     #[inline(always)]
     fn buf_at(&self, buffer: &Buffer, pos: usize) -> char {
         let c = buffer[pos];
@@ -158,6 +288,26 @@ impl Pattern for TermPattern {
         buf.insert_str(buffer);
         self.find(&buf, pos)
     }
+
+    fn rfind(&self, buffer: &Buffer, pos: usize) -> Option<(usize, usize)> {
+        self.rsearch(buffer, pos).or_else(|| {
+            if pos < buffer.size() {
+                self.rsearch(buffer, buffer.size())
+            } else {
+                None
+            }
+        })
+    }
+
+    fn rfind_str(&self, buffer: &str, pos: usize) -> Option<(usize, usize)> {
+        let mut buf = Buffer::new();
+        buf.insert_str(buffer);
+        self.rfind(&buf, pos)
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
 }
 
 /// A regex-oriented pattern-matching algorithm.
@@ -165,6 +315,7 @@ impl Pattern for TermPattern {
 /// The most efficient method of search is [`find_str()`](Pattern::find_str) because
 /// the algorithm only works directly with `&str` types. Using [`find()`](Pattern::find)
 /// requires an intermediate converstion from [`Buffer`] to `&str`.
+#[derive(Clone)]
 struct RegexPattern {
     regex: Regex,
 }
@@ -189,6 +340,24 @@ impl RegexPattern {
             Some((start_pos, end_pos))
         })
     }
+
+    fn rsearch(&self, buffer: &str, pos: usize) -> Option<(usize, usize)> {
+        // Convert starting position into an offset.
+        let pos_offset = etc::pos_to_offset(buffer, pos);
+
+        // Since regex library only searches forward, the last match that starts
+        // before offset is selected.
+        self.regex
+            .find_iter(buffer)
+            .take_while(|m| m.start() < pos_offset)
+            .last()
+            .map(|m| {
+                let Range { start, end } = m.range();
+                let start_pos = etc::offset_to_pos(buffer, start);
+                let end_pos = start_pos + etc::offset_to_pos(&buffer[start..], end - start);
+                (start_pos, end_pos)
+            })
+    }
 }
 
 impl Pattern for RegexPattern {
@@ -212,4 +381,20 @@ impl Pattern for RegexPattern {
             }
         })
     }
+
+    fn rfind(&self, buffer: &Buffer, pos: usize) -> Option<(usize, usize)> {
+        let buf = buffer.iter().collect::<String>();
+        self.rfind_str(&buf, pos)
+    }
+
+    fn rfind_str(&self, buffer: &str, pos: usize) -> Option<(usize, usize)> {
+        // Unlike forward search, character count of buffer is unknown without a full
+        // scan, so wrapping always occurs.
+        self.rsearch(buffer, pos)
+            .or_else(|| self.rsearch(buffer, usize::MAX))
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
 }
@@ -0,0 +1,147 @@
+//! Saves and restores editing sessions.
+//!
+//! A session captures the files open in editors along with the location of the
+//! cursor in each, the arrangement of windows, and the _active_ window. Only editors
+//! whose source is a file are retained, since the content of ephemeral editors is
+//! never written as part of a session.
+//!
+//! Sessions are stored in the state directory as TOML so that restoring a session
+//! after upgrading the editor, or editing the file by hand, is reasonably tolerant.
+
+use crate::error::{Error, Result};
+use crate::io;
+use crate::sys::{self, AsString};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// A snapshot of editors and windows.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Index in [`windows`](Self::windows) of the _active_ window.
+    pub active: usize,
+
+    /// Editors in the order listed by the environment.
+    #[serde(default)]
+    pub editors: Vec<SessionEditor>,
+
+    /// Windows ordered from top to bottom.
+    #[serde(default)]
+    pub windows: Vec<SessionWindow>,
+}
+
+/// A file open in an editor, where `line` and `col` are the `0`-based location of
+/// the cursor in the buffer, and `row` is the row of the cursor on the display.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionEditor {
+    pub path: String,
+    pub line: u32,
+    pub col: u32,
+    pub row: u32,
+}
+
+/// A window attached to the editor at index `editor` in [`Session::editors`], where
+/// `beside` indicates whether the window shares the rows of the window that precedes
+/// it, and `rows` is the number of rows allocated to its row of windows, or `0` if
+/// rows are shared equally.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionWindow {
+    pub editor: usize,
+    pub ruler: bool,
+
+    #[serde(default)]
+    pub beside: bool,
+
+    #[serde(default)]
+    pub rows: u32,
+}
+
+impl Session {
+    /// Name of the file in the state directory to which sessions are written.
+    const FILE_NAME: &str = "session.toml";
+
+    /// Returns the path of the session file.
+    pub fn path() -> PathBuf {
+        sys::state_dir().join(Self::FILE_NAME)
+    }
+
+    /// Reads the session file, returning `None` if it does not exist.
+    pub fn load() -> Result<Option<Session>> {
+        let path = Self::path();
+        if sys::is_file(&path) {
+            let content = fs::read_to_string(&path).map_err(|e| Error::io(&path.as_string(), e))?;
+            toml::from_str::<Session>(&content)
+                .map(Some)
+                .map_err(|e| Error::session(&path.as_string(), &e.to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Writes this session to the session file, replacing any prior session.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        let content =
+            toml::to_string(self).map_err(|e| Error::session(&path.as_string(), &e.to_string()))?;
+        if let Some(dir) = path.parent() {
+            io::create_dir(dir)?;
+        }
+        fs::write(&path, content).map_err(|e| Error::io(&path.as_string(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let session = Session {
+            active: 1,
+            editors: vec![
+                SessionEditor {
+                    path: "/tmp/a.txt".to_string(),
+                    line: 10,
+                    col: 4,
+                    row: 3,
+                },
+                SessionEditor {
+                    path: "/tmp/b.txt".to_string(),
+                    line: 0,
+                    col: 0,
+                    row: 0,
+                },
+            ],
+            windows: vec![
+                SessionWindow {
+                    editor: 1,
+                    ruler: false,
+                    beside: false,
+                    rows: 12,
+                },
+                SessionWindow {
+                    editor: 0,
+                    ruler: true,
+                    beside: true,
+                    rows: 12,
+                },
+            ],
+        };
+        let content = toml::to_string(&session).unwrap();
+        assert_eq!(toml::from_str::<Session>(&content).unwrap(), session);
+    }
+
+    #[test]
+    fn empty_session() {
+        let session = toml::from_str::<Session>("active = 0").unwrap();
+        assert_eq!(session, Session::default());
+    }
+
+    #[test]
+    fn window_without_rows() {
+        let content = "active = 0\n[[windows]]\neditor = 0\nruler = false\n";
+        let session = toml::from_str::<Session>(content).unwrap();
+        assert_eq!(session.windows[0].rows, 0);
+        assert!(!session.windows[0].beside);
+    }
+}
@@ -1,8 +1,8 @@
 //! Sources of editor content.
 
+use crate::io::Stamp;
 use crate::sys;
 use std::fmt::{self, Display, Formatter};
-use std::time::SystemTime;
 
 /// A representation of various editor sources.
 #[derive(Clone)]
@@ -10,17 +10,17 @@ pub enum Source {
     /// A _null_ source indicating the absence of a source.
     Null,
 
-    /// A _file_ source containing a path and an optional timestamp representing the
-    /// last modification, or `None` if the file is new.
-    File(String, Option<SystemTime>),
+    /// A _file_ source containing a path and an optional stamp representing the state
+    /// of the file when last read or written, or `None` if the file is new.
+    File(String, Option<Stamp>),
 
     /// An _ephemeral_ source with a name.
     Ephemeral(String),
 }
 
 impl Source {
-    pub fn as_file(path: &str, timestamp: Option<SystemTime>) -> Source {
-        Source::File(path.to_string(), timestamp)
+    pub fn as_file(path: &str, stamp: Option<Stamp>) -> Source {
+        Source::File(path.to_string(), stamp)
     }
 
     pub fn as_ephemeral(name: &str) -> Source {
@@ -12,6 +12,45 @@
 //! Any file in the applicable directory with an extension of `.toml` is assumed to
 //! be a syntax configuration.
 //!
+//! A syntax configuration may optionally define `outline` patterns in its `[syntax]`
+//! section, which are regular expressions that match _section_ lines, such as
+//! function signatures or headings, used to show the enclosing context of the text
+//! on the display. Likewise, `indent` and `outdent` patterns match lines after which
+//! indentation increases and lines whose own indentation decreases, respectively,
+//! which are used when reindenting text. Finally, `markers` patterns match text of
+//! interest, such as `TODO` notes or conflict markers, which are visited using the
+//! `next-marker` and `prev-marker` operations, and when defined, take precedence over
+//! the `markers` setting. Likewise, `comments` are literal prefixes, such as `//` or
+//! `#`, that begin line comments, which are preserved when reflowing paragraphs,
+//! and of which the shortest is inserted or removed when toggling comments.
+//!
+//! Optional `pairs` in the `[syntax]` section map regular expressions matching an
+//! opening construct, such as an HTML tag or `$$` in LaTeX, to the text that closes
+//! it, which is inserted by the `expand-pair` operation when the opening construct
+//! immediately precedes the cursor. The closing text may refer to capture groups of
+//! the opening pattern, such as `</$1>` to close a tag by name.
+//!
+//! Syntax configurations are normally found by matching the file name against their
+//! `files` patterns. For files that match none of these, such as scripts without an
+//! extension, optional `first-line` patterns in the `[syntax]` section are matched
+//! against the first line of the file instead, which is useful for recognizing
+//! shebang lines like `#!/usr/bin/env python3` or declarations like `<?xml`.
+//!
+//! A syntax configuration may also _extend_ another by naming it in the `extends`
+//! field of its `[syntax]` section, in which case it inherits the tokens of the
+//! extended configuration as well as any of the `outline`, `indent`, `outdent`,
+//! `markers`, `comments` and `pairs` patterns that it does not define itself. Tokens are layered such that
+//! those of the extending configuration precede inherited tokens, and thus take
+//! precedence when matching, and a token whose pattern is identical to an inherited
+//! token replaces it. File patterns are never inherited. Configurations may extend
+//! one another to any depth, though cycles are reported as errors.
+//!
+//! Colors of individual tokens may be overridden by the `[syntax-colors]` section of
+//! the user configuration without editing syntax files, which is applied via
+//! [`Registry::with_colors()`] once the registry is loaded. Tokens are referenced
+//! either by their regular expression or by `token-<n>`, where `<n>` is the 1-based
+//! position of the token in order of definition.
+//!
 //! The order of precedence for predefined directories follows:
 //!
 //! * `$HOME/.ped/syntax`
@@ -23,11 +62,16 @@ use crate::sys::{self, AsString};
 use indexmap::IndexMap;
 use regex_lite::{Captures, Regex, RegexBuilder};
 use serde::Deserialize;
+use std::cmp;
 use std::collections::HashMap;
 use std::fs;
 use std::ops::Range;
 use std::path::Path;
 
+/// Map of canonical syntax names to color overrides, each of which is a tuple
+/// containing a token reference and a foreground color.
+pub type TokenColors = HashMap<String, Vec<(String, u8)>>;
+
 /// A registry of syntax configurations.
 pub struct Registry {
     /// A map of canonical syntax names to syntax configurations.
@@ -35,6 +79,11 @@ pub struct Registry {
 
     /// A list of regular expressions that map to canonical syntax names.
     re_list: Vec<(Regex, String)>,
+
+    /// A list of regular expressions matching the first line of files that map to
+    /// canonical syntax names, which are only consulted when no regular expression in
+    /// [`re_list`](Self::re_list) matches the file name.
+    first_list: Vec<(Regex, String)>,
 }
 
 /// A syntax configuration.
@@ -50,6 +99,31 @@ pub struct Syntax {
     /// A collection of token definitions whose order is crucial since [`re`](Self::re)
     /// is constructed in the order of iteration.
     tokens: Vec<Token>,
+
+    /// An optional regular expression aggregating all outline patterns, which match
+    /// lines that represent sections.
+    outline: Option<Regex>,
+
+    /// An optional regular expression aggregating all indent patterns, which match
+    /// lines after which indentation increases.
+    indent: Option<Regex>,
+
+    /// An optional regular expression aggregating all outdent patterns, which match
+    /// lines whose indentation decreases.
+    outdent: Option<Regex>,
+
+    /// An optional regular expression aggregating all marker patterns, which match
+    /// text of interest such as notes or conflict markers.
+    markers: Option<Regex>,
+
+    /// Prefixes that begin line comments, ordered from longest to shortest so that
+    /// the first matching prefix is the most specific.
+    comments: Vec<String>,
+
+    /// Pairs of regular expressions matching opening constructs at the end of text
+    /// and the closing text, which may refer to capture groups, in order of
+    /// precedence.
+    pairs: Vec<(Regex, String)>,
 }
 
 /// A token represents a regular expression with a unique identifier that is used in
@@ -70,23 +144,32 @@ struct Token {
     color: u8,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct ExternalConfig {
     syntax: ExternalSyntax,
     tokens: Option<IndexMap<String, ColorValue>>,
 }
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[serde(deny_unknown_fields)]
 struct ExternalSyntax {
     name: String,
     files: Vec<String>,
+    #[serde(rename = "first-line")]
+    first_line: Option<Vec<String>>,
+    extends: Option<String>,
+    outline: Option<Vec<String>>,
+    indent: Option<Vec<String>>,
+    outdent: Option<Vec<String>>,
+    markers: Option<Vec<String>>,
+    comments: Option<Vec<String>>,
+    pairs: Option<IndexMap<String, String>>,
 }
 
 impl Syntax {
     /// Name of default syntax.
-    const DEFAULT_NAME: &str = "Text";
+    pub const DEFAULT_NAME: &str = "Text";
 
     /// A regular expression that never matches, which is used when no tokens are
     /// provided.
@@ -130,10 +213,221 @@ impl Syntax {
             Err(e) => return Err(Error::invalid_regex(&pattern, &e)),
         };
 
-        let this = Syntax { name, re, tokens };
+        let this = Syntax {
+            name,
+            re,
+            tokens,
+            outline: None,
+            indent: None,
+            outdent: None,
+            markers: None,
+            comments: Vec::new(),
+            pairs: Vec::new(),
+        };
         Ok(this)
     }
 
+    /// Returns this syntax with outline `patterns`, each of which is a regular
+    /// expression matching lines that represent sections.
+    ///
+    /// If any of the regular expressions are malformed, then an error is returned.
+    pub fn with_outline(self, patterns: Vec<String>) -> Result<Syntax> {
+        let outline = Self::join_patterns(&patterns)?;
+        Ok(Syntax { outline, ..self })
+    }
+
+    /// Returns this syntax with `indent` patterns, each of which is a regular
+    /// expression matching lines after which indentation increases, and `outdent`
+    /// patterns, each of which is a regular expression matching lines whose
+    /// indentation decreases.
+    ///
+    /// If any of the regular expressions are malformed, then an error is returned.
+    pub fn with_indent(self, indent: Vec<String>, outdent: Vec<String>) -> Result<Syntax> {
+        let indent = Self::join_patterns(&indent)?;
+        let outdent = Self::join_patterns(&outdent)?;
+        Ok(Syntax {
+            indent,
+            outdent,
+            ..self
+        })
+    }
+
+    /// Returns this syntax with marker `patterns`, each of which is a regular
+    /// expression matching text of interest.
+    ///
+    /// If any of the regular expressions are malformed, then an error is returned.
+    pub fn with_markers(self, patterns: Vec<String>) -> Result<Syntax> {
+        let markers = Self::marker_regex(&patterns)?;
+        Ok(Syntax { markers, ..self })
+    }
+
+    /// Returns this syntax with line comment `prefixes`, each of which is literal text
+    /// that begins a comment extending to the end of the line.
+    pub fn with_comments(self, prefixes: Vec<String>) -> Syntax {
+        let mut comments = prefixes
+            .into_iter()
+            .filter(|prefix| !prefix.is_empty())
+            .collect::<Vec<_>>();
+        comments.sort_by_key(|prefix| cmp::Reverse(prefix.len()));
+        Syntax { comments, ..self }
+    }
+
+    /// Returns the prefixes that begin line comments ordered from longest to
+    /// shortest.
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// Returns this syntax with `pairs`, each of which is a tuple containing a regular
+    /// expression matching an opening construct and the text that closes it.
+    ///
+    /// If any of the regular expressions are malformed, then an error is returned.
+    pub fn with_pairs(self, pairs: Vec<(String, String)>) -> Result<Syntax> {
+        let pairs = pairs
+            .into_iter()
+            .map(|(open, close)| {
+                let pattern = format!("(?:{open})$");
+                Regex::new(&pattern)
+                    .map(|re| (re, close))
+                    .map_err(|e| Error::invalid_regex(&pattern, &e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Syntax { pairs, ..self })
+    }
+
+    /// Returns the text that closes the construct at the end of `text` according to
+    /// the first matching pair, or `None` if no pair matches.
+    ///
+    /// References in the closing text of the form `$<n>` or `${<name>}` are replaced
+    /// by the corresponding capture groups of the opening pattern, and any other `$`
+    /// is taken literally, so that closing text like `$$` needs no escaping.
+    pub fn closing_of(&self, text: &str) -> Option<String> {
+        self.pairs
+            .iter()
+            .find_map(|(re, close)| re.captures(text).map(|caps| Self::expand(close, &caps)))
+    }
+
+    fn expand(close: &str, caps: &Captures) -> String {
+        let mut closing = String::new();
+        let mut rest = close;
+        while let Some(i) = rest.find('$') {
+            closing.push_str(&rest[..i]);
+            rest = &rest[i + 1..];
+            let (group, skip) = if let Some(name) = rest.strip_prefix('{') {
+                match name.find('}') {
+                    Some(n) => (Some(&name[..n]), n + 2),
+                    None => (None, 0),
+                }
+            } else {
+                let n = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                (if n > 0 { Some(&rest[..n]) } else { None }, n)
+            };
+            if let Some(group) = group {
+                let m = match group.parse::<usize>() {
+                    Ok(n) => caps.get(n),
+                    Err(_) => caps.name(group),
+                };
+                closing.push_str(m.map(|m| m.as_str()).unwrap_or(""));
+                rest = &rest[skip..];
+            } else {
+                closing.push('$');
+            }
+        }
+        closing.push_str(rest);
+        closing
+    }
+
+    /// Returns the regular expression aggregating the marker patterns, or `None` if
+    /// not defined.
+    pub fn markers(&self) -> Option<&Regex> {
+        self.markers.as_ref()
+    }
+
+    /// Returns a regular expression that matches any of the marker `patterns`, or
+    /// `None` if `patterns` is empty.
+    ///
+    /// Unlike other patterns, which match individual lines, markers are matched
+    /// against the entire buffer, so `^` and `$` match at line boundaries.
+    pub fn marker_regex(patterns: &[String]) -> Result<Option<Regex>> {
+        if !patterns.is_empty() {
+            let pattern = patterns
+                .iter()
+                .map(|pattern| format!("(?:{pattern})"))
+                .collect::<Vec<_>>()
+                .join("|");
+            let re = RegexBuilder::new(&pattern)
+                .multi_line(true)
+                .build()
+                .map_err(|e| Error::invalid_regex(&pattern, &e))?;
+            Ok(Some(re))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns this syntax with the colors of tokens overridden by `colors`, each of
+    /// which is a tuple containing a token reference and a foreground color.
+    ///
+    /// A token is referenced either by its regular expression or by `token-<n>`,
+    /// where `<n>` is the 1-based position of the token in order of definition. An
+    /// error is returned if any reference does not match a token.
+    pub fn with_colors(mut self, colors: &[(String, u8)]) -> Result<Syntax> {
+        for (token_ref, color) in colors {
+            let token = self
+                .tokens
+                .iter_mut()
+                .filter(|token| token.pattern != Self::EMPTY_REGEX)
+                .find(|token| {
+                    token.pattern == *token_ref || format!("token-{}", token.id) == *token_ref
+                })
+                .ok_or_else(|| Error::invalid_token(&self.name, token_ref))?;
+            token.color = *color;
+        }
+        Ok(self)
+    }
+
+    /// Returns `true` if `line`, excluding any trailing `\n`, matches one of the
+    /// outline patterns.
+    pub fn is_outline(&self, line: &str) -> bool {
+        self.outline
+            .as_ref()
+            .map(|re| re.is_match(line.strip_suffix('\n').unwrap_or(line)))
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if outline patterns are defined.
+    pub fn has_outline(&self) -> bool {
+        self.outline.is_some()
+    }
+
+    /// Returns the regular expressions aggregating the indent and outdent patterns,
+    /// respectively, or `None` if neither is defined.
+    pub fn indent_rules(&self) -> Option<(Option<&Regex>, Option<&Regex>)> {
+        if self.indent.is_some() || self.outdent.is_some() {
+            Some((self.indent.as_ref(), self.outdent.as_ref()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a regular expression that matches any of `patterns`, or `None` if
+    /// `patterns` is empty.
+    fn join_patterns(patterns: &[String]) -> Result<Option<Regex>> {
+        if !patterns.is_empty() {
+            let pattern = patterns
+                .iter()
+                .map(|pattern| format!("(?:{pattern})"))
+                .collect::<Vec<_>>()
+                .join("|");
+            let re = Regex::new(&pattern).map_err(|e| Error::invalid_regex(&pattern, &e))?;
+            Ok(Some(re))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Returns the token id and the byte offset range for the matching capture group
     /// `cap`.
     ///
@@ -146,6 +440,16 @@ impl Syntax {
             .unwrap_or_else(|| panic!("{}: capture group expected for token", &cap[0]))
     }
 
+    /// Returns tuples containing the regular expression and foreground color of each
+    /// token in order of definition.
+    pub fn tokens(&self) -> Vec<(&str, u8)> {
+        self.tokens
+            .iter()
+            .filter(|token| token.pattern != Self::EMPTY_REGEX)
+            .map(|token| (token.pattern.as_str(), token.color))
+            .collect()
+    }
+
     /// Returns the foreground color associated with the token referenced by `id`.
     pub fn color(&self, id: usize) -> Option<u8> {
         if id == 0 {
@@ -201,6 +505,22 @@ impl Registry {
         }
     }
 
+    /// Returns this registry with token colors of syntax configurations overridden by
+    /// `colors`, which are keyed by canonical syntax name.
+    ///
+    /// Overrides for syntax configurations absent from the registry are ignored, since
+    /// the set of installed syntax files may vary, but an error is returned if any
+    /// token reference is not defined by its syntax configuration.
+    pub fn with_colors(mut self, colors: &TokenColors) -> Result<Registry> {
+        for (name, colors) in colors {
+            if let Some(syntax) = self.syntax_map.remove(name) {
+                let syntax = syntax.with_colors(colors)?;
+                self.syntax_map.insert(name.clone(), syntax);
+            }
+        }
+        Ok(self)
+    }
+
     /// Returns the first syntax configuration matching the file name of `path`, or
     /// `None` if no match is found.
     pub fn find<P: AsRef<Path>>(&self, path: P) -> Option<&Syntax> {
@@ -212,6 +532,31 @@ impl Registry {
         })
     }
 
+    /// Returns the first syntax configuration matching the file name of `path`,
+    /// otherwise the first whose `first-line` patterns match `first_line`, or `None`
+    /// if neither yields a match.
+    pub fn detect<P: AsRef<Path>>(&self, path: P, first_line: &str) -> Option<&Syntax> {
+        self.find(path).or_else(|| {
+            self.first_list
+                .iter()
+                .find(|(re, _)| re.is_match(first_line))
+                .and_then(|(_, name)| self.syntax_map.get(name))
+        })
+    }
+
+    /// Returns the syntax configuration whose canonical name is `name`, or `None` if
+    /// no such configuration exists.
+    pub fn get(&self, name: &str) -> Option<&Syntax> {
+        self.syntax_map.get(name)
+    }
+
+    /// Returns the canonical names of all syntax configurations in sorted order.
+    pub fn names(&self) -> Vec<String> {
+        let mut names = self.syntax_map.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
     /// Creates a registry by enumerating and loading files from `dir`.
     fn load_registry<P: AsRef<Path>>(dir: P, colors: &Colors) -> Result<Registry> {
         let paths = sys::list_files(dir.as_ref());
@@ -226,29 +571,96 @@ impl Registry {
             })
             .collect::<Vec<_>>();
 
+        // All files are read before any syntax is created since extended syntax
+        // configurations must be resolvable by name.
+        let mut configs = HashMap::new();
+        for path in paths {
+            let config = Self::read_file(path)?;
+            configs.insert(config.syntax.name.clone(), (path.as_string(), config));
+        }
+
         let mut syntax_map = HashMap::new();
         let mut re_list = Vec::new();
-        for path in paths {
-            let (syntax, res) = Self::load_syntax(path, colors)?;
+        let mut first_list = Vec::new();
+        for (path, config) in configs.values() {
+            let config = Self::inherit(path, config, &configs, &mut Vec::new())?;
+            let (syntax, res, first_res) = Self::load_syntax(config, colors)?;
             let name = syntax.name.clone();
             for re in res {
                 re_list.push((re, name.clone()));
             }
+            for re in first_res {
+                first_list.push((re, name.clone()));
+            }
             syntax_map.insert(name, syntax);
         }
 
         let registry = Registry {
             syntax_map,
             re_list,
+            first_list,
         };
         Ok(registry)
     }
 
-    /// Loads the syntax configuration referenced by `path`, returning the syntax
-    /// along with a vector of regular expressions for matching file names.
-    fn load_syntax<P: AsRef<Path>>(path: P, colors: &Colors) -> Result<(Syntax, Vec<Regex>)> {
-        let config = Self::read_file(path.as_ref())?;
+    /// Returns `config`, which was read from `path`, with tokens and patterns layered
+    /// on top of those of the syntax configuration it extends, if any, which is found
+    /// in `configs` by name.
+    ///
+    /// `chain` contains the names of configurations visited thus far while resolving
+    /// the extension, which is used to detect cycles.
+    fn inherit(
+        path: &str,
+        config: &ExternalConfig,
+        configs: &HashMap<String, (String, ExternalConfig)>,
+        chain: &mut Vec<String>,
+    ) -> Result<ExternalConfig> {
+        let Some(ref base_name) = config.syntax.extends else {
+            return Ok(config.clone());
+        };
+
+        chain.push(config.syntax.name.clone());
+        if chain.contains(base_name) {
+            let cause = format!("{} -> {base_name}: cycle in extends", chain.join(" -> "));
+            return Err(Error::invalid_extends(path, &cause));
+        }
+        let (base_path, base) = configs.get(base_name).ok_or_else(|| {
+            Error::invalid_extends(path, &format!("{base_name}: syntax not found"))
+        })?;
+        let base = Self::inherit(base_path, base, configs, chain)?;
+
+        // Tokens of this configuration precede inherited tokens so they take
+        // precedence when matching.
+        let mut tokens = config.tokens.clone().unwrap_or_default();
+        for (pattern, color) in base.tokens.unwrap_or_default() {
+            tokens.entry(pattern).or_insert(color);
+        }
+
+        let syntax = &config.syntax;
+        let config = ExternalConfig {
+            syntax: ExternalSyntax {
+                name: syntax.name.clone(),
+                files: syntax.files.clone(),
+                first_line: syntax.first_line.clone(),
+                extends: None,
+                outline: syntax.outline.clone().or(base.syntax.outline),
+                indent: syntax.indent.clone().or(base.syntax.indent),
+                outdent: syntax.outdent.clone().or(base.syntax.outdent),
+                markers: syntax.markers.clone().or(base.syntax.markers),
+                comments: syntax.comments.clone().or(base.syntax.comments),
+                pairs: syntax.pairs.clone().or(base.syntax.pairs),
+            },
+            tokens: Some(tokens),
+        };
+        Ok(config)
+    }
 
+    /// Creates a syntax from `config`, returning the syntax along with vectors of
+    /// regular expressions for matching file names and first lines, respectively.
+    fn load_syntax(
+        config: ExternalConfig,
+        colors: &Colors,
+    ) -> Result<(Syntax, Vec<Regex>, Vec<Regex>)> {
         // Build tokens and create syntax configuration.
         let tokens = if let Some(tokens) = config.tokens {
             let mut ts = Vec::new();
@@ -263,15 +675,40 @@ impl Registry {
         } else {
             Vec::new()
         };
-        let syntax = Syntax::new(config.syntax.name, tokens)?;
-
-        // Convert file patterns to regular expressions.
-        let mut res = Vec::new();
-        for pattern in config.syntax.files {
-            let re = Regex::new(&pattern).map_err(|e| Error::invalid_regex(&pattern, &e))?;
-            res.push(re);
-        }
-        Ok((syntax, res))
+        let syntax = Syntax::new(config.syntax.name, tokens)?
+            .with_outline(config.syntax.outline.unwrap_or_default())?
+            .with_indent(
+                config.syntax.indent.unwrap_or_default(),
+                config.syntax.outdent.unwrap_or_default(),
+            )?
+            .with_markers(config.syntax.markers.unwrap_or_default())?
+            .with_comments(config.syntax.comments.unwrap_or_default())
+            .with_pairs(
+                config
+                    .syntax
+                    .pairs
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect(),
+            )?;
+
+        // Convert file and first line patterns to regular expressions.
+        let to_regex =
+            |pattern: &String| Regex::new(pattern).map_err(|e| Error::invalid_regex(pattern, &e));
+        let res = config
+            .syntax
+            .files
+            .iter()
+            .map(to_regex)
+            .collect::<Result<Vec<_>>>()?;
+        let first_res = config
+            .syntax
+            .first_line
+            .unwrap_or_default()
+            .iter()
+            .map(to_regex)
+            .collect::<Result<Vec<_>>>()?;
+        Ok((syntax, res, first_res))
     }
 
     fn read_file(path: &Path) -> Result<ExternalConfig> {
@@ -285,6 +722,7 @@ impl Default for Registry {
         Registry {
             syntax_map: HashMap::new(),
             re_list: Vec::new(),
+            first_list: Vec::new(),
         }
     }
 }
@@ -335,6 +773,201 @@ pub mod tests {
         assert!(syntax.is_err());
     }
 
+    #[test]
+    fn outline_match() {
+        let syntax = build_syntax()
+            .with_outline(vec!["^fn ".to_string(), r"^#+\s".to_string()])
+            .unwrap();
+        assert!(syntax.has_outline());
+        assert!(syntax.is_outline("fn foo() {\n"));
+        assert!(syntax.is_outline("## heading"));
+        assert!(!syntax.is_outline("    fn bar() {\n"));
+        assert!(!build_syntax().is_outline("fn foo() {\n"));
+    }
+
+    #[test]
+    fn indent_rules() {
+        assert!(build_syntax().indent_rules().is_none());
+        let syntax = build_syntax()
+            .with_indent(vec![":$".to_string()], Vec::new())
+            .unwrap();
+        let (indent, outdent) = syntax.indent_rules().unwrap();
+        assert!(indent.unwrap().is_match("if x:"));
+        assert!(outdent.is_none());
+    }
+
+    #[test]
+    fn comments_ordered() {
+        assert!(build_syntax().comments().is_empty());
+        let syntax =
+            build_syntax().with_comments(vec!["//".to_string(), String::new(), "///".to_string()]);
+        assert_eq!(syntax.comments(), ["///", "//"]);
+    }
+
+    #[test]
+    fn pairs_close() {
+        assert!(build_syntax().closing_of("<p>").is_none());
+        let syntax = build_syntax()
+            .with_pairs(vec![
+                (
+                    r"<([A-Za-z][\w.:-]*)[^<>/]*>".to_string(),
+                    "</$1>".to_string(),
+                ),
+                (r"\$\$".to_string(), "$$".to_string()),
+            ])
+            .unwrap();
+        assert_eq!(
+            syntax.closing_of(r#"text <div class="a">"#),
+            Some("</div>".to_string())
+        );
+        assert_eq!(syntax.closing_of("$$"), Some("$$".to_string()));
+        assert!(syntax.closing_of("<br/>").is_none());
+        assert!(syntax.closing_of("<p> text").is_none());
+        let syntax = build_syntax()
+            .with_pairs(vec![(
+                r"\\begin\{(?<env>\w+)\}".to_string(),
+                "\\end{${env}}$".to_string(),
+            )])
+            .unwrap();
+        assert_eq!(
+            syntax.closing_of(r"\begin{align}"),
+            Some(r"\end{align}$".to_string())
+        );
+        assert!(build_syntax()
+            .with_pairs(vec![("(bad".to_string(), String::new())])
+            .is_err());
+    }
+
+    #[test]
+    fn markers_match() {
+        assert!(build_syntax().markers().is_none());
+        let syntax = build_syntax()
+            .with_markers(vec![r"\bTODO\b".to_string(), "^={7}$".to_string()])
+            .unwrap();
+        let re = syntax.markers().unwrap();
+        assert!(re.is_match("// TODO: fix"));
+        assert!(re.is_match("foo\n=======\nbar"));
+        assert!(!re.is_match("TODOS ========"));
+        assert!(build_syntax()
+            .with_markers(vec!["(bad".to_string()])
+            .is_err());
+    }
+
+    #[test]
+    fn override_colors() {
+        let colors = vec![
+            ("token-1".to_string(), 11),
+            (SYNTAX_TOKENS[2].0.to_string(), 13),
+        ];
+        let syntax = build_syntax().with_colors(&colors).unwrap();
+        assert_eq!(syntax.color(1), Some(11));
+        assert_eq!(syntax.color(2), Some(SYNTAX_TOKENS[1].1));
+        assert_eq!(syntax.color(3), Some(13));
+
+        let colors = vec![("token-4".to_string(), 0)];
+        assert!(build_syntax().with_colors(&colors).is_err());
+        let colors = vec![("token-1".to_string(), 0)];
+        assert!(build_empty_syntax().with_colors(&colors).is_err());
+    }
+
+    #[test]
+    fn inherit_syntax() {
+        let configs = build_configs(&[
+            (
+                "base",
+                r#"
+                [syntax]
+                name = "base"
+                files = []
+                outline = ["^fn "]
+                markers = ["TODO"]
+                [tokens]
+                "a" = 1
+                "b" = 2
+                "#,
+            ),
+            (
+                "derived",
+                r#"
+                [syntax]
+                name = "derived"
+                files = []
+                extends = "base"
+                markers = ["FIXME"]
+                [tokens]
+                "c" = 3
+                "b" = 4
+                "#,
+            ),
+        ]);
+        let (path, config) = &configs["derived"];
+        let config = Registry::inherit(path, config, &configs, &mut Vec::new()).unwrap();
+        let (syntax, _, _) = Registry::load_syntax(config, &Colors::default()).unwrap();
+        assert_eq!(syntax.tokens(), vec![("c", 3), ("b", 4), ("a", 1)]);
+        assert!(syntax.is_outline("fn foo() {\n"));
+        assert!(syntax.markers().unwrap().is_match("FIXME"));
+        assert!(!syntax.markers().unwrap().is_match("TODO"));
+    }
+
+    #[test]
+    fn inherit_cycle() {
+        let configs = build_configs(&[
+            (
+                "a",
+                r#"
+                [syntax]
+                name = "a"
+                files = []
+                extends = "b"
+                "#,
+            ),
+            (
+                "b",
+                r#"
+                [syntax]
+                name = "b"
+                files = []
+                extends = "a"
+                "#,
+            ),
+            (
+                "c",
+                r#"
+                [syntax]
+                name = "c"
+                files = []
+                extends = "d"
+                "#,
+            ),
+        ]);
+        for name in ["a", "c"] {
+            let (path, config) = &configs[name];
+            assert!(Registry::inherit(path, config, &configs, &mut Vec::new()).is_err());
+        }
+    }
+
+    #[test]
+    fn detect_first_line() {
+        let registry = Registry {
+            syntax_map: HashMap::from([(SYNTAX_NAME.to_string(), build_syntax())]),
+            re_list: vec![(Regex::new(r"\.foo$").unwrap(), SYNTAX_NAME.to_string())],
+            first_list: vec![(
+                Regex::new(r"^#!.*\bfoo\b").unwrap(),
+                SYNTAX_NAME.to_string(),
+            )],
+        };
+        assert!(registry.detect("a.foo", "").is_some());
+        assert!(registry.detect("a", "#!/usr/bin/env foo").is_some());
+        assert!(registry.detect("a", "#!/bin/sh").is_none());
+        assert!(registry.find("a").is_none());
+    }
+
+    #[test]
+    fn invalid_outline() {
+        let syntax = build_syntax().with_outline(vec!["(bad".to_string()]);
+        assert!(syntax.is_err());
+    }
+
     pub fn build_syntax() -> Syntax {
         Syntax::new(SYNTAX_NAME.to_string(), build_tokens()).unwrap()
     }
@@ -343,6 +976,16 @@ pub mod tests {
         Syntax::new(SYNTAX_NAME.to_string(), Vec::new()).unwrap()
     }
 
+    fn build_configs(sources: &[(&str, &str)]) -> HashMap<String, (String, ExternalConfig)> {
+        sources
+            .iter()
+            .map(|(name, source)| {
+                let config = toml::from_str::<ExternalConfig>(source).unwrap();
+                (name.to_string(), (name.to_string(), config))
+            })
+            .collect()
+    }
+
     fn build_tokens() -> Vec<(String, u8)> {
         SYNTAX_TOKENS
             .iter()
@@ -5,7 +5,11 @@
 //! opinionated stance on how to interpret errors.
 
 use std::env;
+use std::ffi::{CStr, CString, OsStr};
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
 use std::path::{Path, PathBuf};
+use std::ptr;
 
 pub trait AsString {
     fn as_string(&self) -> String;
@@ -27,7 +31,7 @@ impl AsString for Path {
 /// error occurred while getting the value of `HOME`.
 pub fn home_dir() -> PathBuf {
     env::var_os("HOME")
-        .map(|path| PathBuf::from(path))
+        .map(PathBuf::from)
         .unwrap_or_else(|| this_dir())
 }
 
@@ -112,6 +116,85 @@ pub fn pretty_path<P: AsRef<Path>>(path: P) -> String {
         .unwrap_or(path)
 }
 
+/// Returns `path` with a leading `~` or `~user` replaced by the respective home
+/// directory, and with `$VAR` or `${VAR}` references replaced by the values of the
+/// respective environment variables.
+///
+/// References that cannot be resolved, such as undefined variables or unknown users,
+/// are left intact.
+pub fn expand_path(path: &str) -> String {
+    let path = if let Some(suffix) = path.strip_prefix('~') {
+        let (user, rest) = suffix.split_at(suffix.find('/').unwrap_or(suffix.len()));
+        let home = if !user.is_empty() {
+            user_home_dir(user)
+        } else {
+            Some(home_dir())
+        };
+        home.map(|home| home.as_string() + rest)
+            .unwrap_or_else(|| path.to_string())
+    } else {
+        path.to_string()
+    };
+
+    let mut expanded = String::new();
+    let mut chars = path.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' {
+            let braced = chars.next_if_eq(&'{').is_some();
+            let mut name = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_') {
+                name.push(c);
+            }
+            let closed = braced && chars.next_if_eq(&'}').is_some();
+            match env::var(&name) {
+                Ok(value) if !name.is_empty() && braced == closed => expanded.push_str(&value),
+                _ => {
+                    expanded.push('$');
+                    if braced {
+                        expanded.push('{');
+                    }
+                    expanded.push_str(&name);
+                    if closed {
+                        expanded.push('}');
+                    }
+                }
+            }
+        } else {
+            expanded.push(c);
+        }
+    }
+    expanded
+}
+
+/// Returns the home directory of `user` as recorded in the password database, or
+/// `None` if `user` is unknown.
+fn user_home_dir(user: &str) -> Option<PathBuf> {
+    let user = CString::new(user).ok()?;
+    unsafe {
+        let pw = libc::getpwnam(user.as_ptr());
+        if pw.is_null() || (*pw).pw_dir.is_null() {
+            None
+        } else {
+            let dir = CStr::from_ptr((*pw).pw_dir);
+            Some(PathBuf::from(OsStr::from_bytes(dir.to_bytes())))
+        }
+    }
+}
+
+/// Returns the nearest directory containing `.git`, starting with `dir` and moving
+/// through its ancestors, or `None` if no such directory exists.
+pub fn project_dir<P: AsRef<Path>>(dir: P) -> Option<PathBuf> {
+    dir.as_ref()
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| dir.to_path_buf())
+}
+
+/// Returns `true` if `path` is a regular file.
+pub fn is_file<P: AsRef<Path>>(path: P) -> bool {
+    Path::new(path.as_ref()).is_file()
+}
+
 /// Returns `true` if `path` is a directory.
 pub fn is_dir<P: AsRef<Path>>(path: P) -> bool {
     Path::new(path.as_ref()).is_dir()
@@ -152,6 +235,59 @@ pub fn list_dir<P: AsRef<Path>>(dir: P) -> Vec<PathBuf> {
     entries
 }
 
+/// Returns the default trash directory, which follows the FreeDesktop.org convention
+/// of `$XDG_DATA_HOME/Trash`, or `$HOME/.local/share/Trash` if `XDG_DATA_HOME` is
+/// not defined.
+pub fn trash_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".local/share"))
+        .join("Trash")
+}
+
+/// Returns the directory in which the editor keeps state, which is
+/// `$XDG_STATE_HOME/ped`, or `$HOME/.local/state/ped` if `XDG_STATE_HOME` is not
+/// defined.
+pub fn state_dir() -> PathBuf {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home_dir().join(".local/state"))
+        .join("ped")
+}
+
+/// Returns `true` if `path` is writable by the current user, or if `path` does not
+/// exist, whether a file could be created in its parent directory.
+pub fn is_writable<P: AsRef<Path>>(path: P) -> bool {
+    let path = path.as_ref();
+    let path = if path.exists() {
+        path.to_path_buf()
+    } else {
+        base_dir(path)
+    };
+    CString::new(path.as_os_str().as_bytes())
+        .map(|path| unsafe { libc::access(path.as_ptr(), libc::W_OK) == 0 })
+        .unwrap_or(false)
+}
+
+/// Returns the current local time formatted as `YYYY-MM-DDThh:mm:ss`.
+pub fn local_time() -> String {
+    let tm = unsafe {
+        let now = libc::time(ptr::null_mut());
+        let mut tm = mem::zeroed::<libc::tm>();
+        libc::localtime_r(&now, &mut tm);
+        tm
+    };
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec
+    )
+}
+
 /// Returns the canonicalized form of `path`, or `path` itself if the canonicalization
 /// failed for any reason.
 pub fn canonicalize<P: AsRef<Path>>(path: P) -> PathBuf {
@@ -160,11 +296,51 @@ pub fn canonicalize<P: AsRef<Path>>(path: P) -> PathBuf {
         .unwrap_or_else(|_| path.as_ref().to_path_buf())
 }
 
-/// Returns the file name portion of `path`, or `path` itself if the file name cannot
-/// be extracted.
-pub fn file_name<P: AsRef<Path>>(path: P) -> String {
-    path.as_ref()
-        .file_name()
-        .map(|name| name.to_string_lossy().to_string())
-        .unwrap_or(path.as_ref().as_string())
+/// Returns the shortest suffix of each path in `paths`, formed from whole components,
+/// that distinguishes it from all other paths.
+///
+/// In most cases, the suffix is simply the file name portion of the path. However, if
+/// multiple paths share the same file name, then enough parent components are added
+/// to disambiguate, such as `src/main.rs` and `tests/main.rs`. A path that cannot be
+/// distinguished by any suffix, such as a duplicate, is returned in its
+/// [pretty](pretty_path) form.
+pub fn uniquify<P: AsRef<Path>>(paths: &[P]) -> Vec<String> {
+    let comps = paths
+        .iter()
+        .map(|path| {
+            path.as_ref()
+                .components()
+                .rev()
+                .map(|comp| comp.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    comps
+        .iter()
+        .enumerate()
+        .map(|(i, comp)| {
+            // Find number of trailing components needed to distinguish this path from
+            // each of the other paths.
+            let n = comps
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, other)| {
+                    comp.iter()
+                        .zip(other.iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                        + 1
+                })
+                .max()
+                .unwrap_or(1);
+            if n < comp.len() {
+                let suffix = comp[..n].iter().rev().cloned().collect::<Vec<_>>();
+                suffix.join("/")
+            } else {
+                pretty_path(&paths[i])
+            }
+        })
+        .collect()
 }
@@ -0,0 +1,82 @@
+//! Contains everything related to templates that pre-fill the contents of new files.
+//!
+//! A template is any file in the templates directory whose extension matches the
+//! extension of the new file. For example, a template named `main.rs` applies to any
+//! file with an extension of `.rs`. If more than one template matches, then the first
+//! in lexicographic order is chosen.
+//!
+//! The order of precedence for predefined directories follows:
+//!
+//! * `$HOME/.ped/templates`
+//! * `$HOME/.config/ped/templates`
+//!
+//! The following variables are substituted when a template is expanded, and any
+//! other occurrence of `${...}` is left intact:
+//!
+//! * `${file}` : file name, such as `main.rs`
+//! * `${name}` : file name without the extension, such as `main`
+//! * `${date}` : current date formatted as `YYYY-MM-DD`
+//! * `${time}` : current time formatted as `hh:mm:ss`
+//! * `${year}` : current year
+//!
+//! Editor variables of the form `%{...}` are also substituted using [`Variables`].
+
+use crate::error::{Error, Result};
+use crate::sys::{self, AsString};
+use crate::var::Variables;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A collection of directories to try loading templates.
+const TRY_DIRS: [&str; 2] = [".ped/templates", ".config/ped/templates"];
+
+/// Returns the expanded template whose extension matches the extension of `path`,
+/// or `None` if no such template exists, where editor variables are substituted
+/// using `vars`.
+///
+/// An error is returned if the matching template could not be read.
+pub fn load<P: AsRef<Path>>(path: P, vars: &Variables) -> Result<Option<String>> {
+    if let Some(template_path) = find(&path) {
+        let text = fs::read_to_string(&template_path)
+            .map_err(|e| Error::io(&template_path.as_string(), e))?;
+        Ok(Some(vars.expand(&expand(&text, path))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns the path of the first template whose extension matches the extension of
+/// `path`, or `None` if `path` has no extension or no template matches.
+fn find<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+    let ext = path.as_ref().extension()?;
+    let root_path = sys::home_dir();
+    let dir = TRY_DIRS
+        .iter()
+        .map(|dir| root_path.join(dir))
+        .find(|dir| sys::is_dir(dir))?;
+    sys::list_files(dir)
+        .into_iter()
+        .find(|file| file.extension() == Some(ext))
+}
+
+/// Returns `text` with variables substituted using the name of `path` and the current
+/// local time.
+fn expand<P: AsRef<Path>>(text: &str, path: P) -> String {
+    let path = path.as_ref();
+    let file = path
+        .file_name()
+        .map(|file| file.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let now = sys::local_time();
+    let (date, time) = now.split_once('T').unwrap_or((&now, ""));
+    let year = date.split('-').next().unwrap_or("");
+    text.replace("${file}", &file)
+        .replace("${name}", &name)
+        .replace("${date}", date)
+        .replace("${time}", time)
+        .replace("${year}", year)
+}
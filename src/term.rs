@@ -4,9 +4,11 @@
 //! keystrokes can be read without blocking. It also provides a means of detecting
 //! terminal size changes via signal handlers.
 
+use crate::ansi;
 use crate::error::{Error, Result};
 use libc::{c_int, c_void, sigaction, sighandler_t, siginfo_t, termios, winsize};
 use libc::{SA_SIGINFO, SIGWINCH, STDIN_FILENO, STDOUT_FILENO, TCSADRAIN, TIOCGWINSZ, VMIN, VTIME};
+use std::io::{self, Write};
 use std::mem::MaybeUninit;
 use std::ptr;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -33,6 +35,35 @@ pub fn restore() -> Result<()> {
         .and_then(|term| unsafe { check_err(libc::tcsetattr(STDIN_FILENO, TCSADRAIN, &term)) })
 }
 
+/// Temporarily relinquishes the terminal to another program by leaving the alternate
+/// screen and restoring the original configuration, which is reversed by calling
+/// [`resume`].
+pub fn suspend() -> Result<()> {
+    print!(
+        "{}{}{}{}",
+        ansi::clear_screen(),
+        ansi::reset_cursor_shape(),
+        ansi::track_mouse(false),
+        ansi::alt_screen(false)
+    );
+    let _ = io::stdout().flush();
+    restore()
+}
+
+/// Reclaims the terminal following a call to [`suspend`], which leaves the screen
+/// cleared such that everything must be redrawn.
+pub fn resume() -> Result<()> {
+    init()?;
+    print!(
+        "{}{}{}",
+        ansi::alt_screen(true),
+        ansi::track_mouse(true),
+        ansi::clear_screen()
+    );
+    let _ = io::stdout().flush();
+    Ok(())
+}
+
 /// Returns the size of the terminal as (rows, cols).
 ///
 /// Calls to this function always query the underlying driver, as the terminal size may
@@ -18,6 +18,15 @@ pub struct Tokenizer {
 
     /// The list of token spans generated during tokenization.
     spans: Vec<Span>,
+
+    /// The region of the buffer changed since the last tokenization, if any.
+    dirty: Option<Dirty>,
+
+    /// Indicates that the buffer changed since it was last tokenized in its entirety.
+    stale: bool,
+
+    /// Progress of tokenizing the entire buffer in chunks, if started.
+    rescan: Option<Rescan>,
 }
 
 pub type TokenizerRef = Rc<RefCell<Tokenizer>>;
@@ -51,6 +60,38 @@ struct Token {
     end_pos: usize,
 }
 
+/// Tracks changes to the buffer since the last tokenization so that only the affected
+/// region needs to be tokenized again.
+struct Dirty {
+    /// Index of the span containing the earliest change.
+    index: usize,
+
+    /// Starting position of the span at [`index`](Self::index).
+    start_pos: usize,
+
+    /// Range of buffer positions encompassing all changes.
+    range: Range<usize>,
+}
+
+/// Tracks progress of tokenizing the entire buffer in chunks, which is abandoned
+/// whenever the buffer changes.
+struct Rescan {
+    /// Contents of the buffer when rescanning started.
+    text: String,
+
+    /// Byte offset in [`text`](Self::text) at which scanning resumes.
+    offset: usize,
+
+    /// Buffer position corresponding to [`offset`](Self::offset).
+    pos: usize,
+
+    /// Byte offset at which the last token ended, if any.
+    last_end: Option<usize>,
+
+    /// The list of token spans generated so far.
+    spans: Vec<Span>,
+}
+
 /// A span represents a slice of text that matchs a token `id`.
 struct Span {
     id: usize,
@@ -84,12 +125,23 @@ impl Cursor {
 }
 
 impl Tokenizer {
+    /// Initial number of characters following the last change that are scanned when
+    /// tokenizing incrementally, which doubles until the scan resynchronizes with the
+    /// prior tokenization or reaches the end of the buffer.
+    const WINDOW_CHARS: usize = 1024;
+
+    /// Number of characters rescanned in each chunk by [`rescan()`](Self::rescan).
+    const RESCAN_CHARS: usize = 65_536;
+
     /// Creates a new tokenizer using the `syntax` configuration.
     pub fn new(syntax: Syntax) -> Tokenizer {
         Tokenizer {
             syntax,
             chars: 0,
             spans: Vec::new(),
+            dirty: None,
+            stale: false,
+            rescan: None,
         }
     }
 
@@ -105,61 +157,327 @@ impl Tokenizer {
 
     /// Tokenizes `buffer` and returns a cursor at position `0`.
     pub fn tokenize(&mut self, buffer: &Buffer) -> Cursor {
-        self.spans.clear();
+        self.dirty = None;
+        self.stale = false;
+        self.rescan = None;
         self.chars = buffer.size();
         if self.chars > 0 {
             // Converting entire buffer to string is an unfortunate requirement since
             // regex library provide iterator support.
             let buf = buffer.iter().collect::<String>();
+            let tokens = self.scan(&buf, 0);
+            self.spans = Self::spans_of(&tokens, 0, self.chars);
+        } else {
+            // An empty buffer requires zero-length gap to be appended to spans to
+            // ensure other functions work correctly.
+            self.spans = vec![Span::gap(0)];
+        }
+        self.cursor_at(0, 0)
+    }
+
+    /// Tokenizes only the region of `buffer` affected by changes since the prior
+    /// tokenization and returns a cursor at the start of that region, or `None` if
+    /// no changes occurred.
+    ///
+    /// Scanning starts at the token preceding the earliest change, which is presumed
+    /// to be a stable boundary, and stops at the first token following the latest
+    /// change that coincides with a token from the prior tokenization, beyond which
+    /// existing spans remain intact. Note that a change causing a token to start
+    /// before that boundary, such as closing a multi-line comment, is only recognized
+    /// when the entire buffer is tokenized, either at once or in chunks using
+    /// [`rescan()`](Self::rescan).
+    pub fn retokenize(&mut self, buffer: &Buffer) -> Option<Cursor> {
+        match self.dirty.take() {
+            None => None,
+            Some(dirty)
+                if self.chars == 0
+                    || self.chars != buffer.size()
+                    || dirty.index >= self.spans.len() =>
+            {
+                Some(self.tokenize(buffer))
+            }
+            Some(Dirty {
+                index,
+                start_pos,
+                range,
+            }) => {
+                // Back up to start of closest token preceding span with earliest
+                // change, or start of buffer if none exists.
+                let (mut index, mut pos) = (index, start_pos);
+                while index > 0 {
+                    index -= 1;
+                    pos -= self.spans[index].len;
+                    if self.spans[index].id > 0 {
+                        break;
+                    }
+                }
 
-            // Keep track of byte offset and character position following last span.
-            let mut offset = 0;
-            let mut pos = 0;
+                // Scan successively larger windows of text until tokens resynchronize
+                // with existing spans or end of buffer is reached.
+                let mut window = Self::WINDOW_CHARS;
+                loop {
+                    let end_pos = cmp::min(range.end + window, self.chars);
+                    let text = buffer.copy_as_string(pos, end_pos);
+                    let tokens = self.scan(&text, pos);
+
+                    // Tokens near end of window are not trusted for purposes of
+                    // resynchronization, since truncation of text may have prevented
+                    // recognition of longer tokens that would otherwise enclose them.
+                    let limit_pos = if end_pos < self.chars {
+                        range.end + window / 2
+                    } else {
+                        end_pos + 1
+                    };
+                    if let Some((n, sync_index, sync_pos)) =
+                        self.find_sync(index, pos, &tokens, range.end, limit_pos)
+                    {
+                        let spans = Self::spans_of(&tokens[..n], pos, sync_pos);
+                        self.spans.splice(index..sync_index, spans);
+                        break;
+                    } else if end_pos == self.chars {
+                        let spans = Self::spans_of(&tokens, pos, end_pos);
+                        self.spans.truncate(index);
+                        self.spans.extend(spans);
+                        break;
+                    } else {
+                        window *= 2;
+                    }
+                }
+
+                // New spans may start with a gap that follows an existing gap, which
+                // must be merged since gaps are never adjacent.
+                if index > 0
+                    && index < self.spans.len()
+                    && self.spans[index - 1].id == 0
+                    && self.spans[index].id == 0
+                {
+                    index -= 1;
+                    pos -= self.spans[index].len;
+                    self.spans[index].len += self.spans.remove(index + 1).len;
+                }
+                Some(self.cursor_at(index, pos))
+            }
+        }
+    }
 
-            for cap in self.syntax.re.captures_iter(&buf) {
-                // Get token information associated with capture group.
-                let (id, Range { start, end }) = self.syntax.lookup(&cap);
+    /// Returns `true` if the buffer changed since it was last tokenized in its
+    /// entirety, either at once or in chunks using [`rescan()`](Self::rescan).
+    pub fn is_rescanning(&self) -> bool {
+        self.stale
+    }
 
-                // Byte offsets returned by regex library must be converted to their
-                // corresponding character positions.
-                let start_pos = pos + etc::offset_to_pos(&buf[offset..], start - offset);
-                let end_pos = start_pos + etc::offset_to_pos(&buf[start..], end - start);
+    /// Scans the next chunk of `buffer` as part of tokenizing the entire buffer,
+    /// returning a cursor at position `0` once the end of the buffer is reached, at
+    /// which point the spans are replaced, otherwise `None`.
+    ///
+    /// This produces the same spans as [`tokenize()`](Self::tokenize), since the
+    /// contents of the buffer are scanned from start to end as a whole, but without
+    /// the latency of doing so at once. Rescanning starts over if the buffer changes
+    /// before reaching the end.
+    pub fn rescan(&mut self, buffer: &Buffer) -> Option<Cursor> {
+        if !self.stale {
+            return None;
+        } else if self.chars == 0 || self.chars != buffer.size() {
+            return Some(self.tokenize(buffer));
+        }
 
-                // Insert gap span if non-zero distance exists between this token and
-                // prior token.
-                if start_pos > pos {
-                    self.spans.push(Span::gap(start_pos - pos));
+        let rescan = self.rescan.get_or_insert_with(|| Rescan {
+            text: buffer.iter().collect(),
+            offset: 0,
+            pos: 0,
+            last_end: None,
+            spans: Vec::new(),
+        });
+
+        // Scanning resumes where the prior chunk stopped using the same rules as
+        // iterating over all matches of the entire text, notably that an empty token
+        // never immediately follows the prior token.
+        let limit_pos = rescan.pos + Self::RESCAN_CHARS;
+        while rescan.pos < limit_pos {
+            let mut cap = self.syntax.re.captures_at(&rescan.text, rescan.offset);
+            if let Some(m) = cap.as_ref().and_then(|cap| cap.get(0)) {
+                if m.is_empty() && Some(m.end()) == rescan.last_end {
+                    let n = rescan.text[m.end()..]
+                        .chars()
+                        .next()
+                        .map_or(1, |c| c.len_utf8());
+                    cap = self.syntax.re.captures_at(&rescan.text, m.end() + n);
+                }
+            }
+            let Some(cap) = cap else {
+                // End of buffer reached, so spans are replaced.
+                if self.chars > rescan.pos {
+                    rescan.spans.push(Span::gap(self.chars - rescan.pos));
                 }
+                self.spans = self.rescan.take().unwrap().spans;
+                self.dirty = None;
+                self.stale = false;
+                return Some(self.cursor_at(0, 0));
+            };
 
-                // Add new token span.
-                self.spans.push(Span::token(id, end_pos - start_pos));
-                offset = end;
-                pos = end_pos;
+            // Byte offsets returned by regex library must be converted to their
+            // corresponding character positions.
+            let (id, Range { start, end }) = self.syntax.lookup(&cap);
+            let text = &rescan.text;
+            let start_pos =
+                rescan.pos + etc::offset_to_pos(&text[rescan.offset..], start - rescan.offset);
+            let end_pos = start_pos + etc::offset_to_pos(&text[start..], end - start);
+            if start_pos > rescan.pos {
+                rescan.spans.push(Span::gap(start_pos - rescan.pos));
             }
+            rescan.spans.push(Span::token(id, end_pos - start_pos));
+            rescan.offset = end;
+            rescan.pos = end_pos;
+            rescan.last_end = Some(end);
+        }
+        None
+    }
 
-            // Add gap span if non-zero distance between last token and end of buffer.
-            if offset < buf.len() {
-                let end_pos = pos + etc::offset_to_pos(&buf[offset..], buf.len() - offset);
-                self.spans.push(Span::gap(end_pos - pos));
+    /// Scans `text`, whose first character corresponds to buffer position `pos`, and
+    /// returns tuples containing the token id and the range of buffer positions of
+    /// each token in order of appearance.
+    fn scan(&self, text: &str, pos: usize) -> Vec<(usize, Range<usize>)> {
+        // Keep track of byte offset and character position following last token.
+        let mut offset = 0;
+        let mut pos = pos;
+        let mut tokens = Vec::new();
+
+        for cap in self.syntax.re.captures_iter(text) {
+            // Get token information associated with capture group.
+            let (id, Range { start, end }) = self.syntax.lookup(&cap);
+
+            // Byte offsets returned by regex library must be converted to their
+            // corresponding character positions.
+            let start_pos = pos + etc::offset_to_pos(&text[offset..], start - offset);
+            let end_pos = start_pos + etc::offset_to_pos(&text[start..], end - start);
+            tokens.push((id, start_pos..end_pos));
+            offset = end;
+            pos = end_pos;
+        }
+        tokens
+    }
+
+    /// Returns spans corresponding to `tokens` that cover the range of buffer positions
+    /// from `pos` to `end_pos`, inserting gaps between tokens as necessary.
+    fn spans_of(tokens: &[(usize, Range<usize>)], pos: usize, end_pos: usize) -> Vec<Span> {
+        let mut pos = pos;
+        let mut spans = Vec::new();
+        for (id, Range { start, end }) in tokens {
+            // Insert gap span if non-zero distance exists between this token and
+            // prior token.
+            if *start > pos {
+                spans.push(Span::gap(start - pos));
             }
-        } else {
-            // An empty buffer requires zero-length gap to be appended to spans to
-            // ensure other functions work correctly.
-            self.spans.push(Span::gap(0));
+            spans.push(Span::token(*id, end - start));
+            pos = *end;
         }
 
-        // Return cursor at position 0.
+        // Add gap span if non-zero distance between last token and end of range.
+        if end_pos > pos {
+            spans.push(Span::gap(end_pos - pos));
+        }
+        spans
+    }
+
+    /// Returns the number of `tokens` preceding the first token that coincides with an
+    /// existing span, along with the index and starting position of that span, where
+    /// `index` and `pos` refer to the span at which the scan of `tokens` started.
+    ///
+    /// Only tokens starting at or after `dirty_pos` and ending before `limit_pos` are
+    /// considered.
+    fn find_sync(
+        &self,
+        index: usize,
+        pos: usize,
+        tokens: &[(usize, Range<usize>)],
+        dirty_pos: usize,
+        limit_pos: usize,
+    ) -> Option<(usize, usize, usize)> {
+        let (mut index, mut pos) = (index, pos);
+        for (n, (id, Range { start, end })) in tokens.iter().enumerate() {
+            if *end >= limit_pos {
+                break;
+            } else if *start >= dirty_pos {
+                while index < self.spans.len() && pos < *start {
+                    pos += self.spans[index].len;
+                    index += 1;
+                }
+                if index == self.spans.len() {
+                    break;
+                }
+                let span = &self.spans[index];
+                if pos == *start && span.id == *id && span.len == end - start {
+                    return Some((n, index, pos));
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a cursor at the start of the span at `index`, whose starting position
+    /// is `pos`.
+    fn cursor_at(&self, index: usize, pos: usize) -> Cursor {
         Cursor {
-            pos: 0,
+            pos,
             token: Token {
-                index: 0,
-                start_pos: 0,
-                end_pos: self.spans[0].len,
+                index,
+                start_pos: pos,
+                end_pos: pos + self.spans[index].len,
             },
-            color: self.color(0),
+            color: self.color(index),
         }
     }
 
+    /// Records a change to the range of buffer positions `pos`..`end_pos` in the span
+    /// referenced by `token`, where `shift` is the net change in characters prior
+    /// to `end_pos`.
+    fn mark_dirty(&mut self, token: &Token, pos: usize, end_pos: usize, shift: isize) {
+        self.stale = true;
+        self.rescan = None;
+        self.dirty = match self.dirty.take() {
+            Some(dirty) if dirty.range.start <= pos => {
+                let end = if dirty.range.end > pos {
+                    cmp::max(dirty.range.end as isize + shift, pos as isize) as usize
+                } else {
+                    dirty.range.end
+                };
+                Some(Dirty {
+                    range: dirty.range.start..cmp::max(end, end_pos),
+                    ..dirty
+                })
+            }
+            dirty => {
+                let end = dirty
+                    .map(|dirty| cmp::max(dirty.range.end as isize + shift, pos as isize) as usize)
+                    .unwrap_or(end_pos);
+                Some(Dirty {
+                    index: token.index,
+                    start_pos: token.start_pos,
+                    range: pos..cmp::max(end, end_pos),
+                })
+            }
+        };
+    }
+
+    /// Returns the number of spans, including gaps, produced by tokenization.
+    pub fn span_count(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns tuples containing the token id and the range of buffer positions of
+    /// each token, excluding gaps, in order of appearance.
+    pub fn tokens(&self) -> Vec<(usize, Range<usize>)> {
+        let mut pos = 0;
+        let mut tokens = Vec::new();
+        for span in &self.spans {
+            if span.id > 0 {
+                tokens.push((span.id, pos..pos + span.len));
+            }
+            pos += span.len;
+        }
+        tokens
+    }
+
     /// Finds the cursor at position `pos` relative to `cursor`.
     pub fn find(&self, cursor: Cursor, pos: usize) -> Cursor {
         let pos = cmp::min(pos, self.chars);
@@ -249,17 +567,14 @@ impl Tokenizer {
     /// of the underlying span, returning a new cursor at the same position.
     pub fn insert(&mut self, cursor: Cursor, len: usize) -> Cursor {
         if len > 0 {
-            let token = &cursor.token;
+            let token = Token {
+                end_pos: cursor.token.end_pos + len,
+                ..cursor.token
+            };
             self.spans[token.index].len += len;
             self.chars += len;
-
-            Cursor {
-                token: Token {
-                    end_pos: token.end_pos + len,
-                    ..*token
-                },
-                ..cursor
-            }
+            self.mark_dirty(&token, cursor.pos, cursor.pos + len, len as isize);
+            Cursor { token, ..cursor }
         } else {
             cursor
         }
@@ -334,6 +649,7 @@ impl Tokenizer {
                 }
             };
             self.chars -= len;
+            self.mark_dirty(&token, cursor.pos, cursor.pos, -(len as isize));
 
             Cursor {
                 pos: cursor.pos,
@@ -357,6 +673,7 @@ impl Tokenizer {
 mod tests {
     use super::*;
     use crate::syntax::tests::{build_empty_syntax, build_syntax};
+    use std::time::Instant;
 
     const TOKENS: [(&str, u8); 3] = [
         (r#"-?\d+(?:\.\d+)?(?:[eE]-?\d+)?"#, 1),
@@ -405,6 +722,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn tokens_exclude_gaps() {
+        let mut tz = build_tokenizer();
+        let buf = build_buffer();
+        tz.tokenize(&buf);
+
+        let expected = SPANS
+            .iter()
+            .scan(0, |pos, (id, len, text)| {
+                let start = *pos;
+                *pos += len;
+                Some((*id, start..*pos, *text))
+            })
+            .filter(|(id, _, _)| *id > 0)
+            .collect::<Vec<_>>();
+
+        let tokens = tz.tokens();
+        assert_eq!(tokens.len(), expected.len());
+        for ((id, range), (expect_id, expect_range, text)) in tokens.into_iter().zip(expected) {
+            assert_eq!(id, expect_id);
+            assert_eq!(range, expect_range);
+            assert_eq!(buf.copy_as_string(range.start, range.end), text);
+        }
+    }
+
     #[test]
     fn tokenize_empty_buffer() {
         let mut tz = build_tokenizer();
@@ -689,6 +1031,212 @@ mod tests {
         assert_eq!(cursor.pos, 0);
     }
 
+    #[test]
+    fn retokenize_insert() {
+        let mut tz = build_tokenizer();
+        let mut buf = build_buffer();
+        let cursor = tz.tokenize(&buf);
+
+        // Extends number token across gap, and forms new keyword token.
+        let cursor = edit(&mut tz, &mut buf, cursor, 9, 0, "5");
+        let cursor = edit(&mut tz, &mut buf, cursor, 3, 0, " fo");
+        edit(&mut tz, &mut buf, cursor, 6, 0, "o");
+        assert!(tz.retokenize(&buf).is_some());
+        assert_tokenized(&tz, &buf);
+        assert!(tz.retokenize(&buf).is_none());
+    }
+
+    #[test]
+    fn retokenize_remove() {
+        let mut tz = build_tokenizer();
+        let mut buf = build_buffer();
+        let cursor = tz.tokenize(&buf);
+
+        // Breaks string token apart by removing closing quote, then removes keyword
+        // token entirely.
+        let cursor = edit(&mut tz, &mut buf, cursor, 32, 1, "");
+        edit(&mut tz, &mut buf, cursor, 21, 4, "");
+        let cursor = tz.retokenize(&buf).unwrap();
+        assert_tokenized(&tz, &buf);
+
+        let size = buf.size();
+        edit(&mut tz, &mut buf, cursor, 0, size, "");
+        tz.retokenize(&buf);
+        assert_tokenized(&tz, &buf);
+    }
+
+    #[test]
+    fn retokenize_many_edits() {
+        let mut tz = build_tokenizer();
+        let mut buf = Buffer::new();
+        buf.insert_str(&TEXT.repeat(64));
+        let mut cursor = tz.tokenize(&buf);
+
+        // Pseudo-random sequence of edits, some of which are retokenized together.
+        let mut seed = 7_usize;
+        let mut next = |n: usize| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345) % (1 << 31);
+            seed % n
+        };
+        for i in 0..256 {
+            let pos = next(buf.size() + 1);
+            cursor = if next(2) == 0 {
+                let text = ["\"", "1", ".", " ", "foo", "\n", "x"][next(7)];
+                edit(&mut tz, &mut buf, cursor, pos, 0, text)
+            } else {
+                let len = next(8);
+                edit(&mut tz, &mut buf, cursor, pos, len, "")
+            };
+            if i % 3 == 0 {
+                cursor = tz.retokenize(&buf).unwrap();
+                assert_tokenized(&tz, &buf);
+            }
+        }
+    }
+
+    #[test]
+    fn rescan_comment_closed_ahead_of_scan() {
+        let tokens = vec![
+            (r"/\*(?s:.)*?\*/".to_string(), 1),
+            (r"\b(?:foo|bar)\b".to_string(), 2),
+        ];
+        let mut tz = Tokenizer::new(Syntax::new("c".to_string(), tokens).unwrap());
+        let mut buf = Buffer::new();
+        buf.insert_str(&format!("foo /* bar\n{}", "foo bar\n".repeat(16_384)));
+        let cursor = tz.tokenize(&buf);
+        assert!(!tz.is_rescanning());
+
+        // Closing the comment forms a token that starts well before the token at
+        // which incremental tokenization begins, which is not recognized until the
+        // entire buffer is rescanned.
+        let pos = buf.size() - 4;
+        let commented = |tz: &Tokenizer| tz.tokens().contains(&(1, 4..pos + 2));
+        edit(&mut tz, &mut buf, cursor, pos, 0, "*/");
+        tz.retokenize(&buf).unwrap();
+        assert!(!commented(&tz));
+        assert!(tz.is_rescanning());
+        let cursor = rescan(&mut tz, &buf);
+        assert!(commented(&tz));
+        assert_tokenized(&tz, &buf);
+
+        // Rescanning starts over if the buffer changes before finishing, which in
+        // this case reopens the comment by removing the closing delimiter.
+        edit(&mut tz, &mut buf, cursor, pos, 0, " ");
+        let cursor = tz.retokenize(&buf).unwrap();
+        assert!(tz.rescan(&buf).is_none());
+        edit(&mut tz, &mut buf, cursor, pos, 3, "");
+        tz.retokenize(&buf).unwrap();
+        rescan(&mut tz, &buf);
+        assert!(!commented(&tz));
+        assert_tokenized(&tz, &buf);
+    }
+
+    #[test]
+    fn rescan_empty_tokens() {
+        let syntax = Syntax::new("empty".to_string(), vec![(r"x*".to_string(), 1)]).unwrap();
+        let mut tz = Tokenizer::new(syntax.clone());
+        let mut buf = Buffer::new();
+        buf.insert_str(&"axxb\u{1f600}\n".repeat(16_384));
+        let cursor = tz.tokenize(&buf);
+        edit(&mut tz, &mut buf, cursor, 0, 0, "x");
+        rescan(&mut tz, &buf);
+
+        // Empty tokens are compared as well, unlike other tests.
+        let mut expect = Tokenizer::new(syntax);
+        expect.tokenize(&buf);
+        let spans = |tz: &Tokenizer| {
+            tz.spans
+                .iter()
+                .map(|span| (span.id, span.len))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(spans(&tz), spans(&expect));
+    }
+
+    /// Not a test per se, but rather a benchmark that compares the latency of typing
+    /// in a large buffer when tokenizing incrementally versus in its entirety.
+    ///
+    /// Run with `cargo test --release -- --ignored --nocapture retokenize_latency`.
+    #[test]
+    #[ignore]
+    fn retokenize_latency() {
+        const KEYS: usize = 100;
+
+        for size in [1, 4, 16] {
+            let mut tz = build_tokenizer();
+            let mut buf = Buffer::new();
+            buf.insert_str(&TEXT.repeat(size * 1024 * 1024 / TEXT.len()));
+            let pos = buf.size() / 2;
+            let cursor = tz.tokenize(&buf);
+            let mut cursor = tz.find(cursor, pos);
+
+            let timer = Instant::now();
+            for i in 0..KEYS {
+                edit(&mut tz, &mut buf, cursor, pos + i, 0, "x");
+                cursor = tz.retokenize(&buf).unwrap();
+            }
+            let incremental = timer.elapsed() / KEYS as u32;
+
+            let timer = Instant::now();
+            for i in 0..KEYS / 10 {
+                edit(&mut tz, &mut buf, cursor, pos + i, 0, "x");
+                cursor = tz.tokenize(&buf);
+            }
+            let entire = timer.elapsed() / (KEYS / 10) as u32;
+
+            println!("{size} MiB: incremental {incremental:?}/key, entire {entire:?}/key");
+            assert!(incremental * 10 < entire);
+        }
+    }
+
+    /// Applies an edit to both `buf` and `tz` by removing `len` characters at `pos`
+    /// and inserting `text`, where `cursor` is the starting point for finding `pos`.
+    fn edit(
+        tz: &mut Tokenizer,
+        buf: &mut Buffer,
+        cursor: Cursor,
+        pos: usize,
+        len: usize,
+        text: &str,
+    ) -> Cursor {
+        let cursor = tz.find(cursor, pos);
+        buf.set_pos(pos);
+        let len = buf.remove(len).len();
+        let cursor = tz.remove(cursor, len);
+        let n = buf.insert_str(text) - pos;
+        tz.insert(cursor, n)
+    }
+
+    /// Rescans `buf` in chunks until finished, verifying that more than one chunk was
+    /// necessary, and returns the resulting cursor.
+    fn rescan(tz: &mut Tokenizer, buf: &Buffer) -> Cursor {
+        let mut chunks = 0;
+        loop {
+            chunks += 1;
+            if let Some(cursor) = tz.rescan(buf) {
+                assert!(chunks > 1);
+                assert!(!tz.is_rescanning());
+                return cursor;
+            }
+        }
+    }
+
+    /// Verifies that spans in `tz` are identical to those produced by tokenizing `buf`
+    /// in its entirety.
+    fn assert_tokenized(tz: &Tokenizer, buf: &Buffer) {
+        let mut expect = Tokenizer::new(tz.syntax().clone());
+        expect.tokenize(buf);
+        let spans = |tz: &Tokenizer| {
+            tz.spans
+                .iter()
+                .map(|span| (span.id, span.len))
+                .filter(|(_, len)| *len > 0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(spans(tz), spans(&expect));
+        assert_eq!(tz.chars, buf.size());
+    }
+
     fn build_tokenizer() -> Tokenizer {
         Tokenizer::new(build_syntax())
     }
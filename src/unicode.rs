@@ -0,0 +1,178 @@
+//! Unicode properties of characters.
+//!
+//! Names are taken from the Unicode character database, though characters without a
+//! name, such as controls and private use characters, are described by their general
+//! category instead.
+//!
+//! Grapheme clusters are approximated by attaching combining marks, variation
+//! selectors, emoji modifiers and tags to the preceding character, joining
+//! characters on either side of a zero width joiner, and pairing regional indicators
+//! that form flags. This covers the clusters commonly found in text without
+//! implementing the full segmentation algorithm.
+//!
+//! Display widths follow the conventions of `wcwidth`, where East Asian wide and
+//! fullwidth characters, including most emoji, occupy two columns and combining
+//! marks occupy none.
+
+use crate::buffer::Buffer;
+use std::ops::Range;
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_width::UnicodeWidthChar;
+
+/// Zero width joiner, which combines the characters on either side.
+const ZWJ: char = '\u{200d}';
+
+/// Returns the name of `c`, or `None` if it has no name.
+pub fn name(c: char) -> Option<String> {
+    unicode_names2::name(c).map(|name| name.to_string())
+}
+
+/// Returns a tuple containing the abbreviation and description of the general
+/// category of `c`, such as `Lu` and `uppercase letter`.
+pub fn category(c: char) -> (&'static str, String) {
+    let category = get_general_category(c);
+
+    // Description is derived from the name of the category, which is written in
+    // camel case, by separating words.
+    let mut desc = String::new();
+    for c in format!("{category:?}").chars() {
+        if c.is_uppercase() && !desc.is_empty() {
+            desc.push(' ');
+        }
+        desc.push(c.to_ascii_lowercase());
+    }
+    (category.abbreviation(), desc)
+}
+
+/// Returns the number of columns occupied by `c` on the display, which is `0` for
+/// characters that combine with the preceding character and `2` for wide characters.
+///
+/// Control characters, including `\n` and `\t`, occupy a single column since the
+/// editor always shows them as glyphs.
+pub fn width(c: char) -> u32 {
+    if c.is_control() {
+        1
+    } else {
+        c.width().unwrap_or(1) as u32
+    }
+}
+
+/// Returns the range of positions in `buffer` spanned by the grapheme cluster that
+/// contains `pos`, which is empty only if `pos` is at the end of `buffer`.
+pub fn grapheme_at(buffer: &Buffer, pos: usize) -> Range<usize> {
+    let size = buffer.size();
+    if pos >= size {
+        return size..size;
+    }
+
+    // Find start of cluster by moving backward over characters that extend or are
+    // joined to their predecessor.
+    let mut start = pos;
+    while start > 0 && extends(buffer, start) {
+        start -= 1;
+    }
+
+    // Regional indicators pair up from the start of a run, so a character in the
+    // second half of a pair belongs to the cluster of its predecessor.
+    if is_regional(buffer.get_char(start)) {
+        let run = (0..start)
+            .rev()
+            .take_while(|i| is_regional(buffer.get_char(*i)))
+            .count();
+        if run % 2 == 1 {
+            start -= 1;
+        }
+    }
+
+    let mut end = start + 1;
+    if is_regional(buffer.get_char(start)) && is_regional(buffer.get_char(end)) {
+        end += 1;
+    }
+    while end < size && extends(buffer, end) {
+        end += 1;
+    }
+    start..end
+}
+
+/// Returns `true` if the character at `pos` belongs to the same grapheme cluster as
+/// the character that precedes it.
+fn extends(buffer: &Buffer, pos: usize) -> bool {
+    match buffer.get_char(pos) {
+        Some(c) if is_extender(c) => true,
+        Some(_) => pos > 0 && buffer.get_char(pos - 1) == Some(ZWJ),
+        None => false,
+    }
+}
+
+/// Returns `true` if `c` always attaches to the preceding character.
+fn is_extender(c: char) -> bool {
+    matches!(
+        get_general_category(c),
+        GeneralCategory::NonspacingMark
+            | GeneralCategory::SpacingMark
+            | GeneralCategory::EnclosingMark
+    ) || matches!(c, ZWJ | '\u{1f3fb}'..='\u{1f3ff}' | '\u{e0020}'..='\u{e007f}')
+}
+
+fn is_regional(c: Option<char>) -> bool {
+    matches!(c, Some('\u{1f1e6}'..='\u{1f1ff}'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer_of(text: &str) -> Buffer {
+        let mut buffer = Buffer::new();
+        buffer.insert_str(text);
+        buffer
+    }
+
+    #[test]
+    fn combining_marks() {
+        let buffer = buffer_of("ae\u{301}\u{323}b");
+        assert_eq!(grapheme_at(&buffer, 0), 0..1);
+        assert_eq!(grapheme_at(&buffer, 1), 1..4);
+        assert_eq!(grapheme_at(&buffer, 3), 1..4);
+        assert_eq!(grapheme_at(&buffer, 4), 4..5);
+        assert_eq!(grapheme_at(&buffer, 5), 5..5);
+    }
+
+    #[test]
+    fn joined_emoji() {
+        // Family of man, woman and girl, followed by thumbs up with skin tone.
+        let buffer = buffer_of("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f44d}\u{1f3fd}");
+        assert_eq!(grapheme_at(&buffer, 2), 0..5);
+        assert_eq!(grapheme_at(&buffer, 5), 5..7);
+        assert_eq!(grapheme_at(&buffer, 6), 5..7);
+    }
+
+    #[test]
+    fn regional_indicators() {
+        // Flags of Canada and France.
+        let buffer = buffer_of("\u{1f1e8}\u{1f1e6}\u{1f1eb}\u{1f1f7}");
+        assert_eq!(grapheme_at(&buffer, 1), 0..2);
+        assert_eq!(grapheme_at(&buffer, 2), 2..4);
+        assert_eq!(grapheme_at(&buffer, 3), 2..4);
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(width('a'), 1);
+        assert_eq!(width('\n'), 1);
+        assert_eq!(width('\t'), 1);
+        assert_eq!(width('\x7f'), 1);
+        assert_eq!(width('\u{301}'), 0);
+        assert_eq!(width(ZWJ), 0);
+        assert_eq!(width('\u{65e5}'), 2);
+        assert_eq!(width('\u{ff21}'), 2);
+        assert_eq!(width('\u{1f600}'), 2);
+    }
+
+    #[test]
+    fn categories() {
+        assert_eq!(category('A'), ("Lu", "uppercase letter".to_string()));
+        assert_eq!(category('\u{301}'), ("Mn", "nonspacing mark".to_string()));
+        assert_eq!(name('A'), Some("LATIN CAPITAL LETTER A".to_string()));
+    }
+}
@@ -1,7 +1,8 @@
 //! A collection of types and implementations for interfacing with users.
 
 use crate::env::Environment;
-use crate::key::Key;
+use crate::ignore::Ignore;
+use crate::key::{Key, TAB};
 use crate::op::Action;
 use crate::sys::{self, AsString};
 use std::path::{Path, PathBuf};
@@ -18,6 +19,26 @@ pub trait Inquirer {
         null_completer()
     }
 
+    /// Returns `true` if input should be masked when displayed, such as when
+    /// soliciting a passphrase, in which case each character is shown as `*` unless
+    /// temporarily revealed by the user, the [`completer`](Self::completer) is
+    /// ignored, and the value is not retained by the input editor once the inquiry
+    /// concludes.
+    ///
+    /// The default implementation returns `false`.
+    fn masked(&self) -> bool {
+        false
+    }
+
+    /// Allows the inquirer to intercept `key` before it is processed by the input
+    /// editor, which is useful for inquiries that solicit keys rather than text.
+    ///
+    /// The default implementation returns [`Intercept::Pass`].
+    #[allow(unused_variables)]
+    fn intercept(&mut self, env: &mut Environment, key: &Key) -> Intercept {
+        Intercept::Pass
+    }
+
     /// Allows the inquirer to react to a partial input `value` following the
     /// processing of `key` that is not yet committed or cancelled, returning an
     /// optional _hint_.
@@ -35,6 +56,86 @@ pub trait Inquirer {
     fn respond(&mut self, env: &mut Environment, value: Option<&str>) -> Option<Action>;
 }
 
+/// The outcome of an [`Inquirer`] intercepting a key.
+pub enum Intercept {
+    /// The key is passed along to the input editor for normal processing.
+    Pass,
+
+    /// The key is consumed, producing an optional _hint_, and the inquiry continues.
+    Consume(Option<String>),
+
+    /// The key concludes the inquiry, producing an optional action to be taken by the
+    /// controller.
+    Conclude(Option<Action>),
+}
+
+/// A list of items that are individually checked or unchecked, which an [`Inquirer`]
+/// presents as a hint and manipulates by intercepting keys.
+///
+/// `SPACE` toggles the item with focus, `a` toggles all items, and `TAB`, `SHIFT-TAB`
+/// and arrow keys move focus to the next or previous item.
+pub struct Checklist {
+    items: Vec<String>,
+    checked: Vec<bool>,
+    focus: usize,
+}
+
+impl Checklist {
+    /// Creates a checklist of `items`, all of which are initially checked.
+    pub fn new(items: Vec<String>) -> Checklist {
+        let checked = vec![true; items.len()];
+        Checklist {
+            items,
+            checked,
+            focus: 0,
+        }
+    }
+
+    /// Returns `true` if the item at `index` is checked.
+    pub fn is_checked(&self, index: usize) -> bool {
+        self.checked[index]
+    }
+
+    /// Processes `key`, returning `true` if the checklist changed as a consequence.
+    pub fn process_key(&mut self, key: &Key) -> bool {
+        match key {
+            Key::Char(' ') => {
+                self.checked[self.focus] = !self.checked[self.focus];
+            }
+            Key::Char('a') => {
+                let check = !self.checked.iter().all(|c| *c);
+                self.checked.fill(check);
+            }
+            Key::Right(..) | Key::Down(..) => {
+                self.focus = (self.focus + 1) % self.items.len();
+            }
+            Key::ShiftTab | Key::Left(..) | Key::Up(..) => {
+                self.focus = (self.focus + self.items.len() - 1) % self.items.len();
+            }
+            _ if *key == TAB => {
+                self.focus = (self.focus + 1) % self.items.len();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns a representation of the checklist suitable for use as a hint, where
+    /// the item with focus is marked with `>`.
+    pub fn hint(&self) -> String {
+        self.items
+            .iter()
+            .zip(self.checked.iter())
+            .enumerate()
+            .map(|(i, (item, checked))| {
+                let focus = if i == self.focus { '>' } else { ' ' };
+                let check = if *checked { 'x' } else { ' ' };
+                format!(" {focus}[{check}] {item}")
+            })
+            .collect()
+    }
+}
+
 /// Defines an interface for a versatile completion assistant when soliciting input
 /// from a user.
 pub trait Completer {
@@ -63,6 +164,16 @@ pub trait Completer {
     /// Under normal circumstances, this method is called only when the user requests
     /// that the input be accepted, such as pressing the RETURN key.
     fn accept(&mut self, value: &str) -> Option<String>;
+
+    /// Allows the completer to toggle an alternate mode of operation, such as the
+    /// filtering of completions, given the input `value` in its current form, and
+    /// return an optional hint.
+    ///
+    /// The default implementation does nothing and returns `None`.
+    #[allow(unused_variables)]
+    fn toggle(&mut self, value: &str) -> Option<String> {
+        None
+    }
 }
 
 /// Returns an implementation of [`Completer`] that essentially provides no assistance
@@ -88,15 +199,40 @@ pub fn number_completer() -> Box<dyn Completer> {
     Box::new(NumberCompleter::new())
 }
 
+/// Returns an implementation of [`Completer`] that accepts a line number, a line
+/// and column in the form `line:col`, or a range of lines in the form `first,last`.
+pub fn line_completer() -> Box<dyn Completer> {
+    Box::new(LineCompleter::new())
+}
+
 /// Returns an implementation of [`Completer`] that accepts a finite collection of
 /// strings and provides searchability over the collection.
 pub fn list_completer(accepted: Vec<String>) -> Box<dyn Completer> {
-    Box::new(ListCompleter::new(accepted))
+    Box::new(ListCompleter::new(accepted, false))
+}
+
+/// Returns an implementation of [`Completer`] similar to [`list_completer`], except
+/// that values match if the characters of the input appear in order, though not
+/// necessarily adjacent, and matches retain the order of `accepted`.
+pub fn fuzzy_completer(accepted: Vec<String>) -> Box<dyn Completer> {
+    Box::new(ListCompleter::new(accepted, true))
+}
+
+/// Returns an implementation of [`Completer`] that shows `hint` and accepts any input,
+/// which is useful for inquiries whose state is presented entirely as a hint, such as
+/// a [`Checklist`].
+pub fn hint_completer(hint: String) -> Box<dyn Completer> {
+    Box::new(HintCompleter { hint })
 }
 
 /// Returns an implementation of [`Completer`] that navigates files and directories.
-pub fn file_completer(dir: PathBuf) -> Box<dyn Completer> {
-    Box::new(FileCompleter::new(dir))
+///
+/// Paths are expanded using [`sys::expand_path`], directories are ordered ahead of
+/// files and suggested with a trailing `/`, and hidden files as well as files ignored
+/// by [`Ignore`] using the `ignore` patterns are excluded until toggled using
+/// [`toggle`](Completer::toggle).
+pub fn file_completer(dir: PathBuf, ignore: Vec<String>) -> Box<dyn Completer> {
+    Box::new(FileCompleter::new(dir, ignore))
 }
 
 /// A completer that does nothing.
@@ -120,6 +256,29 @@ impl Completer for NullCompleter {
     }
 }
 
+/// A completer that shows a fixed hint.
+struct HintCompleter {
+    hint: String,
+}
+
+impl Completer for HintCompleter {
+    fn prepare(&mut self) -> Option<String> {
+        Some(self.hint.clone())
+    }
+
+    fn evaluate(&mut self, _: &str) -> Option<String> {
+        Some(self.hint.clone())
+    }
+
+    fn suggest(&mut self, _: &str) -> (Option<String>, Option<String>) {
+        (None, Some(self.hint.clone()))
+    }
+
+    fn accept(&mut self, value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+}
+
 /// A completer that accepts case-insensitive values `"y"` and `"n"`, always yielding
 /// accepted values in lowercase.
 struct YesNoCompleter {
@@ -249,35 +408,111 @@ impl Completer for NumberCompleter {
     }
 }
 
+/// A completer that accepts a line number, a line and column in the form `line:col`,
+/// or a range of lines in the form `first,last`.
+pub struct LineCompleter {
+    hint: Option<String>,
+}
+
+impl LineCompleter {
+    const HINT: &str = " (enter line, line:col or first,last)";
+
+    pub fn new() -> LineCompleter {
+        LineCompleter {
+            hint: Some(Self::HINT.to_string()),
+        }
+    }
+
+    /// Returns the normalized form of `value` if it is a line number, a line and
+    /// column, or a range of lines.
+    fn parse(value: &str) -> Option<String> {
+        let value = value.trim();
+        let pair = |sep: char| {
+            value.split_once(sep).and_then(|(a, b)| {
+                match (a.trim().parse::<u32>(), b.trim().parse::<u32>()) {
+                    (Ok(a), Ok(b)) => Some(format!("{a}{sep}{b}")),
+                    _ => None,
+                }
+            })
+        };
+        if value.contains(',') {
+            pair(',')
+        } else if value.contains(':') {
+            pair(':')
+        } else {
+            value.parse::<u32>().ok().map(|n| n.to_string())
+        }
+    }
+}
+
+impl Completer for LineCompleter {
+    fn prepare(&mut self) -> Option<String> {
+        None
+    }
+
+    fn evaluate(&mut self, value: &str) -> Option<String> {
+        let value = value.trim();
+        if value.is_empty() || value.ends_with([',', ':']) || Self::parse(value).is_some() {
+            None
+        } else {
+            self.hint.clone()
+        }
+    }
+
+    fn suggest(&mut self, _: &str) -> (Option<String>, Option<String>) {
+        (None, None)
+    }
+
+    fn accept(&mut self, value: &str) -> Option<String> {
+        Self::parse(value)
+    }
+}
+
 /// A completer that accepts a finite collection of strings and provides searchability
 /// over the collection.
 struct ListCompleter {
     accepted: Vec<String>,
     matches: Vec<usize>,
     last_match: Option<usize>,
+    fuzzy: bool,
 }
 
 impl ListCompleter {
-    fn new(accepted: Vec<String>) -> ListCompleter {
+    fn new(accepted: Vec<String>, fuzzy: bool) -> ListCompleter {
         ListCompleter {
             accepted,
             matches: Vec::new(),
             last_match: None,
+            fuzzy,
         }
     }
 
     fn refresh(&mut self, value: &str) -> usize {
+        let value = value.to_lowercase();
         self.matches = self
             .accepted
             .iter()
             .enumerate()
-            .filter(|(_, v)| v.to_lowercase().contains(&value.to_lowercase()))
+            .filter(|(_, v)| {
+                let v = v.to_lowercase();
+                if self.fuzzy {
+                    Self::is_subsequence(&value, &v)
+                } else {
+                    v.contains(&value)
+                }
+            })
             .map(|(index, _)| index)
             .collect();
         self.last_match = None;
         self.matches.len()
     }
 
+    /// Returns `true` if the characters of `value` appear in `s` in the same order.
+    fn is_subsequence(value: &str, s: &str) -> bool {
+        let mut chars = s.chars();
+        value.chars().all(|c| chars.any(|sc| sc == c))
+    }
+
     fn match_for(&self, index: usize) -> &String {
         &self.accepted[self.matches[index]]
     }
@@ -340,24 +575,28 @@ impl Completer for ListCompleter {
 struct FileCompleter {
     dir: PathBuf,
     comp_dir: PathBuf,
-    comps: Vec<String>,
+    ignore: Vec<String>,
+    comps: Vec<(String, bool)>,
     matches: Vec<String>,
     last_match: Option<usize>,
+    all: bool,
 }
 
 impl FileCompleter {
-    fn new(dir: PathBuf) -> FileCompleter {
+    fn new(dir: PathBuf, ignore: Vec<String>) -> FileCompleter {
         FileCompleter {
             dir: dir.clone(),
             comp_dir: dir,
+            ignore,
             comps: Vec::new(),
             matches: Vec::new(),
             last_match: None,
+            all: false,
         }
     }
 
     fn refresh(&mut self, value: &str) -> (PathBuf, PathBuf) {
-        let path = self.dir.join(value);
+        let path = self.dir.join(sys::expand_path(value));
         let (prefix, dir) = sys::extract_dir(&path);
 
         // Comparing strings, as opposed to paths, is necessary because notion of
@@ -366,11 +605,11 @@ impl FileCompleter {
         // do not have equivalent strings.
         if self.comp_dir.as_string() != dir.as_string() {
             self.comp_dir = dir.clone();
-            self.comps = Self::completions(&self.comp_dir);
+            self.comps = Self::completions(&self.comp_dir, &self.ignore);
         }
 
         // Always generate new matches regardless of whether completions changed.
-        self.matches = Self::matches(&self.comps, &prefix.as_string());
+        self.matches = Self::matches(&self.comps, &prefix.as_string(), self.all);
         self.last_match = None;
         (prefix, dir)
     }
@@ -389,27 +628,60 @@ impl FileCompleter {
             .unwrap_or(path.to_string())
     }
 
-    fn completions(dir: &Path) -> Vec<String> {
-        sys::list_dir(dir)
-            .iter()
-            .map(|path| path.as_string())
+    /// Returns the list of files and directories in `dir` paired with an indication
+    /// of whether each is ignored according to `ignore` patterns, where directories
+    /// come first and are terminated with `/`.
+    fn completions(dir: &Path, ignore: &[String]) -> Vec<(String, bool)> {
+        let (dirs, files): (Vec<_>, Vec<_>) = sys::list_dir(dir)
+            .into_iter()
+            .partition(|path| path.is_dir());
+
+        // Ignore patterns are matched against canonical paths, which is why the file
+        // name of each path is joined with the canonical form of its directory.
+        let ignore = Ignore::new(dir, ignore);
+        let base = sys::canonicalize(dir);
+        let is_ignored = |path: &PathBuf, is_dir| {
+            path.file_name()
+                .map(|name| ignore.is_ignored(base.join(name), is_dir))
+                .unwrap_or(false)
+        };
+        dirs.iter()
+            .map(|path| (path.join("").as_string(), is_ignored(path, true)))
+            .chain(
+                files
+                    .iter()
+                    .map(|path| (path.as_string(), is_ignored(path, false))),
+            )
             .collect()
     }
 
-    fn matches(comps: &Vec<String>, prefix: &str) -> Vec<String> {
+    /// Returns the subset of `comps` matching `prefix`, though ignored and hidden
+    /// files are excluded unless `all` is `true`, and hidden files are also included
+    /// if the file name in `prefix` itself refers to a hidden file.
+    fn matches(comps: &[(String, bool)], prefix: &str, all: bool) -> Vec<String> {
+        let hidden = all || Self::is_hidden(prefix);
         comps
             .iter()
-            .filter(|path| path.to_lowercase().starts_with(&prefix.to_lowercase()))
-            .cloned()
+            .filter(|(path, _)| path.to_lowercase().starts_with(&prefix.to_lowercase()))
+            .filter(|(path, ignored)| all || !ignored && (hidden || !Self::is_hidden(path)))
+            .map(|(path, _)| path.clone())
             .collect()
     }
+
+    fn is_hidden(path: &str) -> bool {
+        path.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .map(|name| name.starts_with('.') && name != "." && name != "..")
+            .unwrap_or(false)
+    }
 }
 
 impl Completer for FileCompleter {
     fn prepare(&mut self) -> Option<String> {
         let (prefix, _) = sys::extract_dir(&self.comp_dir);
-        self.comps = Self::completions(&self.comp_dir);
-        self.matches = Self::matches(&self.comps, &prefix.as_string());
+        self.comps = Self::completions(&self.comp_dir, &self.ignore);
+        self.matches = Self::matches(&self.comps, &prefix.as_string(), self.all);
         None
     }
 
@@ -443,17 +715,14 @@ impl Completer for FileCompleter {
             if count == 0 {
                 (None, Some(format!(" (no matches)")))
             } else if count == 1 {
-                // Replace input value when single match exists.
+                // Replace input value when single match exists, which navigates into
+                // the directory when the match terminates with /, so completions and
+                // matches are refreshed as side effect.
                 let replace = self.replace_match(0);
-
-                // Hint is provided when match is also directory, as this gives visual
-                // cue to user that typing / will navigate into directory.
-                let hint = if sys::is_dir(&self.matches[0]) {
-                    Some("/".to_string())
-                } else {
-                    None
-                };
-                (Some(replace), hint)
+                if self.matches[0].ends_with('/') {
+                    self.refresh(&replace);
+                }
+                (Some(replace), None)
             } else {
                 // Keep track of index when scrolling through matches, though note this
                 // is only necessary when number of matches more than one.
@@ -467,17 +736,9 @@ impl Completer for FileCompleter {
                 // Replace input value with most recent suggestion from list of matches.
                 let replace = self.replace_match(index);
 
-                // Hint not only appends / for matches that are directories, but also
-                // includes indication of current position in total number of matches.
-                let hint = format!(
-                    "{} ({} of {count} matches)",
-                    if sys::is_dir(&self.matches[index]) {
-                        "/"
-                    } else {
-                        ""
-                    },
-                    index + 1
-                );
+                // Hint includes indication of current position in total number of
+                // matches.
+                let hint = format!(" ({} of {count} matches)", index + 1);
                 (Some(replace), Some(hint))
             }
         }
@@ -486,4 +747,15 @@ impl Completer for FileCompleter {
     fn accept(&mut self, value: &str) -> Option<String> {
         Some(value.to_string())
     }
+
+    fn toggle(&mut self, value: &str) -> Option<String> {
+        self.all = !self.all;
+        self.refresh(value);
+        let hint = if self.all {
+            " (showing all files)"
+        } else {
+            " (hiding hidden and ignored files)"
+        };
+        Some(hint.to_string())
+    }
 }
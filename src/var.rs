@@ -0,0 +1,67 @@
+//! Expansion of editor variables referenced in text as `%{name}`.
+//!
+//! Variables are derived from the state of the session at the time of expansion,
+//! usually from the active editor, and include the following:
+//!
+//! * `%{file}` : path of the file, or empty if the editor is not attached to a file
+//! * `%{dir}` : directory of the file, or the working directory if the editor is not
+//!   attached to a file
+//! * `%{root}` : project root, which is the nearest directory containing `.git`
+//!   starting with `%{dir}`, or `%{dir}` itself if no such directory exists
+//! * `%{selection}` : text of the selection, or empty if no selection is active
+//!
+//! Any other occurrence of `%{...}` is left intact.
+
+use crate::sys::{self, AsString};
+use std::path::Path;
+
+/// A collection of variables available for expansion.
+pub struct Variables {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Variables {
+    /// Creates a collection of variables derived from the path of the current `file`,
+    /// if any, and the text of the `selection`, if any.
+    pub fn new(file: Option<&Path>, selection: Option<String>) -> Variables {
+        let dir = file
+            .map(|file| sys::canonicalize(sys::base_dir(file)))
+            .unwrap_or_else(sys::working_dir);
+        let root = sys::project_dir(&dir).unwrap_or_else(|| dir.clone());
+        let values = vec![
+            (
+                "file",
+                file.map(|file| file.as_string()).unwrap_or_default(),
+            ),
+            ("dir", dir.as_string()),
+            ("root", root.as_string()),
+            ("selection", selection.unwrap_or_default()),
+        ];
+        Variables { values }
+    }
+
+    /// Returns `text` with references to variables replaced by their values.
+    pub fn expand(&self, text: &str) -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("%{") {
+            out.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let value = rest.find('}').and_then(|end| {
+                self.values
+                    .iter()
+                    .find(|(name, _)| *name == &rest[2..end])
+                    .map(|(_, value)| (end, value))
+            });
+            if let Some((end, value)) = value {
+                out.push_str(value);
+                rest = &rest[end + 1..];
+            } else {
+                out.push_str("%{");
+                rest = &rest[2..];
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
@@ -1,15 +1,16 @@
 //! A representation of a window.
 //!
 //! A window contains a _canvas_, which is the area comprised of editor text, and a
-//! _banner_ for displaying other relevant bits of information.
+//! _banner_ for displaying other relevant bits of information. A window may also
+//! contain a _ruler_ above the canvas for displaying column numbers.
 
 use crate::canvas::{Canvas, CanvasRef};
 use crate::color::Color;
 use crate::config::ConfigurationRef;
 use crate::size::{Point, Size};
 use crate::source::Source;
-use crate::sys;
 use std::cell::RefCell;
+use std::cmp;
 use std::ops::Range;
 use std::rc::Rc;
 use std::usize;
@@ -23,10 +24,18 @@ pub struct Banner {
     inactive_bg: u8,
     banner_color: Color,
     accent_color: Color,
+    lock_char: char,
     dirty: bool,
+    readonly: bool,
+    stale: bool,
+    locked: bool,
     source: Source,
+    label: String,
     syntax: String,
+    bom: bool,
+    progress: Option<(&'static str, u8)>,
     loc: Point,
+    selection: Option<(usize, usize)>,
 }
 
 pub type BannerRef = Rc<RefCell<Banner>>;
@@ -46,7 +55,23 @@ impl Banner {
     /// Number of columns allocated to right margin.
     const RIGHT_MARGIN_COLS: u32 = 2;
 
-    /// Prefix to use when truncating the source.
+    /// Shown in the left margin when scrolling is locked with another window.
+    const LOCK_CHAR: char = '\u{2195}';
+
+    /// Equivalent of [`LOCK_CHAR`](Self::LOCK_CHAR) when only ASCII characters are
+    /// permitted.
+    const LOCK_ASCII_CHAR: char = '|';
+
+    /// Shown in place of the dirty marker when the editor is _readonly_.
+    const READONLY_CHAR: char = '%';
+
+    /// Shown in place of the dirty marker when the file in storage has changed.
+    const STALE_CHAR: char = '!';
+
+    /// Appended to the syntax when the file is saved with a byte order mark.
+    const BOM_SUFFIX: &str = ", BOM";
+
+    /// Replacement for the middle portion of the source when truncated.
     const SOURCE_ELLIPSIS: &str = "...";
 
     /// Number of additional columns required in the source and syntax area for
@@ -56,6 +81,10 @@ impl Banner {
     /// Number of columns allocated to whitespace between source and location areas.
     const GAP_COLS: u32 = 2;
 
+    /// Minimum number of columns in the source area that must remain for the source
+    /// before selection statistics are shown.
+    const MIN_SOURCE_COLS: usize = 16;
+
     /// Number of columns allocated to line numbers.
     const LINE_COLS: u32 = 5;
 
@@ -79,6 +108,11 @@ impl Banner {
         let inactive_bg = config.theme.inactive_bg;
         let banner_color = Color::new(config.theme.banner_fg, inactive_bg);
         let accent_color = Color::new(config.theme.accent_fg, inactive_bg);
+        let lock_char = if config.settings.ascii_only {
+            Self::LOCK_ASCII_CHAR
+        } else {
+            Self::LOCK_CHAR
+        };
 
         let mut this = Banner {
             canvas: Canvas::new(origin, Size::new(1, cols)),
@@ -89,10 +123,18 @@ impl Banner {
             inactive_bg,
             banner_color,
             accent_color,
+            lock_char,
             dirty: false,
+            readonly: false,
+            stale: false,
+            locked: false,
             source: Source::Null,
+            label: String::new(),
             syntax: String::new(),
+            bom: false,
+            progress: None,
             loc: Point::ORIGIN,
+            selection: None,
         };
         this.clear();
         this
@@ -108,10 +150,18 @@ impl Banner {
             inactive_bg: 0,
             banner_color: Color::ZERO,
             accent_color: Color::ZERO,
+            lock_char: ' ',
             dirty: false,
+            readonly: false,
+            stale: false,
+            locked: false,
             source: Source::Null,
+            label: String::new(),
             syntax: String::new(),
+            bom: false,
+            progress: None,
             loc: Point::ORIGIN,
+            selection: None,
         }
     }
 
@@ -128,6 +178,7 @@ impl Banner {
     /// Redraws the entire banner regardless of pending changes.
     pub fn redraw(&mut self) {
         self.clear();
+        self.draw_locked();
         self.draw_dirty();
         self.draw_source();
         self.draw_location();
@@ -154,46 +205,157 @@ impl Banner {
         self
     }
 
+    /// Sets whether the editor is _readonly_, which is indicated in place of the dirty
+    /// marker unless the editor is also dirty.
+    pub fn set_readonly(&mut self, readonly: bool) -> &mut Banner {
+        if readonly != self.readonly {
+            self.readonly = readonly;
+            self.draw_dirty();
+        }
+        self
+    }
+
+    /// Sets whether the file in storage has changed since the editor last read or
+    /// wrote it, which is indicated in place of the dirty marker.
+    pub fn set_stale(&mut self, stale: bool) -> &mut Banner {
+        if stale != self.stale {
+            self.stale = stale;
+            self.draw_dirty();
+        }
+        self
+    }
+
+    /// Sets whether scrolling of the window is locked with another window, which is
+    /// indicated in the left margin.
+    pub fn set_locked(&mut self, locked: bool) -> &mut Banner {
+        if locked != self.locked {
+            self.locked = locked;
+            self.draw_locked();
+        }
+        self
+    }
+
     pub fn set_source(&mut self, source: Source) -> &mut Banner {
         self.source = source;
         self.draw_source();
         self
     }
 
+    pub fn set_label(&mut self, label: String) -> &mut Banner {
+        self.label = label;
+        self.draw_source();
+        self
+    }
+
     pub fn set_syntax(&mut self, syntax: String) -> &mut Banner {
         self.syntax = syntax;
         self.draw_source();
         self
     }
 
+    /// Sets whether the file is saved with a byte order mark, which is indicated
+    /// alongside the syntax.
+    pub fn set_bom(&mut self, bom: bool) -> &mut Banner {
+        if bom != self.bom {
+            self.bom = bom;
+            self.draw_source();
+        }
+        self
+    }
+
+    /// Sets the activity, such as loading or saving, along with the percentage of the
+    /// file processed so far, which is indicated alongside the syntax, or `None` if no
+    /// such activity is in progress.
+    pub fn set_progress(&mut self, progress: Option<(&'static str, u8)>) -> &mut Banner {
+        if progress != self.progress {
+            self.progress = progress;
+            self.draw_source();
+        }
+        self
+    }
+
     pub fn set_location(&mut self, loc: Point) -> &mut Banner {
         self.loc = loc;
         self.draw_location();
         self
     }
 
+    /// Sets the selection statistics to a tuple containing the number of characters
+    /// and lines, or `None` if no selection is active.
+    pub fn set_selection(&mut self, selection: Option<(usize, usize)>) -> &mut Banner {
+        if selection != self.selection {
+            self.selection = selection;
+            self.draw_source();
+        }
+        self
+    }
+
     fn clear(&mut self) {
         self.canvas.fill_row(0, ' ', self.banner_color);
     }
 
+    fn draw_locked(&mut self) {
+        if let Some(col) = self.dirty_area {
+            let c = if self.locked { self.lock_char } else { ' ' };
+            self.canvas
+                .set(0, col - Self::LEFT_MARGIN_COLS, c, self.accent_color);
+        }
+    }
+
     fn draw_dirty(&mut self) {
         if let Some(col) = self.dirty_area {
-            let c = if self.dirty { '*' } else { ' ' };
+            let c = if self.stale {
+                Self::STALE_CHAR
+            } else if self.dirty {
+                '*'
+            } else if self.readonly {
+                Self::READONLY_CHAR
+            } else {
+                ' '
+            };
             self.canvas.set(0, col, c, self.accent_color);
         }
     }
 
     fn draw_source(&mut self) {
         if let Some(Range { start, end }) = self.source_area {
+            // Selection statistics, if any, are right-justified in the source area, but
+            // only if enough columns remain for the source.
+            let selection = self.selection.map(|(chars, lines)| {
+                let chars_unit = if chars == 1 { "char" } else { "chars" };
+                let lines_unit = if lines == 1 { "line" } else { "lines" };
+                format!("{chars} {chars_unit}, {lines} {lines_unit}")
+                    .chars()
+                    .collect::<Vec<_>>()
+            });
+            let end = match selection {
+                Some(s) if s.len() + Self::MIN_SOURCE_COLS < (end - start) as usize => {
+                    let sel_start = end - s.len() as u32;
+                    let gap_start = sel_start - Self::GAP_COLS;
+                    self.canvas.write(0, sel_start, &s, self.accent_color);
+                    self.canvas
+                        .fill(0, gap_start..sel_start, ' ', self.banner_color);
+                    gap_start
+                }
+                _ => end,
+            };
+
             let avail_cols = (end - start) as usize;
             let mut source = self.source.to_string().chars().collect::<Vec<_>>();
             let mut syntax = self.syntax.chars().collect::<Vec<_>>();
+            if self.bom {
+                syntax.extend(Self::BOM_SUFFIX.chars());
+            }
+            if let Some((activity, progress)) = self.progress {
+                syntax.extend(format!(", {activity} {progress}%").chars());
+            }
 
             if source.len() + syntax.len() + Self::SOURCE_ADORN_COLS > avail_cols {
-                // Try shortening source by using file name portion only, though note
-                // that shortening may not actually happen.
-                if let Source::File(path, _) = &self.source {
-                    source = sys::file_name(path).chars().collect::<Vec<_>>();
+                // Try shortening source by using the label, which is the shortest
+                // suffix of the path that distinguishes it from other editors, though
+                // note that shortening may not actually happen.
+                if let Source::File(_, _) = &self.source {
+                    source = self.label.chars().collect::<Vec<_>>();
                 }
 
                 if source.len() + syntax.len() + Self::SOURCE_ADORN_COLS > avail_cols {
@@ -202,10 +364,12 @@ impl Banner {
                     syntax.clear();
 
                     if source.len() > avail_cols {
-                        // Final attempt truncates prefix of source, but adds ellipsis as
-                        // visual cue that truncation occurred.
-                        source.drain(0..source.len() - avail_cols + Self::SOURCE_ELLIPSIS.len());
-                        source.splice(0..0, Self::SOURCE_ELLIPSIS.chars());
+                        // Final attempt truncates middle of source, preserving both
+                        // ends, but adds ellipsis as visual cue that truncation occurred.
+                        let keep = avail_cols.saturating_sub(Self::SOURCE_ELLIPSIS.len());
+                        let head = keep / 2;
+                        let tail = source.len() - (keep - head);
+                        source.splice(head..tail, Self::SOURCE_ELLIPSIS.chars());
                     }
                 }
             }
@@ -271,10 +435,135 @@ impl Banner {
     }
 }
 
+pub struct Ruler {
+    canvas: Canvas,
+    ruler_color: Color,
+    accent_color: Color,
+    margin_cols: u32,
+    col: u32,
+    offset: u32,
+}
+
+pub type RulerRef = Rc<RefCell<Ruler>>;
+
+impl Ruler {
+    /// Number of columns between each numbered tick mark.
+    const MAJOR_COLS: u32 = 10;
+
+    /// Number of columns between each unnumbered tick mark.
+    const MINOR_COLS: u32 = 5;
+
+    /// Character shown at unnumbered tick marks.
+    const MINOR_CHAR: char = '+';
+
+    /// Character shown at all other columns.
+    const FILL_CHAR: char = '-';
+
+    fn new(origin: Point, cols: u32, config: ConfigurationRef) -> Ruler {
+        let ruler_color = Color::new(config.theme.margin_fg, config.theme.margin_bg);
+        let accent_color = Color::new(config.theme.accent_fg, config.theme.margin_bg);
+        let mut this = Ruler {
+            canvas: Canvas::new(origin, Size::new(1, cols)),
+            ruler_color,
+            accent_color,
+            margin_cols: 0,
+            col: 0,
+            offset: 0,
+        };
+        this.draw_ticks();
+        this
+    }
+
+    /// Turns the ruler into a [`RulerRef`].
+    pub fn to_ref(self) -> RulerRef {
+        Rc::new(RefCell::new(self))
+    }
+
+    /// Draws the ruler by synchronizing pending changes.
+    pub fn draw(&mut self) {
+        self.canvas.draw();
+    }
+
+    /// Redraws the entire ruler regardless of pending changes.
+    pub fn redraw(&mut self) {
+        self.canvas.clear();
+        self.canvas.draw();
+    }
+
+    /// Sets the number of leftmost columns occupied by the margin of the editor, which
+    /// are excluded from column numbering.
+    pub fn set_margin(&mut self, cols: u32) -> &mut Ruler {
+        if cols != self.margin_cols {
+            self.margin_cols = cols;
+            self.draw_ticks();
+        }
+        self
+    }
+
+    /// Sets the `0`-based column of the cursor, which is highlighted on the ruler.
+    pub fn set_column(&mut self, col: u32) -> &mut Ruler {
+        if col != self.col {
+            self.col = col;
+            self.draw_ticks();
+        }
+        self
+    }
+
+    /// Sets the number of columns by which the editor is scrolled horizontally, which
+    /// offsets the numbering of columns.
+    pub fn set_offset(&mut self, cols: u32) -> &mut Ruler {
+        if cols != self.offset {
+            self.offset = cols;
+            self.draw_ticks();
+        }
+        self
+    }
+
+    fn draw_ticks(&mut self) {
+        let Size { rows, cols } = self.canvas.size();
+        if rows > 0 {
+            let margin_cols = cmp::min(self.margin_cols, cols);
+            let text_cols = cols - margin_cols;
+
+            // Columns are numbered as 1-based, hence adjustment.
+            let offset = self.offset;
+            let mut ticks = (offset + 1..=offset + text_cols)
+                .map(|n| {
+                    if n % Self::MINOR_COLS == 0 {
+                        Self::MINOR_CHAR
+                    } else {
+                        Self::FILL_CHAR
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            // Numbers are right-justified at each major tick mark, though leading
+            // digits of a number straddling the left edge are omitted.
+            let first = (offset / Self::MAJOR_COLS + 1) * Self::MAJOR_COLS;
+            for n in (first..=offset + text_cols).step_by(Self::MAJOR_COLS as usize) {
+                let s = n.to_string().chars().collect::<Vec<_>>();
+                let end = (n - offset) as usize;
+                let skip = s.len().saturating_sub(end);
+                ticks.splice(end + skip - s.len()..end, s.into_iter().skip(skip));
+            }
+
+            self.canvas.fill(0, 0..margin_cols, ' ', self.ruler_color);
+            self.canvas.write(0, margin_cols, &ticks, self.ruler_color);
+
+            // Column of cursor is highlighted as a visual cue.
+            if let Some(c) = ticks.get(self.col as usize) {
+                self.canvas
+                    .set(0, margin_cols + self.col, *c, self.accent_color);
+            }
+        }
+    }
+}
+
 pub struct Window {
     size: Size,
     canvas: CanvasRef,
     banner: BannerRef,
+    ruler: Option<RulerRef>,
 }
 
 pub type WindowRef = Rc<RefCell<Window>>;
@@ -283,10 +572,26 @@ impl Window {
     const CANVAS_ORIGIN_OFFSET: Size = Size::ZERO;
     const CANVAS_SIZE_ADJUST: Size = Size::rows(1);
 
-    pub fn new(origin: Point, size: Size, config: ConfigurationRef) -> Window {
+    /// Minimum number of window rows required to show the ruler, which ensures that at
+    /// least one row remains for the canvas.
+    const MIN_ROWS_FOR_RULER: u32 = 3;
+
+    /// Creates a window at `origin` with `size`, which includes a ruler above the
+    /// canvas if `ruler` is `true` and the window has enough rows to accommodate.
+    pub fn new(origin: Point, size: Size, config: ConfigurationRef, ruler: bool) -> Window {
+        let ruler = if ruler && size.rows >= Self::MIN_ROWS_FOR_RULER {
+            Some(Ruler::new(origin, size.cols, config.clone()).to_ref())
+        } else {
+            None
+        };
+        let ruler_adjust = if ruler.is_some() {
+            Size::rows(1)
+        } else {
+            Size::ZERO
+        };
         let canvas = Canvas::new(
-            origin + Self::CANVAS_ORIGIN_OFFSET,
-            size - Self::CANVAS_SIZE_ADJUST,
+            origin + Self::CANVAS_ORIGIN_OFFSET + ruler_adjust,
+            size - Self::CANVAS_SIZE_ADJUST - ruler_adjust,
         );
         let banner = Banner::new(
             origin + Size::rows(size.rows - 1),
@@ -297,6 +602,7 @@ impl Window {
             size,
             canvas: canvas.to_ref(),
             banner: banner.to_ref(),
+            ruler,
         };
         this.draw();
         this
@@ -307,6 +613,7 @@ impl Window {
             size: Size::ZERO,
             canvas: Canvas::zero().to_ref(),
             banner: Banner::none().to_ref(),
+            ruler: None,
         }
     }
 
@@ -326,8 +633,16 @@ impl Window {
         &self.banner
     }
 
+    /// Returns the ruler if shown, otherwise `None`.
+    pub fn ruler(&self) -> Option<&RulerRef> {
+        self.ruler.as_ref()
+    }
+
     pub fn draw(&mut self) {
         self.banner.borrow_mut().draw();
+        if let Some(ruler) = &self.ruler {
+            ruler.borrow_mut().draw();
+        }
     }
 
     /// Returns the point relative to the window canvas corresponding to `p`, which
@@ -8,6 +8,7 @@ use crate::window::{Window, WindowRef};
 use crate::writer::Writer;
 use std::cell::RefCell;
 use std::cmp;
+use std::ops::Range;
 use std::rc::Rc;
 
 /// Placement directive when adding new [`View`]s to a [`Workspace`].
@@ -19,31 +20,62 @@ pub enum Placement {
     /// Place at the bottom of the workspace.
     Bottom,
 
-    /// Place directly above the view referenced by the contained _id_.
+    /// Place directly above the row of views containing the view referenced by the
+    /// contained _id_.
     Above(u32),
 
-    /// Place directly below the view referenced by the contained _id_.
+    /// Place directly below the row of views containing the view referenced by the
+    /// contained _id_.
     Below(u32),
+
+    /// Place directly to the left of the view referenced by the contained _id_.
+    Left(u32),
+
+    /// Place directly to the right of the view referenced by the contained _id_.
+    Right(u32),
 }
 
 /// A view inside a [`Workspace`].
+///
+/// A view whose `beside` value is `true` is placed to the right of the view that
+/// precedes it, sharing the same rows, otherwise it starts a new row of views.
 pub struct View {
     pub id: u32,
     pub window: WindowRef,
+    pub ruler: bool,
+    pub beside: bool,
+
+    /// Number of rows allocated to the row of views containing this view, or `0` if
+    /// not yet allocated.
+    rows: u32,
 }
 
 impl View {
-    fn new(id: u32, window: WindowRef) -> View {
-        View { id, window }
+    /// Returns the number of rows allocated to the row of views containing this view,
+    /// or `0` if not yet allocated.
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    fn new(id: u32, window: WindowRef, ruler: bool, beside: bool, rows: u32) -> View {
+        View {
+            id,
+            window,
+            ruler,
+            beside,
+            rows,
+        }
     }
 }
 
 /// A workspace is a collection of [`View`]s that encapsulate the entire editing
 /// experience.
 ///
-/// Mutiple views within a workspace are organized vertically with an equal number of
-/// rows. As views are added and removed, the resulting collection of views is resized
-/// accorndingly.
+/// Mutiple views within a workspace are organized vertically as rows of views, and
+/// views within the same row are organized horizontally with an equal number of
+/// columns, separated by a single column divider. As views are added and removed, the
+/// resulting collection of views is resized accorndingly, where rows of views are
+/// given an equal number of rows unless explicitly grown or shrunk.
 ///
 /// A workspace always provides at least `1` view, which implies that the last
 /// remaining view can never be removed.
@@ -54,7 +86,10 @@ pub struct Workspace {
     views_size: Size,
     shared_origin: Point,
     shared_size: Size,
+    shared_rows: u32,
     shared_color: Color,
+    keys_origin: Point,
+    keys_rows: u32,
     id_seq: u32,
     views: Vec<View>,
 }
@@ -65,13 +100,19 @@ impl Workspace {
     /// A lower bound on the size of the workspace area.
     const MIN_SIZE: Size = Size::new(3, 2);
 
-    /// An adjustment to subtract from the workspace area size for calculating the area
-    /// size of views.
-    const VIEWS_SIZE_ADJUST: Size = Size::rows(1);
+    /// Default number of rows allocated to the shared region.
+    const SHARED_ROWS: u32 = 1;
 
     /// Minimum number of rows assigned to a view.
     const MIN_VIEW_ROWS: u32 = 2;
 
+    /// Minimum number of columns assigned to a view that shares its rows with other
+    /// views.
+    const MIN_VIEW_COLS: u32 = 16;
+
+    /// Number of columns dividing adjacent views that share the same rows.
+    const DIVIDER_COLS: u32 = 1;
+
     /// Creates a workspace with the given `config` and consuming the entire terminal.
     pub fn new(config: Configuration) -> Workspace {
         let size = Self::query_size();
@@ -80,13 +121,17 @@ impl Workspace {
             config: config.to_ref(),
             size,
             views_origin: Point::ORIGIN,
-            views_size: size - Self::VIEWS_SIZE_ADJUST,
-            shared_origin: Point::ORIGIN + Size::rows(size.rows - 1),
-            shared_size: Size::new(1, size.cols),
+            views_size: Size::ZERO,
+            shared_origin: Point::ORIGIN,
+            shared_size: Size::ZERO,
+            shared_rows: Self::SHARED_ROWS,
             shared_color,
+            keys_origin: Point::ORIGIN,
+            keys_rows: 0,
             id_seq: 0,
             views: vec![],
         };
+        this.resize_regions();
         this.open_view(Placement::Top);
         this
     }
@@ -100,49 +145,124 @@ impl Workspace {
         (self.shared_origin, self.shared_size)
     }
 
+    /// Returns the origin and size of the key region, which is a single row beneath
+    /// the shared region, or `None` if the region is not shown.
+    ///
+    /// The key region is shown only when the `show-keys` setting is enabled and the
+    /// terminal has enough rows to accommodate.
+    pub fn keys_region(&self) -> Option<(Point, Size)> {
+        if self.keys_rows > 0 {
+            Some((self.keys_origin, Size::new(self.keys_rows, self.size.cols)))
+        } else {
+            None
+        }
+    }
+
+    /// Changes the number of rows allocated to the shared region to `rows`, returning
+    /// `true` if the number of rows changed, which implies that all views were resized
+    /// as a side effect.
+    ///
+    /// The shared region always has at least `1` row, and the number of rows is
+    /// possibly reduced such that no view would drop below
+    /// [`Self::MIN_VIEW_ROWS`].
+    pub fn set_shared_rows(&mut self, rows: u32) -> bool {
+        let max_rows =
+            self.size.rows - self.keys_rows - self.row_count() as u32 * Self::MIN_VIEW_ROWS;
+        let rows = rows.clamp(1, cmp::max(max_rows, 1));
+        if rows != self.shared_rows {
+            self.shared_rows = rows;
+            self.resize_regions();
+            self.resize_views();
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn config(&self) -> &ConfigurationRef {
         &self.config
     }
 
+    /// Shows a ruler above the canvas of the view referenced by `id` if `ruler` is
+    /// `true`, otherwise the ruler is hidden. All views are resized as a side effect.
+    ///
+    /// Note that the ruler is only shown if the view has enough rows to accommodate,
+    /// though the preference is retained such that it appears if the view is later
+    /// resized.
+    ///
+    /// This function panics if `id` is not found, as this would indicate a correctness
+    /// problem by the caller.
+    pub fn set_ruler(&mut self, id: u32, ruler: bool) {
+        let view = self
+            .views
+            .iter_mut()
+            .find(|v| v.id == id)
+            .unwrap_or_else(|| panic!("{id}: view not found"));
+        view.ruler = ruler;
+        self.resize_views();
+    }
+
     /// Opens a new view in the workspace whose placement is based on `place`, returning
     /// the _id_ of the view or `None` if the view could not be created.
     ///
     /// Existing views will be resized as a side effect of opening a new view. However,
     /// the view will not be created, and resizing will not occur, if the resulting
-    /// number of rows would drop below [`Self::MIN_VIEW_ROWS`].
+    /// number of rows would drop below [`Self::MIN_VIEW_ROWS`], or in the case of
+    /// [`Placement::Left`] and [`Placement::Right`], if the resulting number of columns
+    /// would drop below [`Self::MIN_VIEW_COLS`].
     ///
-    /// This function panics if the `id` specified in [`Placement::Above`] or
-    /// [`Placement::Below`] is not found, as this would indicate a correctness
-    /// problem by the caller.
+    /// This function panics if the `id` specified in [`Placement::Above`],
+    /// [`Placement::Below`], [`Placement::Left`] or [`Placement::Right`] is not found,
+    /// as this would indicate a correctness problem by the caller.
     pub fn open_view(&mut self, place: Placement) -> Option<u32> {
-        // Calculate number of rows that would need to be allocated to each view
-        // should another view be added.
-        let rows = self.views_size.rows / (self.views.len() + 1) as u32;
-        if rows < Self::MIN_VIEW_ROWS {
-            None
+        let position_of = |id| {
+            self.views
+                .iter()
+                .position(|v| v.id == id)
+                .unwrap_or_else(|| panic!("{place:?}: view not found"))
+        };
+
+        // Find correct index for insertion of new window and whether it shares the
+        // rows of an existing view.
+        let (index, beside) = match place {
+            Placement::Top => (0, false),
+            Placement::Bottom => (self.views.len(), false),
+            Placement::Above(id) => (self.row_of(position_of(id)).start, false),
+            Placement::Below(id) => (self.row_of(position_of(id)).end, false),
+            Placement::Left(id) => {
+                let i = position_of(id);
+                (i, self.views[i].beside)
+            }
+            Placement::Right(id) => (position_of(id) + 1, true),
+        };
+
+        // Calculate number of rows or columns that would need to be allocated to each
+        // view should another view be added.
+        let fits = if let Placement::Left(id) | Placement::Right(id) = place {
+            let count = self.row_of(position_of(id)).len() as u32 + 1;
+            Self::cols_of(self.views_size.cols, count) >= Self::MIN_VIEW_COLS
         } else {
-            // Find correct index for insertion of new window.
-            let index = match place {
-                Placement::Top => 0,
-                Placement::Bottom => self.views.len(),
-                Placement::Above(id) => self
-                    .views
-                    .iter()
-                    .position(|v| v.id == id)
-                    .unwrap_or_else(|| panic!("{place:?}: view not found")),
-                Placement::Below(id) => self
-                    .views
-                    .iter()
-                    .position(|v| v.id == id)
-                    .map(|i| i + 1)
-                    .unwrap_or_else(|| panic!("{place:?}: view not found")),
-            };
+            self.views_size.rows / (self.row_count() + 1) as u32 >= Self::MIN_VIEW_ROWS
+        };
 
-            // Insert zombie view in correct place before resizing views.
+        if fits {
+            // Insert zombie view in correct place before resizing views, noting that
+            // a view inserted to the left of another now precedes it.
+            let rows = if beside {
+                self.views[index - 1].rows
+            } else {
+                0
+            };
+            if let Placement::Left(_) = place {
+                self.views[index].beside = true;
+            }
             let view_id = self.next_id();
-            self.views.insert(index, self.create_zombie(view_id));
+            self.views
+                .insert(index, self.create_zombie(view_id, beside, rows));
             self.resize_views();
             Some(view_id)
+        } else {
+            None
         }
     }
 
@@ -162,7 +282,7 @@ impl Workspace {
                 .iter()
                 .position(|v| v.id == id)
                 .unwrap_or_else(|| panic!("{id}: view not found"));
-            self.views.remove(i);
+            self.remove_view_at(i);
             self.resize_views();
 
             // Select view above the one removed.
@@ -190,7 +310,9 @@ impl Workspace {
     /// view _ids_ removed due to minimum size constraints of the workspace.
     ///
     /// Under most circumstances, the vector is empty. However, if one or more views
-    /// needs to be removed, the selection starts at the bottom and proceeds up.
+    /// needs to be removed, views that no longer fit beside other views in the same
+    /// row are removed first from right to left, followed by views from the bottom
+    /// and proceeding up.
     ///
     /// There exists the possibility that all but one view are removed if the terminal
     /// is made small enough. Since the workspace guarantees the existence of at least
@@ -201,41 +323,42 @@ impl Workspace {
         if size != self.size {
             // Update size of workspace and view areas, which drive calculation of total
             // number of views and corresponding row allocations.
+            // Shared region reverts to its default size, since any rows added
+            // beyond the default might otherwise violate the minimum view size.
             self.size = size;
-            self.views_size = size - Self::VIEWS_SIZE_ADJUST;
-            self.shared_origin = Point::ORIGIN + Size::rows(size.rows - 1);
-            self.shared_size = Size::new(1, size.cols);
-
-            // Calculate number of rows to allocate to each view, though revised workspace
-            // size might lead to violation of minimum view size constraint, which means
-            // total number of views must be reduced such that constraint is held.
-            let rows = self.views_size.rows / self.views.len() as u32;
-            let count = if rows < Self::MIN_VIEW_ROWS {
-                (self.views_size.rows / Self::MIN_VIEW_ROWS) as usize
-            } else {
-                self.views.len()
-            };
+            self.shared_rows = Self::SHARED_ROWS;
+            self.resize_regions();
+
+            // Revised workspace size might lead to violation of minimum view size
+            // constraints, which means views must be removed such that constraints
+            // are held, though never the view specified by caller regardless of where
+            // it exists in the arrangement of views.
+            let max_cols = (self.views_size.cols + Self::DIVIDER_COLS)
+                / (Self::MIN_VIEW_COLS + Self::DIVIDER_COLS);
+            let mut removed_ids = Vec::new();
+            let mut start = 0;
+            while start < self.views.len() {
+                let mut row = self.row_of(start);
+                while row.len() > cmp::max(max_cols as usize, 1) {
+                    let i = row
+                        .clone()
+                        .rev()
+                        .find(|i| self.views[*i].id != keep_id)
+                        .unwrap_or(row.end - 1);
+                    removed_ids.push(self.remove_view_at(i));
+                    row = self.row_of(start);
+                }
+                start = row.end;
+            }
 
-            // If necessary, remove views from bottom to top, though do not remove view
-            // specified by caller regardless of where it exists in stack of views.
-            let removed_ids =
-                if count < self.views.len() {
-                    let n = self.views.len() - count;
-                    let indexes = self.views.iter().rev().enumerate().fold(
-                        Vec::new(),
-                        |mut indexes, (i, v)| {
-                            if indexes.len() < n && v.id != keep_id {
-                                // Index is flipped since views are being iterated back to
-                                // front.
-                                indexes.push(self.views.len() - i - 1);
-                            }
-                            indexes
-                        },
-                    );
-                    indexes.iter().map(|i| self.views.remove(*i).id).collect()
+            let max_rows = cmp::max(self.views_size.rows / Self::MIN_VIEW_ROWS, 1) as usize;
+            while self.row_count() > max_rows {
+                if let Some(i) = self.views.iter().rposition(|v| v.id != keep_id) {
+                    removed_ids.push(self.remove_view_at(i));
                 } else {
-                    vec![]
-                };
+                    break;
+                }
+            }
 
             self.resize_views();
             Some(removed_ids)
@@ -244,32 +367,290 @@ impl Workspace {
         }
     }
 
-    /// Resizes views with an equal distribution of `rows`, though views towards the top
-    /// will include an additional row if `residual_rows` is greater than 0.
+    /// Calculates the origin and size of the views, shared and key regions, where the
+    /// shared region occupies the bottom-most rows of the workspace, or those directly
+    /// above the key region if shown.
+    fn resize_regions(&mut self) {
+        self.keys_rows = if self.config.settings.show_keys && self.size.rows > Self::MIN_SIZE.rows {
+            1
+        } else {
+            0
+        };
+        let bottom_rows = self.shared_rows + self.keys_rows;
+        self.views_size = self.size - Size::rows(bottom_rows);
+        self.shared_origin = Point::ORIGIN + Size::rows(self.size.rows - bottom_rows);
+        self.shared_size = Size::new(self.shared_rows, self.size.cols);
+        self.keys_origin = Point::ORIGIN + Size::rows(self.size.rows - self.keys_rows);
+    }
+
+    /// Increases the number of rows allocated to the row of views containing `id` by
+    /// `rows`, which are taken from rows of views below, or above if necessary,
+    /// returning `true` if the views were resized.
+    ///
+    /// The number of rows is possibly reduced such that no other row of views would
+    /// drop below [`Self::MIN_VIEW_ROWS`].
+    ///
+    /// This function panics if `id` is not found, as this would indicate a correctness
+    /// problem by the caller.
+    pub fn grow_view(&mut self, id: u32, rows: u32) -> bool {
+        let mut sizes = self.row_sizes();
+        let i = self.row_index_of(id);
+        let mut rows_left = rows;
+        let donors = (i + 1..sizes.len()).chain((0..i).rev()).collect::<Vec<_>>();
+        for j in donors {
+            let take = cmp::min(rows_left, sizes[j] - Self::MIN_VIEW_ROWS);
+            sizes[j] -= take;
+            rows_left -= take;
+        }
+        sizes[i] += rows - rows_left;
+        self.set_row_sizes(sizes, rows_left < rows)
+    }
+
+    /// Decreases the number of rows allocated to the row of views containing `id` by
+    /// `rows`, which are given to the row of views below, or above if `id` is at the
+    /// bottom, returning `true` if the views were resized.
+    ///
+    /// The number of rows is possibly reduced such that the row of views containing
+    /// `id` would not drop below [`Self::MIN_VIEW_ROWS`].
+    ///
+    /// This function panics if `id` is not found, as this would indicate a correctness
+    /// problem by the caller.
+    pub fn shrink_view(&mut self, id: u32, rows: u32) -> bool {
+        let mut sizes = self.row_sizes();
+        let i = self.row_index_of(id);
+        let j = if i + 1 < sizes.len() {
+            i + 1
+        } else if i > 0 {
+            i - 1
+        } else {
+            return false;
+        };
+        let rows = cmp::min(rows, sizes[i] - Self::MIN_VIEW_ROWS);
+        sizes[i] -= rows;
+        sizes[j] += rows;
+        self.set_row_sizes(sizes, rows > 0)
+    }
+
+    /// Allocates rows to each row of views from top to bottom according to `sizes`,
+    /// returning `true` if the views were resized.
+    ///
+    /// Should `sizes` not total the number of rows available, any difference is
+    /// absorbed by rows of views towards the bottom, and if `sizes` does not contain
+    /// a size for every row of views, or any size is less than
+    /// [`Self::MIN_VIEW_ROWS`], all rows of views are allocated an equal number of
+    /// rows instead.
+    pub fn set_view_rows(&mut self, sizes: Vec<u32>) -> bool {
+        let sizes = if sizes.len() == self.row_count() {
+            sizes
+        } else {
+            vec![0; self.row_count()]
+        };
+        let before = self.row_sizes();
+        self.set_row_sizes(sizes, true);
+        self.row_sizes() != before
+    }
+
+    /// Recreates all views in place, which is necessary when the terminal was used by
+    /// another program and its contents must be redrawn.
+    pub fn redraw_views(&mut self) {
+        self.resize_views();
+    }
+
+    /// Allocates an equal number of rows to all rows of views, returning `true` if the
+    /// views were resized.
+    pub fn balance_views(&mut self) -> bool {
+        let sizes = self.row_sizes();
+        let balanced = self.balanced_sizes();
+        let changed = sizes != balanced;
+        self.set_row_sizes(balanced, changed)
+    }
+
+    /// Resizes views according to `sizes`, which contains the number of rows for each
+    /// row of views from top to bottom, but only if `changed`, returning `changed`.
+    fn set_row_sizes(&mut self, sizes: Vec<u32>, changed: bool) -> bool {
+        if changed {
+            let mut start = 0;
+            for rows in sizes {
+                let row = self.row_of(start);
+                for view in &mut self.views[row.clone()] {
+                    view.rows = rows;
+                }
+                start = row.end;
+            }
+            self.resize_views();
+        }
+        changed
+    }
+
+    /// Returns the index of the row of views containing `id`.
+    fn row_index_of(&self, id: u32) -> usize {
+        let i = self
+            .views
+            .iter()
+            .position(|v| v.id == id)
+            .unwrap_or_else(|| panic!("{id}: view not found"));
+        self.views[..=i]
+            .iter()
+            .enumerate()
+            .filter(|(j, v)| *j == 0 || !v.beside)
+            .count()
+            - 1
+    }
+
+    /// Returns the number of rows to allocate to each row of views from top to bottom.
+    ///
+    /// Rows previously allocated are retained so long as every row of views has been
+    /// allocated, where any difference from the total number of rows is absorbed by
+    /// rows of views towards the bottom, otherwise all rows of views are allocated an
+    /// equal number of rows.
+    fn row_sizes(&self) -> Vec<u32> {
+        let mut sizes = self
+            .views
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| *i == 0 || !v.beside)
+            .map(|(_, v)| v.rows)
+            .collect::<Vec<_>>();
+        if sizes.iter().any(|rows| *rows < Self::MIN_VIEW_ROWS) {
+            return self.balanced_sizes();
+        }
+
+        let total = self.views_size.rows;
+        let sum = sizes.iter().sum::<u32>();
+        if sum < total {
+            if let Some(rows) = sizes.last_mut() {
+                *rows += total - sum;
+            }
+        } else if sum > total {
+            let mut excess = sum - total;
+            for rows in sizes.iter_mut().rev() {
+                let take = cmp::min(excess, *rows - Self::MIN_VIEW_ROWS);
+                *rows -= take;
+                excess -= take;
+            }
+            if excess > 0 {
+                return self.balanced_sizes();
+            }
+        }
+        sizes
+    }
+
+    /// Returns an equal distribution of rows to each row of views, though rows of
+    /// views towards the top will include an additional row if the total number of
+    /// rows is not evenly divisible.
+    fn balanced_sizes(&self) -> Vec<u32> {
+        let count = self.row_count() as u32;
+        let rows = self.views_size.rows / count;
+        let residual_rows = self.views_size.rows % count;
+        (0..count)
+            .map(|i| if i < residual_rows { rows + 1 } else { rows })
+            .collect()
+    }
+
+    /// Resizes rows of views according to [`row_sizes`](Self::row_sizes). Views sharing
+    /// the same rows are resized with an equal distribution of columns, and dividers
+    /// are drawn between them.
     fn resize_views(&mut self) {
-        let count = self.views.len();
-        let rows = self.views_size.rows / count as u32;
-        let residual_rows = self.views_size.rows % count as u32;
-
-        let (views, _) = self.views.iter().enumerate().fold(
-            (Vec::new(), self.views_origin),
-            |(mut views, origin), (i, v)| {
-                // Give precedence of residual rows to top-most views.
-                let rows = if i >= residual_rows as usize {
-                    rows
+        let mut views = Vec::new();
+        let mut dividers = Vec::new();
+        let mut origin = self.views_origin;
+        let mut start = 0;
+        for rows in self.row_sizes() {
+            // Recreate views in row with new origin and size, where left-most views
+            // take precedence of residual columns.
+            let row = self.row_of(start);
+            let n = row.len() as u32;
+            let cols = Self::cols_of(self.views_size.cols, n);
+            let residual_cols = self.views_size.cols - Self::DIVIDER_COLS * (n - 1) - cols * n;
+            let mut view_origin = origin;
+            for (j, v) in self.views[row.clone()].iter().enumerate() {
+                let cols = if j >= residual_cols as usize {
+                    cols
                 } else {
-                    rows + 1
+                    cols + 1
                 };
+                let size = Size::new(rows, cols);
+                views.push(self.create_view(v.id, view_origin, size, v.ruler, v.beside, rows));
+                view_origin = view_origin + Size::cols(cols);
+                if j + 1 < n as usize {
+                    dividers.push((view_origin, rows));
+                    view_origin = view_origin + Size::cols(Self::DIVIDER_COLS);
+                }
+            }
 
-                // Recreate view with new origin and size.
-                let view = self.create_view(v.id, origin, rows);
-                views.push(view);
-
-                // Update origin for next iteration of fold.
-                (views, origin + Size::rows(rows))
-            },
-        );
+            // Update origin for next row.
+            origin = origin + Size::rows(rows);
+            start = row.end;
+        }
         self.views = views;
+        self.draw_dividers(&dividers);
+    }
+
+    /// Draws dividers at each origin in `dividers` spanning the given number of rows.
+    fn draw_dividers(&self, dividers: &[(Point, u32)]) {
+        let c = if self.config.settings.ascii_only {
+            '|'
+        } else {
+            '\u{2502}'
+        };
+        let color = Color::new(self.config.theme.banner_fg, self.config.theme.inactive_bg);
+        for (origin, rows) in dividers {
+            for row in 0..*rows {
+                Writer::new_at(*origin + Size::rows(row))
+                    .set_color(color)
+                    .write(c)
+                    .send();
+            }
+        }
+    }
+
+    /// Returns the number of columns allocated to each of `count` views sharing `cols`
+    /// columns, excluding the dividers between them.
+    fn cols_of(cols: u32, count: u32) -> u32 {
+        cols.saturating_sub(Self::DIVIDER_COLS * (count - 1)) / count
+    }
+
+    /// Returns the range of indexes of views sharing the same rows as the view at
+    /// `index`.
+    fn row_of(&self, index: usize) -> Range<usize> {
+        let start = self.views[..=index]
+            .iter()
+            .rposition(|v| !v.beside)
+            .unwrap_or(0);
+        let end = self.views[index + 1..]
+            .iter()
+            .position(|v| !v.beside)
+            .map(|i| index + 1 + i)
+            .unwrap_or(self.views.len());
+        start..end
+    }
+
+    /// Returns the number of rows of views.
+    fn row_count(&self) -> usize {
+        self.views
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| *i == 0 || !v.beside)
+            .count()
+    }
+
+    /// Removes the view at `index` and returns its id, though the view that follows
+    /// inherits the start of the row if applicable.
+    ///
+    /// If the view was the only view in its row, the rows allocated to it are given to
+    /// the row of views above, or below if it was at the top.
+    fn remove_view_at(&mut self, index: usize) -> u32 {
+        let view = self.views.remove(index);
+        let next_beside = self.views.get(index).map(|v| v.beside).unwrap_or(false);
+        if !view.beside && !next_beside && !self.views.is_empty() {
+            let row = self.row_of(if index > 0 { index - 1 } else { index });
+            for v in &mut self.views[row] {
+                v.rows += view.rows;
+            }
+        } else if let Some(next) = self.views.get_mut(index) {
+            next.beside = next.beside && view.beside;
+        }
+        view.id
     }
 
     /// Returns the top-most [`View`] in the workspace.
@@ -312,6 +693,12 @@ impl Workspace {
         &self.views[i]
     }
 
+    /// Returns the _ids_ of all views ordered from top to bottom, and from left to right
+    /// within the same row.
+    pub fn view_ids(&self) -> Vec<u32> {
+        self.views.iter().map(|v| v.id).collect()
+    }
+
     /// Returns the view corresponding to `id`, which must exist.
     ///
     /// This function panics if `id` is not found.
@@ -326,10 +713,12 @@ impl Workspace {
     }
 
     pub fn clear_shared(&mut self) {
-        Writer::new_at(self.shared_origin)
-            .set_color(self.shared_color)
-            .write_str(" ".repeat(self.size.cols as usize).as_str())
-            .send();
+        for row in 0..self.shared_rows {
+            Writer::new_at(self.shared_origin + Size::rows(row))
+                .set_color(self.shared_color)
+                .write_str(" ".repeat(self.size.cols as usize).as_str())
+                .send();
+        }
     }
 
     /// Returns the terminal [size](Size), but possibly changes what is reported by the
@@ -350,16 +739,20 @@ impl Workspace {
         id
     }
 
-    fn create_view(&self, id: u32, origin: Point, rows: u32) -> View {
-        let window = Window::new(
-            origin,
-            Size::new(rows, self.views_size.cols),
-            self.config.clone(),
-        );
-        View::new(id, window.to_ref())
+    fn create_view(
+        &self,
+        id: u32,
+        origin: Point,
+        size: Size,
+        ruler: bool,
+        beside: bool,
+        rows: u32,
+    ) -> View {
+        let window = Window::new(origin, size, self.config.clone(), ruler);
+        View::new(id, window.to_ref(), ruler, beside, rows)
     }
 
-    fn create_zombie(&self, id: u32) -> View {
-        View::new(id, Window::zombie().to_ref())
+    fn create_zombie(&self, id: u32, beside: bool, rows: u32) -> View {
+        View::new(id, Window::zombie().to_ref(), false, beside, rows)
     }
 }
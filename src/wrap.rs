@@ -0,0 +1,272 @@
+//! Conversion of text between soft-wrapped and hard-wrapped forms.
+//!
+//! Text is _hard-wrapped_ when line breaks are inserted such that no line extends
+//! beyond a given number of columns, and _unwrapped_ when the lines of each paragraph
+//! are joined back into a single line. Paragraphs are separated by blank lines.
+//! Text is _reflowed_ when the lines of each paragraph are joined and then broken
+//! again, where paragraphs may also be formed of line comments.
+//!
+//! Columns are measured in characters, so tabs and wide characters are not given
+//! special consideration.
+
+/// Returns `text` with line breaks inserted such that lines do not extend beyond
+/// `cols`, preserving the indentation of each line that is broken.
+///
+/// Lines are only broken at whitespace, so a single word wider than `cols` will
+/// extend beyond the limit.
+pub fn hard_wrap(text: &str, cols: usize) -> String {
+    let mut out = String::new();
+    for line in text.split_inclusive('\n') {
+        let (body, eol) = split_eol(line);
+        if body.chars().count() > cols {
+            let indent = leading_space(body);
+            let indent_len = indent.chars().count();
+            let mut cur = indent.to_string();
+            let mut cur_len = indent_len;
+            for word in body.split_whitespace() {
+                let word_len = word.chars().count();
+                if cur_len == indent_len {
+                    cur.push_str(word);
+                    cur_len += word_len;
+                } else if cur_len + 1 + word_len <= cols {
+                    cur.push(' ');
+                    cur.push_str(word);
+                    cur_len += 1 + word_len;
+                } else {
+                    out.push_str(&cur);
+                    out.push('\n');
+                    cur = format!("{indent}{word}");
+                    cur_len = indent_len + word_len;
+                }
+            }
+            out.push_str(&cur);
+        } else {
+            out.push_str(body);
+        }
+        out.push_str(eol);
+    }
+    out
+}
+
+/// Returns `text` with the lines of each paragraph joined into a single line, which
+/// preserves the indentation of the first line of each paragraph.
+pub fn unwrap(text: &str) -> String {
+    let mut out = String::new();
+    let mut para: Option<String> = None;
+    for line in text.split_inclusive('\n') {
+        let (body, eol) = split_eol(line);
+        if body.trim().is_empty() {
+            if let Some(para) = para.take() {
+                out.push_str(&para);
+                out.push('\n');
+            }
+            out.push_str(line);
+        } else {
+            para = match para {
+                Some(mut para) => {
+                    para.push(' ');
+                    para.push_str(body.trim());
+                    Some(para)
+                }
+                None => Some(body.trim_end().to_string()),
+            };
+            if eol.is_empty() {
+                if let Some(para) = para.take() {
+                    out.push_str(&para);
+                }
+            }
+        }
+    }
+    if let Some(para) = para {
+        out.push_str(&para);
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns `text` with the lines of each paragraph joined and then broken such that
+/// lines do not extend beyond `cols`, where every line of a paragraph begins with
+/// the [prefix](prefix_of) of its first line.
+///
+/// Consecutive lines belong to the same paragraph when their prefixes are equal,
+/// ignoring trailing whitespace, and each contains text beyond its prefix. All other
+/// lines are left intact.
+pub fn reflow(text: &str, cols: usize, comments: &[String]) -> String {
+    let mut out = String::new();
+    let mut para: Option<(&str, &str, Vec<&str>)> = None;
+    for line in text.split_inclusive('\n') {
+        let (body, eol) = split_eol(line);
+        let key = paragraph_key(body, comments);
+        let words = body[prefix_of(body, comments).len()..].split_whitespace();
+        match para.as_mut() {
+            Some((prefix, para_eol, para_words)) if key == Some(prefix_key(prefix)) => {
+                para_words.extend(words);
+                *para_eol = eol;
+            }
+            _ => {
+                if let Some((prefix, para_eol, para_words)) = para.take() {
+                    fill(&mut out, prefix, &para_words, cols);
+                    out.push_str(para_eol);
+                }
+                if key.is_some() {
+                    para = Some((prefix_of(body, comments), eol, words.collect()));
+                } else {
+                    out.push_str(line);
+                }
+            }
+        }
+    }
+    if let Some((prefix, eol, words)) = para {
+        fill(&mut out, prefix, &words, cols);
+        out.push_str(eol);
+    }
+    out
+}
+
+/// Returns the prefix of `line`, which is its leading whitespace followed by the
+/// longest of `comments` that begins the remaining text, if any, and the whitespace
+/// that follows it.
+///
+/// `comments` is expected to be ordered from longest to shortest.
+pub fn prefix_of<'a>(line: &'a str, comments: &[String]) -> &'a str {
+    let indent = leading_space(line);
+    let rest = &line[indent.len()..];
+    match comments
+        .iter()
+        .find(|comment| rest.starts_with(comment.as_str()))
+    {
+        Some(comment) => {
+            let after = &rest[comment.len()..];
+            let n = indent.len() + comment.len() + leading_space(after).len();
+            &line[..n]
+        }
+        None => indent,
+    }
+}
+
+/// Returns the [prefix](prefix_of) of `line`, without whitespace that follows a
+/// comment, if `line` contains text beyond its prefix, otherwise `None`, such that
+/// consecutive lines with equal keys belong to the same paragraph.
+pub fn paragraph_key<'a>(line: &'a str, comments: &[String]) -> Option<&'a str> {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let prefix = prefix_of(line, comments);
+    if line[prefix.len()..].trim().is_empty() {
+        None
+    } else {
+        Some(prefix_key(prefix))
+    }
+}
+
+/// Returns `prefix` without whitespace that follows a comment, if any.
+fn prefix_key(prefix: &str) -> &str {
+    if prefix.trim().is_empty() {
+        prefix
+    } else {
+        prefix.trim_end()
+    }
+}
+
+/// Appends `words` to `out` as lines that begin with `prefix` and do not extend
+/// beyond `cols`, though a line always contains at least one word.
+fn fill(out: &mut String, prefix: &str, words: &[&str], cols: usize) {
+    let prefix_len = prefix.chars().count();
+    let mut cur_len = prefix_len;
+    out.push_str(prefix);
+    for word in words {
+        let word_len = word.chars().count();
+        if cur_len == prefix_len {
+            cur_len += word_len;
+        } else if cur_len + 1 + word_len <= cols {
+            out.push(' ');
+            cur_len += 1 + word_len;
+        } else {
+            out.push('\n');
+            out.push_str(prefix);
+            cur_len = prefix_len + word_len;
+        }
+        out.push_str(word);
+    }
+}
+
+/// Splits `line` into its body and the trailing `\n`, if present.
+fn split_eol(line: &str) -> (&str, &str) {
+    match line.strip_suffix('\n') {
+        Some(body) => (body, "\n"),
+        None => (line, ""),
+    }
+}
+
+/// Returns the leading whitespace of `line`.
+fn leading_space(line: &str) -> &str {
+    let n = line.len() - line.trim_start().len();
+    &line[..n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comments() -> Vec<String> {
+        vec!["///".to_string(), "//".to_string()]
+    }
+
+    #[test]
+    fn hard_wrap_preserves_indent() {
+        let text = "  one two three four\nshort\n";
+        assert_eq!(hard_wrap(text, 10), "  one two\n  three\n  four\nshort\n");
+    }
+
+    #[test]
+    fn hard_wrap_long_words() {
+        assert_eq!(hard_wrap("a verylongword b", 5), "a\nverylongword\nb");
+        assert_eq!(hard_wrap("verylongword", 5), "verylongword");
+    }
+
+    #[test]
+    fn unwrap_paragraphs() {
+        let text = "  one\n  two\n\nthree\nfour";
+        assert_eq!(unwrap(text), "  one two\n\nthree four");
+        assert_eq!(unwrap("one\ntwo\n\n\n"), "one two\n\n\n");
+    }
+
+    #[test]
+    fn reflow_paragraph_boundaries() {
+        let text = "one two\nthree\n\nfour five six\n";
+        assert_eq!(reflow(text, 9, &[]), "one two\nthree\n\nfour five\nsix\n");
+
+        // A change in indentation starts a new paragraph.
+        let text = "one\n  two\n  three\n";
+        assert_eq!(reflow(text, 20, &[]), "one\n  two three\n");
+    }
+
+    #[test]
+    fn reflow_comment_prefixes() {
+        let text = "    // one two\n    //three four\n    /// five\n";
+        assert_eq!(
+            reflow(text, 17, &comments()),
+            "    // one two\n    // three four\n    /// five\n"
+        );
+
+        // Comment lines without text separate paragraphs.
+        let text = "// one\n//\n// two\n// three";
+        assert_eq!(reflow(text, 20, &comments()), "// one\n//\n// two three");
+    }
+
+    #[test]
+    fn reflow_long_words() {
+        let text = "// a verylongword b\n";
+        assert_eq!(
+            reflow(text, 6, &comments()),
+            "// a\n// verylongword\n// b\n"
+        );
+    }
+
+    #[test]
+    fn prefix_and_key() {
+        assert_eq!(prefix_of("  /// text", &comments()), "  /// ");
+        assert_eq!(prefix_of("  text", &comments()), "  ");
+        assert_eq!(paragraph_key("  //  text\n", &comments()), Some("  //"));
+        assert_eq!(paragraph_key("  //  \n", &comments()), None);
+        assert_eq!(paragraph_key("", &comments()), None);
+    }
+}
@@ -3,6 +3,7 @@
 
 use crate::ansi;
 use crate::color::Color;
+use crate::config::CursorShape;
 use crate::size::Point;
 use std::io::{self, Write};
 
@@ -57,6 +58,17 @@ impl Writer {
         self
     }
 
+    pub fn set_cursor_shape(&mut self, shape: CursorShape, blink: bool) -> &mut Writer {
+        self.out
+            .push_str(ansi::set_cursor_shape(shape, blink).as_str());
+        self
+    }
+
+    pub fn set_title(&mut self, title: &str) -> &mut Writer {
+        self.out.push_str(ansi::set_title(title).as_str());
+        self
+    }
+
     pub fn set_color(&mut self, color: Color) -> &mut Writer {
         self.out.push_str(ansi::set_color(color).as_str());
         self